@@ -78,6 +78,11 @@ impl ControlPanel {
                 .service(endpoints::stats)
                 .service(endpoints::get_config)
                 .service(endpoints::set_config)
+                .service(endpoints::execution_algos)
+                .service(endpoints::start_execution_algo)
+                .service(endpoints::cancel_execution_algo)
+                .service(endpoints::pnl)
+                .service(endpoints::update_currency_pairs)
                 .service(
                     actix_files::Files::new("/", webui_dir)
                         .use_last_modified(true)
@@ -41,3 +41,69 @@ pub(super) async fn set_config(body: web::Bytes, client: DataWebMmbRpcClient) ->
 pub(super) async fn stats(client: DataWebMmbRpcClient) -> impl Responder {
     send_request(client, |client| client.stats().boxed()).await
 }
+
+#[get("/execution_algos")]
+pub(super) async fn execution_algos(client: DataWebMmbRpcClient) -> impl Responder {
+    send_request(client, |client| client.execution_algos().boxed()).await
+}
+
+#[get("/pnl")]
+pub(super) async fn pnl(client: DataWebMmbRpcClient) -> impl Responder {
+    send_request(client, |client| client.pnl().boxed()).await
+}
+
+#[post("/execution_algos")]
+pub(super) async fn start_execution_algo(
+    body: web::Bytes,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let parent_order = match String::from_utf8((&body).to_vec()) {
+        Ok(parent_order) => parent_order,
+        Err(err) => {
+            return HttpResponse::BadRequest().body(format!(
+                "Failed to convert input parent order({body:?}) to utf8 string: {err}",
+            ))
+        }
+    };
+
+    send_request(client, move |client| {
+        client.start_execution_algo(parent_order.clone()).boxed()
+    })
+    .await
+}
+
+#[post("/execution_algos/{id}/cancel")]
+pub(super) async fn cancel_execution_algo(
+    id: web::Path<u64>,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let id = id.into_inner();
+    send_request(client, move |client| {
+        client.cancel_execution_algo(id).boxed()
+    })
+    .await
+}
+
+#[post("/exchanges/{exchange_account_id}/currency_pairs")]
+pub(super) async fn update_currency_pairs(
+    exchange_account_id: web::Path<String>,
+    body: web::Bytes,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let exchange_account_id = exchange_account_id.into_inner();
+    let currency_pairs = match String::from_utf8((&body).to_vec()) {
+        Ok(currency_pairs) => currency_pairs,
+        Err(err) => {
+            return HttpResponse::BadRequest().body(format!(
+                "Failed to convert input currency pairs({body:?}) to utf8 string: {err}",
+            ))
+        }
+    };
+
+    send_request(client, move |client| {
+        client
+            .update_currency_pairs(exchange_account_id.clone(), currency_pairs.clone())
+            .boxed()
+    })
+    .await
+}
@@ -25,6 +25,7 @@ use crate::balance::{
 use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
 use crate::exchanges::general::exchange::Exchange;
 use crate::explanation::{Explanation, OptionExplanationAddReasonExt};
+use crate::math::ConvertPercentToRate;
 use crate::misc::reserve_parameters::ReserveParameters;
 use crate::misc::service_value_tree::ServiceValueTree;
 #[double]
@@ -450,7 +451,16 @@ impl BalanceReservationManager {
 
         // isLeveraged is used when we need to know how much funds we can use for orders
         if is_leveraged {
-            balance_in_currency_code *= leverage;
+            // balance should cover margin and expected commission of position,
+            // the same rates are used for reservation cost in `calculate_reservation_cost`
+            let position_cost_rate = self.get_margin_rate(exchange_account_id, &symbol, leverage)
+                + self.get_reservation_commission_rate(
+                    exchange_account_id,
+                    &symbol,
+                    side,
+                    currency_code,
+                );
+            balance_in_currency_code /= position_cost_rate;
             balance_in_currency_code /= symbol.amount_multiplier;
 
             explanation.with_reason(|| format!("balance_in_currency_code with leverage and multiplier: {balance_in_currency_code}"));
@@ -673,6 +683,48 @@ impl BalanceReservationManager {
         position_in_amount_currency
     }
 
+    /// Estimated liquidation price of position opened by `entry_price` with current leverage of exchange
+    pub fn get_liquidation_price(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        symbol: &Symbol,
+        side: OrderSide,
+        entry_price: Price,
+    ) -> Option<Price> {
+        let leverage = self.get_leverage(exchange_account_id, symbol.currency_pair());
+        let maintenance_margin_rate =
+            self.get_maintenance_margin_rate(exchange_account_id, symbol, leverage);
+
+        symbol.liquidation_price(side, entry_price, leverage, maintenance_margin_rate)
+    }
+
+    /// Loss in settlement currency that filled position can take before liquidation.
+    /// Returns `None` if there is no position or it can't be liquidated
+    pub fn get_liquidation_distance(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        symbol: &Symbol,
+        entry_price: Price,
+        mark_price: Price,
+    ) -> Option<Amount> {
+        let position = self
+            .position_by_fill_amount_in_amount_currency
+            .get(exchange_account_id, symbol.currency_pair())
+            .unwrap_or(dec!(0));
+        if !symbol.is_derivative || position.is_zero() {
+            return None;
+        }
+
+        let side = match position.is_sign_positive() {
+            true => OrderSide::Buy,
+            false => OrderSide::Sell,
+        };
+        let liquidation_price =
+            self.get_liquidation_price(exchange_account_id, symbol, side, entry_price)?;
+
+        Some(symbol.liquidation_distance(position, mark_price, liquidation_price))
+    }
+
     fn unreserve_not_approved_part(
         &mut self,
         reservation_id: ReservationId,
@@ -1157,6 +1209,33 @@ impl BalanceReservationManager {
         true
     }
 
+    /// Move reservation with its unreserved amount to another configuration descriptor,
+    /// e.g. when reservation of adopted order is taken by strategy
+    pub fn move_reservation(
+        &mut self,
+        reservation_id: ReservationId,
+        configuration_descriptor: ConfigurationDescriptor,
+    ) -> Result<()> {
+        let reservation = self
+            .get_reservation(reservation_id)
+            .with_context(|| format!("Can't find reservation {reservation_id} for moving"))?;
+        if reservation.configuration_descriptor == configuration_descriptor {
+            return Ok(());
+        }
+
+        let amount = reservation.unreserved_amount;
+        let src_request = BalanceRequest::from_reservation(reservation);
+        self.add_reserved_amount(&src_request, reservation_id, -amount, true)?;
+
+        let reservation = self.get_mut_reservation_expected(reservation_id);
+        reservation.configuration_descriptor = configuration_descriptor;
+        let dst_request = BalanceRequest::from_reservation(reservation);
+        self.add_reserved_amount(&dst_request, reservation_id, amount, true)?;
+
+        log::info!("Moved reservation {reservation_id} to {configuration_descriptor:?}");
+        Ok(())
+    }
+
     fn transfer_amount(
         &mut self,
         src_reservation_id: ReservationId,
@@ -1513,7 +1592,7 @@ impl BalanceReservationManager {
         );
 
         let (cost_in_amount_currency_code, taken_free_amount) =
-            self.calculate_reservation_cost(reserve_parameters);
+            self.calculate_reservation_cost(reserve_parameters, reservation_currency_code);
         let cost_in_reservation_currency_code = symbol.convert_amount_from_amount_currency_code(
             reservation_currency_code,
             cost_in_amount_currency_code,
@@ -1536,9 +1615,19 @@ impl BalanceReservationManager {
     fn calculate_reservation_cost(
         &self,
         reserve_parameters: &ReserveParameters,
+        reservation_currency_code: CurrencyCode,
     ) -> (Amount, Amount) {
+        let commission_rate = self.get_reservation_commission_rate(
+            reserve_parameters.exchange_account_id,
+            &reserve_parameters.symbol,
+            reserve_parameters.order_side,
+            reservation_currency_code,
+        );
+        // commission is paid for whole amount even if it closes existing position
+        let commission = reserve_parameters.amount * commission_rate;
+
         if !reserve_parameters.symbol.is_derivative {
-            return (reserve_parameters.amount, dec!(0));
+            return (reserve_parameters.amount + commission, dec!(0));
         }
 
         let free_amount = self.get_unreserved_position_in_amount_currency_code(
@@ -1551,18 +1640,88 @@ impl BalanceReservationManager {
 
         let taken_free_amount = reserve_parameters.amount - amount_to_pay_for;
 
-        // TODO: use full formula (with fee and etc)
         let leverage = self.get_leverage(
             reserve_parameters.exchange_account_id,
             reserve_parameters.symbol.currency_pair(),
         );
 
+        let margin_rate = self.get_margin_rate(
+            reserve_parameters.exchange_account_id,
+            &reserve_parameters.symbol,
+            leverage,
+        );
+
         (
-            amount_to_pay_for * reserve_parameters.symbol.amount_multiplier / leverage,
+            (amount_to_pay_for * margin_rate + commission)
+                * reserve_parameters.symbol.amount_multiplier,
             taken_free_amount,
         )
     }
 
+    /// Rate of expected commission that is charged from reserved balance. Order role is unknown
+    /// before fill, so the largest of maker and taker fees is used
+    fn get_reservation_commission_rate(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        symbol: &Symbol,
+        side: OrderSide,
+        reservation_currency_code: CurrencyCode,
+    ) -> Decimal {
+        let exchange = self
+            .exchanges_by_id()
+            .get(&exchange_account_id)
+            .with_expect(|| format!("failed to get exchange {exchange_account_id}"));
+        if exchange.exchange_client.get_settings().commission.is_none() {
+            return dec!(0);
+        }
+
+        // commission of derivatives is charged from margin balance
+        let is_charged_from_reservation = symbol.is_derivative
+            || symbol.get_commission_currency_code(side) == reservation_currency_code;
+        if !is_charged_from_reservation {
+            return dec!(0);
+        }
+
+        let commission = &exchange.commission;
+        commission
+            .maker
+            .fee
+            .max(commission.taker.fee)
+            .percent_to_rate()
+    }
+
+    /// Part of position value that is taken from balance: initial margin (`1 / leverage`)
+    /// plus maintenance margin
+    fn get_margin_rate(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        symbol: &Symbol,
+        leverage: Decimal,
+    ) -> Decimal {
+        dec!(1) / leverage + self.get_maintenance_margin_rate(exchange_account_id, symbol, leverage)
+    }
+
+    fn get_maintenance_margin_rate(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        symbol: &Symbol,
+        leverage: Decimal,
+    ) -> Decimal {
+        if !symbol.is_derivative {
+            return dec!(0);
+        }
+
+        self.exchanges_by_id()
+            .get(&exchange_account_id)
+            .with_expect(|| format!("failed to get exchange {exchange_account_id}"))
+            .exchange_client
+            .get_settings()
+            .margin
+            .as_ref()
+            .map(|margin| margin.maintenance_margin_rate(leverage))
+            .unwrap_or_default()
+    }
+
     pub fn try_update_reservation_price(
         &mut self,
         reservation_id: ReservationId,
@@ -1602,6 +1761,16 @@ impl BalanceReservationManager {
         let reservation_amount_diff_in_reservation_currency =
             new_rest_amount_in_reservation_currency - not_approved_amount_in_reservation_currency;
 
+        // expected commission is also changed with reserved amount
+        let commission_rate = self.get_reservation_commission_rate(
+            reservation.exchange_account_id,
+            &reservation.symbol,
+            reservation.order_side,
+            reservation.reservation_currency_code,
+        );
+        let reservation_cost_diff_in_reservation_currency =
+            reservation_amount_diff_in_reservation_currency * (dec!(1) + commission_rate);
+
         let old_balance = self
             .try_get_available_balance(
                 reservation.configuration_descriptor,
@@ -1620,7 +1789,7 @@ impl BalanceReservationManager {
                 )
             });
 
-        let new_balance = old_balance - reservation_amount_diff_in_reservation_currency;
+        let new_balance = old_balance - reservation_cost_diff_in_reservation_currency;
         if new_balance < dec!(0) {
             log::info!(
                 "Failed to update reservation {} {} {} {:?} {} {} {} {} {}",
@@ -1,6 +1,6 @@
 use mockall_double::double;
 
-use mmb_domain::exchanges::symbol::Symbol;
+use mmb_domain::exchanges::symbol::{ContractType, Symbol};
 use mmb_domain::order::fill::OrderFill;
 use mmb_domain::order::snapshot::{OrderSide, OrderSnapshot};
 use std::sync::Arc;
@@ -50,6 +50,15 @@ impl BalanceChangesCalculator {
         order_fill: &OrderFill,
         symbol: Arc<Symbol>,
     ) -> BalanceChangesCalculatorResult {
+        if symbol.contract_type == ContractType::Quanto {
+            return Self::get_quanto_balance_changes(
+                configuration_descriptor,
+                order,
+                order_fill,
+                symbol,
+            );
+        }
+
         let price = order_fill.price();
         let filled_amount = order_fill.amount() * symbol.amount_multiplier;
         let commission_amount = order_fill.commission_amount();
@@ -152,4 +161,48 @@ impl BalanceChangesCalculator {
             exchange_account_id.exchange_id,
         )
     }
+
+    /// Quanto contracts are settled in currency outside currency pair, so changes are calculated
+    /// for amount currency and settlement currency instead of base and quote currencies
+    fn get_quanto_balance_changes(
+        configuration_descriptor: ConfigurationDescriptor,
+        order: &OrderSnapshot,
+        order_fill: &OrderFill,
+        symbol: Arc<Symbol>,
+    ) -> BalanceChangesCalculatorResult {
+        let price = order_fill.price();
+        let value = symbol.position_value(order_fill.amount(), price);
+        let filled_amount = order_fill.amount() * symbol.amount_multiplier;
+
+        let (amount_change, settlement_change) = match order.header.side {
+            OrderSide::Buy => (filled_amount, -value),
+            OrderSide::Sell => (-filled_amount, value),
+        };
+
+        let exchange_account_id = order.header.exchange_account_id;
+        let settlement_currency_code = symbol.settlement_currency_code();
+        let balance_request = |currency_code| {
+            BalanceRequest::new(
+                configuration_descriptor,
+                exchange_account_id,
+                symbol.currency_pair(),
+                currency_code,
+            )
+        };
+
+        let mut res_balance_changes = ServiceValueTree::default();
+        res_balance_changes
+            .set_by_balance_request(&balance_request(symbol.amount_currency_code), amount_change);
+        res_balance_changes.set_by_balance_request(
+            &balance_request(settlement_currency_code),
+            settlement_change - order_fill.commission_amount(),
+        );
+
+        BalanceChangesCalculatorResult::new(
+            res_balance_changes,
+            settlement_currency_code,
+            price,
+            exchange_account_id.exchange_id,
+        )
+    }
 }
@@ -3,6 +3,7 @@ use std::fmt::{Display, Formatter};
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use mmb_database::impl_event;
+use mmb_database::postgres_db::events::EventPriority;
 use mmb_domain::market::{CurrencyCode, ExchangeId, MarketAccountId};
 use mmb_domain::order::snapshot::ClientOrderFillId;
 use mmb_domain::order::snapshot::{Amount, Price};
@@ -32,7 +33,7 @@ pub(crate) struct ProfitLossBalanceChange {
     pub usd_balance_change: Amount,
 }
 
-impl_event!(ProfitLossBalanceChange, "profit_loss_balance_changes");
+impl_event!(ProfitLossBalanceChange, "profit_loss_balance_changes", EventPriority::Critical);
 
 impl ProfitLossBalanceChange {
     pub fn new(
@@ -81,6 +81,7 @@ impl ProfitLossStopper {
                 &self.target_market_account_id,
                 self.balance_manager.clone(),
                 self.engine_api.clone(),
+                None,
                 cancellation_token,
             );
 
@@ -10,6 +10,7 @@ use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairTo
 use crate::explanation::Explanation;
 use crate::misc::reserve_parameters::ReserveParameters;
 use crate::misc::service_value_tree::ServiceValueTree;
+use crate::pnl_ledger::PnlLedger;
 use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
 use mmb_domain::events::ExchangeBalancesAndPositions;
 use mmb_domain::exchanges::symbol::{BeforeAfter, Symbol};
@@ -37,6 +38,7 @@ use crate::database::events::recorder::EventRecorder;
 #[cfg(test)]
 use crate::MOCK_MUTEX;
 use mmb_database::impl_event;
+use mmb_database::postgres_db::events::EventPriority;
 use mmb_domain::order::snapshot::Price;
 use mmb_utils::cancellation_token::CancellationToken;
 #[cfg(test)]
@@ -49,6 +51,7 @@ pub struct BalanceManager {
     balance_reservation_manager: BalanceReservationManager,
     last_order_fills: HashMap<MarketAccountId, OrderFill>,
     balance_changes_service: Option<Arc<BalanceChangesService>>,
+    pnl_ledger: Option<Arc<PnlLedger>>,
     position_differs_times_in_row_by_exchange_id:
         HashMap<ExchangeAccountId, HashMap<CurrencyPair, u32>>,
     event_recorder: Option<Arc<EventRecorder>>,
@@ -61,7 +64,7 @@ struct BalanceUpdateEvent {
     whole_balance_after: HashMap<ExchangeAccountId, HashMap<CurrencyCode, Amount>>,
 }
 
-impl_event!(BalanceUpdateEvent, "balance_updates");
+impl_event!(BalanceUpdateEvent, "balance_updates", EventPriority::Critical);
 
 impl BalanceManager {
     pub fn new(
@@ -75,6 +78,7 @@ impl BalanceManager {
             ),
             last_order_fills: HashMap::new(),
             balance_changes_service: None,
+            pnl_ledger: None,
             position_differs_times_in_row_by_exchange_id: Default::default(),
             event_recorder,
         }))
@@ -172,6 +176,35 @@ impl BalanceManager {
         Ok(())
     }
 
+    /// Release the rest of reservation shared by linked orders (OCO order legs) when all of them are finished.
+    /// The reservation is approved only for the order with specified `client_order_id`
+    pub fn release_linked_orders_reservation(
+        &mut self,
+        reservation_id: ReservationId,
+        client_order_id: &ClientOrderId,
+    ) -> Result<()> {
+        let approved_amount = match self.get_reservation(reservation_id) {
+            None => return Ok(()),
+            Some(reservation) => reservation
+                .approved_parts
+                .get(client_order_id)
+                .filter(|x| !x.is_canceled)
+                .map(|x| x.unreserved_amount),
+        };
+
+        if let Some(amount) = approved_amount {
+            if !amount.is_zero() {
+                self.unreserve_by_client_order_id(reservation_id, client_order_id.clone(), amount)?;
+            }
+        }
+
+        if self.get_reservation(reservation_id).is_some() {
+            self.unreserve_rest(reservation_id)?;
+        }
+
+        Ok(())
+    }
+
     fn save_balances(&mut self) {
         match &self.event_recorder {
             None => {}
@@ -344,11 +377,26 @@ impl BalanceManager {
             .cloned()
             .collect();
 
-        let mut filtered_exchange_balances: HashMap<_, _> = balances_and_positions
-            .balances
-            .iter()
-            .filter(|x| !x.balance.is_zero() || currencies.contains(&x.currency_code)) //We skip currencies with zero balances if they are not part of Exchange currency pairs
-            .map(|x| (x.currency_code, x.balance))
+        // Liabilities of margin account reduce amounts which can be reserved for orders
+        let exchange_balances = match &balances_and_positions.margin_account {
+            Some(margin_account) => margin_account
+                .balances
+                .iter()
+                .map(|x| (x.currency_code, x.available_for_orders()))
+                .collect_vec(),
+            None => balances_and_positions
+                .balances
+                .iter()
+                .map(|x| (x.currency_code, x.balance))
+                .collect_vec(),
+        };
+
+        let mut filtered_exchange_balances: HashMap<_, _> = exchange_balances
+            .into_iter()
+            //We skip currencies with zero balances if they are not part of Exchange currency pairs
+            .filter(|(currency_code, balance)| {
+                !balance.is_zero() || currencies.contains(currency_code)
+            })
             .collect();
 
         for currency in currencies {
@@ -389,6 +437,33 @@ impl BalanceManager {
         Ok(())
     }
 
+    /// Balances that are expected on exchange according to internal model: last received
+    /// exchange balances with reserved amounts and virtual diffs of fills received after that
+    pub(crate) fn get_expected_exchange_balances(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+    ) -> Result<HashMap<CurrencyCode, Amount>> {
+        let mut expected_balances = self
+            .calculate_whole_balances()?
+            .remove(&exchange_account_id)
+            .unwrap_or_default();
+
+        let balance_diffs = self
+            .balance_reservation_manager
+            .virtual_balance_holder
+            .get_virtual_balance_diffs()
+            .get_as_balances();
+        for (balance_request, diff) in balance_diffs {
+            if balance_request.exchange_account_id == exchange_account_id {
+                *expected_balances
+                    .entry(balance_request.currency_code)
+                    .or_default() += diff;
+            }
+        }
+
+        Ok(expected_balances)
+    }
+
     fn calculate_whole_balances(
         &self,
     ) -> Result<HashMap<ExchangeAccountId, HashMap<CurrencyCode, Amount>>> {
@@ -557,12 +632,21 @@ impl BalanceManager {
         self.handle_order_fill(
             configuration_descriptor,
             exchange_account_id,
-            symbol,
+            symbol.clone(),
             order_snapshot,
             order_fill,
         );
         self.save_balances();
 
+        if let Some(pnl_ledger) = &self.pnl_ledger {
+            pnl_ledger.add_fill(
+                configuration_descriptor,
+                &symbol,
+                order_snapshot,
+                order_fill,
+            );
+        }
+
         if let Some(balance_changes_service) = &self.balance_changes_service {
             balance_changes_service.add_balance_change(
                 configuration_descriptor,
@@ -764,6 +848,17 @@ impl BalanceManager {
         true
     }
 
+    pub fn move_reservation(
+        &mut self,
+        reservation_id: ReservationId,
+        configuration_descriptor: ConfigurationDescriptor,
+    ) -> Result<()> {
+        self.balance_reservation_manager
+            .move_reservation(reservation_id, configuration_descriptor)?;
+        self.save_balances();
+        Ok(())
+    }
+
     pub fn try_update_reservation(
         &mut self,
         reservation_id: ReservationId,
@@ -984,6 +1079,10 @@ impl BalanceManager {
         self.balance_changes_service = Some(service);
     }
 
+    pub fn set_pnl_ledger(&mut self, pnl_ledger: Arc<PnlLedger>) {
+        self.pnl_ledger = Some(pnl_ledger);
+    }
+
     pub async fn update_balances_for_exchanges(
         this: Arc<Mutex<Self>>,
         cancellation_token: CancellationToken,
@@ -1061,6 +1160,36 @@ impl BalanceManager {
         self.balance_reservation_manager
             .get_position(exchange_account_id, currency_pair, side)
     }
+
+    pub fn get_liquidation_price(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        symbol: &Symbol,
+        side: OrderSide,
+        entry_price: Price,
+    ) -> Option<Price> {
+        self.balance_reservation_manager.get_liquidation_price(
+            exchange_account_id,
+            symbol,
+            side,
+            entry_price,
+        )
+    }
+
+    pub fn get_liquidation_distance(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        symbol: &Symbol,
+        entry_price: Price,
+        mark_price: Price,
+    ) -> Option<Amount> {
+        self.balance_reservation_manager.get_liquidation_distance(
+            exchange_account_id,
+            symbol,
+            entry_price,
+            mark_price,
+        )
+    }
 }
 
 impl_mock_initializer!(MockBalanceManager);
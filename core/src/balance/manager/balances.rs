@@ -13,6 +13,7 @@ use mmb_domain::order::snapshot::ReservationId;
 use serde::Serialize;
 
 use mmb_database::impl_event;
+use mmb_database::postgres_db::events::EventPriority;
 use mmb_utils::DateTime;
 use rust_decimal::Decimal;
 
@@ -62,4 +63,4 @@ impl Balances {
         1
     }
 }
-impl_event!(Balances, "balances");
+impl_event!(Balances, "balances", EventPriority::Critical);
@@ -82,6 +82,7 @@ impl BalanceManagerBase {
                         })
                         .collect(),
                     positions: None,
+                    margin_account: None,
                 },
             )
             .expect("failed to update exchange balance");
@@ -114,6 +115,7 @@ impl BalanceManagerBase {
                 &ExchangeBalancesAndPositions {
                     balances,
                     positions,
+                    margin_account: None,
                 },
             )
             .expect("failed to update exchange balance");
@@ -18,9 +18,11 @@ use crate::balance::manager::tests::balance_manager_base::BalanceManagerBase;
 use crate::{
     balance::manager::balance_manager::BalanceManager,
     exchanges::general::{
-        currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter, exchange::Exchange,
-        test_helper::get_test_exchange_with_symbol_and_id,
+        currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter,
+        exchange::Exchange,
+        test_helper::{get_test_exchange_with_settings, get_test_exchange_with_symbol_and_id},
     },
+    settings::ExchangeSettings,
 };
 
 pub struct BalanceManagerDerivative {
@@ -54,13 +56,14 @@ impl BalanceManagerDerivative {
     #[allow(clippy::type_complexity)]
     fn create_balance_manager(
         is_reversed: bool,
+        settings: ExchangeSettings,
     ) -> (
         Arc<Symbol>,
         Arc<Mutex<BalanceManager>>,
         HashMap<ExchangeAccountId, Arc<Exchange>>,
     ) {
         let (symbol, exchanges_by_id) =
-            BalanceManagerDerivative::create_balance_manager_ctor_parameters(is_reversed);
+            BalanceManagerDerivative::create_balance_manager_ctor_parameters(is_reversed, settings);
         let currency_pair_to_symbol_converter =
             CurrencyPairToSymbolConverter::new(exchanges_by_id.clone());
 
@@ -70,6 +73,7 @@ impl BalanceManagerDerivative {
 
     fn create_balance_manager_ctor_parameters(
         is_reversed: bool,
+        settings: ExchangeSettings,
     ) -> (Arc<Symbol>, HashMap<ExchangeAccountId, Arc<Exchange>>) {
         let base = BalanceManagerBase::eth();
         let quote = BalanceManagerBase::btc();
@@ -100,9 +104,10 @@ impl BalanceManagerDerivative {
             symbol.amount_multiplier = dec!(0.001);
         }
         let symbol = Arc::from(symbol);
-        let exchange_1 = get_test_exchange_with_symbol_and_id(
+        let exchange_1 = get_test_exchange_with_settings(
             symbol.clone(),
             ExchangeAccountId::new(BalanceManagerBase::exchange_id().as_str(), 0),
+            settings,
         )
         .0;
         let exchange_2 = get_test_exchange_with_symbol_and_id(
@@ -120,8 +125,12 @@ impl BalanceManagerDerivative {
     }
 
     fn new(is_reversed: bool) -> Self {
+        Self::new_with_settings(is_reversed, ExchangeSettings::default())
+    }
+
+    fn new_with_settings(is_reversed: bool, settings: ExchangeSettings) -> Self {
         let (symbol, balance_manager, exchanges_by_id) =
-            BalanceManagerDerivative::create_balance_manager(is_reversed);
+            BalanceManagerDerivative::create_balance_manager(is_reversed, settings);
         let mut balance_manager_base = BalanceManagerBase::new();
         balance_manager_base.set_balance_manager(balance_manager);
         balance_manager_base.set_symbol(symbol);
@@ -233,12 +242,14 @@ mod tests {
     use crate::balance::manager::tests::balance_manager_base::BalanceManagerBase;
     use crate::explanation::Explanation;
     use crate::infrastructure::init_lifetime_manager;
+    use mmb_domain::exchanges::symbol::ContractType;
     use mmb_domain::market::CurrencyCode;
 
     use mmb_domain::order::pool::OrdersPool;
     use mmb_domain::order::snapshot::{OrderSide, OrderStatus, ReservationId};
 
     use super::BalanceManagerDerivative;
+    use crate::settings::{CommissionSettings, ExchangeSettings, MarginSettings, MarginTier};
 
     fn create_eth_btc_test_obj(
         btc_amount: Amount,
@@ -4387,4 +4398,185 @@ mod tests {
             .try_reserve(&reserve_parameters, &mut None,)
             .is_none());
     }
+
+    #[rstest]
+    #[case(ContractType::Linear, dec!(0.076))]
+    #[case(ContractType::Inverse, dec!(1.9))]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn liquidation_distance_of_filled_position(
+        #[case] contract_type: ContractType,
+        #[case] expected_distance: Amount,
+    ) {
+        init_logger();
+        let is_reversed = false;
+        let mut test_object =
+            create_test_obj_by_currency_code(BalanceManagerBase::eth(), dec!(100), is_reversed);
+
+        let exchange_account_id = test_object.balance_manager_base.exchange_account_id_1;
+        let mut symbol = (*test_object.balance_manager_base.symbol()).clone();
+        symbol.contract_type = contract_type;
+        test_object
+            .exchanges_by_id
+            .get_mut(&exchange_account_id)
+            .expect("in test")
+            .leverage_by_currency_pair
+            .insert(symbol.currency_pair(), dec!(5));
+
+        let price = BalanceManagerDerivative::price();
+        assert_eq!(
+            test_object.balance_manager().get_liquidation_distance(
+                exchange_account_id,
+                &symbol,
+                price,
+                price
+            ),
+            None
+        );
+
+        test_object.fill_order(OrderSide::Buy, None, None, is_reversed);
+
+        // whole initial margin is lost at liquidation price
+        let distance = test_object
+            .balance_manager()
+            .get_liquidation_distance(exchange_account_id, &symbol, price, price)
+            .expect("in test");
+        assert_eq!(distance.round_dp(8), expected_distance);
+
+        let liquidation_price = test_object
+            .balance_manager()
+            .get_liquidation_price(exchange_account_id, &symbol, OrderSide::Buy, price)
+            .expect("in test");
+        assert_eq!(
+            test_object
+                .balance_manager()
+                .get_liquidation_distance(exchange_account_id, &symbol, price, liquidation_price)
+                .expect("in test")
+                .round_dp(8),
+            dec!(0)
+        );
+    }
+
+    fn create_test_obj_with_commission_and_margin(
+        maker_fee: Decimal,
+        taker_fee: Decimal,
+        leverage: Decimal,
+    ) -> BalanceManagerDerivative {
+        init_lifetime_manager();
+
+        let settings = ExchangeSettings {
+            commission: Some(CommissionSettings {
+                maker_fee,
+                taker_fee,
+            }),
+            margin: Some(MarginSettings {
+                tiers: vec![
+                    MarginTier {
+                        max_leverage: dec!(20),
+                        maintenance_margin_rate: dec!(0.005),
+                    },
+                    MarginTier {
+                        max_leverage: dec!(50),
+                        maintenance_margin_rate: dec!(0.01),
+                    },
+                ],
+            }),
+            ..Default::default()
+        };
+        let mut test_object = BalanceManagerDerivative::new_with_settings(false, settings);
+
+        let exchange_account_id = test_object.balance_manager_base.exchange_account_id_1;
+        BalanceManagerBase::update_balance(
+            &mut test_object.balance_manager(),
+            exchange_account_id,
+            hashmap![BalanceManagerBase::eth() => dec!(100)],
+        );
+
+        let symbol = test_object.balance_manager_base.symbol();
+        test_object
+            .exchanges_by_id
+            .get_mut(&exchange_account_id)
+            .expect("in test")
+            .leverage_by_currency_pair
+            .insert(symbol.currency_pair(), leverage);
+
+        test_object
+    }
+
+    #[rstest]
+    #[case::taker_fee_is_larger(dec!(0.02), dec!(0.04), dec!(5), dec!(0.2) + dec!(0.005))]
+    #[case::maker_fee_is_larger(dec!(0.04), dec!(0.02), dec!(5), dec!(0.2) + dec!(0.005))]
+    #[case::max_leverage_of_first_tier(dec!(0.02), dec!(0.04), dec!(20), dec!(0.05) + dec!(0.005))]
+    #[case::min_leverage_of_second_tier(dec!(0.02), dec!(0.04), dec!(25), dec!(0.04) + dec!(0.01))]
+    #[case::max_leverage_of_second_tier(dec!(0.02), dec!(0.04), dec!(50), dec!(0.02) + dec!(0.01))]
+    #[case::leverage_above_all_tiers(dec!(0.02), dec!(0.04), dec!(100), dec!(0.01))]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn reservation_cost_includes_commission_and_margin(
+        #[case] maker_fee: Decimal,
+        #[case] taker_fee: Decimal,
+        #[case] leverage: Decimal,
+        #[case] expected_margin_rate: Decimal,
+    ) {
+        init_logger();
+        let test_object =
+            create_test_obj_with_commission_and_margin(maker_fee, taker_fee, leverage);
+
+        let amount = dec!(2);
+        let reserve_parameters = test_object.balance_manager_base.create_reserve_parameters(
+            OrderSide::Buy,
+            BalanceManagerDerivative::price(),
+            amount,
+        );
+        let reservation_id = test_object
+            .balance_manager()
+            .try_reserve(&reserve_parameters, &mut None)
+            .expect("in test");
+
+        // largest of maker and taker fees is reserved because order role is unknown
+        let commission_rate = dec!(0.0004);
+        assert_eq!(
+            test_object
+                .balance_manager()
+                .get_reservation_expected(reservation_id)
+                .cost,
+            amount * (expected_margin_rate + commission_rate)
+        );
+    }
+
+    #[rstest]
+    #[case::first_tier(dec!(5))]
+    #[case::second_tier(dec!(25))]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn leveraged_balance_is_enough_for_reservation_of_its_amount(
+        #[case] leverage: Decimal,
+    ) {
+        init_logger();
+        let test_object =
+            create_test_obj_with_commission_and_margin(dec!(0.02), dec!(0.04), leverage);
+
+        let leveraged_balance = test_object
+            .balance_manager()
+            .get_leveraged_balance_in_amount_currency_code(
+                test_object.balance_manager_base.configuration_descriptor,
+                OrderSide::Buy,
+                test_object.balance_manager_base.exchange_account_id_1,
+                test_object.balance_manager_base.symbol(),
+                BalanceManagerDerivative::price(),
+                &mut None,
+            )
+            .expect("in test");
+
+        let can_reserve = |amount: Amount| {
+            let reserve_parameters = test_object.balance_manager_base.create_reserve_parameters(
+                OrderSide::Buy,
+                BalanceManagerDerivative::price(),
+                amount,
+            );
+            test_object
+                .balance_manager()
+                .can_reserve(&reserve_parameters, &mut None)
+        };
+
+        assert!(can_reserve(leveraged_balance * dec!(0.99)));
+        assert!(!can_reserve(leveraged_balance * dec!(1.01)));
+    }
 }
@@ -144,6 +144,9 @@ mod tests {
     use std::time::Duration;
 
     use chrono::Utc;
+    use mmb_domain::events::{
+        ExchangeBalance, ExchangeBalancesAndPositions, MarginAccount, MarginBalance,
+    };
     use mmb_domain::market::CurrencyCode;
     use mmb_domain::order::snapshot::{Amount, Price};
     use mmb_utils::hashmap;
@@ -158,6 +161,7 @@ mod tests {
     use crate::balance::manager::tests::balance_manager_base::BalanceManagerBase;
     use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
     use crate::misc::reserve_parameters::ReserveParameters;
+    use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
     use mmb_domain::exchanges::symbol::{Precision, Symbol};
     use mmb_domain::market::{ExchangeAccountId, MarketAccountId};
     use mmb_domain::order::pool::OrdersPool;
@@ -349,6 +353,65 @@ mod tests {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn update_exchange_balance_subtracts_margin_liabilities() {
+        init_logger();
+        let test_object = BalanceManagerOrdinal::new();
+
+        let exchange_account_id = test_object.balance_manager_base.exchange_account_id_1;
+        let btc = BalanceManagerBase::btc();
+        let eth = BalanceManagerBase::eth();
+
+        let margin_balance =
+            |currency_code, free, borrowed, interest, max_borrowable| MarginBalance {
+                currency_code,
+                free,
+                locked: dec!(0),
+                borrowed,
+                interest,
+                max_borrowable,
+            };
+        let margin_account = MarginAccount {
+            balances: vec![
+                margin_balance(btc, dec!(2), dec!(0.5), dec!(0.01), dec!(1)),
+                margin_balance(eth, dec!(1), dec!(3), dec!(0.1), dec!(0)),
+            ],
+            margin_level: dec!(1.8),
+        };
+
+        let balance_manager = &mut test_object.balance_manager();
+        balance_manager
+            .update_exchange_balance(
+                exchange_account_id,
+                &ExchangeBalancesAndPositions {
+                    balances: vec![
+                        ExchangeBalance {
+                            currency_code: btc,
+                            balance: dec!(3),
+                        },
+                        ExchangeBalance {
+                            currency_code: eth,
+                            balance: dec!(1),
+                        },
+                    ],
+                    positions: None,
+                    margin_account: Some(margin_account),
+                },
+            )
+            .expect("failed to update exchange balance");
+
+        let symbol = test_object.balance_manager_base.symbol();
+        assert_eq!(
+            balance_manager.get_exchange_balance(exchange_account_id, symbol.clone(), btc),
+            Some(dec!(2) + dec!(1) - dec!(0.5) - dec!(0.01))
+        );
+        // Liabilities exceed free and borrowable amounts, so nothing can be reserved
+        assert_eq!(
+            balance_manager.get_exchange_balance(exchange_account_id, symbol, eth),
+            Some(dec!(0))
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     pub async fn get_balance_buy_returns_quote_balance_and_currency_code() {
         init_logger();
@@ -1535,6 +1598,61 @@ mod tests {
             .is_none());
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn move_reservation_to_other_configuration_descriptor() {
+        init_logger();
+        let test_object = create_eth_btc_test_obj(dec!(5), dec!(5));
+
+        let src_reserve_parameters = test_object.balance_manager_base.create_reserve_parameters(
+            OrderSide::Sell,
+            dec!(0.2),
+            dec!(2),
+        );
+        let reservation_id = test_object
+            .balance_manager()
+            .try_reserve(&src_reserve_parameters, &mut None)
+            .expect("in test");
+
+        let configuration_descriptor = ConfigurationDescriptor::new(
+            "OtherStrategy".into(),
+            src_reserve_parameters
+                .configuration_descriptor
+                .service_configuration_key,
+        );
+        let mut dst_reserve_parameters = src_reserve_parameters.clone();
+        dst_reserve_parameters.configuration_descriptor = configuration_descriptor;
+        let dst_balance_before = test_object
+            .balance_manager()
+            .get_balance_by_reserve_parameters(&dst_reserve_parameters)
+            .expect("in test");
+
+        test_object
+            .balance_manager()
+            .move_reservation(reservation_id, configuration_descriptor)
+            .expect("in test");
+
+        assert_eq!(
+            test_object
+                .balance_manager()
+                .get_balance_by_reserve_parameters(&src_reserve_parameters),
+            Some(dec!(5))
+        );
+        assert_eq!(
+            test_object
+                .balance_manager()
+                .get_balance_by_reserve_parameters(&dst_reserve_parameters),
+            Some(dst_balance_before - dec!(2))
+        );
+
+        let balance_manager = test_object.balance_manager();
+        let reservation = balance_manager.get_reservation_expected(reservation_id);
+        assert_eq!(
+            reservation.configuration_descriptor,
+            configuration_descriptor
+        );
+        assert_eq!(reservation.unreserved_amount, dec!(2));
+    }
+
     #[rstest]
     #[case(dec!(5), dec!(0.2), dec!(3), dec!(0.5), dec!(2) ,dec!(2) )]
     #[case(dec!(5), dec!(0.2), dec!(3), dec!(0.2), dec!(2) ,dec!(2) )]
@@ -25,6 +25,8 @@ pub type Result<T> = std::result::Result<T, ConnectivityError>;
 pub enum WebSocketRole {
     Main,
     Secondary,
+    /// Separate connection for market data of a part of traded currency pairs, contains index of the connection
+    MarketData(usize),
 }
 
 impl Display for WebSocketRole {
@@ -32,6 +34,7 @@ impl Display for WebSocketRole {
         match self {
             WebSocketRole::Main => write!(f, "Main"),
             WebSocketRole::Secondary => write!(f, "Secondary"),
+            WebSocketRole::MarketData(index) => write!(f, "MarketData{index}"),
         }
     }
 }
@@ -48,3 +51,4 @@ impl WebSocketParams {
 }
 
 pub use websocket::{websocket_open, WsSender};
+pub use websocket_connection::{run_reconnecting_connection, ReconnectingConnectionHandler};
@@ -19,7 +19,7 @@ pub struct WsSender {
 
 /// Websocket send end wrapper
 impl WsSender {
-    /// Send to main websocket
+    /// Send to main websocket or to the only websocket of single connection
     pub fn send_main(&self, msg: String) -> Result<()> {
         self.main_sender
             .send(Message::Text(msg))
@@ -45,7 +45,7 @@ pub async fn websocket_open(
     let ret = if let Some(secondary) = secondary {
         connect_both_parallel(main, secondary, exchange_account_id).await?
     } else {
-        connect_only_main(main, exchange_account_id).await?
+        connect_single(WebSocketRole::Main, main, exchange_account_id).await?
     };
     log::trace!("Websocket '{}' connected", exchange_account_id);
     Ok(ret)
@@ -121,19 +121,14 @@ async fn combined_channel_reader(
     }
 }
 
-/// Connect single (main) socket
-async fn connect_only_main(
+/// Connect single socket, messages to it are sent via `WsSender::send_main`
+pub(super) async fn connect_single(
+    role: WebSocketRole,
     params: WebSocketParams,
     exchange_account_id: ExchangeAccountId,
 ) -> Result<(WsSender, mpsc::UnboundedReceiver<String>)> {
     let cancel = CancellationToken::new();
-    let (tx, rx) = open_connection(
-        exchange_account_id,
-        WebSocketRole::Main,
-        params,
-        cancel.clone(),
-    )
-    .await?;
+    let (tx, rx) = open_connection(exchange_account_id, role, params, cancel.clone()).await?;
     let sender = WsSender {
         main_sender: tx,
         secondary_sender: None,
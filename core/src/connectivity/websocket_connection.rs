@@ -1,5 +1,7 @@
+use super::websocket::{connect_single, WsSender};
 use super::{ConnectivityError, Result, WebSocketParams, WebSocketRole};
 use crate::infrastructure::spawn_future_ok;
+use async_trait::async_trait;
 use futures::stream::{SplitSink, SplitStream};
 use futures::{SinkExt, StreamExt};
 use mmb_domain::market::ExchangeAccountId;
@@ -7,7 +9,7 @@ use mmb_utils::infrastructure::SpawnFutureFlags;
 use std::fmt::Formatter;
 use tokio::net::TcpStream;
 use tokio::sync::mpsc;
-use tokio::time::{timeout, timeout_at, Duration, Instant};
+use tokio::time::{sleep, timeout, timeout_at, Duration, Instant};
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 use tokio_util::sync::CancellationToken;
@@ -25,6 +27,9 @@ const WRITE_DEADLINE_TIMEOUT: Duration = Duration::from_secs(1);
 
 const PING_MESSAGE: &[u8; 9] = b"heartbeat";
 
+/// Time interval before reopening of connection which reconnects by itself
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
 type TrySendResult = std::result::Result<(), mpsc::error::TrySendError<Message>>;
 
 /// Compound log records key
@@ -347,3 +352,66 @@ pub async fn open_connection(
 
     Ok((writer_tx, reader_rx))
 }
+
+/// Callbacks of connection which is reopened by itself, see [`run_reconnecting_connection`]
+#[async_trait]
+pub trait ReconnectingConnectionHandler: Send + Sync {
+    /// Params are requested before each connection attempt because they can be changed since
+    /// the previous connection. `None` means that connection isn't needed anymore
+    async fn get_params(&self) -> Option<Result<WebSocketParams>>;
+    fn on_connected(&self, sender: WsSender);
+    fn on_message(&self, msg: &str);
+    /// Sender received in `on_connected` should be dropped here
+    fn on_disconnected(&self);
+}
+
+/// Keep single WebSocket connection open until cancellation is requested.
+///
+/// Connection has its own heartbeat like any other and it's reopened after `RECONNECT_INTERVAL`
+/// when it's closed, so a slow or lost connection doesn't affect other connections of exchange.
+pub async fn run_reconnecting_connection(
+    exchange_account_id: ExchangeAccountId,
+    role: WebSocketRole,
+    handler: impl ReconnectingConnectionHandler,
+    cancel: CancellationToken,
+) {
+    let meta = Meta(exchange_account_id, role);
+
+    while !cancel.is_cancelled() {
+        match handler.get_params().await {
+            None => break,
+            Some(Err(e)) => log::error!("Websocket {meta} failed to get params: {e}"),
+            Some(Ok(params)) => match connect_single(role, params, exchange_account_id).await {
+                Ok((sender, mut reader)) => {
+                    log::info!("Websocket {meta} connected");
+                    handler.on_connected(sender);
+
+                    loop {
+                        let msg = tokio::select! {
+                            biased;
+                            _ = cancel.cancelled() => None,
+                            msg = reader.recv() => msg,
+                        };
+
+                        match msg {
+                            Some(msg) => handler.on_message(&msg),
+                            None => break,
+                        }
+                    }
+
+                    // connection is closed when sender is dropped
+                    handler.on_disconnected();
+                    log::info!("Websocket {meta} disconnected");
+                }
+                Err(e) => log::error!("Websocket {meta} failed to connect: {e}"),
+            },
+        }
+
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = sleep(RECONNECT_INTERVAL) => log::debug!("Websocket {meta} reconnecting"),
+        }
+    }
+
+    log::debug!("Websocket {meta} reconnecting loop finished");
+}
@@ -1,22 +1,25 @@
-use crate::database::events::recorder::save_batch;
+use crate::database::events::recorder::sinks::postgres::save_batch;
 use crate::exchanges::timeouts::timeout_manager;
 use anyhow::{Context, Result};
 use itertools::Itertools;
 use mmb_database::postgres_db::events::InsertEvent;
+use mmb_database::postgres_db::postponed_events::{
+    PostponedEventsFileFormat, NOT_FINISHED_POSTPONED_EVENTS_FILE_PREFIX,
+    POSTPONED_EVENTS_FILE_PREFIX,
+};
 use mmb_database::postgres_db::PgPool;
 use mmb_utils::{nothing_to_do, DateTime};
-use serde::{Deserialize, Serialize};
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 use std::fs::{create_dir_all, DirEntry, File};
-use std::io::{BufReader, BufWriter};
+use std::future::Future;
+use std::io::BufWriter;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::{env, fs};
 use tokio::task::spawn_blocking;
 
 const BUFFER_SIZE: usize = 16384;
-const EVENTS_FILE_PREFIX: &str = "events_";
-const NOT_FINISHED_FILED_PREFIX: &str = "writing_yet_";
 
 fn get_postponed_events_dir(
     postponed_events_dir_from_settings: Option<PathBuf>,
@@ -36,6 +39,25 @@ fn get_postponed_events_dir(
     Ok(path)
 }
 
+/// Events spilled because of EventRecorder overflow are kept apart from events postponed by
+/// Postgres sink, because they are restored to all sinks by EventRecorder
+pub(crate) fn get_overflow_events_dir(
+    postponed_events_dir_from_settings: Option<PathBuf>,
+) -> Result<PathBuf> {
+    const OVERFLOW_EVENTS_FOLDER: &str = "overflow";
+
+    Ok(get_postponed_events_dir(postponed_events_dir_from_settings)?.join(OVERFLOW_EVENTS_FOLDER))
+}
+
+/// Events postponed by sinks other than Postgres are kept in separate dirs,
+/// because they are resaved only to the sink that failed to save them
+pub(crate) fn get_sink_postponed_events_dir(
+    postponed_events_dir_from_settings: Option<PathBuf>,
+    sink_name: &str,
+) -> Result<PathBuf> {
+    Ok(get_postponed_events_dir(postponed_events_dir_from_settings)?.join(sink_name))
+}
+
 fn init_postponed_events_dir(
     postponed_events_dir_from_settings: Option<PathBuf>,
 ) -> Result<Arc<Path>> {
@@ -46,23 +68,6 @@ fn init_postponed_events_dir(
     Ok(path)
 }
 
-#[derive(Debug, Deserialize, Serialize, Eq, PartialEq)]
-struct PostponedEventsFileFormat {
-    version: u32,
-    table_name: String,
-    events: Vec<InsertEvent>,
-}
-
-impl PostponedEventsFileFormat {
-    pub fn new(table_name: String, events: Vec<InsertEvent>) -> Self {
-        Self {
-            version: 1,
-            table_name,
-            events,
-        }
-    }
-}
-
 #[derive(Debug, Clone)]
 pub(crate) struct EventRecorderFallback {
     postponed_events_dir: Arc<Path>,
@@ -129,6 +134,61 @@ impl EventRecorderFallback {
         .await?
     }
 
+    pub(crate) async fn load_postponed_events_file(
+        &self,
+        file_name: &OsStr,
+    ) -> Result<PostponedEventsFileFormat> {
+        load_from_file(self.postponed_events_dir.join(file_name)).await
+    }
+
+    pub(crate) async fn remove_postponed_events_file(&self, file_name: &OsStr) -> Result<()> {
+        let file_path = self.postponed_events_dir.join(file_name);
+        tokio::fs::remove_file(&file_path)
+            .await
+            .with_context(|| format!("failed removing file {}", file_path.display()))
+    }
+
+    /// Resaves postponed events with `save` and removes files of resaved events.
+    /// Restoring is stopped on first failure, so the rest of files are resaved next time
+    pub(crate) async fn try_restore_postponed_events<F, Fut>(&self, save: F)
+    where
+        F: Fn(String, Vec<InsertEvent>) -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        let mut file_names = match self.get_existing_postponed_events_file_names().await {
+            Ok(file_names) => file_names,
+            Err(err) => {
+                log::error!("failed getting postponed events files with error: {err:?}");
+                return;
+            }
+        };
+        file_names.sort();
+
+        for file_name in file_names {
+            let PostponedEventsFileFormat {
+                table_name, events, ..
+            } = match self.load_postponed_events_file(&file_name).await {
+                Ok(file) => file,
+                Err(err) => {
+                    let file_name = file_name.to_string_lossy();
+                    log::error!(
+                        "failed load postponed events file {file_name} with error: {err:?}"
+                    );
+                    continue;
+                }
+            };
+
+            if let Err(err) = save(table_name, events).await {
+                log::error!("failed resaving postponed events: {err:?}");
+                return;
+            }
+
+            if let Err(err) = self.remove_postponed_events_file(&file_name).await {
+                log::error!("failed removing file from postponed events: {err:?}");
+            }
+        }
+    }
+
     pub(crate) async fn try_restore_to_db_postponed_events(
         &self,
         pool: &PgPool,
@@ -167,12 +227,16 @@ struct FileNames {
 
 impl FileNames {
     fn from_date(now: DateTime) -> FileNames {
+        // files can be saved concurrently within the same microsecond, so sequence number makes name unique
+        static FILE_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let sequence = FILE_SEQUENCE.fetch_add(1, Ordering::Relaxed) % 1_000_000;
+
         let formatted_datetime = now.format("%Y.%m.%d_%H.%M.%S.%6f");
         FileNames {
             not_finished: format!(
-                "{NOT_FINISHED_FILED_PREFIX}{EVENTS_FILE_PREFIX}{formatted_datetime}"
+                "{NOT_FINISHED_POSTPONED_EVENTS_FILE_PREFIX}{POSTPONED_EVENTS_FILE_PREFIX}{formatted_datetime}_{sequence:06}"
             ),
-            finished: format!("{EVENTS_FILE_PREFIX}{formatted_datetime}"),
+            finished: format!("{POSTPONED_EVENTS_FILE_PREFIX}{formatted_datetime}_{sequence:06}"),
         }
     }
 }
@@ -197,7 +261,7 @@ fn select_events_file_names(entry: std::io::Result<DirEntry>) -> Option<OsString
         if file_name
             .as_os_str()
             .to_string_lossy()
-            .starts_with(EVENTS_FILE_PREFIX)
+            .starts_with(POSTPONED_EVENTS_FILE_PREFIX)
         {
             return Some(file_name);
         }
@@ -207,26 +271,20 @@ fn select_events_file_names(entry: std::io::Result<DirEntry>) -> Option<OsString
 }
 
 async fn load_from_file(path: PathBuf) -> Result<PostponedEventsFileFormat> {
-    spawn_blocking(move || -> Result<_> {
-        let file = File::open(&path)
-            .with_context(|| format!("can't open postponed events file {}", path.display()))?;
-        let reader = BufReader::with_capacity(BUFFER_SIZE, file);
-        serde_json::from_reader(reader)
-            .with_context(|| format!("can't read postponed events file {}", path.display()))
-    })
-    .await?
+    spawn_blocking(move || PostponedEventsFileFormat::load(&path)).await?
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::database::events::recorder::fallback::{
-        load_from_file, EventRecorderFallback, PostponedEventsFileFormat,
-    };
+    use crate::database::events::recorder::fallback::{load_from_file, EventRecorderFallback};
     use bb8_postgres::bb8::PooledConnection;
     use bb8_postgres::PostgresConnectionManager;
     use chrono::Utc;
     use mmb_database::impl_event;
     use mmb_database::postgres_db::events::{Event, InsertEvent};
+    use mmb_database::postgres_db::postponed_events::{
+        PostponedEventsFileFormat, POSTPONED_EVENTS_FILE_VERSION,
+    };
     use mmb_database::postgres_db::tests::{get_database_url, PgPoolMutex};
     use mmb_utils::DateTime;
     use scopeguard::defer;
@@ -278,10 +336,7 @@ mod tests {
         };
 
         let test_event = test_event();
-        let test_event_data = InsertEvent {
-            version: 0,
-            json: test_event.get_json().expect("in test"),
-        };
+        let test_event_data = InsertEvent::new(0, test_event.get_json().expect("in test"));
 
         let file_names = fallback
             .get_existing_postponed_events_file_names()
@@ -313,7 +368,7 @@ mod tests {
         let file_format = load_from_file(file_path).await.expect("in test");
 
         let expected = PostponedEventsFileFormat {
-            version: 1,
+            version: POSTPONED_EVENTS_FILE_VERSION,
             table_name: TABLE_NAME.to_string(),
             events: vec![test_event_data],
         };
@@ -330,10 +385,7 @@ mod tests {
         };
 
         let test_event = test_event();
-        let test_event_data = InsertEvent {
-            version: 0,
-            json: test_event.get_json().expect("in test"),
-        };
+        let test_event_data = InsertEvent::new(0, test_event.get_json().expect("in test"));
 
         let file_names = fallback
             .get_existing_postponed_events_file_names()
@@ -1,10 +1,18 @@
 mod fallback;
-
-use crate::database::events::recorder::fallback::EventRecorderFallback;
+mod overflow;
+pub mod sinks;
+
+use crate::database::events::recorder::fallback::{get_overflow_events_dir, EventRecorderFallback};
+use crate::database::events::recorder::overflow::EventsOverflow;
+pub use crate::database::events::recorder::overflow::OverflowCounts;
+use crate::database::events::recorder::sinks::postgres::PostgresEventSink;
+use crate::database::events::recorder::sinks::EventSink;
 use crate::infrastructure::spawn_future;
 use anyhow::{bail, Context, Result};
+use futures::future::join_all;
+use itertools::Itertools;
 use mmb_database::postgres_db::events::{
-    save_events_batch, save_events_one_by_one, Event, InsertEvent, TableName,
+    Event, EventPriority, InsertEvent, TableName, TableNameRef,
 };
 use mmb_database::postgres_db::PgPool;
 use mmb_utils::infrastructure::SpawnFutureFlags;
@@ -15,14 +23,17 @@ use std::mem;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::{mpsc, oneshot};
 
 const BATCH_MAX_SIZE: usize = 65_536;
 const BATCH_SIZE_TO_SAVE: usize = 250;
 const SAVING_TIMEOUT: Duration = Duration::from_secs(1);
-
-/// timeout for checking connection health is 5 sec therefore timeout for restoring events should be significant bigger
-const RESTORING_EVENTS_TIMEOUT: Duration = Duration::from_secs(30);
+const CHANNEL_CAPACITY: usize = 20_000;
+/// Part of channel capacity that can be used only by critical events
+const CAPACITY_RESERVED_FOR_CRITICAL_EVENTS: usize = 2_000;
+/// Events spilled to disk are resent to sinks that often, so overload has time to pass
+const RESTORING_OVERFLOWED_EVENTS_TIMEOUT: Duration = Duration::from_secs(30);
 
 pub struct DbSettings {
     pub database_url: String,
@@ -33,71 +44,120 @@ pub struct EventRecorder {
     data_tx: mpsc::Sender<(TableName, InsertEvent)>,
     shutdown_signal_tx: mpsc::UnboundedSender<()>,
     shutdown_rx: Mutex<Option<oneshot::Receiver<Result<()>>>>,
+    overflow: Option<Arc<EventsOverflow>>,
 }
 
 impl EventRecorder {
+    /// Start recording events to Postgres if `pool` is specified
     pub async fn start(
         pool: Option<PgPool>,
         postponed_events_dir: Option<PathBuf>,
     ) -> Result<Arc<EventRecorder>> {
-        let (data_tx, data_rx) = mpsc::channel(20_000);
+        let sinks: Vec<Arc<dyn EventSink>> = match pool {
+            None => vec![],
+            Some(pool) => vec![PostgresEventSink::start(
+                pool,
+                postponed_events_dir.clone(),
+            )?],
+        };
+
+        Self::start_with_sinks(sinks, postponed_events_dir)
+    }
+
+    /// Start recording events to all specified sinks at once.
+    /// Events that don't fit into the channel are spilled to `postponed_events_dir`
+    pub fn start_with_sinks(
+        sinks: Vec<Arc<dyn EventSink>>,
+        postponed_events_dir: Option<PathBuf>,
+    ) -> Result<Arc<EventRecorder>> {
+        let (data_tx, data_rx) = mpsc::channel(CHANNEL_CAPACITY);
         let (shutdown_signal_tx, shutdown_signal_rx) = mpsc::unbounded_channel();
         let (shutdown_tx, shutdown_rx) = oneshot::channel();
 
-        match pool {
-            None => {
-                let _ = shutdown_tx.send(Ok(()));
-                print_info(
-                    "EventRecorder is not started because `database_url` is not set in settings",
-                );
-            }
-            Some(pool) => {
-                let fallback = EventRecorderFallback::new(postponed_events_dir)
-                    .context("failed creation EventRecorderFallback")?;
-
-                let _ = spawn_future(
-                    "start db event recorder",
-                    SpawnFutureFlags::DENY_CANCELLATION | SpawnFutureFlags::STOP_BY_TOKEN,
-                    start_db_event_recorder(
-                        pool.clone(),
-                        data_rx,
-                        shutdown_signal_rx,
-                        shutdown_tx,
-                        fallback.clone(),
-                    ),
-                );
-                let _ = spawn_future(
-                    "start postponed events restoring",
-                    SpawnFutureFlags::DENY_CANCELLATION | SpawnFutureFlags::STOP_BY_TOKEN,
-                    start_postponed_events_restoring(pool, fallback),
-                );
-                print_info("EventRecorder started");
-            }
-        }
+        let overflow = if sinks.is_empty() {
+            let _ = shutdown_tx.send(Ok(()));
+            print_info(
+                "EventRecorder is not started because neither `database_url` nor `event_sinks` are set in settings",
+            );
+
+            None
+        } else {
+            let overflow_events_dir = get_overflow_events_dir(postponed_events_dir)?;
+            let fallback = EventRecorderFallback::new(Some(overflow_events_dir))
+                .context("failed creation EventRecorderFallback for overflowed events")?;
+            let overflow = EventsOverflow::new(fallback);
+
+            let sink_names = sinks.iter().map(|x| x.name()).join(", ");
+
+            let _ = spawn_future(
+                "start overflowed events restoring",
+                SpawnFutureFlags::DENY_CANCELLATION | SpawnFutureFlags::STOP_BY_TOKEN,
+                start_overflowed_events_restoring(sinks.clone(), overflow.clone()),
+            );
+
+            let _ = spawn_future(
+                "start event recorder",
+                SpawnFutureFlags::DENY_CANCELLATION | SpawnFutureFlags::STOP_BY_TOKEN,
+                start_event_recorder(
+                    sinks,
+                    data_rx,
+                    shutdown_signal_rx,
+                    shutdown_tx,
+                    overflow.clone(),
+                ),
+            );
+            print_info(format!("EventRecorder started with sinks: {sink_names}"));
+
+            Some(overflow)
+        };
 
         Ok(Arc::new(Self {
             data_tx,
             shutdown_signal_tx,
             shutdown_rx: Mutex::new(Some(shutdown_rx)),
+            overflow,
         }))
     }
 
+    /// Sends event for saving. If channel is full, event is spilled to disk.
+    /// Normal priority events can't take capacity reserved for critical ones
+    /// and are dropped under heavy overload
     pub fn save<E: Event>(&self, event: E) -> Result<()> {
-        if !self.data_tx.is_closed() {
-            self.data_tx
-                .try_send((
-                    E::TABLE_NAME,
-                    InsertEvent {
-                        version: event.get_version(),
-                        json: event
-                            .get_json()
-                            .context("serialization to json in `EventRecorder::save()`")?,
-                    },
-                ))
-                .context("failed EventRecorder::save()")?
+        let overflow = match &self.overflow {
+            Some(overflow) if !self.data_tx.is_closed() => overflow,
+            _ => return Ok(()),
+        };
+
+        let insert_event = InsertEvent::new(
+            event.get_version(),
+            event
+                .get_json()
+                .context("serialization to json in `EventRecorder::save()`")?,
+        );
+
+        if E::PRIORITY == EventPriority::Normal
+            && self.data_tx.capacity() <= CAPACITY_RESERVED_FOR_CRITICAL_EVENTS
+        {
+            overflow.push(E::TABLE_NAME, E::PRIORITY, insert_event);
+            return Ok(());
         }
 
-        Ok(())
+        match self.data_tx.try_send((E::TABLE_NAME, insert_event)) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full((table_name, insert_event))) => {
+                overflow.push(table_name, E::PRIORITY, insert_event);
+                Ok(())
+            }
+            Err(err @ TrySendError::Closed(_)) => Err(err).context("failed EventRecorder::save()"),
+        }
+    }
+
+    /// Counts of spilled to disk and dropped events by table names
+    pub fn get_overflow_counts(&self) -> HashMap<TableName, OverflowCounts> {
+        self.overflow
+            .as_ref()
+            .map(|x| x.get_counts())
+            .unwrap_or_default()
     }
 
     pub async fn flush_and_stop(&self) -> Result<()> {
@@ -112,41 +172,12 @@ impl EventRecorder {
     }
 }
 
-async fn start_postponed_events_restoring(
-    pool: PgPool,
-    fallback: EventRecorderFallback,
-) -> Result<()> {
-    let mut interval = tokio::time::interval(RESTORING_EVENTS_TIMEOUT);
-    loop {
-        let _ = interval.tick().await;
-
-        let mut file_names = fallback
-            .get_existing_postponed_events_file_names()
-            .await
-            .context("can't get existing postponed events files")?;
-
-        if file_names.is_empty() {
-            // nothing to restore
-            continue;
-        }
-
-        if !pool.is_connection_health().await {
-            continue;
-        }
-
-        file_names.sort();
-        fallback
-            .try_restore_to_db_postponed_events(&pool, &file_names)
-            .await;
-    }
-}
-
-async fn start_db_event_recorder(
-    pool: PgPool,
+async fn start_event_recorder(
+    sinks: Vec<Arc<dyn EventSink>>,
     mut data_rx: mpsc::Receiver<(TableName, InsertEvent)>,
     mut shutdown_signal_rx: mpsc::UnboundedReceiver<()>,
     shutdown_tx: oneshot::Sender<Result<()>>,
-    fallback: EventRecorderFallback,
+    overflow: Arc<EventsOverflow>,
 ) -> Result<()> {
     fn create_batch_size_vec() -> Vec<InsertEvent> {
         Vec::<InsertEvent>::with_capacity(BATCH_MAX_SIZE)
@@ -166,8 +197,8 @@ async fn start_db_event_recorder(
         }
     }
     let mut events_map = HashMap::<TableName, EventsByTableName>::new();
+    let mut interval = tokio::time::interval(SAVING_TIMEOUT);
     loop {
-        let mut interval = tokio::time::interval(SAVING_TIMEOUT);
         tokio::select! {
             _ = shutdown_signal_rx.recv() => break, // in any case we should correctly finish
             result = data_rx.recv() => {
@@ -180,7 +211,7 @@ async fn start_db_event_recorder(
                             events.len() >= BATCH_SIZE_TO_SAVE {
 
                             let events = mem::replace(events, create_batch_size_vec());
-                            save_batch(&pool, table_name, events, &fallback).await.context("from `start_db_event_recorder` in `save_batch`")?;
+                            save_batch(&sinks, table_name, events).await;
 
                             *last_time_to_save = Instant::now();
                         }
@@ -189,10 +220,12 @@ async fn start_db_event_recorder(
                 }
             },
             _ = interval.tick() => {
+                overflow.spill_stale().await;
+
                 for (table_name, EventsByTableName { ref mut events, ref mut last_time_to_save }) in &mut events_map {
-                    if last_time_to_save.elapsed() < SAVING_TIMEOUT {
+                    if !events.is_empty() {
                         let events = mem::replace(events, create_batch_size_vec());
-                        save_batch(&pool, table_name, events, &fallback).await.context("from `start_db_event_recorder` in `save_batch`")?;
+                        save_batch(&sinks, table_name, events).await;
 
                         *last_time_to_save = Instant::now();
                     }
@@ -202,67 +235,66 @@ async fn start_db_event_recorder(
     }
 
     async fn flush_all_events(
-        pool: &PgPool,
+        sinks: &[Arc<dyn EventSink>],
         mut data_rx: mpsc::Receiver<(TableName, InsertEvent)>,
         mut events_map: HashMap<TableName, EventsByTableName>,
-        fallback: EventRecorderFallback,
     ) -> Result<()> {
         while let Ok((table_name, event)) = data_rx.try_recv() {
             events_map.entry(table_name).or_default().events.push(event);
         }
 
         for (table_name, EventsByTableName { events, .. }) in events_map {
-            save_batch(pool, table_name, events, &fallback)
-                .await
-                .context("from `flush_all_events` in `save_batch`")?;
+            save_batch(sinks, table_name, events).await;
         }
 
         Ok(())
     }
 
-    let flush_result = flush_all_events(&pool, data_rx, events_map, fallback).await;
+    let flush_result = flush_all_events(&sinks, data_rx, events_map).await;
+    overflow.spill_all().await;
 
     let _ = shutdown_tx.send(flush_result);
 
     Ok(())
 }
 
-async fn save_batch(
-    pool: &PgPool,
-    table_name: &'_ str,
-    events: Vec<InsertEvent>,
-    fallback: &EventRecorderFallback,
+/// Resends events spilled to disk (including spilled before restart) to all sinks.
+/// First restoring is started immediately
+async fn start_overflowed_events_restoring(
+    sinks: Vec<Arc<dyn EventSink>>,
+    overflow: Arc<EventsOverflow>,
 ) -> Result<()> {
-    match save_events_batch(pool, table_name, &events).await {
-        Ok(()) => return Ok(()),
-        Err(err) => log::error!("Failed to save batch of events with error: {err:?}"),
-    }
+    let mut interval = tokio::time::interval(RESTORING_OVERFLOWED_EVENTS_TIMEOUT);
+    loop {
+        let _ = interval.tick().await;
 
-    let (saving_result, not_written_events) =
-        save_events_one_by_one(pool, table_name, events).await;
-    match saving_result {
-        Ok(()) => if !not_written_events.is_empty() {},
-        Err(err) => {
-            log::error!("Failed to save events one by one with error: {err:?}");
-            save_to_file(table_name, not_written_events, fallback).await;
-        }
+        overflow.restore(&sinks).await;
     }
-
-    Ok(())
 }
 
-async fn save_to_file(
-    table_name: &str,
-    not_written_events: Vec<InsertEvent>,
-    fallback: &EventRecorderFallback,
+/// Saves batch to all sinks concurrently
+async fn save_batch(
+    sinks: &[Arc<dyn EventSink>],
+    table_name: TableNameRef<'_>,
+    events: Vec<InsertEvent>,
 ) {
-    let saving_result = fallback
-        .save_to_file(table_name.to_string(), not_written_events)
-        .await;
+    if events.is_empty() {
+        return;
+    }
+
+    let saving_futures = sinks.iter().map(|sink| {
+        let events = events.clone();
+        async move {
+            if let Err(err) = sink.save_batch(table_name, events).await {
+                log::error!(
+                    "Failed to save batch of events to `{table_name}` in sink `{}` with error: {err:?}",
+                    sink.name()
+                );
+            }
+        }
+    });
 
-    if let Err(err) = saving_result {
-        log::error!("Can't save to file not written events in EventRecorderFallback: {err:?}");
-    };
+    let _ = join_all(saving_futures).await;
 }
 
 #[cfg(test)]
@@ -0,0 +1,335 @@
+use crate::database::events::recorder::fallback::EventRecorderFallback;
+use crate::database::events::recorder::save_batch;
+use crate::database::events::recorder::sinks::EventSink;
+use crate::infrastructure::spawn_future_ok;
+use mmb_database::postgres_db::events::{EventPriority, InsertEvent, TableName};
+use mmb_database::postgres_db::postponed_events::PostponedEventsFileFormat;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::mem;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Count of buffered events of single table that are written to disk at once
+const SPILL_BATCH_SIZE: usize = 1_000;
+/// Max count of normal priority events that are buffered or being written to disk.
+/// Further normal priority events are dropped until pending ones are written
+const MAX_PENDING_NORMAL_EVENTS: usize = 100_000;
+const DROPPED_EVENTS_LOG_PERIOD: u64 = 10_000;
+/// Buffered events are written to disk at least that often even if batch isn't full
+const SPILL_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Counts of events that didn't fit into EventRecorder channel
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OverflowCounts {
+    /// Events written to postponed events files
+    pub spilled: u64,
+    /// Events that were lost
+    pub dropped: u64,
+}
+
+#[derive(Debug)]
+struct SpillBuffer {
+    priority: EventPriority,
+    events: Vec<InsertEvent>,
+    last_spill_time: Instant,
+}
+
+#[derive(Default)]
+struct OverflowState {
+    buffers: HashMap<TableName, SpillBuffer>,
+    counts: HashMap<TableName, OverflowCounts>,
+    pending_normal_events: usize,
+}
+
+/// Spills events that didn't fit into EventRecorder channel to postponed events files.
+/// Spilled files are resent to all sinks of EventRecorder by `restore` and removed afterwards.
+/// They have the same format as files of `EventRecorderFallback`, so they can be imported offline too
+pub(crate) struct EventsOverflow {
+    fallback: EventRecorderFallback,
+    state: Mutex<OverflowState>,
+}
+
+impl EventsOverflow {
+    pub fn new(fallback: EventRecorderFallback) -> Arc<Self> {
+        Arc::new(Self {
+            fallback,
+            state: Mutex::new(OverflowState::default()),
+        })
+    }
+
+    /// Buffers event for spilling to disk. Normal priority events are dropped if too many
+    /// of them are pending, critical events are never dropped
+    pub fn push(
+        self: &Arc<Self>,
+        table_name: TableName,
+        priority: EventPriority,
+        event: InsertEvent,
+    ) {
+        let mut state = self.state.lock();
+
+        if priority == EventPriority::Normal {
+            if state.pending_normal_events >= MAX_PENDING_NORMAL_EVENTS {
+                let counts = state.counts.entry(table_name).or_default();
+                counts.dropped += 1;
+                if counts.dropped % DROPPED_EVENTS_LOG_PERIOD == 1 {
+                    log::error!(
+                        "EventRecorder is overloaded, {} events of `{table_name}` were dropped",
+                        counts.dropped
+                    );
+                }
+                return;
+            }
+
+            state.pending_normal_events += 1;
+        }
+
+        let buffer = state
+            .buffers
+            .entry(table_name)
+            .or_insert_with(|| SpillBuffer {
+                priority,
+                events: Vec::with_capacity(SPILL_BATCH_SIZE),
+                last_spill_time: Instant::now(),
+            });
+        buffer.events.push(event);
+
+        if buffer.events.len() >= SPILL_BATCH_SIZE {
+            let events = mem::replace(&mut buffer.events, Vec::with_capacity(SPILL_BATCH_SIZE));
+            buffer.last_spill_time = Instant::now();
+            drop(state);
+
+            let this = self.clone();
+            let _ = spawn_future_ok(
+                "spill overflowed events to disk",
+                SpawnFutureFlags::DENY_CANCELLATION | SpawnFutureFlags::STOP_BY_TOKEN,
+                async move { this.spill(table_name, priority, events).await },
+            );
+        }
+    }
+
+    /// Writes to disk buffered events that weren't spilled during `SPILL_TIMEOUT`
+    pub async fn spill_stale(&self) {
+        self.spill_buffered(false).await
+    }
+
+    /// Writes all buffered events to disk
+    pub async fn spill_all(&self) {
+        self.spill_buffered(true).await
+    }
+
+    async fn spill_buffered(&self, all: bool) {
+        let buffers = {
+            let mut state = self.state.lock();
+            state
+                .buffers
+                .iter_mut()
+                .filter(|(_, buffer)| {
+                    !buffer.events.is_empty()
+                        && (all || buffer.last_spill_time.elapsed() >= SPILL_TIMEOUT)
+                })
+                .map(|(&table_name, buffer)| {
+                    buffer.last_spill_time = Instant::now();
+                    (table_name, buffer.priority, mem::take(&mut buffer.events))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for (table_name, priority, events) in buffers {
+            self.spill(table_name, priority, events).await;
+        }
+    }
+
+    /// Sends events of spilled files to all sinks and removes the files.
+    /// Sinks handle saving failures by themselves, so files are removed anyway
+    pub async fn restore(&self, sinks: &[Arc<dyn EventSink>]) {
+        let mut file_names = match self
+            .fallback
+            .get_existing_postponed_events_file_names()
+            .await
+        {
+            Ok(file_names) => file_names,
+            Err(err) => {
+                log::error!("Failed to get files of overflowed events: {err:?}");
+                return;
+            }
+        };
+        file_names.sort();
+
+        for file_name in file_names {
+            let PostponedEventsFileFormat {
+                table_name, events, ..
+            } = match self.fallback.load_postponed_events_file(&file_name).await {
+                Ok(file) => file,
+                Err(err) => {
+                    let file_name = file_name.to_string_lossy();
+                    log::error!("Failed to load file {file_name} of overflowed events: {err:?}");
+                    continue;
+                }
+            };
+
+            let events_count = events.len();
+            save_batch(sinks, &table_name, events).await;
+            log::info!(
+                "{events_count} overflowed events of `{table_name}` were restored from disk"
+            );
+
+            if let Err(err) = self.fallback.remove_postponed_events_file(&file_name).await {
+                log::error!("Failed to remove restored overflowed events: {err:?}");
+            }
+        }
+    }
+
+    pub fn get_counts(&self) -> HashMap<TableName, OverflowCounts> {
+        self.state.lock().counts.clone()
+    }
+
+    async fn spill(
+        &self,
+        table_name: TableName,
+        priority: EventPriority,
+        events: Vec<InsertEvent>,
+    ) {
+        let events_count = events.len();
+        let saving_result = self
+            .fallback
+            .save_to_file(table_name.to_string(), events)
+            .await;
+
+        let mut state = self.state.lock();
+        if priority == EventPriority::Normal {
+            state.pending_normal_events -= events_count;
+        }
+
+        let counts = state.counts.entry(table_name).or_default();
+        match saving_result {
+            Ok(()) => {
+                counts.spilled += events_count as u64;
+                log::warn!("EventRecorder channel is full, {events_count} events of `{table_name}` were spilled to disk");
+            }
+            Err(err) => {
+                counts.dropped += events_count as u64;
+                log::error!(
+                    "Failed to spill {events_count} events of `{table_name}` to disk: {err:?}"
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::init_lifetime_manager;
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use mmb_database::postgres_db::events::TableNameRef;
+    use serde_json::json;
+    use std::fs;
+
+    /// Sink that keeps saved events in memory
+    #[derive(Default)]
+    struct MemoryEventSink {
+        events: Mutex<Vec<(String, InsertEvent)>>,
+    }
+
+    #[async_trait]
+    impl EventSink for MemoryEventSink {
+        fn name(&self) -> &str {
+            "memory"
+        }
+
+        async fn save_batch(
+            &self,
+            table_name: TableNameRef<'_>,
+            events: Vec<InsertEvent>,
+        ) -> Result<()> {
+            self.events
+                .lock()
+                .extend(events.into_iter().map(|x| (table_name.to_owned(), x)));
+            Ok(())
+        }
+    }
+
+    fn event(id: usize) -> InsertEvent {
+        InsertEvent::new(1, json!({ "id": id }))
+    }
+
+    #[tokio::test]
+    async fn drop_only_normal_priority_events() {
+        let _ = init_lifetime_manager();
+
+        let dir = std::env::temp_dir().join(format!("mmb_overflow_{}", uuid::Uuid::new_v4()));
+        let fallback = EventRecorderFallback::new(Some(dir.clone())).expect("in test");
+        let overflow = EventsOverflow::new(fallback);
+
+        // imitate that disk writes are too slow for normal events
+        overflow.state.lock().pending_normal_events = MAX_PENDING_NORMAL_EVENTS;
+
+        overflow.push("trades_events", EventPriority::Normal, event(1));
+        overflow.push("orders", EventPriority::Critical, event(2));
+        overflow.push("orders", EventPriority::Critical, event(3));
+        overflow.spill_all().await;
+
+        let counts = overflow.get_counts();
+        assert_eq!(
+            counts.get("trades_events"),
+            Some(&OverflowCounts {
+                spilled: 0,
+                dropped: 1
+            })
+        );
+        assert_eq!(
+            counts.get("orders"),
+            Some(&OverflowCounts {
+                spilled: 2,
+                dropped: 0
+            })
+        );
+        assert_eq!(fs::read_dir(&dir).expect("in test").count(), 1);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn restore_spilled_events_to_all_sinks() {
+        let _ = init_lifetime_manager();
+
+        let dir = std::env::temp_dir().join(format!("mmb_overflow_{}", uuid::Uuid::new_v4()));
+        let fallback = EventRecorderFallback::new(Some(dir.clone())).expect("in test");
+        let overflow = EventsOverflow::new(fallback);
+
+        overflow.push("orders", EventPriority::Critical, event(1));
+        overflow.push("orders", EventPriority::Critical, event(2));
+        overflow.push("trades_events", EventPriority::Normal, event(3));
+        overflow.spill_all().await;
+        assert_eq!(fs::read_dir(&dir).expect("in test").count(), 2);
+
+        let first_sink = Arc::new(MemoryEventSink::default());
+        let second_sink = Arc::new(MemoryEventSink::default());
+        let sinks: Vec<Arc<dyn EventSink>> = vec![first_sink.clone(), second_sink.clone()];
+        overflow.restore(&sinks).await;
+
+        for sink in [first_sink, second_sink] {
+            let mut events = sink
+                .events
+                .lock()
+                .iter()
+                .map(|(table_name, event)| (table_name.clone(), event.json["id"].clone()))
+                .collect::<Vec<_>>();
+            events.sort_by_key(|(_, id)| id.as_u64());
+            assert_eq!(
+                events,
+                vec![
+                    ("orders".to_owned(), json!(1)),
+                    ("orders".to_owned(), json!(2)),
+                    ("trades_events".to_owned(), json!(3)),
+                ]
+            );
+        }
+        assert_eq!(fs::read_dir(&dir).expect("in test").count(), 0);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
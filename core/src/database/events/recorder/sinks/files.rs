@@ -0,0 +1,229 @@
+use crate::database::events::recorder::fallback::{
+    get_sink_postponed_events_dir, EventRecorderFallback,
+};
+use crate::database::events::recorder::sinks::{start_postponed_events_restoring, EventSink};
+use crate::infrastructure::spawn_future;
+use crate::misc::time::time_manager;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use mmb_database::postgres_db::events::{InsertEvent, TableNameRef};
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use mmb_utils::DateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::fs::{create_dir_all, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::task::spawn_blocking;
+
+const BUFFER_SIZE: usize = 16384;
+const FILE_EXTENSION: &str = "jsonl.gz";
+const SINK_NAME: &str = "files";
+
+/// Line of events file
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecordedEvent {
+    pub insert_time: DateTime,
+    pub version: i32,
+    pub json: JsonValue,
+}
+
+/// Saves events to gzip compressed JSON-lines files rotated by day: `{dir}/{table_name}/{date}.jsonl.gz`.
+/// Every batch is appended to file as separate gzip member, so file can be read by any gzip reader.
+/// Events that were not saved are postponed to files and resaved later
+pub struct FilesEventSink {
+    dir: Arc<Path>,
+    fallback: EventRecorderFallback,
+}
+
+impl FilesEventSink {
+    pub fn create(dir: PathBuf, postponed_events_dir: Option<PathBuf>) -> Result<Arc<Self>> {
+        create_dir_all(&dir)
+            .with_context(|| format!("unable create events dir {}", dir.display()))?;
+        let dir: Arc<Path> = dir.as_path().into();
+
+        let fallback = EventRecorderFallback::new(Some(get_sink_postponed_events_dir(
+            postponed_events_dir,
+            SINK_NAME,
+        )?))
+        .context("failed creation EventRecorderFallback for FilesEventSink")?;
+
+        let restoring_dir = dir.clone();
+        let _ = spawn_future(
+            "start files postponed events restoring",
+            SpawnFutureFlags::DENY_CANCELLATION | SpawnFutureFlags::STOP_BY_TOKEN,
+            start_postponed_events_restoring(fallback.clone(), move |table_name, events| {
+                save_events(restoring_dir.clone(), table_name, events)
+            }),
+        );
+
+        Ok(Arc::new(Self { dir, fallback }))
+    }
+}
+
+#[async_trait]
+impl EventSink for FilesEventSink {
+    fn name(&self) -> &str {
+        SINK_NAME
+    }
+
+    async fn save_batch(
+        &self,
+        table_name: TableNameRef<'_>,
+        events: Vec<InsertEvent>,
+    ) -> Result<()> {
+        if let Err(err) = save_events(self.dir.clone(), table_name.to_owned(), events.clone()).await
+        {
+            log::error!("Failed to save batch of events to files with error: {err:?}");
+
+            self.fallback
+                .save_to_file(table_name.to_owned(), events)
+                .await
+                .context("can't save to file events not written by FilesEventSink")?;
+        }
+
+        Ok(())
+    }
+}
+
+async fn save_events(dir: Arc<Path>, table_name: String, events: Vec<InsertEvent>) -> Result<()> {
+    let table_dir = dir.join(table_name);
+    let now = time_manager::now();
+
+    spawn_blocking(move || append_events(&table_dir, now, events)).await?
+}
+
+fn get_file_path(table_dir: &Path, now: DateTime) -> PathBuf {
+    table_dir.join(format!("{}.{FILE_EXTENSION}", now.format("%Y-%m-%d")))
+}
+
+fn append_events(table_dir: &Path, now: DateTime, events: Vec<InsertEvent>) -> Result<()> {
+    create_dir_all(table_dir)
+        .with_context(|| format!("unable create events dir {}", table_dir.display()))?;
+
+    let file_path = get_file_path(table_dir, now);
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&file_path)
+        .with_context(|| format!("can't open events file {}", file_path.display()))?;
+
+    let mut encoder = GzEncoder::new(
+        BufWriter::with_capacity(BUFFER_SIZE, file),
+        Compression::default(),
+    );
+    for InsertEvent { version, json, .. } in events {
+        let recorded_event = RecordedEvent {
+            insert_time: now,
+            version,
+            json,
+        };
+        serde_json::to_writer(&mut encoder, &recorded_event)
+            .with_context(|| format!("failed writing event to {}", file_path.display()))?;
+        encoder.write_all(b"\n")?;
+    }
+
+    encoder
+        .finish()
+        .and_then(|mut writer| writer.flush())
+        .with_context(|| format!("failed finishing events file {}", file_path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::init_lifetime_manager;
+    use chrono::{TimeZone, Utc};
+    use flate2::read::MultiGzDecoder;
+    use serde_json::json;
+    use std::fs::{self, File};
+    use std::io::{BufRead, BufReader};
+
+    fn read_recorded_events(file_path: &Path) -> Vec<RecordedEvent> {
+        let reader = BufReader::new(MultiGzDecoder::new(File::open(file_path).expect("in test")));
+        reader
+            .lines()
+            .map(|line| serde_json::from_str(&line.expect("in test")).expect("in test"))
+            .collect()
+    }
+
+    #[test]
+    fn append_batches_to_day_file() {
+        let table_dir = std::env::temp_dir()
+            .join(format!("mmb_files_sink_{}", uuid::Uuid::new_v4()))
+            .join("persons");
+        let now = Utc.ymd(2022, 1, 2).and_hms(3, 4, 5);
+        let event = |id| InsertEvent::new(1, json!({ "id": id }));
+
+        append_events(&table_dir, now, vec![event(1), event(2)]).expect("in test");
+        append_events(&table_dir, now, vec![event(3)]).expect("in test");
+
+        let file_path = get_file_path(&table_dir, now);
+        assert_eq!(file_path, table_dir.join("2022-01-02.jsonl.gz"));
+
+        let recorded_events = read_recorded_events(&file_path);
+
+        let expected = (1..=3)
+            .map(|id| RecordedEvent {
+                insert_time: now,
+                version: 1,
+                json: json!({ "id": id }),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(recorded_events, expected);
+
+        let _ = fs::remove_dir_all(table_dir.parent().expect("in test"));
+    }
+
+    #[tokio::test]
+    async fn postpone_not_saved_events_and_restore_them() {
+        let _ = init_lifetime_manager();
+
+        let root_dir =
+            std::env::temp_dir().join(format!("mmb_files_sink_{}", uuid::Uuid::new_v4()));
+        let postponed_events_dir = root_dir.join("postponed_events");
+        let sink =
+            FilesEventSink::create(root_dir.join("events"), Some(postponed_events_dir.clone()))
+                .expect("in test");
+        let event = InsertEvent::new(1, json!({ "id": 1 }));
+
+        // file in place of table dir makes saving to events file fail
+        let table_dir = sink.dir.join("persons");
+        let _ = File::create(&table_dir).expect("in test");
+
+        sink.save_batch("persons", vec![event.clone()])
+            .await
+            .expect("in test");
+
+        let sink_postponed_events_dir = postponed_events_dir.join(SINK_NAME);
+        let postponed_files_count = || {
+            fs::read_dir(&sink_postponed_events_dir)
+                .expect("in test")
+                .count()
+        };
+        assert_eq!(postponed_files_count(), 1);
+
+        fs::remove_file(&table_dir).expect("in test");
+        sink.fallback
+            .try_restore_postponed_events(|table_name, events| {
+                save_events(sink.dir.clone(), table_name, events)
+            })
+            .await;
+
+        assert_eq!(postponed_files_count(), 0);
+        let file_path = fs::read_dir(&table_dir)
+            .expect("in test")
+            .next()
+            .expect("in test")
+            .expect("in test")
+            .path();
+        let recorded_events = read_recorded_events(&file_path);
+        assert_eq!(recorded_events.len(), 1);
+        assert_eq!(recorded_events[0].json, event.json);
+
+        let _ = fs::remove_dir_all(root_dir);
+    }
+}
@@ -0,0 +1,44 @@
+pub mod files;
+pub mod postgres;
+pub mod sqlite;
+
+use crate::database::events::recorder::fallback::EventRecorderFallback;
+use anyhow::Result;
+use async_trait::async_trait;
+use mmb_database::postgres_db::events::{InsertEvent, TableNameRef};
+use std::future::Future;
+use std::time::Duration;
+
+/// Events postponed by sink are resaved that often, so failure has time to pass
+const RESTORING_POSTPONED_EVENTS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Destination of events recorded by `EventRecorder`.
+/// Sink should handle failures of saving by itself (e.g. postpone events for resaving),
+/// returned error is only logged by `EventRecorder`
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn save_batch(
+        &self,
+        table_name: TableNameRef<'_>,
+        events: Vec<InsertEvent>,
+    ) -> Result<()>;
+}
+
+/// Resaves events postponed by sink with `save`. First restoring is started immediately
+async fn start_postponed_events_restoring<F, Fut>(
+    fallback: EventRecorderFallback,
+    save: F,
+) -> Result<()>
+where
+    F: Fn(String, Vec<InsertEvent>) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let mut interval = tokio::time::interval(RESTORING_POSTPONED_EVENTS_TIMEOUT);
+    loop {
+        let _ = interval.tick().await;
+
+        fallback.try_restore_postponed_events(&save).await;
+    }
+}
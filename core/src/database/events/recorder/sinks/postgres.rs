@@ -0,0 +1,119 @@
+use crate::database::events::recorder::fallback::EventRecorderFallback;
+use crate::database::events::recorder::sinks::EventSink;
+use crate::infrastructure::spawn_future;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use mmb_database::postgres_db::events::{
+    save_events_batch, save_events_one_by_one, InsertEvent, TableNameRef,
+};
+use mmb_database::postgres_db::PgPool;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// timeout for checking connection health is 5 sec therefore timeout for restoring events should be significant bigger
+const RESTORING_EVENTS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Saves events to Postgres. Events that were not saved are postponed to files and resaved later
+pub struct PostgresEventSink {
+    pool: PgPool,
+    fallback: EventRecorderFallback,
+}
+
+impl PostgresEventSink {
+    pub fn start(pool: PgPool, postponed_events_dir: Option<PathBuf>) -> Result<Arc<Self>> {
+        let fallback = EventRecorderFallback::new(postponed_events_dir)
+            .context("failed creation EventRecorderFallback")?;
+
+        let _ = spawn_future(
+            "start postponed events restoring",
+            SpawnFutureFlags::DENY_CANCELLATION | SpawnFutureFlags::STOP_BY_TOKEN,
+            start_postponed_events_restoring(pool.clone(), fallback.clone()),
+        );
+
+        Ok(Arc::new(Self { pool, fallback }))
+    }
+}
+
+#[async_trait]
+impl EventSink for PostgresEventSink {
+    fn name(&self) -> &str {
+        "postgres"
+    }
+
+    async fn save_batch(
+        &self,
+        table_name: TableNameRef<'_>,
+        events: Vec<InsertEvent>,
+    ) -> Result<()> {
+        save_batch(&self.pool, table_name, events, &self.fallback).await
+    }
+}
+
+async fn start_postponed_events_restoring(
+    pool: PgPool,
+    fallback: EventRecorderFallback,
+) -> Result<()> {
+    let mut interval = tokio::time::interval(RESTORING_EVENTS_TIMEOUT);
+    loop {
+        let _ = interval.tick().await;
+
+        let mut file_names = fallback
+            .get_existing_postponed_events_file_names()
+            .await
+            .context("can't get existing postponed events files")?;
+
+        if file_names.is_empty() {
+            // nothing to restore
+            continue;
+        }
+
+        if !pool.is_connection_health().await {
+            continue;
+        }
+
+        file_names.sort();
+        fallback
+            .try_restore_to_db_postponed_events(&pool, &file_names)
+            .await;
+    }
+}
+
+pub(crate) async fn save_batch(
+    pool: &PgPool,
+    table_name: &'_ str,
+    events: Vec<InsertEvent>,
+    fallback: &EventRecorderFallback,
+) -> Result<()> {
+    match save_events_batch(pool, table_name, &events).await {
+        Ok(()) => return Ok(()),
+        Err(err) => log::error!("Failed to save batch of events with error: {err:?}"),
+    }
+
+    let (saving_result, not_written_events) =
+        save_events_one_by_one(pool, table_name, events).await;
+    match saving_result {
+        Ok(()) => if !not_written_events.is_empty() {},
+        Err(err) => {
+            log::error!("Failed to save events one by one with error: {err:?}");
+            save_to_file(table_name, not_written_events, fallback).await;
+        }
+    }
+
+    Ok(())
+}
+
+async fn save_to_file(
+    table_name: &str,
+    not_written_events: Vec<InsertEvent>,
+    fallback: &EventRecorderFallback,
+) {
+    let saving_result = fallback
+        .save_to_file(table_name.to_string(), not_written_events)
+        .await;
+
+    if let Err(err) = saving_result {
+        log::error!("Can't save to file not written events in EventRecorderFallback: {err:?}");
+    };
+}
@@ -0,0 +1,72 @@
+use crate::database::events::recorder::fallback::{
+    get_sink_postponed_events_dir, EventRecorderFallback,
+};
+use crate::database::events::recorder::sinks::{start_postponed_events_restoring, EventSink};
+use crate::infrastructure::spawn_future;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use mmb_database::postgres_db::events::{InsertEvent, TableNameRef};
+use mmb_database::sqlite_db::events::save_events_batch;
+use mmb_database::sqlite_db::SqlitePool;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const SINK_NAME: &str = "sqlite";
+
+/// Saves events to SQLite database file with the same tables layout as Postgres.
+/// Events that were not saved are postponed to files and resaved later
+pub struct SqliteEventSink {
+    pool: SqlitePool,
+    fallback: EventRecorderFallback,
+}
+
+impl SqliteEventSink {
+    pub async fn create(path: &Path, postponed_events_dir: Option<PathBuf>) -> Result<Arc<Self>> {
+        let pool = SqlitePool::create(path)
+            .await
+            .context("failed creation SqliteEventSink")?;
+
+        let fallback = EventRecorderFallback::new(Some(get_sink_postponed_events_dir(
+            postponed_events_dir,
+            SINK_NAME,
+        )?))
+        .context("failed creation EventRecorderFallback for SqliteEventSink")?;
+
+        let restoring_pool = pool.clone();
+        let _ = spawn_future(
+            "start sqlite postponed events restoring",
+            SpawnFutureFlags::DENY_CANCELLATION | SpawnFutureFlags::STOP_BY_TOKEN,
+            start_postponed_events_restoring(fallback.clone(), move |table_name, events| {
+                let pool = restoring_pool.clone();
+                async move { save_events_batch(&pool, &table_name, &events).await }
+            }),
+        );
+
+        Ok(Arc::new(Self { pool, fallback }))
+    }
+}
+
+#[async_trait]
+impl EventSink for SqliteEventSink {
+    fn name(&self) -> &str {
+        SINK_NAME
+    }
+
+    async fn save_batch(
+        &self,
+        table_name: TableNameRef<'_>,
+        events: Vec<InsertEvent>,
+    ) -> Result<()> {
+        if let Err(err) = save_events_batch(&self.pool, table_name, &events).await {
+            log::error!("Failed to save batch of events to SQLite with error: {err:?}");
+
+            self.fallback
+                .save_to_file(table_name.to_owned(), events)
+                .await
+                .context("can't save to file events not written to SQLite")?;
+        }
+
+        Ok(())
+    }
+}
@@ -15,6 +15,7 @@ use crate::disposition_execution::strategy::DispositionStrategy;
 use crate::disposition_execution::trading_context_calculation::calculate_trading_context;
 use crate::exchanges::general::exchange::Exchange;
 use crate::exchanges::general::request_type::RequestType;
+use crate::exchanges::general::self_trade_prevention::SelfTradeCheck;
 use crate::explanation::{Explanation, WithExplanation};
 use crate::lifecycle::trading_engine::{EngineContext, Service};
 use crate::misc::reserve_parameters::ReserveParameters;
@@ -36,9 +37,7 @@ use mmb_domain::market::{ExchangeAccountId, MarketAccountId};
 use mmb_domain::order::event::OrderEventType;
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::snapshot::{Amount, Price, UserOrder};
-use mmb_domain::order::snapshot::{
-    ClientOrderId, OrderHeader, OrderSide, OrderSnapshot, OrderStatus,
-};
+use mmb_domain::order::snapshot::{OrderHeader, OrderSide, OrderSnapshot, OrderStatus};
 use mmb_utils::cancellation_token::CancellationToken;
 
 static DISPOSITION_EXECUTOR: &str = "DispositionExecutor";
@@ -165,6 +164,8 @@ impl DispositionExecutor {
     }
 
     pub async fn start(&mut self) -> Result<()> {
+        self.take_adopted_orders();
+
         let mut trading_context: Option<TradingContext> = None;
 
         loop {
@@ -180,6 +181,38 @@ impl DispositionExecutor {
         }
     }
 
+    /// Orders of previous runs with tag of current strategy are managed like orders created by executor
+    fn take_adopted_orders(&mut self) {
+        let configuration_descriptor = self.strategy.configuration_descriptor();
+        let timeout_manager = &self.engine_ctx.timeout_manager;
+        let exchange_account_id = self.exchange_account_id;
+        // Order without requests group is left adopted, so its reservation isn't moved to the strategy
+        let adopted_orders = self.exchange().take_adopted_orders(
+            self.symbol.currency_pair(),
+            configuration_descriptor,
+            |order| {
+                let requests_group_id = timeout_manager.try_reserve_group(
+                    exchange_account_id,
+                    GROUP_REQUESTS_COUNT,
+                    DISPOSITION_EXECUTOR_REQUESTS_GROUP.to_string(),
+                );
+                if requests_group_id.is_none() {
+                    log::warn!(
+                        "Can't reserve requests group for adopted order {} in DispositionExecutor",
+                        order.client_order_id()
+                    );
+                }
+                requests_group_id
+            },
+        );
+
+        for (order, requests_group_id) in adopted_orders {
+            let side = order.side();
+            let price_slot = &self.orders_state.by_side[side].slots[0];
+            price_slot.add_order(side, order.price(), order, requests_group_id);
+        }
+    }
+
     fn handle_event(
         &mut self,
         event: &ExchangeEvent,
@@ -198,6 +231,11 @@ impl DispositionExecutor {
                     return Ok(());
                 }
 
+                // Orders on other exchanges (e.g. hedge orders of strategy) are not managed by DispositionExecutor
+                if order.exchange_account_id() != self.exchange_account_id {
+                    return Ok(());
+                }
+
                 match order_event.event_type {
                     OrderEventType::CreateOrderSucceeded => nothing_to_do(),
                     OrderEventType::CreateOrderFailed => {
@@ -616,7 +654,8 @@ impl DispositionExecutor {
             );
         }
 
-        let new_client_order_id = ClientOrderId::unique_id();
+        let strategy_tag = self.strategy.configuration_descriptor().service_name;
+        let new_client_order_id = self.exchange().new_client_order_id(strategy_tag.as_str());
 
         let requests_group_id = self.engine_ctx.timeout_manager.try_reserve_group(
             self.exchange_account_id,
@@ -712,6 +751,45 @@ impl DispositionExecutor {
 
         let exchange = self.exchange();
 
+        let rejection_reason = match exchange.check_self_trade(&order_header) {
+            // Repriced order doesn't match price slot, so new order will be created after recalculation of dispositions
+            SelfTradeCheck::Skip { .. } | SelfTradeCheck::Reprice { .. } => {
+                exchange.register_prevented_self_trade(&order_header);
+                Some("order crosses own not finished order".to_owned())
+            }
+            SelfTradeCheck::NoCross | SelfTradeCheck::CancelResting { .. } => {
+                match exchange.check_pre_trade_risk(&order_header) {
+                    Ok(()) => None,
+                    Err(violation) => {
+                        self.statistics.register_rejected_by_risk_checks_order(
+                            new_disposition.market_account_id(),
+                        );
+                        Some(format!(
+                            "order was rejected by pre-trade risk checks: {violation}"
+                        ))
+                    }
+                }
+            }
+        };
+
+        if let Some(rejection_reason) = rejection_reason {
+            self.engine_ctx
+                .balance_manager
+                .lock()
+                .unreserve_rest(reservation_id)
+                .with_expect(|| format!("DispositionExecutor::try_create_order() failed to unreserve_rest for: {reservation_id:?}"));
+
+            let _ = self
+                .engine_ctx
+                .timeout_manager
+                .remove_group(self.exchange_account_id, requests_group_id);
+
+            return log_trace(
+                format!("Finished `try_create_order` because {rejection_reason}"),
+                explanation,
+            );
+        }
+
         let new_order = exchange.orders.add_simple_initial(
             &order_header,
             now,
@@ -824,6 +902,9 @@ impl DispositionExecutor {
 
         price_slot.remove_order(order);
 
+        self.strategy
+            .handle_order_finished(order, self.exchange_account_id);
+
         log::trace!("Finished DispositionExecutor::finish_order {client_order_id}");
         Ok(())
     }
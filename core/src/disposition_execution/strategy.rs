@@ -9,6 +9,7 @@ use crate::order_book::local_snapshot_service::LocalSnapshotsService;
 use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
 use mmb_domain::events::ExchangeEvent;
 use mmb_domain::market::ExchangeAccountId;
+use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::snapshot::OrderSnapshot;
 use mmb_utils::cancellation_token::CancellationToken;
 
@@ -29,5 +30,9 @@ pub trait DispositionStrategy: Send + Sync + 'static {
         cancellation_token: CancellationToken,
     ) -> Result<()>;
 
+    /// Called when order reached terminal state (completed, canceled or failed to create)
+    /// and will not be handled by strategy anymore
+    fn handle_order_finished(&self, _order: &OrderRef, _target_eai: ExchangeAccountId) {}
+
     fn configuration_descriptor(&self) -> ConfigurationDescriptor;
 }
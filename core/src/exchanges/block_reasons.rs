@@ -12,3 +12,5 @@ impl_block_reason!(CREATE_ORDER_INSUFFICIENT_FUNDS);
 impl_block_reason!(REST_RATE_LIMIT);
 impl_block_reason!(GRACEFUL_SHUTDOWN);
 impl_block_reason!(EXCHANGE_UNAVAILABLE);
+impl_block_reason!(RECONCILIATION_DRIFT);
+impl_block_reason!(LOW_MARGIN_LEVEL);
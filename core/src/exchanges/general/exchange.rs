@@ -9,6 +9,7 @@ use crate::exchanges::exchange_blocker::{BlockType, ExchangeBlocker};
 use crate::exchanges::general::features::ExchangeFeatures;
 use crate::exchanges::general::order::cancel::CancelOrderResult;
 use crate::exchanges::general::order::create::CreateOrderResult;
+use crate::exchanges::general::pre_trade_risk::PreTradeRiskChecker;
 use crate::exchanges::general::request_type::RequestType;
 use crate::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
 use crate::exchanges::timeouts::timeout_manager::TimeoutManager;
@@ -18,12 +19,12 @@ use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
 use crate::misc::time::time_manager;
 use crate::orders::buffered_fills::buffered_canceled_orders_manager::BufferedCanceledOrdersManager;
 use crate::orders::buffered_fills::buffered_fills_manager::BufferedFillsManager;
+use crate::statistic_service::StatisticService;
 use anyhow::{bail, Context, Result};
 use dashmap::DashMap;
 use function_name::named;
 use futures::future::join_all;
 use itertools::Itertools;
-use mmb_database::impl_event;
 use mmb_domain::events::{
     BalanceUpdateEvent, ExchangeBalancesAndPositions, ExchangeEvent, LiquidationPriceEvent,
     MetricsEvent, MetricsEventInfo, MetricsEventInfoBase, MetricsEventType, MetricsTime, Trade,
@@ -47,7 +48,6 @@ use mmb_utils::send_expected::SendExpectedByRef;
 use mmb_utils::{nothing_to_do, DateTime};
 use parking_lot::Mutex;
 use rust_decimal::Decimal;
-use serde::Serialize;
 use std::fmt::Debug;
 use std::ops::DerefMut;
 use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
@@ -55,6 +55,7 @@ use std::sync::{Arc, Weak};
 use std::time::Duration;
 use tokio::sync::{broadcast, oneshot};
 use tokio::time::sleep;
+use tokio_util::sync::DropGuard as CancellationTokenDropGuard;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum RequestResult<T> {
@@ -83,10 +84,6 @@ pub struct OrderBookTop {
     pub bid: Option<PriceLevel>,
 }
 
-#[derive(Serialize)]
-struct LiquidationPrice(Price);
-impl_event!(LiquidationPrice, "liquidation_prices");
-
 pub struct Exchange {
     pub exchange_account_id: ExchangeAccountId,
     pub symbols: DashMap<CurrencyPair, Arc<Symbol>>,
@@ -96,10 +93,10 @@ pub struct Exchange {
     pub leverage_by_currency_pair: DashMap<CurrencyPair, Decimal>,
     pub order_book_top: DashMap<CurrencyPair, OrderBookTop>,
     pub exchange_client: BoxExchangeClient,
-    pub(super) features: ExchangeFeatures,
+    pub(crate) features: ExchangeFeatures,
     pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
     pub(super) lifetime_manager: Arc<AppLifetimeManager>,
-    pub(super) commission: Commission,
+    pub(crate) commission: Commission,
     pub(super) wait_cancel_order: DashMap<ClientOrderId, broadcast::Sender<()>>,
     pub(super) wait_finish_order: DashMap<ClientOrderId, broadcast::Sender<OrderRef>>,
     pub(super) polling_trades_counts: DashMap<ExchangeAccountId, u32>,
@@ -112,6 +109,10 @@ pub struct Exchange {
     pub(crate) balance_manager: Mutex<Option<Weak<Mutex<BalanceManager>>>>,
     pub(super) buffered_fills_manager: Mutex<BufferedFillsManager>,
     pub(super) buffered_canceled_orders_manager: Mutex<BufferedCanceledOrdersManager>,
+    pub(super) pre_trade_risk_checker: PreTradeRiskChecker,
+    pub(super) statistic_service: Mutex<Option<Arc<StatisticService>>>,
+    /// Orders of previous runs with strategy tag that are waiting for the strategy to take them
+    pub(super) adopted_orders: DashMap<ClientOrderId, OrderRef>,
     // It allows to send and receive notification about event in websocket channel
     // Websocket event is main source detecting order creation result
     // Rest response using only for unsuccessful operations as error
@@ -130,8 +131,12 @@ pub struct Exchange {
             Option<oneshot::Receiver<CancelOrderResult>>,
         ),
     >,
-    exchange_blocker: Weak<ExchangeBlocker>,
+    pub(super) exchange_blocker: Weak<ExchangeBlocker>,
     ws_sender: Mutex<Option<WsSender>>,
+    /// Senders of established market data connections by index of connection
+    pub(super) market_data_senders: DashMap<usize, WsSender>,
+    /// Market data connections are stopped when it's dropped
+    pub(super) market_data_connections_cancel: Mutex<Option<CancellationTokenDropGuard>>,
     auto_reconnect: AtomicBool,
 
     // Temporary fix before integration ExchangeBlocker to wait_order_finish/wait_cancel_order fallbacks #641
@@ -169,6 +174,8 @@ impl Exchange {
                 exchange_client,
                 orders,
                 ws_sender: Default::default(),
+                market_data_senders: DashMap::new(),
+                market_data_connections_cancel: Mutex::new(None),
                 order_creation_events: DashMap::new(),
                 order_cancellation_events: DashMap::new(),
                 lifetime_manager,
@@ -192,6 +199,9 @@ impl Exchange {
                 buffered_fills_manager: Default::default(),
                 exchange_blocker,
                 buffered_canceled_orders_manager: Default::default(),
+                pre_trade_risk_checker: Default::default(),
+                statistic_service: Mutex::new(None),
+                adopted_orders: DashMap::new(),
                 auto_reconnect: AtomicBool::new(false),
                 timeout,
                 server_time_latency: Default::default(),
@@ -224,6 +234,18 @@ impl Exchange {
             }
         }));
 
+        exchange_client.set_order_creation_failed_callback(Box::new({
+            let exchange_weak = exchange_weak.clone();
+            move |client_order_id, exchange_error, source_type| match exchange_weak.upgrade() {
+                Some(exchange) => exchange.raise_order_creation_failed(
+                    &client_order_id,
+                    exchange_error,
+                    source_type,
+                ),
+                None => log::info!("Unable to upgrade weak reference to Exchange instance"),
+            }
+        }));
+
         exchange_client.set_handle_order_filled_callback(Box::new({
             let exchange_weak = exchange_weak.clone();
             move |mut event_data| match exchange_weak.upgrade() {
@@ -265,7 +287,7 @@ impl Exchange {
         }))
     }
 
-    fn on_websocket_message(&self, msg: &str) {
+    pub(super) fn on_websocket_message(&self, msg: &str) {
         self.maybe_log_websocket_message(msg);
 
         if let Err(error) = self.exchange_client.on_websocket_message(msg) {
@@ -368,11 +390,34 @@ impl Exchange {
         // prevent auto reconnect
         self.auto_reconnect.store(false, Ordering::SeqCst);
         self.ws_sender.lock().take();
+        self.stop_market_data_connections();
     }
 
     pub async fn connect_ws(self: &Arc<Self>) -> Result<()> {
+        if self.exchange_client.is_connection_managed_by_client() {
+            log::info!(
+                "Connection of {} is managed by exchange client",
+                self.exchange_account_id
+            );
+            return Ok(());
+        }
+
         // fire connecting callback
         self.on_connecting();
+
+        if self.start_market_data_connections()
+            && !self
+                .exchange_client
+                .is_websocket_enabled(WebSocketRole::Main)
+        {
+            log::info!(
+                "Main websocket disabled for {}, only market data connections are used",
+                self.exchange_account_id
+            );
+            self.on_connected();
+            return Ok(());
+        }
+
         // do connect
         match self.connect_internal().await {
             Ok(reader) => {
@@ -465,11 +510,21 @@ impl Exchange {
     }
 
     fn forward_websocket_message(&self, role: WebSocketRole, msg: String) -> Result<()> {
+        if let WebSocketRole::MarketData(index) = role {
+            return match self.market_data_senders.get(&index) {
+                Some(sender) => sender.send_main(msg).map_err(|e| e.into()),
+                None => Err(ConnectivityError::NotConnected.into()),
+            };
+        }
+
         let mut locked = self.ws_sender.lock();
         if let Some(sender) = locked.deref_mut() {
             match role {
                 WebSocketRole::Main => sender.send_main(msg),
                 WebSocketRole::Secondary => sender.send_secondary(msg),
+                WebSocketRole::MarketData(_) => {
+                    unreachable!("Sent to market data connection above")
+                }
             }
             .map_err(|e| e.into())
         } else {
@@ -504,6 +559,7 @@ impl Exchange {
 
         if order.is_finished() {
             let _ = self.orders.not_finished.remove(&order.client_order_id());
+            self.handle_linked_order_finished(order);
         }
 
         let event = ExchangeEvent::OrderEvent(OrderEvent::new(order.clone(), event_type));
@@ -685,6 +741,19 @@ impl Exchange {
         }
     }
 
+    /// Price of the last trade on market or middle of order book top if trades aren't received
+    pub(crate) fn get_market_price(&self, currency_pair: CurrencyPair) -> Option<Price> {
+        let market_id = MarketId::new(self.exchange_account_id.exchange_id, currency_pair);
+        if let Some(trade) = self.last_trades.get(&market_id) {
+            return Some(trade.price);
+        }
+
+        let order_book_top = self.order_book_top.get(&currency_pair)?;
+        let ask = order_book_top.ask.as_ref()?.price;
+        let bid = order_book_top.bid.as_ref()?.price;
+        Some((ask + bid) / Decimal::TWO)
+    }
+
     fn handle_balances_and_positions(
         &self,
         balances_and_positions: ExchangeBalancesAndPositions,
@@ -706,6 +775,10 @@ impl Exchange {
             }
         }
 
+        if let Some(margin_account) = &balances_and_positions.margin_account {
+            self.check_margin_level(margin_account.margin_level);
+        }
+
         balances_and_positions
     }
 
@@ -765,14 +838,25 @@ impl Exchange {
         entry_price: Price,
         side: OrderSide,
     ) {
-        if !self.symbols.contains_key(&currency_pair) {
-            log::warn!(
-                "Unknown currency pair {} in handle_liquidation_price for {}",
-                currency_pair,
-                self.exchange_account_id
-            );
-            return;
-        }
+        let symbol = match self.symbols.get(&currency_pair) {
+            Some(symbol) => symbol.clone(),
+            None => {
+                log::warn!(
+                    "Unknown currency pair {} in handle_liquidation_price for {}",
+                    currency_pair,
+                    self.exchange_account_id
+                );
+                return;
+            }
+        };
+
+        // some exchanges don't report liquidation price of position, so it's estimated
+        let liquidation_price = match liquidation_price.is_zero() && !entry_price.is_zero() {
+            true => self
+                .estimate_liquidation_price(&symbol, side, entry_price)
+                .unwrap_or(liquidation_price),
+            false => liquidation_price,
+        };
 
         let event = LiquidationPriceEvent::new(
             time_manager::now(),
@@ -783,12 +867,34 @@ impl Exchange {
             side,
         );
 
+        self.event_recorder
+            .save(event.clone())
+            .expect("Failure save liquidation_price");
+
         self.events_channel
             .send_expected(ExchangeEvent::LiquidationPrice(event));
+    }
 
-        self.event_recorder
-            .save(LiquidationPrice(liquidation_price))
-            .expect("Failure save liquidation_price");
+    fn estimate_liquidation_price(
+        &self,
+        symbol: &Symbol,
+        side: OrderSide,
+        entry_price: Price,
+    ) -> Option<Price> {
+        let leverage = self
+            .leverage_by_currency_pair
+            .get(&symbol.currency_pair())
+            .map(|x| *x)
+            .unwrap_or(Decimal::ONE);
+        let maintenance_margin_rate = self
+            .exchange_client
+            .get_settings()
+            .margin
+            .as_ref()
+            .map(|margin| margin.maintenance_margin_rate(leverage))
+            .unwrap_or_default();
+
+        symbol.liquidation_price(side, entry_price, leverage, maintenance_margin_rate)
     }
 
     pub(crate) fn get_timeout(&self) -> Duration {
@@ -818,7 +924,9 @@ impl Exchange {
             }
             MetricsEventType::MlPrediction
             | MetricsEventType::OrderFromCreateToFill
-            | MetricsEventType::TradeToMl => 0,
+            | MetricsEventType::TradeToMl
+            | MetricsEventType::CreateOrderRequest(_)
+            | MetricsEventType::CancelOrderRequest(_) => 0,
             MetricsEventType::OrderLifeCycle(_) => unimplemented!(),
         };
 
@@ -14,8 +14,9 @@ use crate::{
     settings::CoreSettings,
 };
 use mmb_domain::events::ExchangeEvent;
-use mmb_domain::exchanges::commission::Commission;
+use mmb_domain::exchanges::commission::{Commission, CommissionForType};
 use mmb_domain::order::pool::OrdersPool;
+use rust_decimal_macros::dec;
 use tokio::sync::broadcast;
 
 pub fn create_timeout_manager(
@@ -75,7 +76,7 @@ pub async fn create_exchange(
         lifetime_manager,
         timeout_manager,
         exchange_blocker,
-        Commission::default(),
+        create_commission(user_settings),
         event_recorder,
     );
 
@@ -84,3 +85,13 @@ pub async fn create_exchange(
 
     exchange
 }
+
+pub(crate) fn create_commission(user_settings: &ExchangeSettings) -> Commission {
+    match &user_settings.commission {
+        Some(commission) => Commission::new(
+            CommissionForType::new(commission.maker_fee, dec!(0)),
+            CommissionForType::new(commission.taker_fee, dec!(0)),
+        ),
+        None => Commission::default(),
+    }
+}
@@ -1,3 +1,4 @@
+use anyhow::Result;
 use dashmap::DashMap;
 use itertools::Itertools;
 use mmb_domain::market::CurrencyCode;
@@ -38,6 +39,23 @@ impl Exchange {
         ));
     }
 
+    /// Change traded currency pairs at runtime, so market data subscriptions are changed by exchange client.
+    /// Symbols of removed currency pairs are kept because there can be orders for them
+    pub async fn update_currency_pairs(
+        &self,
+        currency_pair_settings: &[CurrencyPairSetting],
+    ) -> Result<()> {
+        let exchange_symbols = self.exchange_client.build_all_symbols().await?;
+
+        self.setup_symbols(get_symbols(
+            currency_pair_settings,
+            &exchange_symbols,
+            self.exchange_account_id,
+        ));
+
+        Ok(())
+    }
+
     async fn request_symbols_with_retries(&self) -> Vec<Arc<Symbol>> {
         const MAX_RETRIES: u8 = 5;
         for retry in 0..=MAX_RETRIES {
@@ -69,13 +87,18 @@ impl Exchange {
     }
 
     fn setup_symbols(&self, symbols: Vec<Arc<Symbol>>) {
-        let currencies = symbols
-            .iter()
-            .flat_map(|x| [x.base_currency_code, x.quote_currency_code])
-            .collect::<HashSet<_>>()
-            .into_iter()
-            .collect_vec();
-        *self.currencies.lock() = currencies;
+        // currencies of removed currency pairs are kept like their symbols
+        {
+            let mut currencies = self.currencies.lock();
+            let known = currencies.iter().copied().collect::<HashSet<_>>();
+            let new_currencies = symbols
+                .iter()
+                .flat_map(|x| [x.base_currency_code, x.quote_currency_code])
+                .filter(|x| !known.contains(x))
+                .unique()
+                .collect_vec();
+            currencies.extend(new_currencies);
+        }
 
         symbols.iter().for_each(|symbol| {
             self.symbols.insert(symbol.currency_pair(), symbol.clone());
@@ -143,3 +166,116 @@ fn get_matched_currency_pair(
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::market_data_connections::{
+        MarketDataShardChanges, MarketDataShards,
+    };
+    use crate::exchanges::general::test_helper::{get_test_exchange_with_client, TestClient};
+    use crate::exchanges::traits::Support;
+    use mmb_domain::exchanges::symbol::Precision;
+    use mmb_domain::market::{CurrencyPair, SpecificCurrencyPair};
+    use parking_lot::Mutex;
+
+    fn symbol(base: &str, quote: &str) -> Arc<Symbol> {
+        Arc::new(Symbol::new(
+            false,
+            base.into(),
+            base.into(),
+            quote.into(),
+            quote.into(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            base.into(),
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0) },
+        ))
+    }
+
+    fn pair_settings(pairs: &[(&str, &str)]) -> Vec<CurrencyPairSetting> {
+        pairs
+            .iter()
+            .map(|&(base, quote)| CurrencyPairSetting::Ordinary {
+                base: base.into(),
+                quote: quote.into(),
+            })
+            .collect()
+    }
+
+    fn specific(test_client: &TestClient, pairs: &[(&str, &str)]) -> Vec<SpecificCurrencyPair> {
+        pairs
+            .iter()
+            .map(|&(base, quote)| {
+                test_client
+                    .get_specific_currency_pair(CurrencyPair::from_codes(base.into(), quote.into()))
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn market_data_connections_are_resharded_after_currency_pairs_update() {
+        let symbols = vec![
+            symbol("BTC", "USDT"),
+            symbol("ETH", "USDT"),
+            symbol("BNB", "USDT"),
+        ];
+        let exchange_client = TestClient {
+            symbols: symbols.clone(),
+            market_data_shards: Mutex::new(MarketDataShards::new(2)),
+            ..Default::default()
+        };
+        let (exchange, _rx) = get_test_exchange_with_client(
+            symbols[0].clone(),
+            ExchangeAccountId::new("local_exchange_account_id", 0),
+            exchange_client,
+        );
+        let test_client = exchange
+            .exchange_client
+            .as_any()
+            .downcast_ref::<TestClient>()
+            .expect("exchange client should be TestClient");
+
+        exchange
+            .update_currency_pairs(&pair_settings(&[("BTC", "USDT"), ("ETH", "USDT")]))
+            .await
+            .expect("in test");
+        test_client.market_data_changes.lock().clear();
+
+        exchange
+            .update_currency_pairs(&pair_settings(&[("ETH", "USDT"), ("BNB", "USDT")]))
+            .await
+            .expect("in test");
+
+        assert_eq!(
+            *test_client.market_data_changes.lock(),
+            vec![MarketDataShardChanges {
+                shard: 0,
+                added: specific(test_client, &[("BNB", "USDT")]),
+                removed: specific(test_client, &[("BTC", "USDT")]),
+            }]
+        );
+        let market_data_shards = test_client.market_data_shards.lock();
+        assert_eq!(
+            market_data_shards.currency_pairs(0),
+            specific(test_client, &[("BNB", "USDT")])
+        );
+        assert_eq!(
+            market_data_shards.currency_pairs(1),
+            specific(test_client, &[("ETH", "USDT")])
+        );
+
+        // symbol and currencies of removed currency pair are kept because there can be orders for them
+        assert!(exchange.symbols.contains_key(&symbols[0].currency_pair()));
+        let currencies = exchange.currencies.lock().clone();
+        assert_eq!(currencies.len(), 4);
+        for currency in ["BTC", "ETH", "BNB", "USDT"] {
+            assert!(currencies.contains(&currency.into()), "{currency}");
+        }
+    }
+}
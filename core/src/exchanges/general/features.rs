@@ -83,9 +83,14 @@ pub struct OrderFeatures {
     /// Stop loss orders are supported
     // TODO Flag is not used in core, is it redundant?
     pub supports_stop_loss_order: bool,
+    /// Exchange side timer cancelling all orders if it isn't rearmed in time is supported
+    pub supports_cancel_all_after: bool,
+    /// One-cancels-the-other orders (a linked pair of limit and stop loss orders) are supported
+    pub supports_oco_order: bool,
 }
 
 impl OrderFeatures {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         maker_only: bool,
         supports_get_order_info_by_client_order_id: bool,
@@ -94,6 +99,8 @@ impl OrderFeatures {
         order_was_completed_error_for_cancellation: bool,
         supports_already_cancelled_order: bool,
         supports_stop_loss_order: bool,
+        supports_cancel_all_after: bool,
+        supports_oco_order: bool,
     ) -> Self {
         Self {
             maker_only,
@@ -103,6 +110,8 @@ impl OrderFeatures {
             order_was_completed_error_for_cancellation,
             supports_already_cancelled_order,
             supports_stop_loss_order,
+            supports_cancel_all_after,
+            supports_oco_order,
         }
     }
 }
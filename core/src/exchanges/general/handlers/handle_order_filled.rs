@@ -689,12 +689,15 @@ impl Exchange {
         {
             Some(order_ref) => fill_event.client_order_id = Some(order_ref.client_order_id()),
             None => {
-                let order_options = match fill_event.fill_type == OrderFillType::ClosePosition {
-                    true => OrderOptions::close_position(fill_event.fill_price),
-                    false => OrderOptions::liquidation(fill_event.fill_price),
+                let order_options = match fill_event.fill_type {
+                    OrderFillType::ClosePosition => {
+                        OrderOptions::close_position(fill_event.fill_price)
+                    }
+                    OrderFillType::MissedFill => OrderOptions::missed_fill(fill_event.fill_price),
+                    _ => OrderOptions::liquidation(fill_event.fill_price),
                 };
 
-                // Liquidation and ClosePosition are always Takers
+                // Special orders (Liquidation, ClosePosition and MissedFill) are always Takers
                 let order =
                     self.create_special_order_in_pool(special, order_options, OrderRole::Taker);
 
@@ -710,7 +713,48 @@ impl Exchange {
         }
     }
 
-    // Create special order (Liquidation or ClosePosition) in pool
+    /// Add fill that was detected by reconciliation with exchange but wasn't received from it
+    pub(crate) fn handle_missed_fill(
+        &self,
+        currency_pair: CurrencyPair,
+        order_side: OrderSide,
+        amount: Amount,
+        price: Price,
+    ) {
+        let source_type = match self.features.allowed_fill_event_source_type {
+            AllowedEventSourceType::FallbackOnly => EventSourceType::RestFallback,
+            _ => EventSourceType::Rest,
+        };
+
+        let mut fill_event = FillEvent {
+            source_type,
+            trade_id: None,
+            client_order_id: None,
+            exchange_order_id: ExchangeOrderId::new(
+                format!("missed_fill_{}", Uuid::new_v4()).into(),
+            ),
+            fill_price: price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: amount,
+                total_filled_amount: Some(amount),
+            },
+            order_role: Some(OrderRole::Taker),
+            commission_currency_code: None,
+            commission_rate: None,
+            commission_amount: None,
+            fill_type: OrderFillType::MissedFill,
+            special_order_data: Some(SpecialOrderData {
+                currency_pair,
+                order_side,
+                order_amount: amount,
+            }),
+            fill_date: None,
+        };
+
+        self.handle_order_filled(&mut fill_event);
+    }
+
+    // Create special order (Liquidation, ClosePosition or MissedFill) in pool
     fn create_special_order_in_pool(
         &self,
         special: SpecialOrderData,
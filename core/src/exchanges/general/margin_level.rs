@@ -0,0 +1,85 @@
+use super::exchange::Exchange;
+use crate::exchanges::block_reasons::LOW_MARGIN_LEVEL;
+use crate::exchanges::exchange_blocker::{BlockType, ExchangeBlocker};
+use mmb_domain::market::ExchangeAccountId;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+/// Block creation of new orders while margin level is below specified minimum and unblock it when margin level is restored
+pub(crate) fn update_margin_level_block(
+    exchange_blocker: &Arc<ExchangeBlocker>,
+    exchange_account_id: ExchangeAccountId,
+    margin_level: Decimal,
+    min_margin_level: Decimal,
+) {
+    let is_blocked = exchange_blocker.is_blocked_by_reason(exchange_account_id, LOW_MARGIN_LEVEL);
+    if margin_level < min_margin_level {
+        if !is_blocked {
+            log::warn!("Margin level {margin_level} on {exchange_account_id} is below {min_margin_level}, new orders are blocked");
+            exchange_blocker.block(exchange_account_id, LOW_MARGIN_LEVEL, BlockType::Manual);
+        }
+    } else if is_blocked {
+        log::info!("Margin level {margin_level} on {exchange_account_id} is restored, exchange is unblocked");
+        exchange_blocker.unblock(exchange_account_id, LOW_MARGIN_LEVEL);
+    }
+}
+
+impl Exchange {
+    pub(super) fn check_margin_level(&self, margin_level: Decimal) {
+        let min_margin_level = match self.exchange_client.get_settings().min_margin_level {
+            Some(min_margin_level) => min_margin_level,
+            None => return,
+        };
+
+        if let Some(exchange_blocker) = self.exchange_blocker.upgrade() {
+            update_margin_level_block(
+                &exchange_blocker,
+                self.exchange_account_id,
+                margin_level,
+                min_margin_level,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::init_lifetime_manager;
+    use mmb_utils::cancellation_token::CancellationToken;
+    use ntest::timeout;
+    use rust_decimal_macros::dec;
+
+    fn exchange_account_id() -> ExchangeAccountId {
+        ExchangeAccountId::new("ExchangeId", 0)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[timeout(120_000)]
+    async fn block_on_low_margin_level_and_unblock_on_restore() {
+        let _ = init_lifetime_manager();
+        let exchange_blocker = ExchangeBlocker::new(vec![exchange_account_id()]);
+
+        update_margin_level_block(&exchange_blocker, exchange_account_id(), dec!(2), dec!(1.5));
+        assert!(!exchange_blocker.is_blocked(exchange_account_id()));
+
+        update_margin_level_block(
+            &exchange_blocker,
+            exchange_account_id(),
+            dec!(1.3),
+            dec!(1.5),
+        );
+        assert!(exchange_blocker.is_blocked_by_reason(exchange_account_id(), LOW_MARGIN_LEVEL));
+
+        update_margin_level_block(
+            &exchange_blocker,
+            exchange_account_id(),
+            dec!(1.5),
+            dec!(1.5),
+        );
+        exchange_blocker
+            .wait_unblock(exchange_account_id(), CancellationToken::new())
+            .await;
+        assert!(!exchange_blocker.is_blocked(exchange_account_id()));
+    }
+}
@@ -0,0 +1,249 @@
+use super::exchange::Exchange;
+use crate::connectivity::{
+    run_reconnecting_connection, ConnectivityError, ReconnectingConnectionHandler, WebSocketParams,
+    WebSocketRole, WsSender,
+};
+use crate::infrastructure::spawn_future_ok;
+use async_trait::async_trait;
+use itertools::Itertools;
+use mmb_domain::market::SpecificCurrencyPair;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use std::collections::HashSet;
+use std::sync::{Arc, Weak};
+use tokio_util::sync::CancellationToken;
+
+/// Distribution of traded currency pairs across market data connections.
+/// A pair stays on its connection while it's traded, new pairs are added to the least loaded connection
+#[derive(Debug, Default)]
+pub struct MarketDataShards {
+    shards: Vec<Vec<SpecificCurrencyPair>>,
+}
+
+/// Subscriptions of market data connection that should be changed
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MarketDataShardChanges {
+    pub shard: usize,
+    pub added: Vec<SpecificCurrencyPair>,
+    pub removed: Vec<SpecificCurrencyPair>,
+}
+
+impl MarketDataShards {
+    pub fn new(count: usize) -> Self {
+        MarketDataShards {
+            shards: vec![Vec::new(); count],
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.shards.len()
+    }
+
+    pub fn currency_pairs(&self, shard: usize) -> &[SpecificCurrencyPair] {
+        self.shards.get(shard).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Redistribute currency pairs and return changes of connections which subscriptions are changed
+    pub fn set_currency_pairs(
+        &mut self,
+        currency_pairs: &[SpecificCurrencyPair],
+    ) -> Vec<MarketDataShardChanges> {
+        if self.shards.is_empty() {
+            return vec![];
+        }
+
+        let mut changes = (0..self.shards.len())
+            .map(|shard| MarketDataShardChanges {
+                shard,
+                ..Default::default()
+            })
+            .collect_vec();
+
+        let traded = currency_pairs.iter().collect::<HashSet<_>>();
+        for (shard, pairs) in self.shards.iter_mut().enumerate() {
+            pairs.retain(|currency_pair| {
+                let is_traded = traded.contains(currency_pair);
+                if !is_traded {
+                    changes[shard].removed.push(*currency_pair);
+                }
+                is_traded
+            });
+        }
+
+        let distributed = self
+            .shards
+            .iter()
+            .flatten()
+            .copied()
+            .collect::<HashSet<_>>();
+        for currency_pair in currency_pairs.iter().unique() {
+            if distributed.contains(currency_pair) {
+                continue;
+            }
+
+            let (shard, pairs) = self
+                .shards
+                .iter_mut()
+                .enumerate()
+                .min_by_key(|(_, pairs)| pairs.len())
+                .expect("Market data shards shouldn't be empty");
+            pairs.push(*currency_pair);
+            changes[shard].added.push(*currency_pair);
+        }
+
+        changes
+            .into_iter()
+            .filter(|x| !x.added.is_empty() || !x.removed.is_empty())
+            .collect()
+    }
+}
+
+/// Market data connection of exchange, that is reconnected independently of other connections
+struct MarketDataConnection {
+    exchange: Weak<Exchange>,
+    index: usize,
+}
+
+#[async_trait]
+impl ReconnectingConnectionHandler for MarketDataConnection {
+    async fn get_params(&self) -> Option<crate::connectivity::Result<WebSocketParams>> {
+        let exchange = self.exchange.upgrade()?;
+        let role = WebSocketRole::MarketData(self.index);
+        let params = exchange
+            .get_websocket_params(role)
+            .await
+            .map_err(|e| ConnectivityError::FailedToGetParams(role, e.to_string()));
+
+        Some(params)
+    }
+
+    fn on_connected(&self, sender: WsSender) {
+        if let Some(exchange) = self.exchange.upgrade() {
+            let _ = exchange.market_data_senders.insert(self.index, sender);
+        }
+    }
+
+    fn on_message(&self, msg: &str) {
+        if let Some(exchange) = self.exchange.upgrade() {
+            exchange.on_websocket_message(msg);
+        }
+    }
+
+    fn on_disconnected(&self) {
+        if let Some(exchange) = self.exchange.upgrade() {
+            let _ = exchange.market_data_senders.remove(&self.index);
+        }
+    }
+}
+
+impl Exchange {
+    /// Start market data connections if they are specified in settings and aren't started yet.
+    /// Returns `true` if market data is received via separate connections
+    pub(super) fn start_market_data_connections(self: &Arc<Self>) -> bool {
+        let count = self
+            .exchange_client
+            .get_settings()
+            .market_data_connections
+            .unwrap_or_default();
+        if count == 0 {
+            return false;
+        }
+
+        if !self
+            .exchange_client
+            .is_websocket_enabled(WebSocketRole::MarketData(0))
+        {
+            log::warn!(
+                "Market data connections aren't supported for {}, main websocket is used instead",
+                self.exchange_account_id
+            );
+            return false;
+        }
+
+        let mut cancel_guard = self.market_data_connections_cancel.lock();
+        if cancel_guard.is_some() {
+            return true;
+        }
+
+        let cancel = CancellationToken::new();
+        for index in 0..count {
+            let role = WebSocketRole::MarketData(index);
+            let handler = MarketDataConnection {
+                exchange: Arc::downgrade(self),
+                index,
+            };
+            let _ = spawn_future_ok(
+                &format!(
+                    "Exchange account id {} {role} websocket",
+                    self.exchange_account_id
+                ),
+                SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+                run_reconnecting_connection(
+                    self.exchange_account_id,
+                    role,
+                    handler,
+                    cancel.clone(),
+                ),
+            );
+        }
+        *cancel_guard = Some(cancel.drop_guard());
+
+        true
+    }
+
+    pub(super) fn stop_market_data_connections(&self) {
+        self.market_data_connections_cancel.lock().take();
+        self.market_data_senders.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(names: &[&str]) -> Vec<SpecificCurrencyPair> {
+        names
+            .iter()
+            .map(|&x| SpecificCurrencyPair::from(x))
+            .collect()
+    }
+
+    #[test]
+    fn currency_pairs_are_distributed_evenly() {
+        let mut shards = MarketDataShards::new(2);
+
+        let changes = shards.set_currency_pairs(&pairs(&["BTCUSDT", "ETHUSDT", "BNBUSDT"]));
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(shards.currency_pairs(0), pairs(&["BTCUSDT", "BNBUSDT"]));
+        assert_eq!(shards.currency_pairs(1), pairs(&["ETHUSDT"]));
+    }
+
+    #[test]
+    fn currency_pairs_stay_on_their_connections() {
+        let mut shards = MarketDataShards::new(2);
+        let _ = shards.set_currency_pairs(&pairs(&["BTCUSDT", "ETHUSDT", "BNBUSDT"]));
+
+        let changes = shards.set_currency_pairs(&pairs(&["ETHUSDT", "BNBUSDT", "XRPUSDT"]));
+
+        assert_eq!(
+            changes,
+            vec![MarketDataShardChanges {
+                shard: 0,
+                added: pairs(&["XRPUSDT"]),
+                removed: pairs(&["BTCUSDT"]),
+            }]
+        );
+        assert_eq!(shards.currency_pairs(0), pairs(&["BNBUSDT", "XRPUSDT"]));
+        assert_eq!(shards.currency_pairs(1), pairs(&["ETHUSDT"]));
+    }
+
+    #[test]
+    fn no_changes_for_same_currency_pairs() {
+        let mut shards = MarketDataShards::new(3);
+        let _ = shards.set_currency_pairs(&pairs(&["BTCUSDT", "ETHUSDT"]));
+
+        let changes = shards.set_currency_pairs(&pairs(&["ETHUSDT", "BTCUSDT"]));
+
+        assert!(changes.is_empty());
+    }
+}
@@ -5,9 +5,13 @@ pub mod exchange_creation;
 pub mod exchange_symbol;
 pub mod features;
 pub mod handlers;
+pub mod margin_level;
+pub mod market_data_connections;
 pub mod order;
 pub mod polling_timeout_manager;
+pub mod pre_trade_risk;
 pub mod request_type;
+pub mod self_trade_prevention;
 
 #[cfg(test)]
 pub mod test_helper;
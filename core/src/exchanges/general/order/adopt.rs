@@ -0,0 +1,552 @@
+use crate::balance::manager::balance_manager::BalanceManager;
+use crate::exchanges::general::exchange::Exchange;
+use crate::exchanges::general::features::RestFillsType;
+use crate::exchanges::general::request_type::RequestType;
+use crate::misc::reserve_parameters::ReserveParameters;
+use crate::misc::time::time_manager;
+use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+use crate::settings::OrphanedOrdersPolicy;
+use anyhow::Result;
+use itertools::Itertools;
+use mmb_domain::market::CurrencyPair;
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{
+    ClientOrderId, OrderHeader, OrderInfo, OrderOptions, OrderStatus,
+};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::nothing_to_do;
+use parking_lot::Mutex;
+use std::sync::{Arc, Weak};
+
+/// Service name of adopted orders without strategy tag
+const FOREIGN_ORDERS_SERVICE_NAME: &str = "ForeignOrders";
+
+impl Exchange {
+    /// Id for new order of strategy. Ids are tagged only if policies for orphaned orders are specified
+    /// because some exchanges support only numeric client order ids
+    pub fn new_client_order_id(&self, strategy_tag: &str) -> ClientOrderId {
+        match self.exchange_client.get_settings().orphaned_orders {
+            Some(_) => ClientOrderId::unique_id_with_strategy_tag(strategy_tag),
+            None => ClientOrderId::unique_id(),
+        }
+    }
+
+    /// Apply configured policies to open orders on exchange that are unknown for engine,
+    /// e.g. orders of previous run or orders created by a human
+    pub async fn handle_orphaned_orders(
+        self: Arc<Self>,
+        balance_manager: Arc<Mutex<BalanceManager>>,
+        cancellation_token: CancellationToken,
+    ) {
+        let settings = match &self.exchange_client.get_settings().orphaned_orders {
+            Some(settings) => settings.clone(),
+            None => return,
+        };
+
+        let open_orders = match self.get_open_orders(false).await {
+            Ok(open_orders) => open_orders,
+            Err(err) => {
+                log::error!(
+                    "Unable to get open orders for handling orphaned orders on {}: {err:?}",
+                    self.exchange_account_id
+                );
+                return;
+            }
+        };
+
+        let mut orders_to_cancel = Vec::new();
+        for order_info in open_orders {
+            if self
+                .orders
+                .cache_by_exchange_id
+                .contains_key(&order_info.exchange_order_id)
+            {
+                continue;
+            }
+
+            let policy = match order_info.client_order_id.strategy_tag() {
+                Some(_) => settings.own,
+                None => settings.foreign,
+            };
+
+            log::info!(
+                "Found orphaned order {} {} on {} with policy {policy:?}",
+                order_info.client_order_id,
+                order_info.exchange_order_id,
+                self.exchange_account_id,
+            );
+
+            match policy {
+                OrphanedOrdersPolicy::Adopt => {
+                    if let Err(err) = self
+                        .adopt_order(&order_info, &balance_manager, cancellation_token.clone())
+                        .await
+                    {
+                        log::error!(
+                            "Failed to adopt order {} {} on {}: {err:?}",
+                            order_info.client_order_id,
+                            order_info.exchange_order_id,
+                            self.exchange_account_id,
+                        );
+                    }
+                }
+                OrphanedOrdersPolicy::Cancel => orders_to_cancel.push(order_info),
+                OrphanedOrdersPolicy::Ignore => nothing_to_do(),
+            }
+        }
+
+        if !orders_to_cancel.is_empty() {
+            self.add_missing_open_orders(&orders_to_cancel);
+            self.cancel_orders(orders_to_cancel, cancellation_token)
+                .await;
+        }
+    }
+
+    async fn adopt_order(
+        &self,
+        order_info: &OrderInfo,
+        balance_manager: &Arc<Mutex<BalanceManager>>,
+        cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        let currency_pair = order_info.currency_pair;
+        let symbol = self.get_symbol(currency_pair)?;
+
+        let strategy_tag = order_info.client_order_id.strategy_tag();
+        let service_name = strategy_tag.unwrap_or(FOREIGN_ORDERS_SERVICE_NAME);
+        let configuration_descriptor = ConfigurationDescriptor::new(
+            service_name.into(),
+            format!("{};{currency_pair}", self.exchange_account_id)
+                .as_str()
+                .into(),
+        );
+
+        // Nothing handles fills and cancellation of foreign orders, so their reservations would never be released
+        let rest_amount = order_info.amount - order_info.filled_amount;
+        let reservation_id = match strategy_tag {
+            Some(_) => {
+                let reserve_parameters = ReserveParameters::new(
+                    configuration_descriptor,
+                    self.exchange_account_id,
+                    symbol.clone(),
+                    order_info.order_side,
+                    order_info.price,
+                    rest_amount,
+                );
+                let reservation_id = balance_manager
+                    .lock()
+                    .try_reserve(&reserve_parameters, &mut None);
+                if reservation_id.is_none() {
+                    log::warn!(
+                        "Unable to reserve balance {rest_amount} for adopted order {} on {}",
+                        order_info.exchange_order_id,
+                        self.exchange_account_id,
+                    );
+                }
+                reservation_id
+            }
+            None => None,
+        };
+
+        let client_order_id = match order_info.client_order_id.as_str().is_empty() {
+            true => ClientOrderId::unique_id(),
+            false => order_info.client_order_id.clone(),
+        };
+
+        let header = OrderHeader::with_options(
+            client_order_id.clone(),
+            self.exchange_account_id,
+            currency_pair,
+            order_info.order_side,
+            order_info.amount,
+            OrderOptions::limit(order_info.price),
+            reservation_id,
+            None,
+            service_name.to_owned(),
+        );
+
+        let now = time_manager::now();
+        let order = self
+            .orders
+            .add_simple_initial(&header, now, order_info.extension_data.clone());
+        order.fn_mut(|x| {
+            x.props.exchange_order_id = Some(order_info.exchange_order_id.clone());
+            x.set_status(OrderStatus::Created, now);
+        });
+        let _ = self
+            .orders
+            .cache_by_exchange_id
+            .insert(order_info.exchange_order_id.clone(), order.clone());
+
+        if let Some(reservation_id) = reservation_id {
+            balance_manager.lock().approve_reservation(
+                reservation_id,
+                &client_order_id,
+                rest_amount,
+            );
+        }
+
+        if !order_info.filled_amount.is_zero() {
+            self.load_adopted_order_fills(&order, cancellation_token)
+                .await?;
+        }
+
+        if strategy_tag.is_some() && !order.is_finished() {
+            let _ = self.adopted_orders.insert(client_order_id, order);
+        }
+
+        log::info!(
+            "Adopted order {} {} on {}",
+            order_info.client_order_id,
+            order_info.exchange_order_id,
+            self.exchange_account_id,
+        );
+
+        Ok(())
+    }
+
+    async fn load_adopted_order_fills(
+        &self,
+        order: &OrderRef,
+        cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        let request_type = match self.features.rest_fills_features.fills_type {
+            RestFillsType::None => return Ok(()),
+            RestFillsType::MyTrades => RequestType::GetOrderTrades,
+            RestFillsType::GetOrderInfo => RequestType::GetOrderInfo,
+        };
+
+        let symbol = self.get_symbol(order.currency_pair())?;
+        let result = self
+            .check_order_fills_using_request_type(
+                order,
+                &symbol,
+                request_type,
+                None,
+                cancellation_token,
+            )
+            .await?;
+
+        if let Some(error) = result.get_error() {
+            log::warn!(
+                "Unable to load fills of adopted order {} on {}: {error:?}",
+                order.client_order_id(),
+                self.exchange_account_id,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Take not finished adopted orders of the strategy, so the strategy manages them as its own.
+    /// Order is taken only if `try_take` returns value for it, otherwise it stays adopted.
+    /// Reservations of taken orders are moved to configuration descriptor of the strategy
+    pub fn take_adopted_orders<T>(
+        &self,
+        currency_pair: CurrencyPair,
+        configuration_descriptor: ConfigurationDescriptor,
+        mut try_take: impl FnMut(&OrderRef) -> Option<T>,
+    ) -> Vec<(OrderRef, T)> {
+        let strategy_tag =
+            ClientOrderId::shorten_strategy_tag(configuration_descriptor.service_name.as_str());
+        let client_order_ids = self
+            .adopted_orders
+            .iter()
+            .filter(|x| {
+                x.currency_pair() == currency_pair
+                    && x.client_order_id().strategy_tag() == Some(strategy_tag.as_str())
+            })
+            .map(|x| x.key().clone())
+            .collect_vec();
+
+        let balance_manager = self.balance_manager.lock().as_ref().and_then(Weak::upgrade);
+        let mut taken_orders = vec![];
+        for client_order_id in client_order_ids {
+            let Some(order) = self.adopted_orders.get(&client_order_id).map(|x| x.clone()) else {
+                continue;
+            };
+
+            if order.is_finished() {
+                let _ = self.adopted_orders.remove(&client_order_id);
+                continue;
+            }
+
+            let Some(value) = try_take(&order) else {
+                continue;
+            };
+
+            let _ = self.adopted_orders.remove(&client_order_id);
+            if let (Some(balance_manager), Some(reservation_id)) =
+                (&balance_manager, order.header().reservation_id)
+            {
+                if let Err(err) = balance_manager
+                    .lock()
+                    .move_reservation(reservation_id, configuration_descriptor)
+                {
+                    log::error!(
+                        "Failed to move reservation of adopted order {} on {}: {err:?}",
+                        order.client_order_id(),
+                        self.exchange_account_id,
+                    );
+                }
+            }
+
+            taken_orders.push((order, value));
+        }
+
+        taken_orders
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
+    use crate::exchanges::general::test_helper::{
+        get_test_exchange, get_test_exchange_with_client, TestClient,
+    };
+    use crate::settings::{ExchangeSettings, OrphanedOrdersSettings};
+    use mmb_domain::exchanges::symbol::{Precision, Symbol};
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_domain::order::snapshot::OrderSide;
+    use mmb_utils::hashmap;
+    use rstest::rstest;
+    use rust_decimal_macros::dec;
+
+    fn add_adopted_order(
+        exchange: &Exchange,
+        client_order_id: ClientOrderId,
+        currency_pair: CurrencyPair,
+    ) -> OrderRef {
+        let header = OrderHeader::with_options(
+            client_order_id.clone(),
+            exchange.exchange_account_id,
+            currency_pair,
+            OrderSide::Buy,
+            dec!(1),
+            OrderOptions::limit(dec!(0.5)),
+            None,
+            None,
+            "".to_string(),
+        );
+        let order = exchange
+            .orders
+            .add_simple_initial(&header, time_manager::now(), None);
+        let _ = exchange
+            .adopted_orders
+            .insert(client_order_id, order.clone());
+        order
+    }
+
+    fn configuration_descriptor(strategy_name: &str) -> ConfigurationDescriptor {
+        ConfigurationDescriptor::new(strategy_name.into(), "PHB/BTC".into())
+    }
+
+    #[tokio::test]
+    async fn take_adopted_orders_of_strategy() {
+        let (exchange, _rx) = get_test_exchange(false);
+        let currency_pair = CurrencyPair::from_codes("phb".into(), "btc".into());
+        let other_currency_pair = CurrencyPair::from_codes("eth".into(), "btc".into());
+
+        let order = add_adopted_order(
+            &exchange,
+            ClientOrderId::unique_id_with_strategy_tag("Strategy"),
+            currency_pair,
+        );
+        let _ = add_adopted_order(
+            &exchange,
+            ClientOrderId::unique_id_with_strategy_tag("OtherStrategy"),
+            currency_pair,
+        );
+        let _ = add_adopted_order(
+            &exchange,
+            ClientOrderId::unique_id_with_strategy_tag("Strategy"),
+            other_currency_pair,
+        );
+
+        let taken_orders = exchange.take_adopted_orders(
+            currency_pair,
+            configuration_descriptor("Strategy"),
+            |_| Some(()),
+        );
+
+        assert_eq!(
+            taken_orders
+                .iter()
+                .map(|(x, _)| x.client_order_id())
+                .collect_vec(),
+            vec![order.client_order_id()]
+        );
+        assert_eq!(exchange.adopted_orders.len(), 2);
+        assert!(exchange
+            .take_adopted_orders(currency_pair, configuration_descriptor("Strategy"), |_| {
+                Some(())
+            })
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn adopted_order_is_not_taken_if_refused() {
+        let (exchange, _rx) = get_test_exchange(false);
+        let currency_pair = CurrencyPair::from_codes("phb".into(), "btc".into());
+
+        let order = add_adopted_order(
+            &exchange,
+            ClientOrderId::unique_id_with_strategy_tag("Strategy"),
+            currency_pair,
+        );
+
+        let taken_orders = exchange.take_adopted_orders(
+            currency_pair,
+            configuration_descriptor("Strategy"),
+            |_| None::<()>,
+        );
+        assert!(taken_orders.is_empty());
+        assert!(exchange
+            .adopted_orders
+            .contains_key(&order.client_order_id()));
+
+        let taken_orders = exchange.take_adopted_orders(
+            currency_pair,
+            configuration_descriptor("Strategy"),
+            |_| Some(()),
+        );
+        assert_eq!(taken_orders, vec![(order, ())]);
+    }
+
+    #[tokio::test]
+    async fn take_adopted_orders_of_strategy_with_long_name() {
+        let (exchange, _rx) = get_test_exchange(false);
+        let currency_pair = CurrencyPair::from_codes("phb".into(), "btc".into());
+        let strategy_name = "CrossExchangeStrategy";
+
+        let order = add_adopted_order(
+            &exchange,
+            ClientOrderId::unique_id_with_strategy_tag(strategy_name),
+            currency_pair,
+        );
+
+        let taken_orders = exchange.take_adopted_orders(
+            currency_pair,
+            configuration_descriptor(strategy_name),
+            |_| Some(()),
+        );
+
+        assert_eq!(taken_orders, vec![(order, ())]);
+    }
+
+    fn open_order_info(
+        client_order_id: ClientOrderId,
+        exchange_order_id: &str,
+        currency_pair: CurrencyPair,
+    ) -> OrderInfo {
+        OrderInfo::new(
+            currency_pair,
+            exchange_order_id.into(),
+            client_order_id,
+            OrderSide::Buy,
+            OrderStatus::Created,
+            dec!(0.5),
+            dec!(1),
+            dec!(0),
+            dec!(0),
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[rstest]
+    #[case::adopt(OrphanedOrdersPolicy::Adopt)]
+    #[case::cancel(OrphanedOrdersPolicy::Cancel)]
+    #[case::ignore(OrphanedOrdersPolicy::Ignore)]
+    #[tokio::test]
+    async fn handle_orphaned_orders_by_policy(#[case] policy: OrphanedOrdersPolicy) {
+        let symbol = Arc::new(Symbol::new(
+            false,
+            "PHB".into(),
+            "PHB".into(),
+            "BTC".into(),
+            "BTC".into(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            "PHB".into(),
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0.001) },
+        ));
+        let currency_pair = symbol.currency_pair();
+        let exchange_account_id = ExchangeAccountId::new("local_exchange_account_id", 0);
+
+        let own_order = open_order_info(
+            ClientOrderId::unique_id_with_strategy_tag("Strategy"),
+            "1",
+            currency_pair,
+        );
+        let foreign_order = open_order_info("web-order".into(), "2", currency_pair);
+
+        let exchange_client = TestClient {
+            settings: ExchangeSettings {
+                orphaned_orders: Some(OrphanedOrdersSettings {
+                    own: policy,
+                    foreign: OrphanedOrdersPolicy::Ignore,
+                }),
+                ..Default::default()
+            },
+            open_orders: vec![own_order.clone(), foreign_order.clone()],
+            ..Default::default()
+        };
+        let (exchange, _rx) =
+            get_test_exchange_with_client(symbol, exchange_account_id, exchange_client);
+        let balance_manager = BalanceManager::new(
+            CurrencyPairToSymbolConverter::new(hashmap![exchange_account_id => exchange.clone()]),
+            None,
+        );
+
+        exchange
+            .clone()
+            .handle_orphaned_orders(balance_manager, CancellationToken::default())
+            .await;
+
+        let test_client = exchange
+            .exchange_client
+            .as_any()
+            .downcast_ref::<TestClient>()
+            .expect("exchange client should be TestClient");
+        let own_order_status = exchange
+            .orders
+            .cache_by_exchange_id
+            .get(&own_order.exchange_order_id)
+            .map(|x| x.status());
+
+        match policy {
+            OrphanedOrdersPolicy::Adopt => {
+                assert_eq!(own_order_status, Some(OrderStatus::Created));
+                assert!(exchange
+                    .adopted_orders
+                    .contains_key(&own_order.client_order_id));
+                assert!(test_client.cancelled_orders.lock().is_empty());
+            }
+            OrphanedOrdersPolicy::Cancel => {
+                assert_eq!(own_order_status, Some(OrderStatus::Canceled));
+                assert!(exchange.adopted_orders.is_empty());
+                assert_eq!(
+                    *test_client.cancelled_orders.lock(),
+                    vec![own_order.exchange_order_id.clone()]
+                );
+            }
+            OrphanedOrdersPolicy::Ignore => {
+                assert_eq!(own_order_status, None);
+                assert!(exchange.adopted_orders.is_empty());
+                assert!(test_client.cancelled_orders.lock().is_empty());
+            }
+        }
+
+        assert!(!exchange
+            .orders
+            .cache_by_exchange_id
+            .contains_key(&foreign_order.exchange_order_id));
+    }
+}
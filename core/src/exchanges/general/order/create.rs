@@ -1,6 +1,7 @@
 use crate::exchanges::general::exchange::RequestResult::{Error, Success};
 use crate::exchanges::general::handlers::should_ignore_event;
 use crate::exchanges::general::request_type::RequestType;
+use crate::exchanges::general::self_trade_prevention::SelfTradeCheck;
 use crate::exchanges::timeouts::requests_timeout_manager::RequestGroupId;
 use crate::exchanges::traits::ExchangeError;
 use crate::misc::time::time_manager;
@@ -9,6 +10,7 @@ use anyhow::{bail, Context, Result};
 use chrono::Utc;
 use function_name::named;
 use futures::pin_mut;
+use itertools::Itertools;
 use mmb_domain::events::{AllowedEventSourceType, EventSourceType};
 use mmb_domain::market::{ExchangeAccountId, ExchangeErrorType};
 use mmb_domain::order::event::OrderEventType;
@@ -57,12 +59,81 @@ impl Exchange {
 
         log::info!("Submitting order {order_header:?}");
 
+        let self_trade_check = self.check_self_trade(order_header);
+        let order_header = match &self_trade_check {
+            SelfTradeCheck::Reprice { header } => header,
+            _ => order_header,
+        };
+
         let order = self.orders.add_simple_initial(
             order_header,
             time_manager::now(),
             self.exchange_client.get_initial_extension_data(),
         );
 
+        let client_order_id = &order_header.client_order_id;
+        if let SelfTradeCheck::Skip { crossed_orders } = &self_trade_check {
+            self.register_prevented_self_trade(order_header);
+
+            let crossed_orders = crossed_orders
+                .iter()
+                .map(|x| x.client_order_id())
+                .collect_vec();
+            let message = format!("order crosses own orders {crossed_orders:?}");
+            self.reject_order_before_creation(
+                &order,
+                ExchangeErrorType::SelfTradePrevented,
+                message.clone(),
+            )?;
+
+            bail!("Order {client_order_id} was rejected by self-trade prevention: {message}");
+        }
+
+        if let Err(violation) = self
+            .apply_pre_trade_risk_checks(order_header, cancellation_token.clone())
+            .await
+        {
+            self.reject_order_before_creation(
+                &order,
+                ExchangeErrorType::RiskCheckFailed,
+                violation.to_string(),
+            )?;
+
+            bail!("Order {client_order_id} was rejected by pre-trade risk checks: {violation}");
+        }
+
+        match &self_trade_check {
+            SelfTradeCheck::Reprice { .. } => {
+                if !self.try_update_repriced_reservation(order_header) {
+                    let message = format!(
+                        "balance isn't enough to update reservation for repriced price {:?}",
+                        order_header.source_price
+                    );
+                    self.reject_order_before_creation(
+                        &order,
+                        ExchangeErrorType::InsufficientFunds,
+                        message.clone(),
+                    )?;
+
+                    bail!(
+                        "Order {client_order_id} was rejected by self-trade prevention: {message}"
+                    );
+                }
+
+                self.register_prevented_self_trade(order_header);
+                log::info!(
+                    "Order {client_order_id} was repriced to {:?} to prevent self-trade",
+                    order_header.source_price
+                );
+            }
+            SelfTradeCheck::CancelResting { crossed_orders } => {
+                self.register_prevented_self_trade(order_header);
+                self.cancel_crossed_orders(crossed_orders, cancellation_token.clone())
+                    .await;
+            }
+            SelfTradeCheck::NoCross | SelfTradeCheck::Skip { .. } => nothing_to_do(),
+        }
+
         let linked_ct = cancellation_token.create_linked_token();
 
         let create_order_fut = self.create_order_base(&order, linked_ct.clone());
@@ -515,7 +586,7 @@ impl Exchange {
     }
 
     #[named]
-    fn handle_create_order_failed(
+    pub(super) fn handle_create_order_failed(
         &self,
         client_order_id: &ClientOrderId,
         exchange_error: &ExchangeError,
@@ -554,6 +625,23 @@ impl Exchange {
         self.react_on_status_when_failed(&order_ref, args_to_log, source_type, exchange_error)
     }
 
+    /// Order is marked as failed to create without sending request to exchange
+    pub(super) fn reject_order_before_creation(
+        &self,
+        order: &OrderRef,
+        error_type: ExchangeErrorType,
+        message: String,
+    ) -> Result<()> {
+        let client_order_id = order.client_order_id();
+        let args_to_log = (self.exchange_account_id, &client_order_id, &None);
+        self.react_on_status_when_failed(
+            order,
+            args_to_log,
+            EventSourceType::Rest,
+            &ExchangeError::new(error_type, message, None),
+        )
+    }
+
     fn react_on_status_when_failed(
         &self,
         order: &OrderRef,
@@ -701,7 +789,9 @@ impl Exchange {
 
                 let header = order.header();
                 let client_order_id = header.client_order_id.clone();
-                if order.order_type() != OrderType::Liquidation {
+                if order.order_type() != OrderType::Liquidation
+                    && !self.is_secondary_linked_order(order)
+                {
                     match header.reservation_id {
                         None => {
                             log::warn!("Created order {client_order_id} without reservation_id")
@@ -0,0 +1,281 @@
+use crate::exchanges::general::exchange::RequestResult::{Error, Success};
+use crate::exchanges::general::exchange::{Exchange, RequestResult};
+use crate::exchanges::traits::ExchangeError;
+use crate::misc::time::time_manager;
+use anyhow::{bail, Result};
+use mmb_domain::events::EventSourceType;
+use mmb_domain::market::ExchangeErrorType;
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{ExchangeOrderId, OrderHeader, OrderType};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::OPERATION_CANCELED_MSG;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct CreateOcoOrderResult {
+    /// Exchange order ids of limit and stop loss legs respectively
+    pub outcome: RequestResult<(ExchangeOrderId, ExchangeOrderId)>,
+    pub source_type: EventSourceType,
+}
+
+impl CreateOcoOrderResult {
+    pub fn succeed(
+        limit_order_id: &ExchangeOrderId,
+        stop_loss_order_id: &ExchangeOrderId,
+        source_type: EventSourceType,
+    ) -> Self {
+        CreateOcoOrderResult {
+            outcome: Success((limit_order_id.clone(), stop_loss_order_id.clone())),
+            source_type,
+        }
+    }
+
+    pub fn failed(error: ExchangeError, source_type: EventSourceType) -> Self {
+        CreateOcoOrderResult {
+            outcome: Error(error),
+            source_type,
+        }
+    }
+}
+
+impl Exchange {
+    /// Create one-cancels-the-other order: a pair of linked limit and stop loss orders.
+    /// Both legs should share the same reservation. It's approved for the limit leg only
+    /// and the rest of it is released when both legs are finished, so caller shouldn't unreserve it.
+    pub async fn create_oco_order(
+        &self,
+        limit_header: &OrderHeader,
+        stop_loss_header: &OrderHeader,
+        cancellation_token: CancellationToken,
+    ) -> Result<(OrderRef, OrderRef)> {
+        log::info!("Submitting OCO order {limit_header:?} {stop_loss_header:?}");
+
+        if !self.features.order_features.supports_oco_order {
+            bail!(
+                "OCO orders aren't supported on {}",
+                self.exchange_account_id
+            );
+        }
+
+        if limit_header.order_type != OrderType::Limit
+            || stop_loss_header.order_type != OrderType::StopLoss
+        {
+            bail!(
+                "OCO order should consist of Limit and StopLoss orders, but got {:?} and {:?}",
+                limit_header.order_type,
+                stop_loss_header.order_type
+            );
+        }
+
+        if limit_header.currency_pair != stop_loss_header.currency_pair
+            || limit_header.side != stop_loss_header.side
+            || limit_header.amount != stop_loss_header.amount
+            || limit_header.reservation_id != stop_loss_header.reservation_id
+        {
+            bail!("Legs of OCO order should have the same currency pair, side, amount and reservation");
+        }
+
+        let now = time_manager::now();
+        let limit_order = self.orders.add_simple_initial(
+            limit_header,
+            now,
+            self.exchange_client.get_initial_extension_data(),
+        );
+        let stop_loss_order = self.orders.add_simple_initial(
+            stop_loss_header,
+            now,
+            self.exchange_client.get_initial_extension_data(),
+        );
+        self.orders.add_linked_pair(&limit_order, &stop_loss_order);
+
+        if let Err(violation) = self
+            .apply_pre_trade_risk_checks(limit_header, cancellation_token.clone())
+            .await
+        {
+            for order in [&limit_order, &stop_loss_order] {
+                self.reject_order_before_creation(
+                    order,
+                    ExchangeErrorType::RiskCheckFailed,
+                    violation.to_string(),
+                )?;
+            }
+
+            bail!(
+                "OCO order {} was rejected by pre-trade risk checks: {violation}",
+                limit_header.client_order_id
+            );
+        }
+
+        let create_result = tokio::select! {
+            result = self.exchange_client.create_oco_order(&limit_order, &stop_loss_order) => result,
+            _ = cancellation_token.when_cancelled() => bail!(OPERATION_CANCELED_MSG),
+        };
+
+        match &create_result.outcome {
+            Success((limit_order_id, stop_loss_order_id)) => {
+                for (order, exchange_order_id) in [
+                    (&limit_order, limit_order_id),
+                    (&stop_loss_order, stop_loss_order_id),
+                ] {
+                    self.handle_create_order_succeeded(
+                        self.exchange_account_id,
+                        &order.client_order_id(),
+                        exchange_order_id,
+                        create_result.source_type,
+                    )?;
+                }
+            }
+            Error(exchange_error) => {
+                for order in [&limit_order, &stop_loss_order] {
+                    self.handle_create_order_failed(
+                        &order.client_order_id(),
+                        exchange_error,
+                        create_result.source_type,
+                    )?;
+                }
+
+                bail!(
+                    "OCO order {} creation failed: {exchange_error:?}",
+                    limit_header.client_order_id
+                );
+            }
+        }
+
+        Ok((limit_order, stop_loss_order))
+    }
+
+    /// Reservation shared by OCO order legs is approved and released for the limit leg only
+    pub(super) fn is_secondary_linked_order(&self, order: &OrderRef) -> bool {
+        order.order_type() == OrderType::StopLoss
+            && self
+                .orders
+                .linked_orders
+                .contains_key(&order.client_order_id())
+    }
+
+    /// Exchange finalizes the opposite leg of OCO order by itself, so we just wait for its event
+    /// and release the shared reservation when both legs are finished
+    pub(crate) fn handle_linked_order_finished(&self, order: &OrderRef) {
+        let client_order_id = order.client_order_id();
+        let linked_order = match self.orders.get_linked_order(&client_order_id) {
+            None => return,
+            Some(linked_order) => linked_order,
+        };
+
+        if !linked_order.is_finished() {
+            log::info!(
+                "OCO order leg {client_order_id} was finished with status {:?}, waiting for linked order {} to be finalized on {}",
+                order.status(),
+                linked_order.client_order_id(),
+                self.exchange_account_id
+            );
+            return;
+        }
+
+        self.orders.remove_linked_pair(&client_order_id);
+
+        let limit_order = match order.order_type() {
+            OrderType::StopLoss => &linked_order,
+            _ => order,
+        };
+
+        let reservation_id = match limit_order.header().reservation_id {
+            None => return,
+            Some(reservation_id) => reservation_id,
+        };
+
+        let bm_lock = self.balance_manager.lock();
+        match bm_lock.as_ref().and_then(|x| x.upgrade()) {
+            None => log::warn!("BalanceManager ref can't be upgraded to release reservation {reservation_id} of OCO order {client_order_id}"),
+            Some(balance_manager) => {
+                if let Err(error) = balance_manager
+                    .lock()
+                    .release_linked_orders_reservation(reservation_id, &limit_order.client_order_id())
+                {
+                    log::error!("Unable to release reservation {reservation_id} of OCO order {client_order_id}: {error:?}");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper::get_test_exchange;
+    use mmb_domain::market::CurrencyPair;
+    use mmb_domain::order::snapshot::{ClientOrderId, OrderOptions, OrderSide, UserOrder};
+    use rust_decimal_macros::dec;
+
+    fn order_header(exchange: &Exchange, options: OrderOptions) -> OrderHeader {
+        OrderHeader::with_options(
+            ClientOrderId::unique_id_with_strategy_tag("Strategy"),
+            exchange.exchange_account_id,
+            CurrencyPair::from_codes("phb".into(), "btc".into()),
+            OrderSide::Sell,
+            dec!(1),
+            options,
+            None,
+            None,
+            "".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn create_oco_order_when_not_supported() {
+        let (exchange, _rx) = get_test_exchange(false);
+        let limit_header = order_header(&exchange, OrderOptions::limit(dec!(0.5)));
+        let stop_loss_header = order_header(
+            &exchange,
+            OrderOptions::User(UserOrder::StopLoss {
+                stop_price: dec!(0.3),
+            }),
+        );
+
+        let result = exchange
+            .create_oco_order(&limit_header, &stop_loss_header, CancellationToken::new())
+            .await;
+
+        assert!(result.is_err());
+        assert!(exchange.orders.cache_by_client_id.is_empty());
+    }
+
+    #[tokio::test]
+    async fn only_stop_loss_leg_is_secondary_linked_order() {
+        let (exchange, _rx) = get_test_exchange(false);
+        let limit_header = order_header(&exchange, OrderOptions::limit(dec!(0.5)));
+        let stop_loss_header = order_header(
+            &exchange,
+            OrderOptions::User(UserOrder::StopLoss {
+                stop_price: dec!(0.3),
+            }),
+        );
+
+        let limit_order =
+            exchange
+                .orders
+                .add_simple_initial(&limit_header, time_manager::now(), None);
+        let stop_loss_order =
+            exchange
+                .orders
+                .add_simple_initial(&stop_loss_header, time_manager::now(), None);
+        assert!(!exchange.is_secondary_linked_order(&stop_loss_order));
+
+        exchange
+            .orders
+            .add_linked_pair(&limit_order, &stop_loss_order);
+
+        assert!(!exchange.is_secondary_linked_order(&limit_order));
+        assert!(exchange.is_secondary_linked_order(&stop_loss_order));
+        assert_eq!(
+            exchange
+                .orders
+                .get_linked_order(&limit_order.client_order_id()),
+            Some(stop_loss_order.clone())
+        );
+
+        exchange
+            .orders
+            .remove_linked_pair(&stop_loss_order.client_order_id());
+        assert!(exchange.orders.linked_orders.is_empty());
+    }
+}
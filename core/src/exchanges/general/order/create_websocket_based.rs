@@ -3,6 +3,7 @@ use mmb_domain::order::snapshot::{ClientOrderId, ExchangeOrderId};
 use mmb_utils::cancellation_token::CancellationToken;
 use tokio::sync::oneshot;
 
+use crate::exchanges::traits::ExchangeError;
 use crate::{exchanges::general::exchange::Exchange, exchanges::general::exchange::RequestResult};
 use mmb_domain::order::pool::OrderRef;
 use mmb_utils::infrastructure::WithExpect;
@@ -64,4 +65,21 @@ impl Exchange {
             .with_expect(|| format!("Error handle create order succeeded for {client_order_id:?}"));
         }
     }
+
+    pub(crate) fn raise_order_creation_failed(
+        &self,
+        client_order_id: &ClientOrderId,
+        exchange_error: ExchangeError,
+        source_type: EventSourceType,
+    ) {
+        if let Some((_, (tx, _))) = self.order_creation_events.remove(client_order_id) {
+            if let Err(error) = tx.send(CreateOrderResult::failed(exchange_error, source_type)) {
+                log::error!("Unable to send thru oneshot channel: {error:?}");
+            }
+        } else if let Err(error) =
+            self.handle_create_order_failed(client_order_id, &exchange_error, source_type)
+        {
+            log::error!("Error handle create order failed for {client_order_id:?}: {error:?}");
+        }
+    }
 }
@@ -88,7 +88,7 @@ impl Exchange {
         Ok(open_orders)
     }
 
-    fn add_missing_open_orders(&self, open_orders: &[OrderInfo]) {
+    pub(super) fn add_missing_open_orders(&self, open_orders: &[OrderInfo]) {
         for order_info in open_orders {
             if order_info.client_order_id.as_str().is_empty()
                 && self
@@ -12,7 +12,7 @@ use mmb_domain::order::snapshot::{ExchangeOrderId, OrderRole};
 use mmb_utils::DateTime;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OrderTrade {
     pub exchange_order_id: ExchangeOrderId,
     pub trade_id: TradeId,
@@ -1,5 +1,7 @@
+pub mod adopt;
 pub mod cancel;
 pub mod create;
+pub mod create_oco;
 pub mod create_websocket_based;
 pub mod get_info;
 pub mod get_open_orders;
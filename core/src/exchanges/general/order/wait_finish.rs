@@ -289,7 +289,7 @@ impl Exchange {
         }
     }
 
-    pub(super) async fn check_order_fills(
+    pub(crate) async fn check_order_fills(
         &self,
         order: &OrderRef,
         exit_on_order_is_finished_even_if_fills_didnt_received: bool,
@@ -0,0 +1,493 @@
+use crate::exchanges::general::exchange::Exchange;
+use crate::misc::time::time_manager;
+#[double]
+use crate::services::usd_convertion::usd_converter::UsdConverter;
+use mmb_domain::market::CurrencyCode;
+use mmb_domain::order::snapshot::{Amount, OrderHeader, OrderSide, Price};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::DateTime;
+use mockall_double::double;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Reason why order was rejected by pre-trade risk checks
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PreTradeRiskViolation {
+    #[error("order notional {notional_usd} USD exceeds limit {limit} USD")]
+    OrderNotionalTooHigh { notional_usd: Amount, limit: Amount },
+    #[error("can't calculate order notional in USD")]
+    UnknownOrderNotional,
+    #[error("order price {price} deviates from mid price {mid_price} more than {limit}")]
+    PriceDeviationTooHigh {
+        price: Price,
+        mid_price: Price,
+        limit: Decimal,
+    },
+    #[error("open orders count {count} reached limit {limit}")]
+    TooManyOpenOrders { count: usize, limit: usize },
+    #[error("orders rate of strategy `{strategy_name}` reached limit {limit} orders per second")]
+    OrdersRateTooHigh { strategy_name: String, limit: u32 },
+    #[error("net position {position} after order execution exceeds limit {limit}")]
+    NetPositionTooHigh { position: Amount, limit: Amount },
+}
+
+/// State of pre-trade risk checks for single exchange.
+/// Limits are taken from `ExchangeSettings::pre_trade_risk`
+#[derive(Default)]
+pub struct PreTradeRiskChecker {
+    usd_converter: Mutex<Option<Arc<UsdConverter>>>,
+    orders_times_by_strategy: Mutex<HashMap<String, VecDeque<DateTime>>>,
+}
+
+impl Exchange {
+    pub fn setup_usd_converter(&self, usd_converter: Arc<UsdConverter>) {
+        *self.pre_trade_risk_checker.usd_converter.lock() = Some(usd_converter);
+    }
+
+    /// Checks all pre-trade risk limits except order notional in USD that requires async conversion.
+    /// Doesn't change orders rate state, so it can be used for preliminary check before order creation
+    pub fn check_pre_trade_risk(
+        &self,
+        order_header: &OrderHeader,
+    ) -> Result<(), PreTradeRiskViolation> {
+        let settings = match &self.exchange_client.get_settings().pre_trade_risk {
+            Some(settings) => settings,
+            None => return Ok(()),
+        };
+
+        if let Some(max_price_deviation) = settings.max_price_deviation {
+            if let (Some(price), Some(mid_price)) =
+                (order_header.source_price, self.mid_price(order_header))
+            {
+                check_price_deviation(price, mid_price, max_price_deviation)?;
+            }
+        }
+
+        if let Some(max_open_orders) = settings.max_open_orders_per_market {
+            let count = self
+                .orders
+                .not_finished
+                .iter()
+                .filter(|x| {
+                    x.currency_pair() == order_header.currency_pair
+                        && x.client_order_id() != order_header.client_order_id
+                })
+                .count();
+
+            if count >= max_open_orders {
+                return Err(PreTradeRiskViolation::TooManyOpenOrders {
+                    count,
+                    limit: max_open_orders,
+                });
+            }
+        }
+
+        if let Some(max_orders_per_second) = settings.max_orders_per_second_per_strategy {
+            let mut orders_times_by_strategy =
+                self.pre_trade_risk_checker.orders_times_by_strategy.lock();
+            let orders_times = orders_times_by_strategy
+                .entry(order_header.strategy_name.clone())
+                .or_default();
+
+            check_orders_rate(
+                orders_times,
+                time_manager::now(),
+                max_orders_per_second,
+                &order_header.strategy_name,
+            )?;
+        }
+
+        if let Some(max_net_position) = settings.max_net_position {
+            if let Some(position) = self.get_net_position(order_header) {
+                check_net_position(
+                    position,
+                    order_header.side,
+                    order_header.amount,
+                    max_net_position,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks all pre-trade risk limits and registers order for orders rate limit if checks passed
+    pub(crate) async fn apply_pre_trade_risk_checks(
+        &self,
+        order_header: &OrderHeader,
+        cancellation_token: CancellationToken,
+    ) -> Result<(), PreTradeRiskViolation> {
+        let settings = match &self.exchange_client.get_settings().pre_trade_risk {
+            Some(settings) => settings,
+            None => return Ok(()),
+        };
+
+        self.check_pre_trade_risk(order_header)?;
+
+        if let Some(max_order_notional_usd) = settings.max_order_notional_usd {
+            let notional_usd = self
+                .calc_order_notional_usd(order_header, cancellation_token)
+                .await
+                .ok_or(PreTradeRiskViolation::UnknownOrderNotional)?;
+
+            if notional_usd > max_order_notional_usd {
+                return Err(PreTradeRiskViolation::OrderNotionalTooHigh {
+                    notional_usd,
+                    limit: max_order_notional_usd,
+                });
+            }
+        }
+
+        if let Some(max_orders_per_second) = settings.max_orders_per_second_per_strategy {
+            // Orders rate is checked again under the same lock as order registration,
+            // so concurrent orders can't exceed the limit while notional conversion is awaited
+            let mut orders_times_by_strategy =
+                self.pre_trade_risk_checker.orders_times_by_strategy.lock();
+            let orders_times = orders_times_by_strategy
+                .entry(order_header.strategy_name.clone())
+                .or_default();
+
+            let now = time_manager::now();
+            check_orders_rate(
+                orders_times,
+                now,
+                max_orders_per_second,
+                &order_header.strategy_name,
+            )?;
+            orders_times.push_back(now);
+        }
+
+        Ok(())
+    }
+
+    fn mid_price(&self, order_header: &OrderHeader) -> Option<Price> {
+        let order_book_top = self.order_book_top.get(&order_header.currency_pair)?;
+        let ask = order_book_top.ask.as_ref()?.price;
+        let bid = order_book_top.bid.as_ref()?.price;
+        Some((ask + bid) / dec!(2))
+    }
+
+    /// Signed net position: positive for long, negative for short
+    fn get_net_position(&self, order_header: &OrderHeader) -> Option<Amount> {
+        let symbol = self.symbols.get(&order_header.currency_pair)?.clone();
+        let balance_manager = self.balance_manager.lock().as_ref()?.upgrade()?;
+
+        let side = match symbol.is_derivative {
+            true => OrderSide::Buy,
+            false => OrderSide::Sell,
+        };
+
+        let position = balance_manager.lock().get_position(
+            self.exchange_account_id,
+            order_header.currency_pair,
+            side,
+        );
+
+        Some(position)
+    }
+
+    async fn calc_order_notional_usd(
+        &self,
+        order_header: &OrderHeader,
+        cancellation_token: CancellationToken,
+    ) -> Option<Amount> {
+        let symbol = self.symbols.get(&order_header.currency_pair)?.clone();
+        let price = order_header
+            .source_price
+            .or_else(|| self.mid_price(order_header))?;
+
+        let quote = symbol.quote_currency_code;
+        let notional =
+            symbol.convert_amount_from_amount_currency_code(quote, order_header.amount, price);
+
+        let usd_converter = self.pre_trade_risk_checker.usd_converter.lock().clone();
+        match usd_converter {
+            Some(usd_converter) => {
+                usd_converter
+                    .convert_amount(quote, notional, cancellation_token)
+                    .await
+            }
+            None => is_usd(quote).then_some(notional),
+        }
+    }
+}
+
+fn is_usd(currency_code: CurrencyCode) -> bool {
+    currency_code == "USD".into() || currency_code == "USDT".into()
+}
+
+fn check_price_deviation(
+    price: Price,
+    mid_price: Price,
+    max_price_deviation: Decimal,
+) -> Result<(), PreTradeRiskViolation> {
+    if mid_price.is_zero() {
+        return Ok(());
+    }
+
+    if ((price - mid_price) / mid_price).abs() > max_price_deviation {
+        return Err(PreTradeRiskViolation::PriceDeviationTooHigh {
+            price,
+            mid_price,
+            limit: max_price_deviation,
+        });
+    }
+
+    Ok(())
+}
+
+fn check_orders_rate(
+    orders_times: &mut VecDeque<DateTime>,
+    now: DateTime,
+    max_orders_per_second: u32,
+    strategy_name: &str,
+) -> Result<(), PreTradeRiskViolation> {
+    let window_start = now - chrono::Duration::seconds(1);
+    while orders_times.front().map_or(false, |&x| x <= window_start) {
+        let _ = orders_times.pop_front();
+    }
+
+    if orders_times.len() >= max_orders_per_second as usize {
+        return Err(PreTradeRiskViolation::OrdersRateTooHigh {
+            strategy_name: strategy_name.to_owned(),
+            limit: max_orders_per_second,
+        });
+    }
+
+    Ok(())
+}
+
+/// Orders that reduce absolute value of net position are always allowed
+fn check_net_position(
+    position: Amount,
+    side: OrderSide,
+    amount: Amount,
+    max_net_position: Amount,
+) -> Result<(), PreTradeRiskViolation> {
+    let new_position = match side {
+        OrderSide::Buy => position + amount,
+        OrderSide::Sell => position - amount,
+    };
+
+    if new_position.abs() > max_net_position && new_position.abs() > position.abs() {
+        return Err(PreTradeRiskViolation::NetPositionTooHigh {
+            position: new_position,
+            limit: max_net_position,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper::{
+        create_order_ref, get_test_exchange_with_settings,
+    };
+    use crate::settings::{ExchangeSettings, PreTradeRiskSettings};
+    use chrono::{Duration, TimeZone, Utc};
+    use mmb_domain::exchanges::symbol::{Precision, Symbol};
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_domain::order::snapshot::ClientOrderId;
+
+    #[tokio::test]
+    async fn order_notional_in_non_usd_quote_currency() {
+        let exchange_account_id = ExchangeAccountId::new("local_exchange_account_id", 0);
+        let symbol = Arc::new(Symbol::new(
+            false,
+            "ETH".into(),
+            "ETH".into(),
+            "EUR".into(),
+            "EUR".into(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            "ETH".into(),
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0) },
+        ));
+        let settings = ExchangeSettings {
+            pre_trade_risk: Some(PreTradeRiskSettings {
+                max_order_notional_usd: Some(dec!(210)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let (exchange, _rx) =
+            get_test_exchange_with_settings(symbol.clone(), exchange_account_id, settings);
+
+        let (mut usd_converter, _usd_converter_locker) = UsdConverter::init_mock();
+        usd_converter
+            .expect_convert_amount()
+            .returning(|from, amount, _| (from == "EUR".into()).then(|| amount * dec!(1.1)));
+        exchange.setup_usd_converter(Arc::new(usd_converter));
+
+        let order_header = |amount| {
+            create_order_ref(
+                &ClientOrderId::unique_id(),
+                None,
+                exchange_account_id,
+                symbol.currency_pair(),
+                dec!(100),
+                amount,
+                OrderSide::Buy,
+            )
+            .header()
+            .clone()
+        };
+
+        // 1.9 ETH * 100 EUR * 1.1 USD = 209 USD
+        assert_eq!(
+            exchange
+                .apply_pre_trade_risk_checks(&order_header(dec!(1.9)), CancellationToken::new())
+                .await,
+            Ok(())
+        );
+        // 2 ETH * 100 EUR * 1.1 USD = 220 USD
+        assert_eq!(
+            exchange
+                .apply_pre_trade_risk_checks(&order_header(dec!(2)), CancellationToken::new())
+                .await,
+            Err(PreTradeRiskViolation::OrderNotionalTooHigh {
+                notional_usd: dec!(220),
+                limit: dec!(210),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn orders_rate_is_checked_on_order_registration() {
+        let exchange_account_id = ExchangeAccountId::new("local_exchange_account_id", 0);
+        let symbol = Arc::new(Symbol::new(
+            false,
+            "ETH".into(),
+            "ETH".into(),
+            "USDT".into(),
+            "USDT".into(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            "ETH".into(),
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0) },
+        ));
+        let settings = ExchangeSettings {
+            pre_trade_risk: Some(PreTradeRiskSettings {
+                max_orders_per_second_per_strategy: Some(1),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let (exchange, _rx) =
+            get_test_exchange_with_settings(symbol.clone(), exchange_account_id, settings);
+
+        let order_header = || {
+            create_order_ref(
+                &ClientOrderId::unique_id(),
+                None,
+                exchange_account_id,
+                symbol.currency_pair(),
+                dec!(100),
+                dec!(1),
+                OrderSide::Buy,
+            )
+            .header()
+            .clone()
+        };
+        let first_order_header = order_header();
+        let second_order_header = order_header();
+
+        let (first_result, second_result) = tokio::join!(
+            exchange.apply_pre_trade_risk_checks(&first_order_header, CancellationToken::new()),
+            exchange.apply_pre_trade_risk_checks(&second_order_header, CancellationToken::new()),
+        );
+
+        assert_eq!(first_result, Ok(()));
+        assert_eq!(
+            second_result,
+            Err(PreTradeRiskViolation::OrdersRateTooHigh {
+                strategy_name: second_order_header.strategy_name.clone(),
+                limit: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn price_deviation() {
+        assert_eq!(
+            check_price_deviation(dec!(104), dec!(100), dec!(0.05)),
+            Ok(())
+        );
+        assert_eq!(
+            check_price_deviation(dec!(96), dec!(100), dec!(0.05)),
+            Ok(())
+        );
+        assert_eq!(
+            check_price_deviation(dec!(110), dec!(100), dec!(0.05)),
+            Err(PreTradeRiskViolation::PriceDeviationTooHigh {
+                price: dec!(110),
+                mid_price: dec!(100),
+                limit: dec!(0.05),
+            })
+        );
+    }
+
+    #[test]
+    fn orders_rate() {
+        let now = Utc.ymd(2022, 1, 1).and_hms(0, 0, 10);
+        let mut orders_times = VecDeque::from(vec![
+            now - Duration::milliseconds(1500),
+            now - Duration::milliseconds(500),
+            now - Duration::milliseconds(100),
+        ]);
+
+        assert_eq!(check_orders_rate(&mut orders_times, now, 3, "test"), Ok(()));
+        assert_eq!(orders_times.len(), 2);
+
+        assert_eq!(
+            check_orders_rate(&mut orders_times, now, 2, "test"),
+            Err(PreTradeRiskViolation::OrdersRateTooHigh {
+                strategy_name: "test".to_owned(),
+                limit: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn net_position() {
+        assert_eq!(
+            check_net_position(dec!(8), OrderSide::Buy, dec!(2), dec!(10)),
+            Ok(())
+        );
+        assert_eq!(
+            check_net_position(dec!(8), OrderSide::Buy, dec!(3), dec!(10)),
+            Err(PreTradeRiskViolation::NetPositionTooHigh {
+                position: dec!(11),
+                limit: dec!(10),
+            })
+        );
+        assert_eq!(
+            check_net_position(dec!(-9), OrderSide::Sell, dec!(2), dec!(10)),
+            Err(PreTradeRiskViolation::NetPositionTooHigh {
+                position: dec!(-11),
+                limit: dec!(10),
+            })
+        );
+        // reducing of position above limit is allowed
+        assert_eq!(
+            check_net_position(dec!(15), OrderSide::Sell, dec!(2), dec!(10)),
+            Ok(())
+        );
+    }
+}
@@ -22,4 +22,5 @@ pub enum RequestType {
     GetProfileId,
     GetMyTrades,
     SetLeverage,
+    ConnectWebSocketApi,
 }
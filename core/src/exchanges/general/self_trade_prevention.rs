@@ -0,0 +1,224 @@
+use crate::exchanges::general::exchange::Exchange;
+use crate::settings::SelfTradePreventionPolicy;
+use crate::statistic_service::StatisticService;
+use futures::future::join_all;
+use mmb_domain::market::MarketAccountId;
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{
+    OrderHeader, OrderOptions, OrderSide, OrderStatus, Price, UserOrder,
+};
+use mmb_utils::cancellation_token::CancellationToken;
+use std::sync::Arc;
+
+/// Result of self-trade check of new order against not finished orders of opposite side
+#[derive(Debug)]
+pub enum SelfTradeCheck {
+    NoCross,
+    Skip { crossed_orders: Vec<OrderRef> },
+    Reprice { header: OrderHeader },
+    CancelResting { crossed_orders: Vec<OrderRef> },
+}
+
+impl Exchange {
+    pub fn setup_statistic_service(&self, statistic_service: Arc<StatisticService>) {
+        *self.statistic_service.lock() = Some(statistic_service);
+    }
+
+    /// Detects crossing of new order with not finished orders of the same market
+    /// and chooses reaction according to `ExchangeSettings::self_trade_prevention`
+    pub fn check_self_trade(&self, order_header: &OrderHeader) -> SelfTradeCheck {
+        let settings = match &self.exchange_client.get_settings().self_trade_prevention {
+            Some(settings) => settings,
+            None => return SelfTradeCheck::NoCross,
+        };
+
+        let crossed_orders = self.find_crossed_orders(order_header);
+        if crossed_orders.is_empty() {
+            return SelfTradeCheck::NoCross;
+        }
+
+        match settings.policy {
+            SelfTradePreventionPolicy::SkipNew => SelfTradeCheck::Skip { crossed_orders },
+            SelfTradePreventionPolicy::RepriceNew => {
+                // Order that is already in orders pool (e.g. registered by DispositionExecutor) can't be repriced
+                if self
+                    .orders
+                    .cache_by_client_id
+                    .contains_key(&order_header.client_order_id)
+                {
+                    return SelfTradeCheck::Skip { crossed_orders };
+                }
+
+                match self.reprice_header(order_header, &crossed_orders) {
+                    Some(header) => SelfTradeCheck::Reprice { header },
+                    None => SelfTradeCheck::Skip { crossed_orders },
+                }
+            }
+            SelfTradePreventionPolicy::CancelResting => {
+                SelfTradeCheck::CancelResting { crossed_orders }
+            }
+        }
+    }
+
+    pub(crate) fn register_prevented_self_trade(&self, order_header: &OrderHeader) {
+        if let Some(statistic_service) = self.statistic_service.lock().as_ref() {
+            statistic_service.register_prevented_self_trade(MarketAccountId::new(
+                self.exchange_account_id,
+                order_header.currency_pair,
+            ));
+        }
+    }
+
+    /// Reservation made for the original price is recalculated with the price of repriced order.
+    /// Returns `false` if balance isn't enough for the new price
+    pub(super) fn try_update_repriced_reservation(&self, order_header: &OrderHeader) -> bool {
+        let (reservation_id, price) = match (order_header.reservation_id, order_header.source_price)
+        {
+            (Some(reservation_id), Some(price)) => (reservation_id, price),
+            _ => return true,
+        };
+
+        let balance_manager = self
+            .balance_manager
+            .lock()
+            .as_ref()
+            .and_then(|balance_manager| balance_manager.upgrade());
+        match balance_manager {
+            Some(balance_manager) => balance_manager
+                .lock()
+                .try_update_reservation(reservation_id, price),
+            None => {
+                log::warn!(
+                    "BalanceManager ref can't be upgraded to update reservation {reservation_id} of repriced order {}",
+                    order_header.client_order_id
+                );
+                true
+            }
+        }
+    }
+
+    pub(super) async fn cancel_crossed_orders(
+        &self,
+        crossed_orders: &[OrderRef],
+        cancellation_token: CancellationToken,
+    ) {
+        let cancel_futures = crossed_orders.iter().map(|order| {
+            let client_order_id = order.client_order_id();
+            log::info!("Cancelling order {client_order_id} to prevent self-trade");
+
+            self.wait_cancel_order(order.clone(), None, true, cancellation_token.clone())
+        });
+
+        for result in join_all(cancel_futures).await {
+            if let Err(error) = result {
+                log::error!("Failed to cancel order to prevent self-trade: {error:?}");
+            }
+        }
+    }
+
+    fn find_crossed_orders(&self, order_header: &OrderHeader) -> Vec<OrderRef> {
+        self.orders
+            .not_finished
+            .iter()
+            .filter(|x| {
+                x.currency_pair() == order_header.currency_pair
+                    && x.side() != order_header.side
+                    && x.client_order_id() != order_header.client_order_id
+                    && x.status() != OrderStatus::FailedToCreate
+            })
+            .filter(|x| match x.source_price() {
+                Some(resting_price) => {
+                    is_crossing(order_header.side, order_header.source_price, resting_price)
+                }
+                None => false,
+            })
+            .map(|x| x.clone())
+            .collect()
+    }
+
+    fn reprice_header(
+        &self,
+        order_header: &OrderHeader,
+        crossed_orders: &[OrderRef],
+    ) -> Option<OrderHeader> {
+        let execution_type = match &order_header.options {
+            OrderOptions::User(UserOrder::Limit { execution_type, .. }) => *execution_type,
+            _ => return None,
+        };
+
+        let tick = self
+            .symbols
+            .get(&order_header.currency_pair)?
+            .price_precision
+            .get_tick();
+
+        let crossed_prices = crossed_orders.iter().filter_map(|x| x.source_price());
+        let price = calc_repriced_price(order_header.side, crossed_prices, tick)?;
+
+        let mut header = order_header.clone();
+        header.options = OrderOptions::User(UserOrder::Limit {
+            price,
+            execution_type,
+        });
+        header.source_price = Some(price);
+
+        Some(header)
+    }
+}
+
+/// New order without price (market order) crosses any resting order of opposite side
+fn is_crossing(side: OrderSide, price: Option<Price>, resting_price: Price) -> bool {
+    match (side, price) {
+        (_, None) => true,
+        (OrderSide::Buy, Some(price)) => price >= resting_price,
+        (OrderSide::Sell, Some(price)) => price <= resting_price,
+    }
+}
+
+/// Price one tick before the best crossed order
+fn calc_repriced_price(
+    side: OrderSide,
+    crossed_prices: impl Iterator<Item = Price>,
+    tick: Price,
+) -> Option<Price> {
+    match side {
+        OrderSide::Buy => crossed_prices.min().map(|x| x - tick),
+        OrderSide::Sell => crossed_prices.max().map(|x| x + tick),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn crossing() {
+        assert!(is_crossing(OrderSide::Buy, Some(dec!(100)), dec!(100)));
+        assert!(is_crossing(OrderSide::Buy, Some(dec!(101)), dec!(100)));
+        assert!(!is_crossing(OrderSide::Buy, Some(dec!(99)), dec!(100)));
+
+        assert!(is_crossing(OrderSide::Sell, Some(dec!(99)), dec!(100)));
+        assert!(!is_crossing(OrderSide::Sell, Some(dec!(101)), dec!(100)));
+
+        assert!(is_crossing(OrderSide::Sell, None, dec!(100)));
+    }
+
+    #[test]
+    fn repriced_price() {
+        let prices = || vec![dec!(100), dec!(99.5), dec!(101)].into_iter();
+
+        assert_eq!(
+            calc_repriced_price(OrderSide::Buy, prices(), dec!(0.1)),
+            Some(dec!(99.4))
+        );
+        assert_eq!(
+            calc_repriced_price(OrderSide::Sell, prices(), dec!(0.1)),
+            Some(dec!(101.1))
+        );
+        assert_eq!(
+            calc_repriced_price(OrderSide::Buy, vec![].into_iter(), dec!(0.1)),
+            None
+        );
+    }
+}
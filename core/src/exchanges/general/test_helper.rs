@@ -22,21 +22,25 @@ use crate::{
     },
     settings::ExchangeSettings,
 };
-use anyhow::Result;
+use anyhow::{bail, Result};
 use async_trait::async_trait;
 use chrono::Duration;
 use dashmap::DashMap;
 use futures::executor::block_on;
-use mmb_domain::events::{AllowedEventSourceType, ExchangeBalancesAndPositions, ExchangeEvent};
+use mmb_domain::events::{
+    AllowedEventSourceType, EventSourceType, ExchangeBalancesAndPositions, ExchangeEvent,
+};
 use mmb_domain::exchanges::commission::{Commission, CommissionForType};
 use mmb_domain::exchanges::symbol::{BeforeAfter, Precision, Symbol};
 use mmb_domain::market::{
-    CurrencyCode, CurrencyId, CurrencyPair, ExchangeAccountId, SpecificCurrencyPair,
+    CurrencyCode, CurrencyId, CurrencyPair, ExchangeAccountId, ExchangeErrorType,
+    SpecificCurrencyPair,
 };
 use mmb_domain::order::pool::{OrderRef, OrdersPool};
 use mmb_domain::order::snapshot::{Amount, ExchangeOrderId, OrderOptions, Price};
 use mmb_domain::order::snapshot::{ClientOrderId, OrderInfo, OrderRole, OrderSide, OrderSnapshot};
 use mmb_domain::position::{ActivePosition, ClosedPosition};
+use parking_lot::Mutex;
 use rust_decimal_macros::dec;
 use tokio::sync::broadcast;
 use url::Url;
@@ -44,6 +48,7 @@ use url::Url;
 use crate::database::events::recorder::EventRecorder;
 use crate::exchanges::exchange_blocker::ExchangeBlocker;
 use crate::exchanges::general::exchange::RequestResult;
+use crate::exchanges::general::exchange_creation::create_commission;
 use crate::exchanges::general::order::cancel::CancelOrderResult;
 use crate::exchanges::general::order::create::CreateOrderResult;
 use crate::exchanges::timeouts::requests_timeout_manager_factory::RequestsTimeoutManagerFactory;
@@ -52,9 +57,20 @@ use crate::exchanges::traits::{
 };
 use mmb_utils::{cancellation_token::CancellationToken, hashmap, DateTime};
 
+use super::market_data_connections::{MarketDataShardChanges, MarketDataShards};
 use super::order::get_order_trades::OrderTrade;
 
-pub struct TestClient;
+#[derive(Default)]
+pub struct TestClient {
+    pub settings: ExchangeSettings,
+    pub symbols: Vec<Arc<Symbol>>,
+    pub market_data_shards: Mutex<MarketDataShards>,
+    pub market_data_changes: Mutex<Vec<MarketDataShardChanges>>,
+    pub open_orders: Vec<OrderInfo>,
+    pub cancelled_orders: Mutex<Vec<ExchangeOrderId>>,
+    pub fail_cancel_all_orders: bool,
+    pub cancelled_all_orders: Mutex<Vec<CurrencyPair>>,
+}
 
 #[async_trait]
 impl ExchangeClient for TestClient {
@@ -62,20 +78,34 @@ impl ExchangeClient for TestClient {
         unimplemented!("doesn't need in UT")
     }
 
+    /// Orders are considered as already cancelled on exchange, so cancellation is finished without websocket event
     async fn cancel_order(
         &self,
         _order: &OrderRef,
-        _exchange_order_id: &ExchangeOrderId,
+        exchange_order_id: &ExchangeOrderId,
     ) -> CancelOrderResult {
-        unimplemented!("doesn't need in UT")
+        self.cancelled_orders.lock().push(exchange_order_id.clone());
+        CancelOrderResult::failed(
+            ExchangeError::new(
+                ExchangeErrorType::OrderNotFound,
+                "Order was cancelled in UT".to_owned(),
+                None,
+            ),
+            EventSourceType::Rest,
+        )
     }
 
-    async fn cancel_all_orders(&self, _currency_pair: CurrencyPair) -> Result<()> {
-        unimplemented!("doesn't need in UT")
+    async fn cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()> {
+        if self.fail_cancel_all_orders {
+            bail!("Failed to cancel all orders {currency_pair} in UT");
+        }
+
+        self.cancelled_all_orders.lock().push(currency_pair);
+        Ok(())
     }
 
     async fn get_open_orders(&self) -> Result<Vec<OrderInfo>> {
-        unimplemented!("doesn't need in UT")
+        Ok(self.open_orders.clone())
     }
 
     async fn get_open_orders_by_currency_pair(
@@ -114,7 +144,7 @@ impl ExchangeClient for TestClient {
     }
 
     async fn build_all_symbols(&self) -> Result<Vec<Arc<Symbol>>> {
-        unimplemented!("doesn't need in UT")
+        Ok(self.symbols.clone())
     }
 
     async fn get_server_time(&self) -> Option<Result<i64>> {
@@ -155,7 +185,13 @@ impl Support for TestClient {
 
     fn set_handle_metrics_callback(&mut self, _callback: HandleMetricsCb) {}
 
-    fn set_traded_specific_currencies(&self, _currencies: Vec<SpecificCurrencyPair>) {}
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        let changes = self
+            .market_data_shards
+            .lock()
+            .set_currency_pairs(&currencies);
+        self.market_data_changes.lock().extend(changes);
+    }
 
     fn is_websocket_enabled(&self, _role: WebSocketRole) -> bool {
         unimplemented!("doesn't need in UT")
@@ -165,8 +201,8 @@ impl Support for TestClient {
         unimplemented!("doesn't need in UT")
     }
 
-    fn get_specific_currency_pair(&self, _currency_pair: CurrencyPair) -> SpecificCurrencyPair {
-        unimplemented!("doesn't need in UT")
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        currency_pair.as_str().into()
     }
 
     fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
@@ -190,7 +226,7 @@ impl Support for TestClient {
     }
 
     fn get_settings(&self) -> &ExchangeSettings {
-        unimplemented!("doesn't need in UT")
+        &self.settings
     }
 }
 
@@ -255,16 +291,39 @@ pub(crate) fn get_test_exchange_with_symbol(
 pub(crate) fn get_test_exchange_with_symbol_and_id(
     symbol: Arc<Symbol>,
     exchange_account_id: ExchangeAccountId,
+) -> (Arc<Exchange>, broadcast::Receiver<ExchangeEvent>) {
+    get_test_exchange_with_settings(symbol, exchange_account_id, ExchangeSettings::default())
+}
+
+pub(crate) fn get_test_exchange_with_settings(
+    symbol: Arc<Symbol>,
+    exchange_account_id: ExchangeAccountId,
+    settings: ExchangeSettings,
+) -> (Arc<Exchange>, broadcast::Receiver<ExchangeEvent>) {
+    let exchange_client = TestClient {
+        settings,
+        ..Default::default()
+    };
+    get_test_exchange_with_client(symbol, exchange_account_id, exchange_client)
+}
+
+pub(crate) fn get_test_exchange_with_client(
+    symbol: Arc<Symbol>,
+    exchange_account_id: ExchangeAccountId,
+    exchange_client: TestClient,
 ) -> (Arc<Exchange>, broadcast::Receiver<ExchangeEvent>) {
     let lifetime_manager = AppLifetimeManager::new(CancellationToken::new());
     let (tx, rx) = broadcast::channel(10);
 
-    let exchange_client = Box::new(TestClient);
     let referral_reward = dec!(40);
-    let commission = Commission::new(
-        CommissionForType::new(dec!(0.1), referral_reward),
-        CommissionForType::new(dec!(0.2), referral_reward),
-    );
+    let commission = match exchange_client.settings.commission {
+        Some(_) => create_commission(&exchange_client.settings),
+        None => Commission::new(
+            CommissionForType::new(dec!(0.1), referral_reward),
+            CommissionForType::new(dec!(0.2), referral_reward),
+        ),
+    };
+    let exchange_client = Box::new(exchange_client);
 
     let exchange_blocker = ExchangeBlocker::new(vec![exchange_account_id]);
 
@@ -24,6 +24,17 @@ pub trait RestHeaders {
         uri: &Uri,
         request_type: RequestType,
     ) -> Builder;
+
+    /// Should be overridden if request body is needed for headers, e.g. for signature
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        request_type: RequestType,
+        _body: &[u8],
+    ) -> Builder {
+        self.add_specific_headers(builder, uri, request_type)
+    }
 }
 
 #[derive(Default)]
@@ -303,15 +314,13 @@ impl<ErrHandler: ErrorHandler + Send + Sync + 'static, SpecHeaders: RestHeaders
 
         let builder = Request::builder().method(Method::POST);
         let request_type = RequestType::Post;
+        let query = query.unwrap_or_default();
         let req = self
             .headers
-            .add_specific_headers(builder, &uri, request_type)
+            .add_specific_headers_with_body(builder, &uri, request_type, &query)
             .uri(uri)
             .header(hyper::header::CONNECTION, KEEP_ALIVE)
-            .body(match query {
-                Some(query) => Body::from(query),
-                None => Body::empty(),
-            })
+            .body(Body::from(query))
             .with_expect(|| {
                 format!("Error during creation of http {request_type} request {request_id}")
             });
@@ -227,6 +227,37 @@ impl RequestsTimeoutManager {
             .try_reserve_request_instant(request_type, current_time)
     }
 
+    /// Adds requests that were counted by exchange but aren't known locally (e.g. requests over
+    /// other connection or weight of request that is more than one), so local limit isn't exceeded
+    pub fn sync_used_requests_count(
+        &self,
+        request_type: RequestType,
+        used_requests_count: usize,
+        current_time: DateTime,
+    ) {
+        let mut inner = self.inner.lock();
+
+        let current_time = inner.get_non_decreasing_time(current_time);
+        inner.remove_outdated_requests(current_time);
+
+        let known_requests_count = inner
+            .requests
+            .iter()
+            .filter(|request| request.allowed_start_time <= current_time)
+            .count();
+        let used_requests_count = used_requests_count.min(inner.requests_per_period);
+        if used_requests_count <= known_requests_count {
+            return;
+        }
+
+        for _ in known_requests_count..used_requests_count {
+            let _ = inner.add_request(request_type, current_time, None);
+        }
+        inner.last_time = Some(current_time);
+
+        log::info!("Synchronized used requests count {used_requests_count} (known {known_requests_count}) by {request_type:?} in time {current_time}");
+    }
+
     pub fn reserve_when_available(
         self: Arc<Self>,
         request_type: RequestType,
@@ -379,6 +410,10 @@ impl RequestsTimeoutManager {
             .push(Box::new(trigger));
     }
 
+    pub fn get_requests_per_period(&self) -> usize {
+        self.inner.lock().requests_per_period
+    }
+
     pub fn get_period_duration(&self) -> std::time::Duration {
         self.inner.lock().get_period_duration().to_std_expected()
     }
@@ -2205,6 +2240,59 @@ mod test {
         }
     }
 
+    mod sync_used_requests_count {
+        use super::*;
+
+        #[rstest]
+        fn add_requests_unknown_locally(timeout_manager: Arc<RequestsTimeoutManager>) {
+            let current_time = Utc::now();
+            assert!(timeout_manager.try_reserve_instant(
+                RequestType::CreateOrder,
+                current_time,
+                None
+            ));
+
+            timeout_manager.sync_used_requests_count(RequestType::CreateOrder, 4, current_time);
+
+            assert_eq!(timeout_manager.inner.lock().requests.len(), 4);
+            assert!(timeout_manager.try_reserve_instant(
+                RequestType::CreateOrder,
+                current_time,
+                None
+            ));
+            assert!(!timeout_manager.try_reserve_instant(
+                RequestType::CreateOrder,
+                current_time,
+                None
+            ));
+        }
+
+        #[rstest]
+        fn ignore_count_less_than_known(timeout_manager: Arc<RequestsTimeoutManager>) {
+            let current_time = Utc::now();
+            for _ in 0..3 {
+                assert!(timeout_manager.try_reserve_instant(
+                    RequestType::CreateOrder,
+                    current_time,
+                    None
+                ));
+            }
+
+            timeout_manager.sync_used_requests_count(RequestType::CreateOrder, 1, current_time);
+
+            assert_eq!(timeout_manager.inner.lock().requests.len(), 3);
+        }
+
+        #[rstest]
+        fn count_is_limited_by_requests_per_period(timeout_manager: Arc<RequestsTimeoutManager>) {
+            let current_time = Utc::now();
+
+            timeout_manager.sync_used_requests_count(RequestType::CreateOrder, 100, current_time);
+
+            assert_eq!(timeout_manager.inner.lock().requests.len(), 5);
+        }
+    }
+
     mod triggers {
         use parking_lot::Mutex;
 
@@ -115,6 +115,23 @@ impl TimeoutManager {
         Either::Left(convert(result.0))
     }
 
+    pub fn sync_used_requests_count(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        request_type: RequestType,
+        used_requests_count: usize,
+    ) {
+        self.inner[&exchange_account_id].sync_used_requests_count(
+            request_type,
+            used_requests_count,
+            now(),
+        )
+    }
+
+    pub fn get_requests_per_period(&self, exchange_account_id: ExchangeAccountId) -> usize {
+        self.inner[&exchange_account_id].get_requests_per_period()
+    }
+
     pub fn get_period_duration(&self, exchange_account_id: ExchangeAccountId) -> Duration {
         self.inner
             .get(&exchange_account_id)
@@ -9,15 +9,16 @@ use crate::exchanges::general::exchange::{Exchange, RequestResult};
 use crate::exchanges::general::features::ExchangeFeatures;
 use crate::exchanges::general::order::cancel::CancelOrderResult;
 use crate::exchanges::general::order::create::CreateOrderResult;
+use crate::exchanges::general::order::create_oco::CreateOcoOrderResult;
 use crate::exchanges::timeouts::timeout_manager::TimeoutManager;
 use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
 use crate::settings::ExchangeSettings;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use async_trait::async_trait;
 use dashmap::DashMap;
 use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions, MetricsEventInfo};
 use mmb_domain::events::{ExchangeEvent, Trade};
-use mmb_domain::exchanges::symbol::{BeforeAfter, Symbol};
+use mmb_domain::exchanges::symbol::{BeforeAfter, ContractType, Symbol};
 use mmb_domain::market::CurrencyId;
 use mmb_domain::market::{
     CurrencyCode, CurrencyPair, ExchangeAccountId, ExchangeErrorType, ExchangeId,
@@ -100,6 +101,30 @@ pub trait ExchangeClient: Support {
 
     async fn cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()>;
 
+    /// Arm exchange side timer that cancels all open orders if it isn't rearmed during `timeout`
+    /// Must be implemented if `OrderFeatures::supports_cancel_all_after` is set
+    /// NOTE: some exchanges support the timer only per currency pair
+    async fn cancel_all_orders_after(
+        &self,
+        _currency_pairs: &[CurrencyPair],
+        _timeout: Duration,
+    ) -> Result<()> {
+        bail!("Cancel all orders after timeout isn't supported by exchange")
+    }
+
+    /// Create linked pair of limit and stop loss orders where execution of one of them cancels the other one
+    /// Must be implemented if `OrderFeatures::supports_oco_order` is set
+    async fn create_oco_order(
+        &self,
+        _limit_order: &OrderRef,
+        _stop_loss_order: &OrderRef,
+    ) -> CreateOcoOrderResult {
+        CreateOcoOrderResult::failed(
+            ExchangeError::unknown("OCO orders aren't supported by exchange"),
+            EventSourceType::Rest,
+        )
+    }
+
     async fn get_open_orders(&self) -> Result<Vec<OrderInfo>>;
 
     async fn get_open_orders_by_currency_pair(
@@ -150,6 +175,9 @@ pub type OrderCreatedCb =
 pub type OrderCancelledCb =
     Box<dyn Fn(ClientOrderId, ExchangeOrderId, EventSourceType) + Send + Sync>;
 
+pub type OrderCreationFailedCb =
+    Box<dyn Fn(ClientOrderId, ExchangeError, EventSourceType) + Send + Sync>;
+
 pub type HandleTradeCb = Box<dyn Fn(CurrencyPair, Trade) + Send + Sync>;
 
 pub type HandleOrderFilledCb = Box<dyn Fn(FillEvent) + Send + Sync>;
@@ -175,6 +203,9 @@ pub trait Support: Send + Sync {
 
     fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb);
 
+    /// Needed only for exchange clients which receive order rejections not as responses to requests
+    fn set_order_creation_failed_callback(&mut self, _callback: OrderCreationFailedCb) {}
+
     fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb);
 
     fn set_handle_trade_callback(&mut self, callback: HandleTradeCb);
@@ -185,6 +216,12 @@ pub trait Support: Send + Sync {
 
     fn is_websocket_enabled(&self, role: WebSocketRole) -> bool;
 
+    /// Exchange clients with their own transport (e.g. FIX session over TCP) establish
+    /// and restore connection by themselves, so websocket connection is skipped for them
+    fn is_connection_managed_by_client(&self) -> bool {
+        false
+    }
+
     async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url>;
 
     fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair;
@@ -202,7 +239,11 @@ pub trait Support: Send + Sync {
         symbol: Arc<Symbol>,
         side: OrderSide,
     ) -> CurrencyCode {
-        symbol.get_trade_code(side, BeforeAfter::Before)
+        match (symbol.contract_type, symbol.balance_currency_code) {
+            // quanto contracts are settled in currency outside currency pair
+            (ContractType::Quanto, Some(balance_currency_code)) => balance_currency_code,
+            _ => symbol.get_trade_code(side, BeforeAfter::Before),
+        }
     }
 
     fn get_settings(&self) -> &ExchangeSettings;
@@ -0,0 +1,147 @@
+pub mod schedule;
+pub mod service;
+
+use anyhow::{bail, Result};
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, MarketAccountId};
+use mmb_domain::order::snapshot::{Amount, ClientOrderId, OrderSide, Price};
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+pub type ExecutionAlgoId = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecutionAlgoType {
+    /// Evenly distributed execution between start and end time
+    Twap,
+    /// Execution follows traded volume of market multiplied by participation rate
+    Vwap,
+    /// Only `display_amount` of parent order is placed on the order book at limit price at any time
+    Iceberg { display_amount: Amount },
+}
+
+/// Large order that is executed by child orders over time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParentOrder {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub side: OrderSide,
+    pub amount: Amount,
+    /// Child orders are never placed worse than limit price
+    pub limit_price: Option<Price>,
+    pub start_time: DateTime,
+    pub end_time: DateTime,
+    /// Max part of market traded volume that can be executed by child orders
+    pub participation_rate: Option<Decimal>,
+    pub algo_type: ExecutionAlgoType,
+}
+
+impl ParentOrder {
+    pub fn market_account_id(&self) -> MarketAccountId {
+        MarketAccountId::new(self.exchange_account_id, self.currency_pair)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.amount <= dec!(0) {
+            bail!("Amount of parent order should be positive: {}", self.amount);
+        }
+
+        if self.end_time <= self.start_time {
+            bail!(
+                "End time {} of parent order should be after start time {}",
+                self.end_time,
+                self.start_time
+            );
+        }
+
+        if let Some(participation_rate) = self.participation_rate {
+            if participation_rate <= dec!(0) || participation_rate > dec!(1) {
+                bail!("Participation rate should be in range (0, 1]: {participation_rate}");
+            }
+        }
+
+        match self.algo_type {
+            ExecutionAlgoType::Twap => {}
+            ExecutionAlgoType::Vwap => {
+                if self.participation_rate.is_none() {
+                    bail!("Participation rate is required for VWAP");
+                }
+            }
+            ExecutionAlgoType::Iceberg { display_amount } => {
+                if display_amount <= dec!(0) {
+                    bail!("Display amount of iceberg should be positive: {display_amount}");
+                }
+                if self.limit_price.is_none() {
+                    bail!("Limit price is required for iceberg");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum ExecutionAlgoStatus {
+    /// Start time is not reached yet
+    Pending,
+    Running,
+    /// Whole amount of parent order is filled
+    Completed,
+    Cancelled,
+    /// End time is reached before whole amount was filled
+    Expired,
+    Failed(String),
+}
+
+impl ExecutionAlgoStatus {
+    pub fn is_finished(&self) -> bool {
+        !matches!(
+            self,
+            ExecutionAlgoStatus::Pending | ExecutionAlgoStatus::Running
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionAlgoProgress {
+    pub id: ExecutionAlgoId,
+    pub parent_order: ParentOrder,
+    pub status: ExecutionAlgoStatus,
+    pub filled_amount: Amount,
+    pub average_price: Option<Price>,
+    pub child_orders_count: u32,
+    pub failed_child_orders_count: u32,
+    pub active_child_order: Option<ClientOrderId>,
+}
+
+impl ExecutionAlgoProgress {
+    fn new(id: ExecutionAlgoId, parent_order: ParentOrder) -> Self {
+        ExecutionAlgoProgress {
+            id,
+            parent_order,
+            status: ExecutionAlgoStatus::Pending,
+            filled_amount: dec!(0),
+            average_price: None,
+            child_orders_count: 0,
+            failed_child_orders_count: 0,
+            active_child_order: None,
+        }
+    }
+
+    fn add_fill(&mut self, price: Price, amount: Amount) {
+        if amount.is_zero() {
+            return;
+        }
+
+        let filled_amount = self.filled_amount + amount;
+        let previous_cost = self.average_price.unwrap_or_default() * self.filled_amount;
+        self.average_price = Some((previous_cost + price * amount) / filled_amount);
+        self.filled_amount = filled_amount;
+    }
+
+    pub fn remaining_amount(&self) -> Amount {
+        self.parent_order.amount - self.filled_amount
+    }
+}
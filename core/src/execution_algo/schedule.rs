@@ -0,0 +1,160 @@
+use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use super::{ExecutionAlgoType, ParentOrder};
+
+/// Amount of parent order that should be executed at the moment `now`
+/// `market_volume` is traded volume of market since start of parent order
+pub fn calc_target_amount(
+    parent_order: &ParentOrder,
+    now: DateTime,
+    market_volume: Amount,
+) -> Amount {
+    let by_volume = parent_order
+        .participation_rate
+        .map(|participation_rate| market_volume * participation_rate);
+
+    let target_amount = match parent_order.algo_type {
+        ExecutionAlgoType::Twap => {
+            let by_time = calc_elapsed_part(parent_order, now) * parent_order.amount;
+            by_volume.map_or(by_time, |by_volume| by_time.min(by_volume))
+        }
+        // participation rate is checked by ParentOrder::validate() for VWAP
+        ExecutionAlgoType::Vwap => by_volume.unwrap_or_default(),
+        ExecutionAlgoType::Iceberg { .. } => by_volume.unwrap_or(parent_order.amount),
+    };
+
+    target_amount.min(parent_order.amount)
+}
+
+/// Amount of the next child order that should be created
+pub fn calc_child_amount(
+    parent_order: &ParentOrder,
+    target_amount: Amount,
+    filled_amount: Amount,
+) -> Amount {
+    let remaining_amount = parent_order.amount - filled_amount;
+    let amount = match parent_order.algo_type {
+        ExecutionAlgoType::Twap | ExecutionAlgoType::Vwap => target_amount - filled_amount,
+        ExecutionAlgoType::Iceberg { display_amount } => {
+            display_amount.min(target_amount - filled_amount)
+        }
+    };
+
+    amount.min(remaining_amount).max(dec!(0))
+}
+
+/// Price of the next child order: TWAP and VWAP take liquidity from the top of order book,
+/// iceberg rests on the order book at limit price. Limit price of parent order is never crossed.
+pub fn calc_child_price(parent_order: &ParentOrder, top_price: Option<Price>) -> Option<Price> {
+    let limit_price = parent_order.limit_price;
+    if let ExecutionAlgoType::Iceberg { .. } = parent_order.algo_type {
+        return limit_price;
+    }
+
+    let top_price = match top_price {
+        Some(top_price) => top_price,
+        None => return limit_price,
+    };
+
+    let price = match (parent_order.side, limit_price) {
+        (_, None) => top_price,
+        (OrderSide::Buy, Some(limit_price)) => top_price.min(limit_price),
+        (OrderSide::Sell, Some(limit_price)) => top_price.max(limit_price),
+    };
+
+    Some(price)
+}
+
+fn calc_elapsed_part(parent_order: &ParentOrder, now: DateTime) -> Decimal {
+    let duration = (parent_order.end_time - parent_order.start_time).num_milliseconds();
+    let elapsed = (now - parent_order.start_time).num_milliseconds();
+    if duration <= 0 {
+        return dec!(1);
+    }
+
+    (Decimal::from(elapsed) / Decimal::from(duration))
+        .max(dec!(0))
+        .min(dec!(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+    use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+
+    fn parent_order(
+        algo_type: ExecutionAlgoType,
+        participation_rate: Option<Decimal>,
+    ) -> ParentOrder {
+        let start_time = Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+        ParentOrder {
+            exchange_account_id: ExchangeAccountId::new("Binance", 0),
+            currency_pair: CurrencyPair::from_codes("base".into(), "quote".into()),
+            side: OrderSide::Buy,
+            amount: dec!(10),
+            limit_price: Some(dec!(100)),
+            start_time,
+            end_time: start_time + Duration::seconds(100),
+            participation_rate,
+            algo_type,
+        }
+    }
+
+    #[test]
+    fn twap_target_amount_is_proportional_to_elapsed_time() {
+        let parent_order = parent_order(ExecutionAlgoType::Twap, None);
+        let at = |secs| parent_order.start_time + Duration::seconds(secs);
+
+        assert_eq!(calc_target_amount(&parent_order, at(-10), dec!(0)), dec!(0));
+        assert_eq!(
+            calc_target_amount(&parent_order, at(25), dec!(0)),
+            dec!(2.5)
+        );
+        assert_eq!(
+            calc_target_amount(&parent_order, at(200), dec!(0)),
+            dec!(10)
+        );
+    }
+
+    #[test]
+    fn target_amount_is_limited_by_participation_rate() {
+        let now = parent_order(ExecutionAlgoType::Twap, None).start_time + Duration::seconds(50);
+
+        let twap = parent_order(ExecutionAlgoType::Twap, Some(dec!(0.1)));
+        assert_eq!(calc_target_amount(&twap, now, dec!(20)), dec!(2));
+        assert_eq!(calc_target_amount(&twap, now, dec!(100)), dec!(5));
+
+        let vwap = parent_order(ExecutionAlgoType::Vwap, Some(dec!(0.1)));
+        assert_eq!(calc_target_amount(&vwap, now, dec!(70)), dec!(7));
+        assert_eq!(calc_target_amount(&vwap, now, dec!(1000)), dec!(10));
+    }
+
+    #[test]
+    fn iceberg_child_amount_is_limited_by_display_amount() {
+        let iceberg = parent_order(
+            ExecutionAlgoType::Iceberg {
+                display_amount: dec!(3),
+            },
+            None,
+        );
+
+        assert_eq!(calc_child_amount(&iceberg, dec!(10), dec!(0)), dec!(3));
+        assert_eq!(calc_child_amount(&iceberg, dec!(10), dec!(8.5)), dec!(1.5));
+        assert_eq!(calc_child_price(&iceberg, Some(dec!(90))), Some(dec!(100)));
+    }
+
+    #[test]
+    fn child_price_does_not_cross_limit_price() {
+        let mut twap = parent_order(ExecutionAlgoType::Twap, None);
+        assert_eq!(calc_child_price(&twap, Some(dec!(99))), Some(dec!(99)));
+        assert_eq!(calc_child_price(&twap, Some(dec!(101))), Some(dec!(100)));
+
+        twap.side = OrderSide::Sell;
+        assert_eq!(calc_child_price(&twap, Some(dec!(99))), Some(dec!(100)));
+        assert_eq!(calc_child_price(&twap, Some(dec!(101))), Some(dec!(101)));
+    }
+}
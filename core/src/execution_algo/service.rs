@@ -0,0 +1,507 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use dashmap::DashMap;
+use itertools::Itertools;
+use mmb_domain::events::{ExchangeEvent, TradesEvent};
+use mmb_domain::exchanges::symbol::{Round, Symbol};
+use mmb_domain::market::{ExchangeAccountId, MarketAccountId};
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, OrderHeader, OrderSide, Price, ReservationId, UserOrder,
+};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::{FutureOutcome, SpawnFutureFlags, WithExpect};
+use parking_lot::Mutex;
+use rust_decimal_macros::dec;
+use tokio::sync::{broadcast, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout};
+
+use super::schedule::{calc_child_amount, calc_child_price, calc_target_amount};
+use super::{
+    ExecutionAlgoId, ExecutionAlgoProgress, ExecutionAlgoStatus, ExecutionAlgoType, ParentOrder,
+};
+use crate::balance::manager::balance_manager::BalanceManager;
+use crate::exchanges::general::exchange::Exchange;
+use crate::infrastructure::spawn_future;
+use crate::lifecycle::trading_engine::Service;
+use crate::misc::reserve_parameters::ReserveParameters;
+use crate::misc::time::time_manager;
+use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+
+const SERVICE_NAME: &str = "ExecutionAlgoService";
+
+/// Interval between recalculations of parent order schedule
+const SLICE_INTERVAL: Duration = Duration::from_secs(1);
+/// TWAP and VWAP child orders that are not filled during this time are cancelled and replaced
+const CHILD_ORDER_LIFETIME: Duration = Duration::from_secs(10);
+
+struct ExecutionAlgo {
+    progress: ExecutionAlgoProgress,
+    cancellation_token: CancellationToken,
+}
+
+/// Average price and filled amount of finished child order
+struct ChildOrderFill {
+    average_price: Price,
+    filled_amount: Amount,
+}
+
+enum ChildOrderOutcome {
+    Finished(ChildOrderFill),
+    /// Child order was alive after failed cancellation, so execution of parent order has to be stopped
+    CancellationFailed(ChildOrderFill, anyhow::Error),
+}
+
+/// Executes large parent orders by slicing them into child orders according to TWAP, VWAP or iceberg logic
+pub struct ExecutionAlgoService {
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    balance_manager: Arc<Mutex<BalanceManager>>,
+    stop_token: CancellationToken,
+    algos: Mutex<HashMap<ExecutionAlgoId, ExecutionAlgo>>,
+    /// Traded volume of markets since start of service
+    market_volumes: Mutex<HashMap<MarketAccountId, Amount>>,
+    next_id: AtomicU64,
+}
+
+impl ExecutionAlgoService {
+    pub fn new(
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        balance_manager: Arc<Mutex<BalanceManager>>,
+        stop_token: CancellationToken,
+    ) -> Arc<Self> {
+        Arc::new(ExecutionAlgoService {
+            exchanges,
+            balance_manager,
+            stop_token,
+            algos: Default::default(),
+            market_volumes: Default::default(),
+            next_id: AtomicU64::new(1),
+        })
+    }
+
+    /// Collect traded volume of markets from `TradesEvent`s for VWAP and participation rate
+    pub async fn start_volume_tracking(
+        self: Arc<Self>,
+        mut events_receiver: broadcast::Receiver<ExchangeEvent>,
+    ) -> Result<()> {
+        loop {
+            let event = tokio::select! {
+                event_res = events_receiver.recv() => event_res.context("Error during receiving event in ExecutionAlgoService::start_volume_tracking()")?,
+                _ = self.stop_token.when_cancelled() => return Ok(()),
+            };
+
+            if let ExchangeEvent::Trades(trades_event) = event {
+                self.handle_trades(&trades_event);
+            }
+        }
+    }
+
+    fn handle_trades(&self, trades_event: &TradesEvent) {
+        let market_account_id =
+            MarketAccountId::new(trades_event.exchange_account_id, trades_event.currency_pair);
+        let volume: Amount = trades_event.trades.iter().map(|x| x.quantity).sum();
+
+        *self
+            .market_volumes
+            .lock()
+            .entry(market_account_id)
+            .or_default() += volume;
+    }
+
+    fn get_market_volume(&self, market_account_id: &MarketAccountId) -> Amount {
+        self.market_volumes
+            .lock()
+            .get(market_account_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Start execution of parent order. Returned handle is finished when execution algo is finished.
+    pub fn start(
+        self: &Arc<Self>,
+        parent_order: ParentOrder,
+        cancellation_token: CancellationToken,
+    ) -> Result<(ExecutionAlgoId, JoinHandle<FutureOutcome>)> {
+        parent_order.validate()?;
+
+        let exchange_account_id = parent_order.exchange_account_id;
+        if !self.exchanges.contains_key(&exchange_account_id) {
+            bail!("Exchange {exchange_account_id} for parent order is not found");
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let cancellation_token = cancellation_token.create_linked_token();
+        self.algos.lock().insert(
+            id,
+            ExecutionAlgo {
+                progress: ExecutionAlgoProgress::new(id, parent_order.clone()),
+                cancellation_token: cancellation_token.clone(),
+            },
+        );
+
+        log::info!("Execution algo {id} started for {parent_order:?}");
+
+        let this = self.clone();
+        let action = async move {
+            let status = match this.execute(id, &parent_order, cancellation_token).await {
+                Ok(status) => status,
+                Err(err) => {
+                    log::error!("Execution algo {id} failed: {err:?}");
+                    ExecutionAlgoStatus::Failed(err.to_string())
+                }
+            };
+
+            log::info!("Execution algo {id} finished with status {status:?}");
+            this.update_progress(id, |progress| progress.status = status);
+            Ok(())
+        };
+
+        let handle = spawn_future(
+            &format!("Execution algo {id}"),
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            action,
+        );
+
+        Ok((id, handle))
+    }
+
+    pub fn cancel(&self, id: ExecutionAlgoId) -> Result<()> {
+        match self.algos.lock().get(&id) {
+            Some(algo) => algo.cancellation_token.cancel(),
+            None => bail!("Execution algo {id} is not found"),
+        }
+
+        log::info!("Execution algo {id} cancellation requested");
+        Ok(())
+    }
+
+    pub fn get_progress(&self, id: ExecutionAlgoId) -> Option<ExecutionAlgoProgress> {
+        self.algos.lock().get(&id).map(|x| x.progress.clone())
+    }
+
+    pub fn get_all_progress(&self) -> Vec<ExecutionAlgoProgress> {
+        self.algos
+            .lock()
+            .values()
+            .map(|x| x.progress.clone())
+            .sorted_by_key(|x| x.id)
+            .collect_vec()
+    }
+
+    fn update_progress(&self, id: ExecutionAlgoId, f: impl FnOnce(&mut ExecutionAlgoProgress)) {
+        let mut algos = self.algos.lock();
+        let algo = algos
+            .get_mut(&id)
+            .with_expect(|| format!("Execution algo {id} should exist"));
+        f(&mut algo.progress)
+    }
+
+    async fn execute(
+        &self,
+        id: ExecutionAlgoId,
+        parent_order: &ParentOrder,
+        cancellation_token: CancellationToken,
+    ) -> Result<ExecutionAlgoStatus> {
+        let exchange = self
+            .exchanges
+            .get(&parent_order.exchange_account_id)
+            .with_context(|| {
+                format!(
+                    "Failed to get exchange {}",
+                    parent_order.exchange_account_id
+                )
+            })?
+            .clone();
+        let symbol = exchange.get_symbol(parent_order.currency_pair)?;
+        let market_account_id = parent_order.market_account_id();
+        let configuration_descriptor = ConfigurationDescriptor::new(
+            SERVICE_NAME.into(),
+            market_account_id.to_string().as_str().into(),
+        );
+
+        let wait_start_time = parent_order.start_time - time_manager::now();
+        if let Ok(wait_start_time) = wait_start_time.to_std() {
+            tokio::select! {
+                _ = sleep(wait_start_time) => {}
+                _ = cancellation_token.when_cancelled() => return Ok(ExecutionAlgoStatus::Cancelled),
+            }
+        }
+
+        self.update_progress(id, |progress| {
+            progress.status = ExecutionAlgoStatus::Running
+        });
+        let start_market_volume = self.get_market_volume(&market_account_id);
+
+        loop {
+            if cancellation_token.is_cancellation_requested() {
+                return Ok(ExecutionAlgoStatus::Cancelled);
+            }
+
+            let filled_amount = self
+                .get_progress(id)
+                .with_expect(|| format!("Execution algo {id} should exist"))
+                .filled_amount;
+
+            let remaining_amount =
+                symbol.amount_round(parent_order.amount - filled_amount, Round::Floor);
+            if remaining_amount.is_zero()
+                || symbol.min_amount.map_or(false, |x| remaining_amount < x)
+            {
+                return Ok(ExecutionAlgoStatus::Completed);
+            }
+
+            let now = time_manager::now();
+            if now >= parent_order.end_time {
+                return Ok(ExecutionAlgoStatus::Expired);
+            }
+
+            let market_volume = self.get_market_volume(&market_account_id) - start_market_volume;
+            let target_amount = calc_target_amount(parent_order, now, market_volume);
+            let child_amount = symbol.amount_round(
+                calc_child_amount(parent_order, target_amount, filled_amount),
+                Round::Floor,
+            );
+
+            let is_child_amount_too_small =
+                child_amount.is_zero() || symbol.min_amount.map_or(false, |x| child_amount < x);
+            if is_child_amount_too_small {
+                sleep(SLICE_INTERVAL).await;
+                continue;
+            }
+
+            let top_price = exchange
+                .order_book_top
+                .get(&parent_order.currency_pair)
+                .and_then(|top| match parent_order.side {
+                    OrderSide::Buy => top.ask.as_ref().map(|x| x.price),
+                    OrderSide::Sell => top.bid.as_ref().map(|x| x.price),
+                });
+
+            let price = match calc_child_price(parent_order, top_price) {
+                Some(price) => price,
+                None => {
+                    log::warn!("Execution algo {id}: there is no price for child order");
+                    sleep(SLICE_INTERVAL).await;
+                    continue;
+                }
+            };
+
+            let child_order_lifetime = match parent_order.algo_type {
+                ExecutionAlgoType::Iceberg { .. } => (parent_order.end_time - now)
+                    .to_std()
+                    .unwrap_or(CHILD_ORDER_LIFETIME),
+                _ => CHILD_ORDER_LIFETIME,
+            };
+
+            let child_order_result = self
+                .execute_child_order(
+                    id,
+                    &exchange,
+                    &symbol,
+                    configuration_descriptor,
+                    parent_order.side,
+                    price,
+                    child_amount,
+                    child_order_lifetime,
+                    cancellation_token.clone(),
+                )
+                .await;
+
+            match child_order_result {
+                Ok(ChildOrderOutcome::Finished(fill)) => self.add_child_order_fill(id, fill),
+                Ok(ChildOrderOutcome::CancellationFailed(fill, err)) => {
+                    self.add_child_order_fill(id, fill);
+                    return Err(err.context("Child order was alive after failed cancellation"));
+                }
+                Err(err) => {
+                    log::warn!("Execution algo {id}: child order failed: {err:?}");
+                    self.update_progress(id, |progress| {
+                        progress.failed_child_orders_count += 1;
+                        progress.active_child_order = None;
+                    });
+                    sleep(SLICE_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    fn add_child_order_fill(&self, id: ExecutionAlgoId, fill: ChildOrderFill) {
+        self.update_progress(id, |progress| {
+            progress.child_orders_count += 1;
+            progress.active_child_order = None;
+            progress.add_fill(fill.average_price, fill.filled_amount);
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn execute_child_order(
+        &self,
+        id: ExecutionAlgoId,
+        exchange: &Arc<Exchange>,
+        symbol: &Arc<Symbol>,
+        configuration_descriptor: ConfigurationDescriptor,
+        side: OrderSide,
+        price: Price,
+        amount: Amount,
+        lifetime: Duration,
+        cancellation_token: CancellationToken,
+    ) -> Result<ChildOrderOutcome> {
+        let exchange_account_id = exchange.exchange_account_id;
+        let reserve_parameters = ReserveParameters::new(
+            configuration_descriptor,
+            exchange_account_id,
+            symbol.clone(),
+            side,
+            price,
+            amount,
+        );
+        let reservation_id = match self
+            .balance_manager
+            .lock()
+            .try_reserve(&reserve_parameters, &mut None)
+        {
+            Some(reservation_id) => reservation_id,
+            None => bail!("Can't reserve balance {amount} for child order {side} {price}"),
+        };
+
+        let order_header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            exchange_account_id,
+            symbol.currency_pair(),
+            side,
+            amount,
+            UserOrder::limit(price),
+            Some(reservation_id),
+            None,
+            SERVICE_NAME.to_string(),
+        );
+
+        self.update_progress(id, |progress| {
+            progress.active_child_order = Some(order_header.client_order_id.clone())
+        });
+
+        let order = match exchange
+            .create_order(&order_header, None, cancellation_token.clone())
+            .await
+        {
+            Ok(order) => order,
+            Err(err) => {
+                if let Err(unreserve_err) =
+                    self.balance_manager.lock().unreserve_rest(reservation_id)
+                {
+                    log::error!("Execution algo {id}: failed to unreserve_rest for {reservation_id:?}: {unreserve_err:?}");
+                }
+                return Err(err);
+            }
+        };
+
+        let wait_finish = exchange
+            .clone()
+            .wait_order_finish(&order, None, cancellation_token);
+        let _ = timeout(lifetime, wait_finish).await;
+
+        // Child order is not finished because of timeout or cancellation of execution algo
+        let mut cancellation_error = None;
+        if !order.is_finished() {
+            if let Err(err) = exchange
+                .wait_cancel_order(order.clone(), None, true, CancellationToken::default())
+                .await
+            {
+                log::error!(
+                    "Execution algo {id}: failed to cancel child order {}: {err:?}",
+                    order.client_order_id()
+                );
+
+                // Child order can be still alive, so it stays reserved and its fills
+                // are accounted only after it's finished
+                if let Err(err) = exchange
+                    .clone()
+                    .wait_order_finish(&order, None, self.stop_token.clone())
+                    .await
+                {
+                    log::error!(
+                        "Execution algo {id}: failed to wait finish of child order {}: {err:?}",
+                        order.client_order_id()
+                    );
+                }
+                cancellation_error = Some(err);
+            }
+        }
+
+        let fill =
+            self.release_child_order(id, configuration_descriptor, &order, reservation_id, price);
+        Ok(match cancellation_error {
+            None => ChildOrderOutcome::Finished(fill),
+            Some(err) => ChildOrderOutcome::CancellationFailed(fill, err),
+        })
+    }
+
+    /// Accounts fills of finished child order and releases its reservation
+    fn release_child_order(
+        &self,
+        id: ExecutionAlgoId,
+        configuration_descriptor: ConfigurationDescriptor,
+        order: &OrderRef,
+        reservation_id: ReservationId,
+        price: Price,
+    ) -> ChildOrderFill {
+        let order_snapshot = order.deep_clone();
+        let mut balance_manager = self.balance_manager.lock();
+        for fill in &order_snapshot.fills.fills {
+            balance_manager.order_was_filled_with_fill(
+                configuration_descriptor,
+                &order_snapshot,
+                fill,
+            );
+        }
+        if let Err(err) = balance_manager.unreserve_by_client_order_id(
+            reservation_id,
+            order_snapshot.client_order_id(),
+            order_snapshot.amount(),
+        ) {
+            log::error!(
+                "Execution algo {id}: failed to unreserve child order {}: {err:?}",
+                order_snapshot.client_order_id()
+            );
+        }
+
+        let filled_amount = order_snapshot.fills.filled_amount;
+        let average_price = match filled_amount.is_zero() {
+            true => price,
+            false => {
+                order_snapshot
+                    .fills
+                    .fills
+                    .iter()
+                    .map(|x| x.price() * x.amount())
+                    .sum::<Price>()
+                    / filled_amount
+            }
+        };
+
+        ChildOrderFill {
+            average_price,
+            filled_amount: filled_amount.max(dec!(0)),
+        }
+    }
+}
+
+impl Service for ExecutionAlgoService {
+    fn name(&self) -> &str {
+        SERVICE_NAME
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<oneshot::Receiver<Result<()>>> {
+        self.algos
+            .lock()
+            .values()
+            .for_each(|x| x.cancellation_token.cancel());
+
+        None
+    }
+}
@@ -29,10 +29,12 @@ pub mod statistic_service;
 pub mod config;
 pub mod database;
 pub mod disposition_execution;
+pub mod execution_algo;
 pub mod explanation;
 pub mod lifecycle;
 pub mod math;
 pub mod order_book;
+pub mod pnl_ledger;
 pub(crate) mod services;
 pub mod settings;
 pub mod text;
@@ -1,5 +1,9 @@
 use crate::balance::manager::balance_manager::BalanceManager;
 use crate::config::{load_pretty_settings, try_load_settings};
+use crate::database::events::recorder::sinks::files::FilesEventSink;
+use crate::database::events::recorder::sinks::postgres::PostgresEventSink;
+use crate::database::events::recorder::sinks::sqlite::SqliteEventSink;
+use crate::database::events::recorder::sinks::EventSink;
 use crate::database::events::recorder::EventRecorder;
 use crate::exchanges::exchange_blocker::ExchangeBlocker;
 use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
@@ -16,7 +20,14 @@ use crate::lifecycle::trading_engine::{EngineContext, TradingEngine};
 use crate::rpc::config_waiter::ConfigWaiter;
 use crate::rpc::core_api::CoreApi;
 use crate::services::cleanup_orders::CleanupOrdersService;
-use crate::settings::{AppSettings, CoreSettings};
+use crate::services::dead_man_switch::DeadManSwitchService;
+use crate::services::reconciliation::ReconciliationService;
+use crate::services::usd_convertion::price_source_service::PriceSourceService;
+use crate::services::usd_convertion::price_sources_loader::PriceSourcesLoader;
+use crate::services::usd_convertion::prices_sources_saver::PriceSourcesSaver;
+#[double]
+use crate::services::usd_convertion::usd_converter::UsdConverter;
+use crate::settings::{AppSettings, CoreSettings, EventSinkSettings, ReconciliationSettings};
 use anyhow::{anyhow, bail, Context, Result};
 use core::fmt::Debug;
 use dashmap::DashMap;
@@ -30,6 +41,7 @@ use mmb_domain::market::ExchangeId;
 use mmb_utils::infrastructure::{init_infrastructure, SpawnFutureFlags};
 use mmb_utils::logger::print_info;
 use mmb_utils::nothing_to_do;
+use mockall_double::double;
 use parking_lot::Mutex;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
@@ -37,6 +49,7 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
 use std::sync::{Arc, Weak};
 use std::time::Duration;
 use tokio::signal;
@@ -178,8 +191,11 @@ where
         (None, None)
     };
 
-    let event_recorder = EventRecorder::start(pool.clone(), postponed_events_dir)
-        .await
+    let event_sinks =
+        create_event_sinks(&settings.core, pool.clone(), postponed_events_dir.clone())
+            .await
+            .expect("can't create event sinks for EventRecorder");
+    let event_recorder = EventRecorder::start_with_sinks(event_sinks, postponed_events_dir)
         .expect("can't start EventRecorder");
 
     let exchanges = create_exchanges(
@@ -201,6 +217,19 @@ where
     let exchanges_hashmap: HashMap<ExchangeAccountId, Arc<Exchange>> =
         exchanges_map.clone().into_iter().collect();
 
+    let usd_converter = create_usd_converter(
+        &settings.core,
+        exchanges_hashmap.clone(),
+        events_sender.subscribe(),
+        event_recorder.clone(),
+        &lifetime_manager,
+    );
+    if let Some(usd_converter) = &usd_converter {
+        for exchange in &exchanges_map {
+            exchange.value().setup_usd_converter(usd_converter.clone());
+        }
+    }
+
     let currency_pair_to_symbol_converter = CurrencyPairToSymbolConverter::new(exchanges_hashmap);
 
     let balance_manager = BalanceManager::new(
@@ -220,6 +249,14 @@ where
             .setup_balance_manager(balance_manager.clone())
     }
 
+    join_all(exchanges_map.iter().map(|exchange| {
+        exchange
+            .value()
+            .clone()
+            .handle_orphaned_orders(balance_manager.clone(), lifetime_manager.stop_token())
+    }))
+    .await;
+
     start_updating_balances(&lifetime_manager, &balance_manager);
 
     let (finish_graceful_shutdown_tx, finish_graceful_shutdown_rx) = oneshot::channel();
@@ -234,6 +271,7 @@ where
         lifetime_manager.clone(),
         balance_manager,
         event_recorder,
+        usd_converter,
     );
 
     Ok((
@@ -246,6 +284,52 @@ where
     ))
 }
 
+/// Create USD converter and start tracking prices of its sources if sources are specified in settings
+fn create_usd_converter(
+    core_settings: &CoreSettings,
+    exchanges: HashMap<ExchangeAccountId, Arc<Exchange>>,
+    events_receiver: broadcast::Receiver<ExchangeEvent>,
+    event_recorder: Arc<EventRecorder>,
+    lifetime_manager: &Arc<AppLifetimeManager>,
+) -> Option<Arc<UsdConverter>> {
+    // PriceSourceService depends on mocked converter in unit tests
+    #[double]
+    use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
+
+    if core_settings.usd_price_sources.is_empty() {
+        return None;
+    }
+
+    let currencies = exchanges
+        .values()
+        .flat_map(|exchange| exchange.currencies.lock().clone())
+        .unique()
+        .collect_vec();
+
+    let price_source_service = PriceSourceService::new(
+        CurrencyPairToSymbolConverter::new(exchanges),
+        &core_settings.usd_price_sources,
+        PriceSourcesLoader::new(),
+    );
+
+    let _ = spawn_future_ok(
+        "PriceSourceService start",
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        price_source_service.clone().start(
+            PriceSourcesSaver::new(event_recorder),
+            events_receiver,
+            lifetime_manager.stop_token(),
+        ),
+    );
+
+    // There is no market prices service for UsdDenominator yet, so only price sources are used
+    Some(Arc::new(UsdConverter::new(
+        &currencies,
+        price_source_service,
+        None,
+    )))
+}
+
 fn start_updating_balances(
     lifetime_manager: &Arc<AppLifetimeManager>,
     balance_manager: &Arc<Mutex<BalanceManager>>,
@@ -268,6 +352,66 @@ fn start_updating_balances(
     );
 }
 
+fn start_reconciliation(
+    engine_context: &Arc<EngineContext>,
+    reconciliation_settings: ReconciliationSettings,
+) {
+    let reconciliation_service = Arc::new(ReconciliationService::new(
+        engine_context.exchanges.clone(),
+        engine_context.balance_manager.clone(),
+        engine_context.exchange_blocker.clone(),
+        reconciliation_settings,
+    ));
+    engine_context
+        .shutdown_service
+        .register_core_service(reconciliation_service.clone());
+
+    let period = reconciliation_service.period();
+    let stop_token = engine_context.lifetime_manager.stop_token();
+    let _ = spawn_by_timer(
+        "reconciliation",
+        period,
+        period,
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        move || reconciliation_service.clone().reconcile(stop_token.clone()),
+    );
+}
+
+fn start_dead_man_switches(engine_context: &Arc<EngineContext>) {
+    for exchange in engine_context.exchanges.iter() {
+        let settings = match &exchange.exchange_client.get_settings().dead_man_switch {
+            Some(settings) => settings.clone(),
+            None => continue,
+        };
+
+        let dead_man_switch_service = Arc::new(DeadManSwitchService::new(
+            exchange.value().clone(),
+            engine_context.exchange_blocker.clone(),
+            engine_context.lifetime_manager.clone(),
+            settings,
+        ));
+        engine_context
+            .shutdown_service
+            .register_core_service(dead_man_switch_service.clone());
+
+        if let Err(err) = dead_man_switch_service.clone().start_local_watchdog() {
+            log::error!(
+                "Failed to start dead man's switch watchdog for {}: {err:?}",
+                exchange.exchange_account_id
+            );
+        }
+
+        let period = dead_man_switch_service.heartbeat_period();
+        let _ = spawn_by_timer(
+            "dead man's switch heartbeat",
+            Duration::ZERO,
+            period,
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            move || dead_man_switch_service.clone().heartbeat(),
+        );
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn run_services<'a, StrategySettings>(
     engine_context: Arc<EngineContext>,
@@ -292,6 +436,9 @@ where
         engine_context.lifetime_manager.clone(),
         load_pretty_settings(init_user_settings),
         engine_context.statistic_service.clone(),
+        engine_context.execution_algo_service.clone(),
+        engine_context.pnl_ledger.clone(),
+        engine_context.exchanges.clone(),
     )
     .expect("Unable to start control panel");
     engine_context
@@ -312,6 +459,28 @@ where
         ),
     );
 
+    engine_context
+        .shutdown_service
+        .register_core_service(engine_context.execution_algo_service.clone());
+
+    let _ = spawn_future(
+        "execution algo volume tracking",
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        engine_context
+            .execution_algo_service
+            .clone()
+            .start_volume_tracking(engine_context.get_events_channel()),
+    );
+
+    let _ = spawn_future(
+        "P&L ledger mark prices tracking",
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        engine_context
+            .pnl_ledger
+            .clone()
+            .start_mark_prices_tracking(engine_context.get_events_channel()),
+    );
+
     if let Some(data_services) = data_services {
         engine_context
             .shutdown_service
@@ -372,6 +541,12 @@ where
         },
     );
 
+    if let Some(reconciliation_settings) = settings.core.reconciliation.clone() {
+        start_reconciliation(&engine_context, reconciliation_settings);
+    }
+
+    start_dead_man_switches(&engine_context);
+
     log::info!("TradingEngine started");
     TradingEngine::new(engine_context, settings, finish_graceful_shutdown_rx)
 }
@@ -521,6 +696,35 @@ where
     result
 }
 
+async fn create_event_sinks(
+    core_settings: &CoreSettings,
+    pool: Option<PgPool>,
+    postponed_events_dir: Option<PathBuf>,
+) -> Result<Vec<Arc<dyn EventSink>>> {
+    let mut event_sinks: Vec<Arc<dyn EventSink>> = vec![];
+
+    if let Some(pool) = pool {
+        event_sinks.push(PostgresEventSink::start(
+            pool,
+            postponed_events_dir.clone(),
+        )?);
+    }
+
+    for event_sink_settings in &core_settings.event_sinks {
+        let event_sink: Arc<dyn EventSink> = match event_sink_settings {
+            EventSinkSettings::Sqlite { path } => {
+                SqliteEventSink::create(path, postponed_events_dir.clone()).await?
+            }
+            EventSinkSettings::Files { dir } => {
+                FilesEventSink::create(dir.clone(), postponed_events_dir.clone())?
+            }
+        };
+        event_sinks.push(event_sink);
+    }
+
+    Ok(event_sinks)
+}
+
 pub async fn create_exchanges(
     core_settings: &CoreSettings,
     build_settings: &EngineBuildConfig,
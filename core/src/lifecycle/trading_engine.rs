@@ -8,11 +8,15 @@ use crate::exchanges::exchange_blocker::BlockType;
 use crate::exchanges::exchange_blocker::ExchangeBlocker;
 use crate::exchanges::general::exchange::Exchange;
 use crate::exchanges::timeouts::timeout_manager::TimeoutManager;
+use crate::execution_algo::service::ExecutionAlgoService;
 use crate::infrastructure::unset_lifetime_manager;
 use crate::lifecycle::app_lifetime_manager::ActionAfterGracefulShutdown;
 use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
 use crate::lifecycle::shutdown::ShutdownService;
 use crate::order_book::local_snapshot_service::LocalSnapshotsService;
+use crate::pnl_ledger::PnlLedger;
+#[double]
+use crate::services::usd_convertion::usd_converter::UsdConverter;
 use crate::settings::DispositionStrategySettings;
 use crate::settings::{AppSettings, CoreSettings};
 use crate::statistic_service::{StatisticEventHandler, StatisticService};
@@ -27,6 +31,7 @@ use mmb_utils::infrastructure::WithExpect;
 use mmb_utils::logger::print_info;
 use mmb_utils::nothing_to_do;
 use mmb_utils::send_expected::SendExpected;
+use mockall_double::double;
 use parking_lot::Mutex;
 use std::panic::AssertUnwindSafe;
 use std::sync::atomic::AtomicBool;
@@ -54,6 +59,8 @@ pub struct EngineContext {
     pub balance_manager: Arc<Mutex<BalanceManager>>,
     pub event_recorder: Arc<EventRecorder>,
     pub statistic_service: Arc<StatisticService>,
+    pub execution_algo_service: Arc<ExecutionAlgoService>,
+    pub pnl_ledger: Arc<PnlLedger>,
     is_graceful_shutdown_started: AtomicBool,
     exchange_events: ExchangeEvents,
     finish_graceful_shutdown_sender: Mutex<Option<oneshot::Sender<ActionAfterGracefulShutdown>>>,
@@ -71,8 +78,29 @@ impl EngineContext {
         lifetime_manager: Arc<AppLifetimeManager>,
         balance_manager: Arc<Mutex<BalanceManager>>,
         event_recorder: Arc<EventRecorder>,
+        usd_converter: Option<Arc<UsdConverter>>,
     ) -> Arc<Self> {
         let statistic_service = StatisticService::new();
+        for exchange in exchanges.iter() {
+            exchange.setup_statistic_service(statistic_service.clone());
+        }
+
+        let execution_algo_service = ExecutionAlgoService::new(
+            exchanges.clone(),
+            balance_manager.clone(),
+            lifetime_manager.stop_token(),
+        );
+
+        let pnl_ledger = PnlLedger::new(
+            core_settings.pnl_ledger.cost_basis,
+            event_recorder.clone(),
+            lifetime_manager.stop_token(),
+        );
+        if let Some(usd_converter) = usd_converter {
+            pnl_ledger.setup_usd_converter(usd_converter);
+        }
+        balance_manager.lock().set_pnl_ledger(pnl_ledger.clone());
+
         let engine_context = Arc::new(EngineContext {
             core_settings,
             exchanges,
@@ -83,6 +111,8 @@ impl EngineContext {
             balance_manager,
             event_recorder,
             statistic_service,
+            execution_algo_service,
+            pnl_ledger,
             is_graceful_shutdown_started: Default::default(),
             exchange_events,
             finish_graceful_shutdown_sender: Mutex::new(Some(finish_graceful_shutdown_sender)),
@@ -1,7 +1,7 @@
 use std::{sync::Arc, time::Duration};
 
 use mmb_domain::market::MarketAccountId;
-use mmb_domain::order::snapshot::OrderSide;
+use mmb_domain::order::snapshot::{Amount, OrderSide};
 use mmb_utils::{
     cancellation_token::CancellationToken,
     infrastructure::{FutureOutcome, SpawnFutureFlags},
@@ -15,29 +15,46 @@ use crate::balance::manager::balance_manager::BalanceManager;
 #[double]
 use crate::exchanges::general::engine_api::EngineApi;
 
+use crate::execution_algo::service::ExecutionAlgoService;
+use crate::execution_algo::{ExecutionAlgoType, ParentOrder};
 use crate::infrastructure::spawn_future_timed;
+use crate::misc::time::time_manager;
+
+/// Parameters for closing position gradually by TWAP instead of a single order
+pub struct GentleUnwind {
+    pub execution_algo_service: Arc<ExecutionAlgoService>,
+    pub duration: Duration,
+}
 
 pub fn close_position_if_needed(
     market_account_id: &MarketAccountId,
     balance_manager: Option<Arc<Mutex<BalanceManager>>>,
     engine_api: Arc<EngineApi>,
+    gentle_unwind: Option<GentleUnwind>,
     cancellation_token: CancellationToken,
 ) -> Option<JoinHandle<FutureOutcome>> {
-    match balance_manager {
+    let position = match balance_manager {
         Some(balance_manager) => {
-            if balance_manager
-                .lock()
-                .get_position(
-                    market_account_id.exchange_account_id,
-                    market_account_id.currency_pair,
-                    OrderSide::Buy,
-                )
-                .is_zero()
-            {
+            let position = balance_manager.lock().get_position(
+                market_account_id.exchange_account_id,
+                market_account_id.currency_pair,
+                OrderSide::Buy,
+            );
+            if position.is_zero() {
                 return None;
             }
+            position
         }
         None => return None,
+    };
+
+    if let Some(gentle_unwind) = gentle_unwind {
+        return unwind_position(
+            market_account_id,
+            position,
+            gentle_unwind,
+            cancellation_token,
+        );
     }
 
     let action = async move {
@@ -54,3 +71,44 @@ pub fn close_position_if_needed(
         action,
     ))
 }
+
+fn unwind_position(
+    market_account_id: &MarketAccountId,
+    position: Amount,
+    gentle_unwind: GentleUnwind,
+    cancellation_token: CancellationToken,
+) -> Option<JoinHandle<FutureOutcome>> {
+    let side = match position.is_sign_positive() {
+        true => OrderSide::Sell,
+        false => OrderSide::Buy,
+    };
+
+    let start_time = time_manager::now();
+    let end_time = start_time
+        + chrono::Duration::from_std(gentle_unwind.duration)
+            .expect("Unable to convert unwind duration");
+
+    let parent_order = ParentOrder {
+        exchange_account_id: market_account_id.exchange_account_id,
+        currency_pair: market_account_id.currency_pair,
+        side,
+        amount: position.abs(),
+        limit_price: None,
+        start_time,
+        end_time,
+        participation_rate: None,
+        algo_type: ExecutionAlgoType::Twap,
+    };
+
+    log::info!("Started gentle unwind of position {position} for {market_account_id}");
+    match gentle_unwind
+        .execution_algo_service
+        .start(parent_order, cancellation_token)
+    {
+        Ok((_, handle)) => Some(handle),
+        Err(err) => {
+            log::error!("Failed to start gentle unwind for {market_account_id}: {err:?}");
+            None
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use mmb_domain::exchanges::symbol::{ContractType, Symbol};
+use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Method of matching closing fills with opened inventory
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CostBasisMode {
+    /// Closing fill realizes P&L against the oldest opened lots first
+    #[default]
+    Fifo,
+    /// All opened lots are merged to single lot with weighted average price
+    AverageCost,
+}
+
+/// Opened part of position. Amount is positive for long lots and negative for short ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Lot {
+    pub amount: Amount,
+    pub price: Price,
+}
+
+/// Inventory of opened lots of single market. P&L is calculated in settlement currency
+/// of symbol according to its contract type
+#[derive(Debug, Clone)]
+pub struct Inventory {
+    mode: CostBasisMode,
+    symbol: Arc<Symbol>,
+    lots: VecDeque<Lot>,
+}
+
+impl Inventory {
+    pub fn new(mode: CostBasisMode, symbol: Arc<Symbol>) -> Self {
+        Self {
+            mode,
+            symbol,
+            lots: VecDeque::new(),
+        }
+    }
+
+    /// Signed position: positive for long, negative for short
+    pub fn position(&self) -> Amount {
+        self.lots.iter().map(|x| x.amount).sum()
+    }
+
+    pub fn average_price(&self) -> Option<Price> {
+        let position = self.position();
+        if position.is_zero() {
+            return None;
+        }
+
+        Some(self.weighted_average_price(self.lots.iter()))
+    }
+
+    /// Applies fill to inventory and returns realized P&L in settlement currency without fees
+    pub fn apply_fill(&mut self, side: OrderSide, amount: Amount, price: Price) -> Decimal {
+        let mut remaining = match side {
+            OrderSide::Buy => amount,
+            OrderSide::Sell => -amount,
+        };
+
+        let mut realized_pnl = Decimal::ZERO;
+        while !remaining.is_zero() {
+            let lot = match self.lots.front_mut() {
+                // fill closes lot of opposite direction
+                Some(lot) if lot.amount.is_sign_positive() != remaining.is_sign_positive() => lot,
+                _ => break,
+            };
+
+            let closed_amount = match lot.amount.abs() <= remaining.abs() {
+                true => lot.amount,
+                false => -remaining,
+            };
+            realized_pnl += self.symbol.position_pnl(closed_amount, lot.price, price);
+
+            lot.amount -= closed_amount;
+            remaining += closed_amount;
+            if lot.amount.is_zero() {
+                let _ = self.lots.pop_front();
+            }
+        }
+
+        if !remaining.is_zero() {
+            self.open_lot(Lot {
+                amount: remaining,
+                price,
+            });
+        }
+
+        realized_pnl
+    }
+
+    /// P&L in settlement currency of closing all lots by mark price
+    pub fn unrealized_pnl(&self, mark_price: Price) -> Decimal {
+        self.lots
+            .iter()
+            .map(|x| self.symbol.position_pnl(x.amount, x.price, mark_price))
+            .sum()
+    }
+
+    fn open_lot(&mut self, lot: Lot) {
+        if self.mode == CostBasisMode::AverageCost && !self.lots.is_empty() {
+            let price = self.weighted_average_price(self.lots.iter().chain([&lot]));
+            let average_lot = &mut self.lots[0];
+            average_lot.amount += lot.amount;
+            average_lot.price = price;
+            return;
+        }
+
+        self.lots.push_back(lot);
+    }
+
+    /// Average entry price of lots of the same direction. Inverse contracts are averaged
+    /// harmonically because their value is proportional to `1 / price`
+    fn weighted_average_price<'a>(&self, lots: impl Iterator<Item = &'a Lot> + Clone) -> Price {
+        let amount: Amount = lots.clone().map(|x| x.amount).sum();
+        match self.symbol.contract_type {
+            ContractType::Linear | ContractType::Quanto => {
+                lots.map(|x| x.amount * x.price).sum::<Decimal>() / amount
+            }
+            ContractType::Inverse => amount / lots.map(|x| x.amount / x.price).sum::<Decimal>(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::exchanges::symbol::Precision;
+    use rust_decimal_macros::dec;
+
+    fn symbol(contract_type: ContractType) -> Arc<Symbol> {
+        let (is_derivative, amount_currency_code, balance_currency_code) = match contract_type {
+            ContractType::Inverse => (true, "usd", Some("btc".into())),
+            _ => (false, "btc", None),
+        };
+
+        let mut symbol = Symbol::new(
+            is_derivative,
+            "btc".into(),
+            "btc".into(),
+            "usd".into(),
+            "usd".into(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            amount_currency_code.into(),
+            balance_currency_code,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0.001) },
+        );
+        symbol.contract_type = contract_type;
+        Arc::new(symbol)
+    }
+
+    #[test]
+    fn fifo_realized_pnl() {
+        let mut inventory = Inventory::new(CostBasisMode::Fifo, symbol(ContractType::Linear));
+
+        assert_eq!(
+            inventory.apply_fill(OrderSide::Buy, dec!(1), dec!(100)),
+            dec!(0)
+        );
+        assert_eq!(
+            inventory.apply_fill(OrderSide::Buy, dec!(1), dec!(110)),
+            dec!(0)
+        );
+        assert_eq!(inventory.average_price(), Some(dec!(105)));
+
+        // closes the first lot bought by 100
+        assert_eq!(
+            inventory.apply_fill(OrderSide::Sell, dec!(1), dec!(120)),
+            dec!(20)
+        );
+        assert_eq!(inventory.position(), dec!(1));
+        assert_eq!(inventory.unrealized_pnl(dec!(120)), dec!(10));
+
+        // closes the second lot and opens short
+        assert_eq!(
+            inventory.apply_fill(OrderSide::Sell, dec!(2), dec!(100)),
+            dec!(-10)
+        );
+        assert_eq!(inventory.position(), dec!(-1));
+        assert_eq!(inventory.average_price(), Some(dec!(100)));
+        assert_eq!(inventory.unrealized_pnl(dec!(90)), dec!(10));
+
+        assert_eq!(
+            inventory.apply_fill(OrderSide::Buy, dec!(1), dec!(95)),
+            dec!(5)
+        );
+        assert_eq!(inventory.position(), dec!(0));
+        assert_eq!(inventory.average_price(), None);
+    }
+
+    #[test]
+    fn average_cost_realized_pnl() {
+        let mut inventory =
+            Inventory::new(CostBasisMode::AverageCost, symbol(ContractType::Linear));
+
+        assert_eq!(
+            inventory.apply_fill(OrderSide::Buy, dec!(1), dec!(100)),
+            dec!(0)
+        );
+        assert_eq!(
+            inventory.apply_fill(OrderSide::Buy, dec!(1), dec!(110)),
+            dec!(0)
+        );
+
+        // realized against average price 105
+        assert_eq!(
+            inventory.apply_fill(OrderSide::Sell, dec!(1), dec!(120)),
+            dec!(15)
+        );
+        assert_eq!(inventory.position(), dec!(1));
+        assert_eq!(inventory.average_price(), Some(dec!(105)));
+    }
+
+    #[test]
+    fn inverse_realized_pnl() {
+        let mut inventory =
+            Inventory::new(CostBasisMode::AverageCost, symbol(ContractType::Inverse));
+
+        // 100 USD contracts by 40000 and 100 USD contracts by 60000
+        assert_eq!(
+            inventory.apply_fill(OrderSide::Buy, dec!(100), dec!(40000)),
+            dec!(0)
+        );
+        assert_eq!(
+            inventory.apply_fill(OrderSide::Buy, dec!(100), dec!(60000)),
+            dec!(0)
+        );
+        // harmonic average price
+        let average_price = inventory.average_price().map(|x| x.round_dp(8));
+        assert_eq!(average_price, Some(dec!(48000)));
+
+        // realized P&L is in BTC
+        let realized_pnl = inventory.apply_fill(OrderSide::Sell, dec!(200), dec!(50000));
+        let expected = dec!(200) * (dec!(1) / dec!(48000) - dec!(1) / dec!(50000));
+        assert_eq!(realized_pnl.round_dp(12), expected.round_dp(12));
+        assert_eq!(inventory.position(), dec!(0));
+    }
+}
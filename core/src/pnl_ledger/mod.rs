@@ -0,0 +1,481 @@
+mod lots;
+
+pub use lots::{CostBasisMode, Inventory, Lot};
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use mmb_database::impl_event;
+use mmb_database::postgres_db::events::EventPriority;
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::exchanges::symbol::Symbol;
+use mmb_domain::market::{CurrencyCode, CurrencyPair, ExchangeAccountId, MarketAccountId};
+use mmb_domain::order::fill::OrderFill;
+use mmb_domain::order::snapshot::{Amount, ClientOrderFillId, OrderSide, OrderSnapshot, Price};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use mmb_utils::DateTime;
+use mockall_double::double;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::database::events::recorder::EventRecorder;
+use crate::infrastructure::spawn_future;
+use crate::order_book::local_snapshot_service::LocalSnapshotsService;
+use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+#[double]
+use crate::services::usd_convertion::usd_converter::UsdConverter;
+
+/// Record of P&L ledger about single fill
+#[derive(Debug, Clone, Serialize)]
+pub struct PnlLedgerEntry {
+    pub configuration_descriptor: ConfigurationDescriptor,
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub client_order_fill_id: Option<ClientOrderFillId>,
+    pub side: OrderSide,
+    pub amount: Amount,
+    pub price: Price,
+    pub fill_time: DateTime,
+    pub cost_basis: CostBasisMode,
+    /// Realized P&L in settlement currency without fees
+    pub realized_pnl: Decimal,
+    pub realized_pnl_usd: Option<Decimal>,
+    pub commission_currency_code: CurrencyCode,
+    pub commission_amount: Amount,
+    pub commission_usd: Option<Amount>,
+    /// Signed position after fill
+    pub position: Amount,
+    pub average_price: Option<Price>,
+}
+
+impl_event!(PnlLedgerEntry, "pnl_ledger", EventPriority::Critical);
+
+/// Current P&L of strategy configuration on single market
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketPnl {
+    pub configuration_descriptor: ConfigurationDescriptor,
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    /// Currency of P&L: quote currency for spot and linear contracts, base currency for inverse
+    /// contracts and currency outside currency pair for quanto contracts
+    pub settlement_currency_code: CurrencyCode,
+    pub position: Amount,
+    pub average_price: Option<Price>,
+    /// Realized P&L in settlement currency without fees
+    pub realized_pnl: Decimal,
+    /// Realized P&L without fees in USD. Fills that couldn't be converted are not taken into account
+    pub realized_pnl_usd: Decimal,
+    pub fees_usd: Decimal,
+    pub mark_price: Option<Price>,
+    pub unrealized_pnl: Option<Decimal>,
+    pub unrealized_pnl_usd: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+struct LedgerKey {
+    configuration_descriptor: ConfigurationDescriptor,
+    market_account_id: MarketAccountId,
+}
+
+struct MarketLedger {
+    settlement_currency_code: CurrencyCode,
+    inventory: Inventory,
+    realized_pnl: Decimal,
+    realized_pnl_usd: Decimal,
+    fees_usd: Decimal,
+}
+
+/// Realized and unrealized P&L of fills grouped by strategy configuration and market.
+/// Realized P&L is calculated by matching fills with opened lots, unrealized P&L is marked
+/// by middle price of local order book snapshots
+pub struct PnlLedger {
+    cost_basis: CostBasisMode,
+    ledgers: Mutex<HashMap<LedgerKey, MarketLedger>>,
+    order_books: Mutex<LocalSnapshotsService>,
+    /// Last known USD price of settlement currencies
+    usd_prices: Mutex<HashMap<CurrencyCode, Price>>,
+    usd_converter: Mutex<Option<Arc<UsdConverter>>>,
+    event_recorder: Arc<EventRecorder>,
+    stop_token: CancellationToken,
+}
+
+impl PnlLedger {
+    pub fn new(
+        cost_basis: CostBasisMode,
+        event_recorder: Arc<EventRecorder>,
+        stop_token: CancellationToken,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            cost_basis,
+            ledgers: Default::default(),
+            order_books: Mutex::new(LocalSnapshotsService::new(HashMap::new())),
+            usd_prices: Default::default(),
+            usd_converter: Default::default(),
+            event_recorder,
+            stop_token,
+        })
+    }
+
+    pub fn setup_usd_converter(&self, usd_converter: Arc<UsdConverter>) {
+        *self.usd_converter.lock() = Some(usd_converter);
+    }
+
+    /// Keep order book snapshots actual for marking of opened positions
+    pub async fn start_mark_prices_tracking(
+        self: Arc<Self>,
+        mut events_receiver: broadcast::Receiver<ExchangeEvent>,
+    ) -> Result<()> {
+        loop {
+            let event = tokio::select! {
+                event_res = events_receiver.recv() => match event_res {
+                    Ok(event) => event,
+                    // marking by the next order book events is good enough
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(err) => return Err(err).context("Error during receiving event in PnlLedger::start_mark_prices_tracking()"),
+                },
+                _ = self.stop_token.when_cancelled() => return Ok(()),
+            };
+
+            if let ExchangeEvent::OrderBookEvent(order_book_event) = event {
+                let _ = self.order_books.lock().update(&order_book_event);
+            }
+        }
+    }
+
+    pub fn add_fill(
+        self: &Arc<Self>,
+        configuration_descriptor: ConfigurationDescriptor,
+        symbol: &Arc<Symbol>,
+        order_snapshot: &OrderSnapshot,
+        order_fill: &OrderFill,
+    ) {
+        let header = &order_snapshot.header;
+        let key = LedgerKey {
+            configuration_descriptor,
+            market_account_id: MarketAccountId::new(
+                header.exchange_account_id,
+                header.currency_pair,
+            ),
+        };
+
+        let entry = {
+            let mut ledgers = self.ledgers.lock();
+            let ledger = ledgers.entry(key).or_insert_with(|| MarketLedger {
+                settlement_currency_code: symbol.settlement_currency_code(),
+                inventory: Inventory::new(self.cost_basis, symbol.clone()),
+                realized_pnl: Decimal::ZERO,
+                realized_pnl_usd: Decimal::ZERO,
+                fees_usd: Decimal::ZERO,
+            });
+
+            let realized_pnl =
+                ledger
+                    .inventory
+                    .apply_fill(header.side, order_fill.amount(), order_fill.price());
+            ledger.realized_pnl += realized_pnl;
+
+            PnlLedgerEntry {
+                configuration_descriptor,
+                exchange_account_id: header.exchange_account_id,
+                currency_pair: header.currency_pair,
+                client_order_fill_id: order_fill.client_order_fill_id().clone(),
+                side: header.side,
+                amount: order_fill.amount(),
+                price: order_fill.price(),
+                fill_time: order_fill.receive_time(),
+                cost_basis: self.cost_basis,
+                realized_pnl,
+                realized_pnl_usd: None,
+                commission_currency_code: order_fill.commission_currency_code(),
+                commission_amount: order_fill.commission_amount(),
+                commission_usd: None,
+                position: ledger.inventory.position(),
+                average_price: ledger.inventory.average_price(),
+            }
+        };
+
+        let usd_converter = match self.usd_converter.lock().clone() {
+            Some(usd_converter) => usd_converter,
+            None => return self.save_entry(entry),
+        };
+
+        let _ = spawn_future(
+            "PnlLedger fill conversion to USD",
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            self.clone().convert_to_usd(
+                key,
+                entry,
+                symbol.settlement_currency_code(),
+                usd_converter,
+            ),
+        );
+    }
+
+    async fn convert_to_usd(
+        self: Arc<Self>,
+        key: LedgerKey,
+        mut entry: PnlLedgerEntry,
+        settlement_currency_code: CurrencyCode,
+        usd_converter: Arc<UsdConverter>,
+    ) -> Result<()> {
+        let cancellation_token = self.stop_token.create_linked_token();
+
+        let usd_price = usd_converter
+            .convert_amount(
+                settlement_currency_code,
+                Decimal::ONE,
+                cancellation_token.clone(),
+            )
+            .await;
+        if let Some(usd_price) = usd_price {
+            let _ = self
+                .usd_prices
+                .lock()
+                .insert(settlement_currency_code, usd_price);
+            entry.realized_pnl_usd = Some(entry.realized_pnl * usd_price);
+        }
+
+        entry.commission_usd = usd_converter
+            .convert_amount(
+                entry.commission_currency_code,
+                entry.commission_amount,
+                cancellation_token,
+            )
+            .await;
+
+        if let Some(ledger) = self.ledgers.lock().get_mut(&key) {
+            ledger.realized_pnl_usd += entry.realized_pnl_usd.unwrap_or_default();
+            ledger.fees_usd += entry.commission_usd.unwrap_or_default();
+        }
+
+        self.save_entry(entry);
+
+        Ok(())
+    }
+
+    fn save_entry(&self, entry: PnlLedgerEntry) {
+        if let Err(err) = self.event_recorder.save(entry) {
+            log::error!("Failed to save P&L ledger entry: {err:?}");
+        }
+    }
+
+    /// P&L of all markets where fills were received
+    pub fn get_pnl(&self) -> Vec<MarketPnl> {
+        let order_books = self.order_books.lock();
+        let usd_prices = self.usd_prices.lock();
+
+        self.ledgers
+            .lock()
+            .iter()
+            .map(|(key, ledger)| {
+                let market_id = key.market_account_id.market_id();
+                let mark_price = order_books
+                    .get_snapshot(market_id)
+                    .and_then(|x| x.calculate_middle_price(market_id));
+                let unrealized_pnl = mark_price.map(|x| ledger.inventory.unrealized_pnl(x));
+                let unrealized_pnl_usd = unrealized_pnl
+                    .zip(usd_prices.get(&ledger.settlement_currency_code))
+                    .map(|(pnl, usd_price)| pnl * usd_price);
+
+                MarketPnl {
+                    configuration_descriptor: key.configuration_descriptor,
+                    exchange_account_id: key.market_account_id.exchange_account_id,
+                    currency_pair: key.market_account_id.currency_pair,
+                    settlement_currency_code: ledger.settlement_currency_code,
+                    position: ledger.inventory.position(),
+                    average_price: ledger.inventory.average_price(),
+                    realized_pnl: ledger.realized_pnl,
+                    realized_pnl_usd: ledger.realized_pnl_usd,
+                    fees_usd: ledger.fees_usd,
+                    mark_price,
+                    unrealized_pnl,
+                    unrealized_pnl_usd,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::init_lifetime_manager;
+    use chrono::Utc;
+    use mmb_domain::exchanges::symbol::Precision;
+    use mmb_domain::order::fill::OrderFillType;
+    use mmb_domain::order::snapshot::{OrderFillRole, OrderOptions};
+    use mmb_utils::infrastructure::WithExpect;
+    use ntest::timeout;
+    use rust_decimal_macros::dec;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    fn symbol() -> Arc<Symbol> {
+        Arc::new(Symbol::new(
+            false,
+            "eth".into(),
+            "ETH".into(),
+            "usdt".into(),
+            "USDT".into(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            "eth".into(),
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0.001) },
+        ))
+    }
+
+    fn order(symbol: &Symbol, side: OrderSide, price: Price, amount: Amount) -> OrderSnapshot {
+        OrderSnapshot::with_params(
+            Uuid::new_v4().to_string().as_str().into(),
+            OrderOptions::liquidation(price),
+            None,
+            ExchangeAccountId::new("Binance", 0),
+            symbol.currency_pair(),
+            amount,
+            side,
+            None,
+            "StrategyInUnitTests",
+        )
+    }
+
+    fn order_fill(price: Price, amount: Amount) -> OrderFill {
+        order_fill_with_commission(price, amount, dec!(0.1))
+    }
+
+    fn order_fill_with_commission(
+        price: Price,
+        amount: Amount,
+        commission_amount: Amount,
+    ) -> OrderFill {
+        OrderFill::new(
+            Uuid::new_v4(),
+            None,
+            Utc::now(),
+            OrderFillType::UserTrade,
+            None,
+            price,
+            amount,
+            price * amount,
+            OrderFillRole::Taker,
+            "usdt".into(),
+            commission_amount,
+            dec!(0),
+            "usdt".into(),
+            dec!(0.1),
+            dec!(0.1),
+            false,
+            None,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn pnl_by_configuration_descriptor() {
+        let event_recorder = EventRecorder::start(None, None)
+            .await
+            .expect("Failure start EventRecorder");
+        let pnl_ledger = PnlLedger::new(
+            CostBasisMode::Fifo,
+            event_recorder,
+            CancellationToken::default(),
+        );
+
+        let symbol = symbol();
+        let strategy_1 = ConfigurationDescriptor::new("strategy_1".into(), "key".into());
+        let strategy_2 = ConfigurationDescriptor::new("strategy_2".into(), "key".into());
+
+        let fills = [
+            (strategy_1, OrderSide::Buy, dec!(100), dec!(2)),
+            (strategy_2, OrderSide::Sell, dec!(105), dec!(1)),
+            (strategy_1, OrderSide::Sell, dec!(110), dec!(1)),
+        ];
+        for (configuration_descriptor, side, price, amount) in fills {
+            pnl_ledger.add_fill(
+                configuration_descriptor,
+                &symbol,
+                &order(&symbol, side, price, amount),
+                &order_fill(price, amount),
+            );
+        }
+
+        let pnl = pnl_ledger.get_pnl();
+        let get_market_pnl = |configuration_descriptor| {
+            pnl.iter()
+                .find(|x| x.configuration_descriptor == configuration_descriptor)
+                .with_expect(|| format!("P&L of {configuration_descriptor:?} should exist"))
+        };
+
+        let strategy_1_pnl = get_market_pnl(strategy_1);
+        assert_eq!(strategy_1_pnl.position, dec!(1));
+        assert_eq!(strategy_1_pnl.average_price, Some(dec!(100)));
+        assert_eq!(strategy_1_pnl.realized_pnl, dec!(10));
+        // there are no order book snapshots for marking
+        assert_eq!(strategy_1_pnl.unrealized_pnl, None);
+
+        let strategy_2_pnl = get_market_pnl(strategy_2);
+        assert_eq!(strategy_2_pnl.position, dec!(-1));
+        assert_eq!(strategy_2_pnl.realized_pnl, dec!(0));
+    }
+
+    #[tokio::test]
+    #[timeout(120_000)]
+    async fn pnl_in_usd() {
+        let _ = init_lifetime_manager();
+        let event_recorder = EventRecorder::start(None, None)
+            .await
+            .expect("Failure start EventRecorder");
+        let pnl_ledger = PnlLedger::new(
+            CostBasisMode::Fifo,
+            event_recorder,
+            CancellationToken::default(),
+        );
+
+        let (mut usd_converter, _usd_converter_locker) = UsdConverter::init_mock();
+        usd_converter
+            .expect_convert_amount()
+            .returning(|_, amount, _| Some(amount * dec!(0.5)));
+        pnl_ledger.setup_usd_converter(Arc::new(usd_converter));
+
+        let symbol = symbol();
+        let strategy = ConfigurationDescriptor::new("strategy".into(), "key".into());
+        pnl_ledger.add_fill(
+            strategy,
+            &symbol,
+            &order(&symbol, OrderSide::Buy, dec!(100), dec!(2)),
+            &order_fill_with_commission(dec!(100), dec!(2), dec!(0)),
+        );
+        pnl_ledger.add_fill(
+            strategy,
+            &symbol,
+            &order(&symbol, OrderSide::Sell, dec!(110), dec!(1)),
+            &order_fill_with_commission(dec!(110), dec!(1), dec!(0.4)),
+        );
+
+        // fills are converted to USD in background
+        let market_pnl = loop {
+            let market_pnl = pnl_ledger
+                .get_pnl()
+                .into_iter()
+                .next()
+                .expect("P&L should exist");
+            if market_pnl.fees_usd > dec!(0) {
+                break market_pnl;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+
+        assert_eq!(market_pnl.realized_pnl, dec!(10));
+        assert_eq!(market_pnl.realized_pnl_usd, dec!(5));
+        assert_eq!(market_pnl.fees_usd, dec!(0.2));
+    }
+}
@@ -1,8 +1,13 @@
 use anyhow::Result;
+use dashmap::DashMap;
+use mmb_domain::market::ExchangeAccountId;
 use parking_lot::Mutex;
 use tokio::sync::{mpsc, oneshot};
 
+use crate::exchanges::general::exchange::Exchange;
+use crate::execution_algo::service::ExecutionAlgoService;
 use crate::lifecycle::app_lifetime_manager::{ActionAfterGracefulShutdown, AppLifetimeManager};
+use crate::pnl_ledger::PnlLedger;
 use std::sync::Arc;
 
 use crate::{lifecycle::trading_engine::Service, statistic_service::StatisticService};
@@ -27,6 +32,9 @@ impl CoreApi {
         lifetime_manager: Arc<AppLifetimeManager>,
         engine_settings: String,
         statistics: Arc<StatisticService>,
+        execution_algo_service: Arc<ExecutionAlgoService>,
+        pnl_ledger: Arc<PnlLedger>,
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
     ) -> Result<Arc<Self>> {
         let (server_stopper_tx, server_stopper_rx) =
             mpsc::channel::<ActionAfterGracefulShutdown>(10);
@@ -38,6 +46,9 @@ impl CoreApi {
         } = crate_server_and_channels(RpcImpl::new(
             server_stopper_tx.clone(),
             statistics,
+            execution_algo_service,
+            pnl_ledger,
+            exchanges,
             engine_settings,
         ));
 
@@ -1,12 +1,22 @@
+use dashmap::DashMap;
 use jsonrpc_core::Result;
+use mmb_domain::market::ExchangeAccountId;
 use mmb_rpc::rest_api::server_side_error;
 use mmb_rpc::rest_api::MmbRpc;
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::SpawnFutureFlags;
 use parking_lot::Mutex;
 use tokio::sync::mpsc;
 
 use std::sync::Arc;
 
+use crate::exchanges::general::exchange::Exchange;
+use crate::execution_algo::service::ExecutionAlgoService;
+use crate::execution_algo::ParentOrder;
+use crate::infrastructure::spawn_future;
 use crate::lifecycle::app_lifetime_manager::ActionAfterGracefulShutdown;
+use crate::pnl_ledger::PnlLedger;
+use crate::settings::CurrencyPairSetting;
 use crate::statistic_service::StatisticService;
 use mmb_rpc::rest_api::ErrorCode;
 
@@ -17,6 +27,9 @@ use super::common::set_config;
 pub struct RpcImpl {
     server_stopper_tx: Arc<Mutex<Option<mpsc::Sender<ActionAfterGracefulShutdown>>>>,
     statistics: Arc<StatisticService>,
+    execution_algo_service: Arc<ExecutionAlgoService>,
+    pnl_ledger: Arc<PnlLedger>,
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
     engine_settings: String,
 }
 
@@ -24,11 +37,17 @@ impl RpcImpl {
     pub fn new(
         server_stopper_tx: Arc<Mutex<Option<mpsc::Sender<ActionAfterGracefulShutdown>>>>,
         statistics: Arc<StatisticService>,
+        execution_algo_service: Arc<ExecutionAlgoService>,
+        pnl_ledger: Arc<PnlLedger>,
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
         engine_settings: String,
     ) -> Self {
         Self {
             server_stopper_tx,
             statistics,
+            execution_algo_service,
+            pnl_ledger,
+            exchanges,
             engine_settings,
         }
     }
@@ -66,4 +85,83 @@ impl MmbRpc for RpcImpl {
 
         Ok(json_statistic)
     }
+
+    fn execution_algos(&self) -> Result<String> {
+        let progress = self.execution_algo_service.get_all_progress();
+        serde_json::to_string(&progress).map_err(|err| {
+            log::warn!("Failed to convert {progress:?} to string: {err}");
+            server_side_error(ErrorCode::FailedToSerializeData)
+        })
+    }
+
+    fn start_execution_algo(&self, parent_order: String) -> Result<String> {
+        let parent_order: ParentOrder = serde_json::from_str(&parent_order).map_err(|err| {
+            log::warn!("Failed to parse parent order {parent_order}: {err}");
+            server_side_error(ErrorCode::FailedToStartExecutionAlgo)
+        })?;
+
+        let (id, _) = self
+            .execution_algo_service
+            .start(parent_order, CancellationToken::default())
+            .map_err(|err| {
+                log::warn!("Failed to start execution algo: {err:?}");
+                server_side_error(ErrorCode::FailedToStartExecutionAlgo)
+            })?;
+
+        Ok(format!("Execution algo {id} is started"))
+    }
+
+    fn cancel_execution_algo(&self, id: u64) -> Result<String> {
+        self.execution_algo_service.cancel(id).map_err(|err| {
+            log::warn!("Failed to cancel execution algo: {err:?}");
+            server_side_error(ErrorCode::ExecutionAlgoNotFound)
+        })?;
+
+        Ok(format!("Execution algo {id} cancellation is requested"))
+    }
+
+    fn pnl(&self) -> Result<String> {
+        let pnl = self.pnl_ledger.get_pnl();
+        serde_json::to_string(&pnl).map_err(|err| {
+            log::warn!("Failed to convert {pnl:?} to string: {err}");
+            server_side_error(ErrorCode::FailedToSerializeData)
+        })
+    }
+
+    fn update_currency_pairs(
+        &self,
+        exchange_account_id: String,
+        currency_pairs: String,
+    ) -> Result<String> {
+        let exchange_account_id: ExchangeAccountId =
+            exchange_account_id.parse().map_err(|err| {
+                log::warn!("Failed to parse exchange account id {exchange_account_id}: {err:?}");
+                server_side_error(ErrorCode::FailedToUpdateCurrencyPairs)
+            })?;
+
+        let exchange = self
+            .exchanges
+            .get(&exchange_account_id)
+            .map(|x| x.value().clone())
+            .ok_or_else(|| {
+                log::warn!("Exchange {exchange_account_id} not found");
+                server_side_error(ErrorCode::FailedToUpdateCurrencyPairs)
+            })?;
+
+        let currency_pairs: Vec<CurrencyPairSetting> = serde_json::from_str(&currency_pairs)
+            .map_err(|err| {
+                log::warn!("Failed to parse currency pairs {currency_pairs}: {err}");
+                server_side_error(ErrorCode::FailedToUpdateCurrencyPairs)
+            })?;
+
+        let _ = spawn_future(
+            &format!("Update currency pairs for {exchange_account_id}"),
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            async move { exchange.update_currency_pairs(&currency_pairs).await },
+        );
+
+        Ok(format!(
+            "Currency pairs update for {exchange_account_id} is requested"
+        ))
+    }
 }
@@ -52,4 +52,28 @@ impl MmbRpc for RpcImplNoConfig {
     fn stats(&self) -> Result<String> {
         Ok(CONFIG_IS_NOT_SET.into())
     }
+
+    fn execution_algos(&self) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn start_execution_algo(&self, _parent_order: String) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn cancel_execution_algo(&self, _id: u64) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn pnl(&self) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn update_currency_pairs(
+        &self,
+        _exchange_account_id: String,
+        _currency_pairs: String,
+    ) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
 }
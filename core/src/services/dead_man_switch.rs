@@ -0,0 +1,387 @@
+use crate::exchanges::exchange_blocker::ExchangeBlocker;
+use crate::exchanges::general::exchange::Exchange;
+use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use crate::lifecycle::trading_engine::Service;
+use crate::settings::DeadManSwitchSettings;
+use anyhow::{anyhow, Context, Result};
+use itertools::Itertools;
+use mmb_domain::market::CurrencyPair;
+use mmb_utils::cancellation_token::CancellationToken;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio::sync::oneshot::Receiver;
+
+/// Period of checks of heartbeat timestamp by local watchdog
+const WATCHDOG_CHECK_PERIOD: Duration = Duration::from_secs(1);
+
+/// Periodically confirms to exchange that engine is healthy, so exchange cancels all open orders
+/// if the engine hangs or loses network. Confirmations stop when graceful shutdown is started
+/// or trading on exchange is blocked.
+/// For exchanges without native support of cancel-all-after timer local watchdog cancels orders.
+/// The watchdog runs on its own thread with its own runtime, so it isn't stopped by hanging of engine runtime.
+/// The thread is stopped and joined on graceful shutdown of core services, after open orders are cancelled
+pub struct DeadManSwitchService {
+    exchange: Arc<Exchange>,
+    exchange_blocker: Arc<ExchangeBlocker>,
+    lifetime_manager: Arc<AppLifetimeManager>,
+    settings: DeadManSwitchSettings,
+    /// Time of last heartbeat of healthy engine, it is `None` when local watchdog isn't armed
+    last_heartbeat: Mutex<Option<Instant>>,
+    watchdog_stop_token: CancellationToken,
+    watchdog_thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Service for DeadManSwitchService {
+    fn name(&self) -> &str {
+        "DeadManSwitchService"
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<Receiver<Result<()>>> {
+        self.watchdog_stop_token.cancel();
+
+        let watchdog_thread = self.watchdog_thread.lock().take()?;
+        let (work_finished_sender, work_finished_receiver) = oneshot::channel();
+        let _ = tokio::task::spawn_blocking(move || {
+            let result = watchdog_thread
+                .join()
+                .map_err(|_| anyhow!("Dead man's switch watchdog thread panicked"));
+            let _ = work_finished_sender.send(result);
+        });
+
+        Some(work_finished_receiver)
+    }
+}
+
+impl DeadManSwitchService {
+    pub fn new(
+        exchange: Arc<Exchange>,
+        exchange_blocker: Arc<ExchangeBlocker>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        settings: DeadManSwitchSettings,
+    ) -> Self {
+        Self {
+            exchange,
+            exchange_blocker,
+            lifetime_manager,
+            settings,
+            last_heartbeat: Mutex::new(None),
+            watchdog_stop_token: CancellationToken::new(),
+            watchdog_thread: Mutex::new(None),
+        }
+    }
+
+    pub fn heartbeat_period(&self) -> Duration {
+        Duration::from_secs(self.settings.heartbeat_period_secs)
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.settings.timeout_secs)
+    }
+
+    fn is_native(&self) -> bool {
+        self.exchange
+            .features
+            .order_features
+            .supports_cancel_all_after
+    }
+
+    fn is_healthy(&self) -> bool {
+        !self
+            .lifetime_manager
+            .stop_token()
+            .is_cancellation_requested()
+            && !self
+                .exchange_blocker
+                .is_blocked(self.exchange.exchange_account_id)
+    }
+
+    pub async fn heartbeat(self: Arc<Self>) {
+        if !self.is_healthy() {
+            log::trace!(
+                "Dead man's switch isn't armed for {} because engine isn't healthy",
+                self.exchange.exchange_account_id
+            );
+            return;
+        }
+
+        match self.is_native() {
+            true => self.arm_exchange_timer().await,
+            false => *self.last_heartbeat.lock() = Some(Instant::now()),
+        }
+    }
+
+    async fn arm_exchange_timer(&self) {
+        let currency_pairs = self.currency_pairs_with_open_orders();
+        if let Err(err) = self
+            .exchange
+            .exchange_client
+            .cancel_all_orders_after(&currency_pairs, self.timeout())
+            .await
+        {
+            log::error!(
+                "Failed to arm dead man's switch for {}: {err:?}",
+                self.exchange.exchange_account_id
+            );
+        }
+    }
+
+    /// Start local watchdog thread if exchange doesn't support cancel-all-after timer
+    pub fn start_local_watchdog(self: Arc<Self>) -> Result<()> {
+        if self.is_native() {
+            return Ok(());
+        }
+
+        let this = self.clone();
+        let watchdog_thread = std::thread::Builder::new()
+            .name("dead_man_switch_watchdog".to_owned())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(err) => {
+                        log::error!(
+                            "Unable to build runtime for dead man's switch watchdog of {}: {err:?}",
+                            this.exchange.exchange_account_id
+                        );
+                        return;
+                    }
+                };
+
+                loop {
+                    let is_stopped = runtime.block_on(async {
+                        tokio::select! {
+                            _ = this.watchdog_stop_token.when_cancelled() => true,
+                            _ = tokio::time::sleep(WATCHDOG_CHECK_PERIOD) => false,
+                        }
+                    });
+                    if is_stopped {
+                        return;
+                    }
+
+                    if this.is_heartbeat_expired(Instant::now()) {
+                        runtime.block_on(this.cancel_all_orders_locally());
+                    }
+                }
+            })
+            .context("Unable to spawn dead man's switch watchdog thread")?;
+
+        *self.watchdog_thread.lock() = Some(watchdog_thread);
+
+        Ok(())
+    }
+
+    /// Disarms local watchdog if there was no heartbeat during timeout
+    fn is_heartbeat_expired(&self, now: Instant) -> bool {
+        let mut last_heartbeat = self.last_heartbeat.lock();
+        match *last_heartbeat {
+            Some(heartbeat) if heartbeat + self.timeout() <= now => {
+                *last_heartbeat = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    async fn cancel_all_orders_locally(&self) {
+        let exchange_account_id = self.exchange.exchange_account_id;
+        log::warn!(
+            "Dead man's switch is triggered for {exchange_account_id}, cancelling all orders"
+        );
+
+        for currency_pair in self.currency_pairs_with_open_orders() {
+            if let Err(err) = self.exchange.cancel_all_orders(currency_pair).await {
+                log::error!(
+                    "Dead man's switch failed to cancel orders {currency_pair} on {exchange_account_id}: {err:?}"
+                );
+
+                // try again on next check of watchdog if there is still no heartbeat
+                let mut last_heartbeat = self.last_heartbeat.lock();
+                if last_heartbeat.is_none() {
+                    *last_heartbeat = Instant::now().checked_sub(self.timeout());
+                }
+            }
+        }
+    }
+
+    fn currency_pairs_with_open_orders(&self) -> Vec<CurrencyPair> {
+        self.exchange
+            .orders
+            .not_finished
+            .iter()
+            .map(|x| x.currency_pair())
+            .unique()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper::{get_test_exchange_with_client, TestClient};
+    use crate::misc::time::time_manager;
+    use mmb_domain::exchanges::symbol::{Precision, Symbol};
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_domain::order::snapshot::{ClientOrderId, OrderHeader, OrderOptions, OrderSide};
+    use mmb_utils::cancellation_token::CancellationToken;
+    use rust_decimal_macros::dec;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn create_dead_man_switch(
+        exchange_client: TestClient,
+        timeout: Duration,
+    ) -> Arc<DeadManSwitchService> {
+        let symbol = Arc::new(Symbol::new(
+            false,
+            "PHB".into(),
+            "PHB".into(),
+            "BTC".into(),
+            "BTC".into(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            "PHB".into(),
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0.001) },
+        ));
+        let currency_pair = symbol.currency_pair();
+        let exchange_account_id = ExchangeAccountId::new("local_exchange_account_id", 0);
+        let (exchange, _rx) =
+            get_test_exchange_with_client(symbol, exchange_account_id, exchange_client);
+
+        let header = OrderHeader::with_options(
+            ClientOrderId::unique_id(),
+            exchange_account_id,
+            currency_pair,
+            OrderSide::Buy,
+            dec!(1),
+            OrderOptions::limit(dec!(0.5)),
+            None,
+            None,
+            "".to_string(),
+        );
+        let _ = exchange
+            .orders
+            .add_simple_initial(&header, time_manager::now(), None);
+
+        Arc::new(DeadManSwitchService::new(
+            exchange,
+            ExchangeBlocker::new(vec![exchange_account_id]),
+            AppLifetimeManager::new(CancellationToken::new()),
+            DeadManSwitchSettings {
+                timeout_secs: timeout.as_secs(),
+                heartbeat_period_secs: 1,
+            },
+        ))
+    }
+
+    fn test_client(dead_man_switch: &DeadManSwitchService) -> &TestClient {
+        dead_man_switch
+            .exchange
+            .exchange_client
+            .as_any()
+            .downcast_ref::<TestClient>()
+            .expect("exchange client should be TestClient")
+    }
+
+    #[tokio::test]
+    async fn heartbeat_rearms_local_watchdog() {
+        let dead_man_switch = create_dead_man_switch(TestClient::default(), TIMEOUT);
+
+        dead_man_switch.clone().heartbeat().await;
+        let now = Instant::now();
+        assert!(!dead_man_switch.is_heartbeat_expired(now));
+        assert!(dead_man_switch.is_heartbeat_expired(now + TIMEOUT));
+        // watchdog is disarmed after triggering
+        assert!(!dead_man_switch.is_heartbeat_expired(now + TIMEOUT));
+
+        dead_man_switch.clone().heartbeat().await;
+        let now = Instant::now();
+        assert!(!dead_man_switch.is_heartbeat_expired(now + TIMEOUT / 2));
+        assert!(dead_man_switch.is_heartbeat_expired(now + TIMEOUT));
+    }
+
+    #[tokio::test]
+    async fn no_heartbeat_when_engine_is_stopping() {
+        let dead_man_switch = create_dead_man_switch(TestClient::default(), TIMEOUT);
+        dead_man_switch.lifetime_manager.stop_token().cancel();
+
+        dead_man_switch.clone().heartbeat().await;
+
+        assert!(dead_man_switch.last_heartbeat.lock().is_none());
+    }
+
+    #[tokio::test]
+    async fn failed_cancellation_is_retried() {
+        let exchange_client = TestClient {
+            fail_cancel_all_orders: true,
+            ..Default::default()
+        };
+        let dead_man_switch = create_dead_man_switch(exchange_client, TIMEOUT);
+
+        dead_man_switch.clone().heartbeat().await;
+        assert!(dead_man_switch.is_heartbeat_expired(Instant::now() + TIMEOUT));
+
+        dead_man_switch.cancel_all_orders_locally().await;
+
+        assert!(dead_man_switch.is_heartbeat_expired(Instant::now()));
+        assert!(test_client(&dead_man_switch)
+            .cancelled_all_orders
+            .lock()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn watchdog_cancels_orders_without_heartbeat() {
+        let dead_man_switch = create_dead_man_switch(TestClient::default(), Duration::ZERO);
+
+        dead_man_switch
+            .clone()
+            .start_local_watchdog()
+            .expect("in test");
+        dead_man_switch.clone().heartbeat().await;
+
+        let currency_pair = dead_man_switch.currency_pairs_with_open_orders()[0];
+        let test_client = test_client(&dead_man_switch);
+        for _ in 0..50 {
+            if !test_client.cancelled_all_orders.lock().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        assert_eq!(
+            *test_client.cancelled_all_orders.lock(),
+            vec![currency_pair]
+        );
+    }
+
+    #[tokio::test]
+    async fn watchdog_thread_is_joined_on_graceful_shutdown() {
+        let dead_man_switch = create_dead_man_switch(TestClient::default(), TIMEOUT);
+        dead_man_switch
+            .clone()
+            .start_local_watchdog()
+            .expect("in test");
+
+        let work_finished_receiver = dead_man_switch
+            .clone()
+            .graceful_shutdown()
+            .expect("watchdog thread should be started");
+
+        tokio::time::timeout(Duration::from_secs(5), work_finished_receiver)
+            .await
+            .expect("watchdog thread should be stopped")
+            .expect("in test")
+            .expect("in test");
+        assert!(dead_man_switch.watchdog_thread.lock().is_none());
+    }
+}
@@ -1,6 +1,8 @@
 pub mod cleanup_database;
 pub mod cleanup_orders;
+pub mod dead_man_switch;
 pub mod exchange_time_latency;
 pub mod live_ranges;
 pub(crate) mod market_prices;
+pub mod reconciliation;
 pub mod usd_convertion;
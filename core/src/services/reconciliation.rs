@@ -0,0 +1,741 @@
+use crate::balance::manager::balance_manager::BalanceManager;
+use crate::exchanges::block_reasons::RECONCILIATION_DRIFT;
+use crate::exchanges::exchange_blocker::{BlockType, ExchangeBlocker};
+use crate::exchanges::general::exchange::Exchange;
+use crate::lifecycle::trading_engine::Service;
+use crate::misc::time::time_manager;
+use crate::settings::ReconciliationSettings;
+use anyhow::{Context, Result};
+use dashmap::DashMap;
+use futures::future::join_all;
+use mmb_domain::events::ExchangeBalancesAndPositions;
+use mmb_domain::market::{CurrencyCode, CurrencyPair, ExchangeAccountId, MarketAccountId};
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, ExchangeOrderId, OrderInfo, OrderSide, OrderStatus, Price,
+};
+use mmb_domain::position::DerivativePosition;
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::DateTime;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::oneshot::Receiver;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Discrepancy {
+    /// Balance expected by internal model differs from exchange balance
+    BalanceDrift {
+        currency_code: CurrencyCode,
+        expected: Amount,
+        actual: Amount,
+    },
+    /// Exchange position differs from position by received fills.
+    /// Missed fill is priced by current market price if it's known
+    MissedFill {
+        currency_pair: CurrencyPair,
+        expected: Amount,
+        actual: Amount,
+        price: Option<Price>,
+    },
+    /// Order is open on exchange but it is absent in orders pool
+    UnknownOrder {
+        exchange_order_id: ExchangeOrderId,
+        currency_pair: CurrencyPair,
+    },
+    /// Order is finished locally but it is still open on exchange
+    StaleCancel {
+        client_order_id: ClientOrderId,
+        exchange_order_id: ExchangeOrderId,
+    },
+    /// Order is open locally but it is absent in open orders on exchange
+    LostOrder { client_order_id: ClientOrderId },
+}
+
+impl Discrepancy {
+    /// Balance drift in percents of exchange balance
+    fn balance_drift_percent(&self) -> Option<Decimal> {
+        match self {
+            Discrepancy::BalanceDrift {
+                expected, actual, ..
+            } => match actual.is_zero() {
+                true => Some(Decimal::MAX),
+                false => Some((expected - actual).abs() / actual.abs() * dec!(100)),
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Periodically compares internal balances, positions and open orders with exchange ones,
+/// repairs found discrepancies where it is safe and blocks exchange if balance drift is too big
+pub struct ReconciliationService {
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    balance_manager: Arc<Mutex<BalanceManager>>,
+    exchange_blocker: Arc<ExchangeBlocker>,
+    settings: ReconciliationSettings,
+    drift_times_in_row: Mutex<HashMap<ExchangeAccountId, u32>>,
+    // Missed fills are repaired only if the same position difference is found twice in a row
+    // so fills that were in flight during the previous reconciliation aren't duplicated
+    pending_missed_fills: Mutex<HashMap<MarketAccountId, Amount>>,
+}
+
+impl Service for ReconciliationService {
+    fn name(&self) -> &str {
+        "ReconciliationService"
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<Receiver<Result<()>>> {
+        None
+    }
+}
+
+impl ReconciliationService {
+    pub fn new(
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        balance_manager: Arc<Mutex<BalanceManager>>,
+        exchange_blocker: Arc<ExchangeBlocker>,
+        settings: ReconciliationSettings,
+    ) -> Self {
+        Self {
+            exchanges,
+            balance_manager,
+            exchange_blocker,
+            settings,
+            drift_times_in_row: Default::default(),
+            pending_missed_fills: Default::default(),
+        }
+    }
+
+    pub fn period(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.settings.period_secs)
+    }
+
+    pub async fn reconcile(self: Arc<Self>, cancellation_token: CancellationToken) {
+        let exchanges = self
+            .exchanges
+            .iter()
+            .map(|x| x.value().clone())
+            .collect::<Vec<_>>();
+
+        let actions = exchanges.into_iter().map(|exchange| {
+            let this = self.clone();
+            let cancellation_token = cancellation_token.clone();
+            async move {
+                let exchange_account_id = exchange.exchange_account_id;
+                if let Err(err) = this.reconcile_exchange(exchange, cancellation_token).await {
+                    log::error!("Failed reconciliation for {exchange_account_id}: {err:?}");
+                }
+            }
+        });
+
+        join_all(actions).await;
+    }
+
+    async fn reconcile_exchange(
+        &self,
+        exchange: Arc<Exchange>,
+        cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        let exchange_account_id = exchange.exchange_account_id;
+
+        let balances_and_positions = exchange
+            .get_balance(cancellation_token.clone())
+            .await
+            .with_context(|| format!("failed get_balance for {exchange_account_id}"))?;
+        let open_orders = exchange
+            .get_open_orders(false)
+            .await
+            .with_context(|| format!("failed get_open_orders for {exchange_account_id}"))?;
+
+        let (expected_balances, fill_positions) = {
+            let balance_manager = self.balance_manager.lock();
+            let expected_balances =
+                balance_manager.get_expected_exchange_balances(exchange_account_id)?;
+            let fill_positions = balance_manager.get_balances().position_by_fill_amount;
+            (expected_balances, fill_positions)
+        };
+
+        let local_positions = balances_and_positions
+            .positions
+            .iter()
+            .flatten()
+            .map(|position| {
+                let local_position = fill_positions
+                    .as_ref()
+                    .and_then(|x| x.get(exchange_account_id, position.currency_pair))
+                    .unwrap_or_default();
+                (position.currency_pair, local_position)
+            })
+            .collect();
+        let market_prices = balances_and_positions
+            .positions
+            .iter()
+            .flatten()
+            .filter_map(|position| {
+                exchange
+                    .get_market_price(position.currency_pair)
+                    .map(|price| (position.currency_pair, price))
+            })
+            .collect();
+
+        let orders_deadline =
+            time_manager::now() - chrono::Duration::seconds(self.settings.period_secs as i64);
+
+        let mut discrepancies = find_balance_drifts(
+            &expected_balances,
+            &get_balances_map(&balances_and_positions),
+        );
+        discrepancies.extend(find_position_discrepancies(
+            &local_positions,
+            &market_prices,
+            balances_and_positions
+                .positions
+                .as_deref()
+                .unwrap_or_default(),
+        ));
+        discrepancies.extend(find_order_discrepancies(
+            &exchange.orders,
+            &open_orders,
+            orders_deadline,
+        ));
+
+        if !discrepancies.is_empty() {
+            log::warn!(
+                "Reconciliation found discrepancies on {exchange_account_id}: {discrepancies:?}"
+            );
+        }
+
+        self.repair(&exchange, &discrepancies, cancellation_token)
+            .await;
+        self.update_balances(exchange_account_id, &balances_and_positions);
+        self.check_drift(exchange_account_id, &discrepancies);
+
+        Ok(())
+    }
+
+    async fn repair(
+        &self,
+        exchange: &Arc<Exchange>,
+        discrepancies: &[Discrepancy],
+        cancellation_token: CancellationToken,
+    ) {
+        let exchange_account_id = exchange.exchange_account_id;
+
+        let confirmed_missed_fills = take_confirmed_missed_fills(
+            &mut self.pending_missed_fills.lock(),
+            exchange_account_id,
+            discrepancies,
+        );
+        for (currency_pair, diff, price) in confirmed_missed_fills {
+            let market_account_id = MarketAccountId::new(exchange_account_id, currency_pair);
+            let price = match price {
+                Some(price) => price,
+                None => {
+                    log::error!("Can't add missed fill {diff} for {market_account_id} because market price is unknown");
+                    continue;
+                }
+            };
+
+            let side = match diff.is_sign_positive() {
+                true => OrderSide::Buy,
+                false => OrderSide::Sell,
+            };
+            log::warn!(
+                "Adding missed fill {side:?} {} by price {price} for {market_account_id}",
+                diff.abs()
+            );
+            exchange.handle_missed_fill(currency_pair, side, diff.abs(), price);
+        }
+
+        for discrepancy in discrepancies {
+            match discrepancy {
+                Discrepancy::StaleCancel {
+                    client_order_id, ..
+                } => {
+                    if let Some(order) = get_order(&exchange.orders, client_order_id) {
+                        log::warn!("Cancelling order {client_order_id} that is still open on {exchange_account_id}");
+                        let _ = exchange
+                            .cancel_order(&order, cancellation_token.clone())
+                            .await;
+                    }
+                }
+                Discrepancy::LostOrder { client_order_id } => {
+                    if let Some(order) = get_order(&exchange.orders, client_order_id) {
+                        log::warn!("Checking fills of order {client_order_id} that isn't open on {exchange_account_id}");
+                        if let Err(err) = exchange
+                            .check_order_fills(&order, false, None, cancellation_token.clone())
+                            .await
+                        {
+                            log::error!("Failed to check fills of order {client_order_id} on {exchange_account_id}: {err:?}");
+                        }
+                    }
+                }
+                // Missed fills are repaired above after confirmation.
+                // Adoption of unknown orders isn't safe without knowing who created them,
+                // balance drift is repaired by updating balances from exchange
+                Discrepancy::MissedFill { .. }
+                | Discrepancy::UnknownOrder { .. }
+                | Discrepancy::BalanceDrift { .. } => {}
+            }
+        }
+    }
+
+    fn update_balances(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        balances_and_positions: &ExchangeBalancesAndPositions,
+    ) {
+        if let Err(err) = self
+            .balance_manager
+            .lock()
+            .update_exchange_balance(exchange_account_id, balances_and_positions)
+        {
+            log::error!(
+                "Failed to update balances on reconciliation for {exchange_account_id}: {err:?}"
+            );
+        }
+    }
+
+    fn check_drift(&self, exchange_account_id: ExchangeAccountId, discrepancies: &[Discrepancy]) {
+        let max_drift_percent = self.settings.max_balance_drift_percent;
+        let exceeded_drifts: Vec<_> = discrepancies
+            .iter()
+            .filter(|x| {
+                x.balance_drift_percent()
+                    .map(|percent| percent > max_drift_percent)
+                    .unwrap_or(false)
+            })
+            .collect();
+
+        let mut drift_times_in_row = self.drift_times_in_row.lock();
+        if exceeded_drifts.is_empty() {
+            let _ = drift_times_in_row.remove(&exchange_account_id);
+            if self
+                .exchange_blocker
+                .is_blocked_by_reason(exchange_account_id, RECONCILIATION_DRIFT)
+            {
+                log::info!("Balance drift on {exchange_account_id} is within limits, exchange is unblocked");
+                self.exchange_blocker
+                    .unblock(exchange_account_id, RECONCILIATION_DRIFT);
+            }
+            return;
+        }
+
+        let times_in_row = drift_times_in_row.entry(exchange_account_id).or_default();
+        *times_in_row += 1;
+
+        log::error!("Balance drift on {exchange_account_id} exceeds {max_drift_percent}% ({times_in_row} times in row): {exceeded_drifts:?}");
+
+        if *times_in_row >= self.settings.max_drift_times_in_row {
+            self.exchange_blocker.block(
+                exchange_account_id,
+                RECONCILIATION_DRIFT,
+                BlockType::Manual,
+            );
+        }
+    }
+}
+
+/// Update pending missed fills of specified exchange and return missed fills which position difference
+/// is the same as on the previous reconciliation. Pending missed fills of other exchanges aren't changed,
+/// so exchanges can be reconciled concurrently
+fn take_confirmed_missed_fills(
+    pending_missed_fills: &mut HashMap<MarketAccountId, Amount>,
+    exchange_account_id: ExchangeAccountId,
+    discrepancies: &[Discrepancy],
+) -> Vec<(CurrencyPair, Amount, Option<Price>)> {
+    let mut previous_missed_fills = HashMap::new();
+    pending_missed_fills.retain(|market_account_id, diff| {
+        let is_other_exchange = market_account_id.exchange_account_id != exchange_account_id;
+        if !is_other_exchange {
+            let _ = previous_missed_fills.insert(*market_account_id, *diff);
+        }
+        is_other_exchange
+    });
+
+    let mut confirmed_missed_fills = Vec::new();
+    for discrepancy in discrepancies {
+        if let Discrepancy::MissedFill {
+            currency_pair,
+            expected,
+            actual,
+            price,
+        } = discrepancy
+        {
+            let market_account_id = MarketAccountId::new(exchange_account_id, *currency_pair);
+            let diff = actual - expected;
+            match previous_missed_fills.remove(&market_account_id) == Some(diff) {
+                true => confirmed_missed_fills.push((*currency_pair, diff, *price)),
+                false => {
+                    let _ = pending_missed_fills.insert(market_account_id, diff);
+                }
+            }
+        }
+    }
+
+    confirmed_missed_fills
+}
+
+fn get_balances_map(
+    balances_and_positions: &ExchangeBalancesAndPositions,
+) -> HashMap<CurrencyCode, Amount> {
+    balances_and_positions
+        .balances
+        .iter()
+        .map(|x| (x.currency_code, x.balance))
+        .collect()
+}
+
+fn get_order(orders: &OrdersPool, client_order_id: &ClientOrderId) -> Option<OrderRef> {
+    orders
+        .cache_by_client_id
+        .get(client_order_id)
+        .map(|x| x.value().clone())
+}
+
+fn find_balance_drifts(
+    expected_balances: &HashMap<CurrencyCode, Amount>,
+    actual_balances: &HashMap<CurrencyCode, Amount>,
+) -> Vec<Discrepancy> {
+    let currency_codes: HashSet<_> = expected_balances
+        .keys()
+        .chain(actual_balances.keys())
+        .collect();
+
+    currency_codes
+        .into_iter()
+        .filter_map(|currency_code| {
+            let expected = expected_balances
+                .get(currency_code)
+                .copied()
+                .unwrap_or_default();
+            let actual = actual_balances
+                .get(currency_code)
+                .copied()
+                .unwrap_or_default();
+
+            (expected != actual).then_some(Discrepancy::BalanceDrift {
+                currency_code: *currency_code,
+                expected,
+                actual,
+            })
+        })
+        .collect()
+}
+
+fn find_position_discrepancies(
+    local_positions: &HashMap<CurrencyPair, Amount>,
+    market_prices: &HashMap<CurrencyPair, Price>,
+    exchange_positions: &[DerivativePosition],
+) -> Vec<Discrepancy> {
+    exchange_positions
+        .iter()
+        .filter_map(|position| {
+            let expected = local_positions
+                .get(&position.currency_pair)
+                .copied()
+                .unwrap_or_default();
+
+            (expected != position.position).then_some(Discrepancy::MissedFill {
+                currency_pair: position.currency_pair,
+                expected,
+                actual: position.position,
+                price: market_prices.get(&position.currency_pair).copied(),
+            })
+        })
+        .collect()
+}
+
+/// Orders created after `deadline` are skipped because they may be not visible on exchange yet
+fn find_order_discrepancies(
+    orders: &OrdersPool,
+    open_orders: &[OrderInfo],
+    deadline: DateTime,
+) -> Vec<Discrepancy> {
+    let mut discrepancies = Vec::new();
+
+    for order_info in open_orders {
+        match orders
+            .cache_by_exchange_id
+            .get(&order_info.exchange_order_id)
+        {
+            None => discrepancies.push(Discrepancy::UnknownOrder {
+                exchange_order_id: order_info.exchange_order_id.clone(),
+                currency_pair: order_info.currency_pair,
+            }),
+            Some(order) if order.is_finished() => discrepancies.push(Discrepancy::StaleCancel {
+                client_order_id: order.client_order_id(),
+                exchange_order_id: order_info.exchange_order_id.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    let open_exchange_order_ids: HashSet<_> =
+        open_orders.iter().map(|x| &x.exchange_order_id).collect();
+
+    for order in orders.not_finished.iter() {
+        let (status, exchange_order_id, init_time) = order.fn_ref(|x| {
+            (
+                x.status(),
+                x.props.exchange_order_id.clone(),
+                x.props.init_time,
+            )
+        });
+
+        let is_lost = status == OrderStatus::Created
+            && init_time < deadline
+            && exchange_order_id
+                .map(|x| !open_exchange_order_ids.contains(&x))
+                .unwrap_or(false);
+
+        if is_lost {
+            discrepancies.push(Discrepancy::LostOrder {
+                client_order_id: order.client_order_id(),
+            });
+        }
+    }
+
+    discrepancies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use mmb_domain::order::snapshot::{OrderHeader, UserOrder};
+    use rstest::rstest;
+
+    fn currency_pair() -> CurrencyPair {
+        CurrencyPair::from_codes("btc".into(), "usdt".into())
+    }
+
+    fn add_order(pool: &OrdersPool, exchange_order_id: &str, init_time: DateTime) -> OrderRef {
+        let header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            ExchangeAccountId::new("Binance", 0),
+            currency_pair(),
+            OrderSide::Buy,
+            dec!(1),
+            UserOrder::limit(dec!(0.5)),
+            None,
+            None,
+            "".to_string(),
+        );
+        let order = pool.add_simple_initial(&header, init_time, None);
+        let exchange_order_id = ExchangeOrderId::from(exchange_order_id);
+        order.fn_mut(|x| {
+            x.props.exchange_order_id = Some(exchange_order_id.clone());
+            x.set_status(OrderStatus::Created, init_time);
+        });
+        let _ = pool
+            .cache_by_exchange_id
+            .insert(exchange_order_id, order.clone());
+        order
+    }
+
+    fn open_order(exchange_order_id: &str) -> OrderInfo {
+        OrderInfo::new(
+            currency_pair(),
+            exchange_order_id.into(),
+            ClientOrderId::unique_id(),
+            OrderSide::Buy,
+            OrderStatus::Created,
+            dec!(0.5),
+            dec!(1),
+            dec!(0),
+            dec!(0),
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[rstest]
+    #[case(dec!(10), dec!(10), None)]
+    #[case(dec!(12), dec!(10), Some(dec!(20)))]
+    #[case(dec!(1), dec!(0), Some(Decimal::MAX))]
+    fn balance_drifts(
+        #[case] expected: Amount,
+        #[case] actual: Amount,
+        #[case] drift_percent: Option<Decimal>,
+    ) {
+        let currency_code = CurrencyCode::from("btc");
+        let expected_balances = HashMap::from([(currency_code, expected)]);
+        let actual_balances = HashMap::from([(currency_code, actual)]);
+
+        let discrepancies = find_balance_drifts(&expected_balances, &actual_balances);
+
+        assert_eq!(
+            discrepancies
+                .first()
+                .and_then(|x| x.balance_drift_percent()),
+            drift_percent
+        );
+    }
+
+    #[test]
+    fn missed_fill_when_position_differs() {
+        let local_positions = HashMap::from([(currency_pair(), dec!(1))]);
+        let exchange_positions = [DerivativePosition::new(
+            currency_pair(),
+            dec!(3),
+            dec!(100),
+            dec!(0),
+            dec!(1),
+        )];
+
+        let market_prices = HashMap::from([(currency_pair(), dec!(120))]);
+
+        let discrepancies =
+            find_position_discrepancies(&local_positions, &market_prices, &exchange_positions);
+
+        assert_eq!(
+            discrepancies,
+            vec![Discrepancy::MissedFill {
+                currency_pair: currency_pair(),
+                expected: dec!(1),
+                actual: dec!(3),
+                price: Some(dec!(120)),
+            }]
+        );
+    }
+
+    fn missed_fill(expected: Amount, actual: Amount) -> Discrepancy {
+        Discrepancy::MissedFill {
+            currency_pair: currency_pair(),
+            expected,
+            actual,
+            price: Some(dec!(100)),
+        }
+    }
+
+    #[test]
+    fn missed_fill_is_confirmed_by_next_reconciliation() {
+        let exchange_account_id = ExchangeAccountId::new("Binance", 0);
+        let mut pending_missed_fills = HashMap::new();
+
+        let confirmed = take_confirmed_missed_fills(
+            &mut pending_missed_fills,
+            exchange_account_id,
+            &[missed_fill(dec!(1), dec!(3))],
+        );
+        assert!(confirmed.is_empty());
+
+        // position difference changed, so fill was in flight
+        let confirmed = take_confirmed_missed_fills(
+            &mut pending_missed_fills,
+            exchange_account_id,
+            &[missed_fill(dec!(2), dec!(3))],
+        );
+        assert!(confirmed.is_empty());
+
+        let confirmed = take_confirmed_missed_fills(
+            &mut pending_missed_fills,
+            exchange_account_id,
+            &[missed_fill(dec!(2), dec!(3))],
+        );
+        assert_eq!(confirmed, vec![(currency_pair(), dec!(1), Some(dec!(100)))]);
+        assert!(pending_missed_fills.is_empty());
+    }
+
+    #[test]
+    fn concurrent_reconciliations_keep_pending_missed_fills_of_other_exchanges() {
+        let exchange_account_ids = [
+            ExchangeAccountId::new("Binance", 0),
+            ExchangeAccountId::new("Binance", 1),
+        ];
+        let pending_missed_fills = Mutex::new(HashMap::new());
+        let reconcile_concurrently = || {
+            std::thread::scope(|scope| {
+                let handles = exchange_account_ids.map(|exchange_account_id| {
+                    let pending_missed_fills = &pending_missed_fills;
+                    scope.spawn(move || {
+                        take_confirmed_missed_fills(
+                            &mut pending_missed_fills.lock(),
+                            exchange_account_id,
+                            &[missed_fill(dec!(1), dec!(3))],
+                        )
+                    })
+                });
+                handles.map(|handle| handle.join().expect("in test"))
+            })
+        };
+
+        let confirmed = reconcile_concurrently();
+        assert!(confirmed.iter().all(|x| x.is_empty()));
+        assert_eq!(pending_missed_fills.lock().len(), 2);
+
+        let confirmed = reconcile_concurrently();
+        assert!(confirmed.iter().all(|x| x.len() == 1));
+        assert!(pending_missed_fills.lock().is_empty());
+    }
+
+    #[test]
+    fn unknown_order() {
+        let pool = OrdersPool::new();
+        let now = time_manager::now();
+
+        let discrepancies = find_order_discrepancies(&pool, &[open_order("unknown")], now);
+
+        assert_eq!(
+            discrepancies,
+            vec![Discrepancy::UnknownOrder {
+                exchange_order_id: "unknown".into(),
+                currency_pair: currency_pair(),
+            }]
+        );
+    }
+
+    #[test]
+    fn stale_cancel() {
+        let pool = OrdersPool::new();
+        let now = time_manager::now();
+        let order = add_order(&pool, "canceled", now);
+        order.fn_mut(|x| x.set_status(OrderStatus::Canceled, now));
+
+        let discrepancies = find_order_discrepancies(&pool, &[open_order("canceled")], now);
+
+        assert_eq!(
+            discrepancies,
+            vec![Discrepancy::StaleCancel {
+                client_order_id: order.client_order_id(),
+                exchange_order_id: "canceled".into(),
+            }]
+        );
+    }
+
+    #[rstest]
+    #[case::old_order(Duration::minutes(10), true)]
+    #[case::recently_created_order(Duration::zero(), false)]
+    fn lost_order(#[case] age: Duration, #[case] is_lost: bool) {
+        let pool = OrdersPool::new();
+        let now = time_manager::now();
+        let order = add_order(&pool, "lost", now - age);
+
+        let discrepancies = find_order_discrepancies(&pool, &[], now - Duration::minutes(1));
+
+        let expected = match is_lost {
+            true => vec![Discrepancy::LostOrder {
+                client_order_id: order.client_order_id(),
+            }],
+            false => vec![],
+        };
+        assert_eq!(discrepancies, expected);
+    }
+
+    #[test]
+    fn known_open_order_is_not_discrepancy() {
+        let pool = OrdersPool::new();
+        let now = time_manager::now();
+        let _ = add_order(&pool, "open", now - Duration::minutes(10));
+
+        let discrepancies =
+            find_order_discrepancies(&pool, &[open_order("open")], now - Duration::minutes(1));
+
+        assert!(discrepancies.is_empty());
+    }
+}
@@ -15,17 +15,18 @@ use super::{
 };
 
 pub struct UsdConverter {
-    price_source_service: PriceSourceService,
+    price_source_service: Arc<PriceSourceService>,
     usd_currency_code: CurrencyCode,
-    denominator_usd_converter: DenominatorUsdConverter,
+    /// Fallback for currencies without price sources. Isn't used if market prices service isn't available
+    denominator_usd_converter: Option<DenominatorUsdConverter>,
 }
 
 #[cfg_attr(test, automock)]
 impl UsdConverter {
     pub fn new(
         currencies: &[CurrencyCode],
-        price_source_service: PriceSourceService,
-        usd_denominator: Arc<UsdDenominator>,
+        price_source_service: Arc<PriceSourceService>,
+        usd_denominator: Option<Arc<UsdDenominator>>,
     ) -> Self {
         let usd = "USD".into();
         let usdt = "USDT".into();
@@ -36,7 +37,7 @@ impl UsdConverter {
                 .find(move |&&x| x == usdt || x == usd)
                 .cloned()
                 .unwrap_or(usd),
-            denominator_usd_converter: DenominatorUsdConverter::new(usd_denominator),
+            denominator_usd_converter: usd_denominator.map(DenominatorUsdConverter::new),
         }
     }
 
@@ -73,9 +74,19 @@ impl UsdConverter {
             ),
         }
 
+        let denominator_usd_converter = match &self.denominator_usd_converter {
+            Some(denominator_usd_converter) => denominator_usd_converter,
+            None => {
+                log::warn!(
+                    "Can't calculate USD price using PriceSourceService ({from_currency_code})"
+                );
+                return None;
+            }
+        };
+
         log::warn!("Can't calculate USD price using PriceSourceService => trying to use UsdDenominator ({})", from_currency_code);
 
-        self.denominator_usd_converter
+        denominator_usd_converter
             .calculate_using_denominator(from_currency_code, src_amount)
             .await
     }
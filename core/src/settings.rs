@@ -1,5 +1,7 @@
+use crate::pnl_ledger::CostBasisMode;
 use mmb_domain::market::{CurrencyCode, CurrencyPair, ExchangeAccountId};
 use mmb_domain::order::snapshot::Amount;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -22,6 +24,17 @@ pub struct AppSettings<StrategySettings: Clone> {
 pub struct CoreSettings {
     pub database: Option<DbSettings>,
     pub exchanges: Vec<ExchangeSettings>,
+    /// Additional destinations for recorded events, e.g. for deployments without Postgres
+    #[serde(default)]
+    pub event_sinks: Vec<EventSinkSettings>,
+    #[serde(default)]
+    pub pnl_ledger: PnlLedgerSettings,
+    /// Periodic comparison of internal state with exchange state. Disabled if not specified
+    pub reconciliation: Option<ReconciliationSettings>,
+    /// Chains of markets for conversion of amounts to USD, e.g. for pre-trade risk checks and P&L.
+    /// Only amounts in USD or USDT are converted if it's empty
+    #[serde(default)]
+    pub usd_price_sources: Vec<CurrencyPriceSourceSettings>,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
@@ -33,6 +46,34 @@ pub struct DbSettings {
     pub postponed_events_dir: Option<PathBuf>,
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PnlLedgerSettings {
+    /// Method of matching closing fills with opened lots for realized P&L calculation
+    #[serde(default)]
+    pub cost_basis: CostBasisMode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ReconciliationSettings {
+    pub period_secs: u64,
+    /// Max difference between internal and exchange balance in percents of exchange balance
+    pub max_balance_drift_percent: Decimal,
+    /// Count of reconciliations in a row with exceeded balance drift after which exchange is blocked
+    pub max_drift_times_in_row: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum EventSinkSettings {
+    Sqlite {
+        path: PathBuf,
+    },
+    /// Gzip compressed JSON-lines files partitioned by table name and day
+    Files {
+        dir: PathBuf,
+    },
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum CurrencyPairSetting {
@@ -52,12 +93,33 @@ pub struct ExchangeSettings {
     pub exchange_account_id: ExchangeAccountId,
     pub api_key: String,
     pub secret_key: String,
+    /// Is required by some exchanges in addition to api key, e.g. OKX
+    pub api_passphrase: Option<String>,
     pub is_margin_trading: bool,
+    /// Is required by exchanges with separate APIs for different markets, e.g. Binance USDⓈ-M and COIN-M futures
+    pub market_type: Option<MarketType>,
     pub request_trades: bool,
     pub is_reducing_market_data: Option<bool>,
     pub subscribe_to_market_data: bool,
     pub websocket_channels: Vec<String>,
+    /// Number of separate websocket connections which traded currency pairs are distributed across.
+    /// Market data is received via main websocket if it isn't specified
+    pub market_data_connections: Option<usize>,
     pub currency_pairs: Option<Vec<CurrencyPairSetting>>,
+    pub pre_trade_risk: Option<PreTradeRiskSettings>,
+    pub self_trade_prevention: Option<SelfTradePreventionSettings>,
+    /// Commission isn't taken into account in balance reservations if it isn't specified
+    pub commission: Option<CommissionSettings>,
+    pub margin: Option<MarginSettings>,
+    /// New orders are blocked while margin level of spot margin account is below this value.
+    /// Margin level isn't checked if it isn't specified
+    pub min_margin_level: Option<Decimal>,
+    /// Open orders of previous runs found on startup are left as is if it isn't specified
+    pub orphaned_orders: Option<OrphanedOrdersSettings>,
+    /// Open orders aren't cancelled on engine hang or network loss if it isn't specified
+    pub dead_man_switch: Option<DeadManSwitchSettings>,
+    /// Orders are created and cancelled via REST only if it isn't specified
+    pub websocket_order_entry: Option<WebSocketOrderEntrySettings>,
 }
 
 impl ExchangeSettings {
@@ -72,12 +134,23 @@ impl ExchangeSettings {
             exchange_account_id,
             api_key,
             secret_key,
+            api_passphrase: None,
             is_margin_trading,
+            market_type: None,
             request_trades: false,
             websocket_channels: vec![],
+            market_data_connections: None,
             currency_pairs: None,
             subscribe_to_market_data: true,
             is_reducing_market_data: None,
+            pre_trade_risk: None,
+            self_trade_prevention: None,
+            commission: None,
+            margin: None,
+            min_margin_level: None,
+            orphaned_orders: None,
+            dead_man_switch: None,
+            websocket_order_entry: None,
         }
     }
 }
@@ -88,16 +161,138 @@ impl Default for ExchangeSettings {
             exchange_account_id: ExchangeAccountId::new("", 0),
             api_key: "".to_string(),
             secret_key: "".to_string(),
+            api_passphrase: None,
             is_margin_trading: false,
+            market_type: None,
             request_trades: false,
             websocket_channels: vec![],
+            market_data_connections: None,
             currency_pairs: None,
             subscribe_to_market_data: true,
             is_reducing_market_data: None,
+            pre_trade_risk: None,
+            self_trade_prevention: None,
+            commission: None,
+            margin: None,
+            min_margin_level: None,
+            orphaned_orders: None,
+            dead_man_switch: None,
+            websocket_order_entry: None,
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum MarketType {
+    Spot,
+    /// Linear futures margined and settled in stablecoins
+    UsdM,
+    /// Inverse futures margined and settled in base currency
+    CoinM,
+    /// Spot pairs traded on cross margin account with borrowing of assets
+    CrossMargin,
+    /// Spot pairs traded on isolated margin account with borrowing of assets, margin is isolated per currency pair
+    IsolatedMargin,
+}
+
+/// Fees of exchange account in percents
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CommissionSettings {
+    pub maker_fee: Decimal,
+    pub taker_fee: Decimal,
+}
+
+/// Margin requirements for leveraged trading of derivatives.
+/// Initial margin rate is `1 / leverage`, maintenance margin rate depends on leverage tier
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MarginSettings {
+    pub tiers: Vec<MarginTier>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MarginTier {
+    pub max_leverage: Decimal,
+    /// Part of position value that should be kept on balance to avoid liquidation (e.g. 0.005 is 0.5%)
+    pub maintenance_margin_rate: Decimal,
+}
+
+impl MarginSettings {
+    /// Maintenance margin rate of tier with the least max leverage that allows specified leverage
+    pub fn maintenance_margin_rate(&self, leverage: Decimal) -> Decimal {
+        self.tiers
+            .iter()
+            .filter(|tier| tier.max_leverage >= leverage)
+            .min_by_key(|tier| tier.max_leverage)
+            .map(|tier| tier.maintenance_margin_rate)
+            .unwrap_or_default()
+    }
+}
+
+/// Limits that are checked before every order creation. Unspecified limits aren't checked
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PreTradeRiskSettings {
+    pub max_order_notional_usd: Option<Amount>,
+    /// Max relative deviation of order price from mid price of order book (e.g. 0.05 is 5%)
+    pub max_price_deviation: Option<Decimal>,
+    pub max_open_orders_per_market: Option<usize>,
+    pub max_orders_per_second_per_strategy: Option<u32>,
+    /// Max absolute value of net position per market in amount currency
+    pub max_net_position: Option<Amount>,
+}
+
+/// Policies for open orders that are found on exchange on startup but are unknown for engine
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct OrphanedOrdersSettings {
+    /// Orders with strategy tag in client order id, e.g. created by previous run of engine
+    pub own: OrphanedOrdersPolicy,
+    /// Orders without strategy tag, e.g. created by a human
+    pub foreign: OrphanedOrdersPolicy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum OrphanedOrdersPolicy {
+    /// Order is added to orders pool. Own orders are handed with reserved balance to the strategy
+    /// with the same tag, foreign orders aren't reserved because nothing manages them
+    Adopt,
+    Cancel,
+    Ignore,
+}
+
+/// All open orders are cancelled if engine doesn't confirm it is healthy during `timeout_secs`.
+/// Exchange side timer is used if exchange supports it, otherwise local watchdog cancels orders
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DeadManSwitchSettings {
+    pub timeout_secs: u64,
+    /// Period of heartbeats, should be several times less than timeout
+    pub heartbeat_period_secs: u64,
+}
+
+/// Orders are created and cancelled via exchange WebSocket API while its session is established,
+/// REST is used as fallback when the session is down
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WebSocketOrderEntrySettings {
+    /// Request is failed if exchange doesn't respond during this time
+    pub response_timeout_secs: u64,
+}
+
+/// Reaction on new order that crosses not finished order of opposite side on the same market
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SelfTradePreventionPolicy {
+    SkipNew,
+    /// New limit order is moved to one tick before the best crossed order. Orders without price are skipped.
+    /// Order reservation is updated with the new price, so order is rejected if balance isn't enough for it
+    RepriceNew,
+    CancelResting,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SelfTradePreventionSettings {
+    pub policy: SelfTradePreventionPolicy,
+    /// Pass exchange native self-trade prevention mode with order if exchange supports it
+    pub use_exchange_native_mode: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct CurrencyPriceSourceSettings {
     pub start_currency_code: CurrencyCode,
     pub end_currency_code: CurrencyCode,
@@ -119,6 +314,7 @@ impl CurrencyPriceSourceSettings {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ExchangeIdCurrencyPairSettings {
     pub exchange_account_id: ExchangeAccountId,
     pub currency_pair: CurrencyPair,
@@ -6,7 +6,7 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use mmb_domain::events::ExchangeEvent;
-use mmb_domain::market::MarketAccountId;
+use mmb_domain::market::{ExchangeErrorType, MarketAccountId};
 use mmb_domain::order::snapshot::ClientOrderId;
 use mmb_domain::order::snapshot::{Amount, Price};
 use parking_lot::{Mutex, RwLock};
@@ -21,6 +21,8 @@ pub struct MarketAccountIdStatistic {
     canceled_orders_count: u64,
     partially_filled_orders_count: u64,
     fully_filled_orders_count: u64,
+    rejected_by_risk_checks_orders_count: u64,
+    prevented_self_trades_count: u64,
     // Calculated only for completely filled orders
     summary_filled_amount: Amount,
     // Calculated only for completely filled orders
@@ -32,6 +34,14 @@ impl MarketAccountIdStatistic {
         self.opened_orders_count += 1;
     }
 
+    fn register_rejected_by_risk_checks_order(&mut self) {
+        self.rejected_by_risk_checks_orders_count += 1;
+    }
+
+    fn register_prevented_self_trade(&mut self) {
+        self.prevented_self_trades_count += 1;
+    }
+
     fn register_canceled_order(&mut self) {
         self.canceled_orders_count += 1;
     }
@@ -81,6 +91,25 @@ impl StatisticServiceState {
             .register_created_order();
     }
 
+    pub(crate) fn register_rejected_by_risk_checks_order(
+        &self,
+        market_account_id: MarketAccountId,
+    ) {
+        self.market_account_id_stats
+            .write()
+            .entry(market_account_id)
+            .or_default()
+            .register_rejected_by_risk_checks_order();
+    }
+
+    pub(crate) fn register_prevented_self_trade(&self, market_account_id: MarketAccountId) {
+        self.market_account_id_stats
+            .write()
+            .entry(market_account_id)
+            .or_default()
+            .register_prevented_self_trade();
+    }
+
     pub(crate) fn register_canceled_order(&self, market_account_id: MarketAccountId) {
         self.market_account_id_stats
             .write()
@@ -158,6 +187,19 @@ impl StatisticService {
             .register_created_order(market_account_id);
     }
 
+    pub(crate) fn register_rejected_by_risk_checks_order(
+        &self,
+        market_account_id: MarketAccountId,
+    ) {
+        self.statistic_service_state
+            .register_rejected_by_risk_checks_order(market_account_id);
+    }
+
+    pub(crate) fn register_prevented_self_trade(&self, market_account_id: MarketAccountId) {
+        self.statistic_service_state
+            .register_prevented_self_trade(market_account_id);
+    }
+
     pub(crate) fn register_canceled_order(
         &self,
         market_account_id: MarketAccountId,
@@ -269,6 +311,15 @@ impl StatisticEventHandler {
                     OrderEventType::CreateOrderSucceeded => {
                         self.stats.register_created_order(market_account_id);
                     }
+                    OrderEventType::CreateOrderFailed => {
+                        let error_type = order_event
+                            .order
+                            .fn_ref(|x| x.internal_props.last_creation_error_type);
+                        if error_type == Some(ExchangeErrorType::RiskCheckFailed) {
+                            self.stats
+                                .register_rejected_by_risk_checks_order(market_account_id);
+                        }
+                    }
                     OrderEventType::CancelOrderSucceeded => {
                         let client_order_id = order_event.order.client_order_id();
                         self.stats
@@ -4,6 +4,7 @@ use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
 
 use mmb_database::impl_event;
+use mmb_database::postgres_db::events::{Event, TableName};
 use mmb_utils::DateTime;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
@@ -25,10 +26,41 @@ pub struct ExchangeBalance {
     pub balance: Decimal,
 }
 
+/// Asset of spot margin account
+#[derive(Debug, Clone)]
+pub struct MarginBalance {
+    pub currency_code: CurrencyCode,
+    pub free: Amount,
+    pub locked: Amount,
+    pub borrowed: Amount,
+    /// Interest accrued on borrowed amount which isn't repaid yet
+    pub interest: Amount,
+    /// Amount that still can be borrowed
+    pub max_borrowable: Amount,
+}
+
+impl MarginBalance {
+    /// Amount that can be used for new orders. Borrowed amount and accrued interest should be repaid,
+    /// so they aren't available for orders even if they are reported as free
+    pub fn available_for_orders(&self) -> Amount {
+        (self.free + self.max_borrowable - self.borrowed - self.interest).max(dec!(0))
+    }
+}
+
+/// State of spot margin account where assets are borrowed for trading
+#[derive(Debug, Clone)]
+pub struct MarginAccount {
+    pub balances: Vec<MarginBalance>,
+    /// Ratio of total assets to total liabilities. Margin call and liquidation happen when it falls to exchange thresholds
+    pub margin_level: Decimal,
+}
+
 #[derive(Clone)]
 pub struct ExchangeBalancesAndPositions {
     pub balances: Vec<ExchangeBalance>,
     pub positions: Option<Vec<DerivativePosition>>,
+    /// Is specified for spot margin accounts only
+    pub margin_account: Option<MarginAccount>,
 }
 
 impl Debug for ExchangeBalancesAndPositions {
@@ -41,6 +73,7 @@ impl Debug for ExchangeBalancesAndPositions {
         f.debug_struct("ExchangeBalancesAndPositions")
             .field("balances", &non_zero_balances)
             .field("positions", &self.positions)
+            .field("margin_account", &self.margin_account)
             .finish()
     }
 }
@@ -53,7 +86,11 @@ pub struct BalanceUpdateEvent {
 
 pub const LIQUIDATION_PRICE_CURRENT_VERSION: u32 = 1;
 
-#[derive(Debug, Clone)]
+/// Version of `liquidation_prices` records. Records of version 1 contained only liquidation price
+/// and are removed by migration `liquidation_prices_v2`
+pub const LIQUIDATION_PRICE_RECORD_VERSION: i32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct LiquidationPriceEvent {
     pub version: u32,
@@ -86,6 +123,18 @@ impl LiquidationPriceEvent {
     }
 }
 
+impl Event for LiquidationPriceEvent {
+    const TABLE_NAME: TableName = "liquidation_prices";
+
+    fn get_version(&self) -> i32 {
+        LIQUIDATION_PRICE_RECORD_VERSION
+    }
+
+    fn get_json(&self) -> serde_json::Result<Value> {
+        serde_json::to_value(self)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Eq)]
 pub enum TradeId {
     Number(u64),
@@ -191,7 +240,7 @@ impl<'de> Deserialize<'de> for TradeId {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trade {
     pub trade_id: TradeId,
     pub price: Price,
@@ -200,9 +249,24 @@ pub struct Trade {
     /// Transaction time received from exchange
     /// If we can't get it we should write trade event bot local time  
     pub transaction_time: DateTime,
+    /// Filled only by exchanges which support tick direction
+    pub tick_direction: Option<TickDirection>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// Relationship between trade price and prices of previous trades
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TickDirection {
+    /// Price is higher than previous trade price
+    PlusTick,
+    /// Price is equal to previous trade price which was higher than the one before it
+    ZeroPlusTick,
+    /// Price is lower than previous trade price
+    MinusTick,
+    /// Price is equal to previous trade price which was lower than the one before it
+    ZeroMinusTick,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradesEvent {
     pub exchange_account_id: ExchangeAccountId,
     pub currency_pair: CurrencyPair,
@@ -256,7 +320,7 @@ impl_event!(MetricsEvent, "metrics_events");
 /// Unix timestamp in milliseconds
 pub type MetricsTime = i64;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsEvent {
     latency: MetricsTime,
     /// Corresponds to end time of measurement (`MetricsEventInfoBase::end_time`)
@@ -272,9 +336,21 @@ impl MetricsEvent {
             event_type: info.event_type,
         }
     }
+
+    pub fn latency(&self) -> MetricsTime {
+        self.latency
+    }
+
+    pub fn measure_time(&self) -> MetricsTime {
+        self.measure_time
+    }
+
+    pub fn event_type(&self) -> MetricsEventType {
+        self.event_type
+    }
 }
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum MetricsEventType {
     TradeEvent,
     OrderBookEvent,
@@ -282,6 +358,10 @@ pub enum MetricsEventType {
     TradeToMl,
     OrderFromCreateToFill,
     OrderLifeCycle(OrderStatus),
+    /// Time from sending of create order request to receiving of exchange response
+    CreateOrderRequest(EventSourceType),
+    /// Time from sending of cancel order request to receiving of exchange response
+    CancelOrderRequest(EventSourceType),
 }
 
 #[derive(Debug)]
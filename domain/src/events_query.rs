@@ -0,0 +1,196 @@
+use crate::events::{
+    LiquidationPriceEvent, MetricsEvent, TradesEvent, LIQUIDATION_PRICE_RECORD_VERSION,
+};
+use crate::market::{CurrencyCode, CurrencyPair, ExchangeAccountId};
+use crate::order::fill::OrderFill;
+use crate::order::snapshot::{Amount, ClientOrderId, OrderSide, OrderSnapshot};
+use anyhow::Result;
+use mmb_database::postgres_db::events::TableName;
+use mmb_database::postgres_db::events_query::{
+    select_all_events, select_events, EventsFilter, EventsPage, Pagination, QueryableEvent,
+};
+use mmb_database::postgres_db::PgPool;
+use mmb_utils::DateTime;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+impl QueryableEvent for OrderSnapshot {
+    const TABLE_NAME: TableName = "orders";
+    const EXCHANGE_ACCOUNT_ID_PATH: Option<&'static str> = Some("$.header.exchange_account_id");
+    const CURRENCY_PAIR_PATH: Option<&'static str> = Some("$.header.currency_pair");
+}
+
+impl QueryableEvent for TradesEvent {
+    const TABLE_NAME: TableName = "trades_events";
+    const EXCHANGE_ACCOUNT_ID_PATH: Option<&'static str> = Some("$.exchange_account_id");
+    const CURRENCY_PAIR_PATH: Option<&'static str> = Some("$.currency_pair");
+}
+
+impl QueryableEvent for LiquidationPriceEvent {
+    const TABLE_NAME: TableName = "liquidation_prices";
+    const VERSION: i32 = LIQUIDATION_PRICE_RECORD_VERSION;
+    const EXCHANGE_ACCOUNT_ID_PATH: Option<&'static str> = Some("$.exchange_account_id");
+    const CURRENCY_PAIR_PATH: Option<&'static str> = Some("$.currency_pair");
+}
+
+impl QueryableEvent for MetricsEvent {
+    const TABLE_NAME: TableName = "metrics_events";
+    const EXCHANGE_ACCOUNT_ID_PATH: Option<&'static str> = None;
+    const CURRENCY_PAIR_PATH: Option<&'static str> = None;
+}
+
+/// Read model of `balance_updates` records
+#[derive(Debug, Clone, Deserialize)]
+pub struct BalanceUpdateRecord {
+    pub whole_balance_before: HashMap<ExchangeAccountId, HashMap<CurrencyCode, Amount>>,
+    pub whole_balance_after: HashMap<ExchangeAccountId, HashMap<CurrencyCode, Amount>>,
+}
+
+impl QueryableEvent for BalanceUpdateRecord {
+    const TABLE_NAME: TableName = "balance_updates";
+    const EXCHANGE_ACCOUNT_ID_PATH: Option<&'static str> =
+        Some("$.whole_balance_after.keyvalue().key");
+    const CURRENCY_PAIR_PATH: Option<&'static str> = None;
+}
+
+/// Fill of recorded order
+#[derive(Debug, Clone)]
+pub struct RecordedFill {
+    pub client_order_id: ClientOrderId,
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub side: OrderSide,
+    pub fill: OrderFill,
+}
+
+/// Typed conditions of selecting recorded events
+#[derive(Debug, Clone, Default)]
+pub struct EventsQuery {
+    pub exchange_account_id: Option<ExchangeAccountId>,
+    pub currency_pair: Option<CurrencyPair>,
+    /// Inclusive start of time range
+    pub from: Option<DateTime>,
+    /// Exclusive end of time range
+    pub to: Option<DateTime>,
+}
+
+impl EventsQuery {
+    pub fn new(exchange_account_id: ExchangeAccountId, currency_pair: CurrencyPair) -> Self {
+        Self {
+            exchange_account_id: Some(exchange_account_id),
+            currency_pair: Some(currency_pair),
+            from: None,
+            to: None,
+        }
+    }
+
+    pub fn with_time_range(mut self, from: DateTime, to: DateTime) -> Self {
+        self.from = Some(from);
+        self.to = Some(to);
+        self
+    }
+
+    /// Selects page of events (orders, trades, balance updates, etc.) recorded in time range
+    pub async fn select<T: QueryableEvent>(
+        &self,
+        pool: &PgPool,
+        pagination: Pagination,
+    ) -> Result<EventsPage<T>> {
+        select_events(pool, &self.to_filter(), pagination).await
+    }
+
+    /// Selects fills received in time range. Fills are taken from the last recorded snapshot of
+    /// every order, so orders recorded after the end of time range are taken into account too
+    pub async fn select_fills(&self, pool: &PgPool) -> Result<Vec<RecordedFill>> {
+        let filter = EventsFilter {
+            to: None,
+            ..self.to_filter()
+        };
+        let orders = select_all_events::<OrderSnapshot>(pool, &filter).await?;
+
+        let mut last_snapshots = HashMap::new();
+        for order in orders {
+            let _ = last_snapshots.insert(order.event.header.client_order_id.clone(), order.event);
+        }
+
+        let mut fills = last_snapshots
+            .into_values()
+            .flat_map(|order| {
+                let header = order.header;
+                order.fills.fills.into_iter().map(move |fill| RecordedFill {
+                    client_order_id: header.client_order_id.clone(),
+                    exchange_account_id: header.exchange_account_id,
+                    currency_pair: header.currency_pair,
+                    side: header.side,
+                    fill,
+                })
+            })
+            .filter(|x| self.contains_time(x.fill.receive_time()))
+            .collect::<Vec<_>>();
+        fills.sort_by_key(|x| x.fill.receive_time());
+
+        Ok(fills)
+    }
+
+    fn contains_time(&self, time: DateTime) -> bool {
+        self.from.map_or(true, |from| from <= time) && self.to.map_or(true, |to| time < to)
+    }
+
+    fn to_filter(&self) -> EventsFilter {
+        EventsFilter {
+            exchange_account_id: self.exchange_account_id.map(|x| x.to_string()),
+            currency_pair: self.currency_pair.map(|x| x.to_string()),
+            from: self.from,
+            to: self.to,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    #[test]
+    fn query_to_filter() {
+        let from = Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+        let to = Utc.ymd(2022, 1, 2).and_hms(0, 0, 0);
+        let query = EventsQuery::new(
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+        )
+        .with_time_range(from, to);
+
+        assert_eq!(
+            query.to_filter(),
+            EventsFilter {
+                exchange_account_id: Some("Binance_0".to_owned()),
+                currency_pair: Some("btc/usdt".to_owned()),
+                from: Some(from),
+                to: Some(to),
+            }
+        );
+        assert!(query.contains_time(from));
+        assert!(!query.contains_time(to));
+    }
+
+    #[test]
+    fn deserialize_balance_update_record() {
+        let json = json!({
+            "reservation": {},
+            "whole_balance_before": { "Binance_0": { "btc": "1" } },
+            "whole_balance_after": { "Binance_0": { "btc": "2" } },
+        });
+
+        let record = BalanceUpdateRecord::from_json(1, json).expect("in test");
+        let exchange_account_id = ExchangeAccountId::new("Binance", 0);
+        assert_eq!(
+            record.whole_balance_after[&exchange_account_id][&"btc".into()],
+            Amount::from(2)
+        );
+
+        // version 1 of liquidation price record can't be deserialized
+        assert!(LiquidationPriceEvent::from_json(1, json!("100")).is_err());
+    }
+}
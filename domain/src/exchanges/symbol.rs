@@ -53,6 +53,21 @@ impl Precision {
     }
 }
 
+/// Type of contract that defines how value and P&L of position are calculated
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize)]
+pub enum ContractType {
+    /// Position is settled in quote currency: value = amount * multiplier * price.
+    /// Spot markets are also considered linear
+    #[default]
+    Linear,
+    /// Amount is specified in quote currency (e.g. USD contracts of Bitmex XBTUSD) and position
+    /// is settled in base currency: value = amount * multiplier / price
+    Inverse,
+    /// Position is settled in currency outside currency pair (`balance_currency_code`)
+    /// with fixed multiplier: value = amount * multiplier * price
+    Quanto,
+}
+
 /// Metadata for a currency pair
 #[derive(Debug, Clone, Eq, Serialize)]
 pub struct Symbol {
@@ -73,6 +88,7 @@ pub struct Symbol {
     pub amount_currency_code: CurrencyCode,
     pub balance_currency_code: Option<CurrencyCode>,
     pub amount_multiplier: Decimal,
+    pub contract_type: ContractType,
 
     pub price_precision: Precision,
     pub amount_precision: Precision,
@@ -118,6 +134,7 @@ impl Symbol {
             min_cost,
             balance_currency_code,
             amount_multiplier: dec!(1),
+            contract_type: ContractType::Linear,
             price_precision,
             amount_precision,
         }
@@ -273,6 +290,10 @@ impl Symbol {
             return amount_in_amount_currency_code;
         }
 
+        if self.is_quanto_settlement_currency(to_currency_code) {
+            return amount_in_amount_currency_code * currency_pair_price;
+        }
+
         if to_currency_code == self.base_currency_code {
             return amount_in_amount_currency_code / currency_pair_price;
         }
@@ -294,6 +315,13 @@ impl Symbol {
         if Some(to_currency_code) == self.balance_currency_code {
             return amount;
         }
+
+        if self.contract_type == ContractType::Quanto
+            && to_currency_code == self.amount_currency_code
+        {
+            return amount / currency_pair_price;
+        }
+
         if to_currency_code == self.base_currency_code {
             return amount / currency_pair_price;
         }
@@ -316,6 +344,10 @@ impl Symbol {
             return amount_in_from_currency_code;
         }
 
+        if self.is_quanto_settlement_currency(from_currency_code) {
+            return amount_in_from_currency_code / currency_pair_price;
+        }
+
         if from_currency_code == self.base_currency_code() {
             return amount_in_from_currency_code * currency_pair_price;
         }
@@ -331,6 +363,82 @@ impl Symbol {
         );
     }
 
+    /// Currency in which value and P&L of positions are calculated
+    pub fn settlement_currency_code(&self) -> CurrencyCode {
+        match (self.is_derivative, self.balance_currency_code) {
+            (true, Some(balance_currency_code)) => balance_currency_code,
+            _ => self.quote_currency_code,
+        }
+    }
+
+    fn is_quanto_settlement_currency(&self, currency_code: CurrencyCode) -> bool {
+        self.contract_type == ContractType::Quanto
+            && Some(currency_code) == self.balance_currency_code
+    }
+
+    /// Value of position in settlement currency where amount is specified in amount currency
+    pub fn position_value(&self, amount: Amount, price: Price) -> Amount {
+        match self.contract_type {
+            ContractType::Linear | ContractType::Quanto => amount * self.amount_multiplier * price,
+            ContractType::Inverse => amount * self.amount_multiplier / price,
+        }
+    }
+
+    /// P&L in settlement currency of signed position (positive for long) opened by
+    /// `entry_price` and closed by `exit_price`
+    pub fn position_pnl(&self, position: Amount, entry_price: Price, exit_price: Price) -> Decimal {
+        match self.contract_type {
+            ContractType::Linear | ContractType::Quanto => {
+                position * self.amount_multiplier * (exit_price - entry_price)
+            }
+            ContractType::Inverse => {
+                position * self.amount_multiplier * (dec!(1) / entry_price - dec!(1) / exit_price)
+            }
+        }
+    }
+
+    /// Estimated liquidation price of isolated position. Position is liquidated when loss reaches
+    /// initial margin (`1 / leverage` of position value) reduced by maintenance margin.
+    /// Returns `None` if position can't be liquidated at any positive price (e.g. short of inverse
+    /// contract without leverage)
+    pub fn liquidation_price(
+        &self,
+        side: OrderSide,
+        entry_price: Price,
+        leverage: Decimal,
+        maintenance_margin_rate: Decimal,
+    ) -> Option<Price> {
+        let margin_rate = dec!(1) / leverage - maintenance_margin_rate;
+        let margin_rate = match side {
+            OrderSide::Buy => -margin_rate,
+            OrderSide::Sell => margin_rate,
+        };
+
+        let liquidation_price = match self.contract_type {
+            ContractType::Linear | ContractType::Quanto => entry_price * (dec!(1) + margin_rate),
+            ContractType::Inverse => {
+                let denominator = dec!(1) - margin_rate;
+                if denominator <= dec!(0) {
+                    return None;
+                }
+                entry_price / denominator
+            }
+        };
+
+        (liquidation_price > dec!(0)).then_some(liquidation_price)
+    }
+
+    /// Loss in settlement currency that signed position can take before liquidation
+    /// if price moves from `mark_price` to `liquidation_price`
+    pub fn liquidation_distance(
+        &self,
+        position: Amount,
+        mark_price: Price,
+        liquidation_price: Price,
+    ) -> Amount {
+        -self.position_pnl(position, mark_price, liquidation_price)
+    }
+
     /// Calculate min order's amount constraint for placing order on exchange
     /// NOTE: `price` needed when `min_cost` specified. It's used only for Binance
     /// (for `MIN_NOTIONAL` constraint on order's amount) now.
@@ -637,4 +745,121 @@ mod test {
                 .expect_err("should be error if min_amount not specified");
         }
     }
+
+    mod contract_type {
+        use super::*;
+
+        fn derivative(
+            contract_type: ContractType,
+            amount_currency_code: &str,
+            balance_currency_code: &str,
+            amount_multiplier: Decimal,
+        ) -> Symbol {
+            let mut symbol = Symbol::new(
+                true,
+                "BTC".into(),
+                "BTC".into(),
+                "USD".into(),
+                "USD".into(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                amount_currency_code.into(),
+                Some(balance_currency_code.into()),
+                Precision::ByTick { tick: dec!(0.5) },
+                Precision::ByTick { tick: dec!(1) },
+            );
+            symbol.contract_type = contract_type;
+            symbol.amount_multiplier = amount_multiplier;
+            symbol
+        }
+
+        #[rstest]
+        #[case(ContractType::Linear, "BTC", "USD", dec!(1), dec!(40000))]
+        #[case(ContractType::Inverse, "USD", "BTC", dec!(1), dec!(0.000025))]
+        #[case(ContractType::Quanto, "USD", "XBT", dec!(0.000001), dec!(0.04))]
+        fn position_value(
+            #[case] contract_type: ContractType,
+            #[case] amount_currency_code: &str,
+            #[case] balance_currency_code: &str,
+            #[case] amount_multiplier: Decimal,
+            #[case] expected: Amount,
+        ) {
+            let symbol = derivative(
+                contract_type,
+                amount_currency_code,
+                balance_currency_code,
+                amount_multiplier,
+            );
+
+            assert_eq!(symbol.position_value(dec!(1), dec!(40000)), expected);
+        }
+
+        #[test]
+        fn inverse_position_pnl() {
+            let symbol = derivative(ContractType::Inverse, "USD", "BTC", dec!(1));
+
+            // 100 USD contracts bought by 40000 and sold by 50000
+            let pnl = symbol.position_pnl(dec!(100), dec!(40000), dec!(50000));
+            assert_eq!(pnl, dec!(0.0005));
+
+            let pnl = symbol.position_pnl(dec!(-100), dec!(40000), dec!(50000));
+            assert_eq!(pnl, dec!(-0.0005));
+        }
+
+        #[test]
+        fn quanto_conversion_to_settlement_currency() {
+            let symbol = derivative(ContractType::Quanto, "USD", "XBT", dec!(0.000001));
+
+            let amount =
+                symbol.convert_amount_from_amount_currency_code("XBT".into(), dec!(2), dec!(1500));
+            assert_eq!(amount, dec!(3000));
+            assert_eq!(
+                symbol.convert_amount_into_amount_currency_code("XBT".into(), amount, dec!(1500)),
+                dec!(2)
+            );
+        }
+
+        #[rstest]
+        #[case(ContractType::Linear, OrderSide::Buy, dec!(36000))]
+        #[case(ContractType::Linear, OrderSide::Sell, dec!(44000))]
+        #[case(ContractType::Inverse, OrderSide::Buy, dec!(40000) / dec!(1.1))]
+        #[case(ContractType::Inverse, OrderSide::Sell, dec!(40000) / dec!(0.9))]
+        fn liquidation_price(
+            #[case] contract_type: ContractType,
+            #[case] side: OrderSide,
+            #[case] expected: Price,
+        ) {
+            let symbol = derivative(contract_type, "USD", "BTC", dec!(1));
+
+            let liquidation_price = symbol
+                .liquidation_price(side, dec!(40000), dec!(10), dec!(0))
+                .expect("in test");
+            assert_eq!(liquidation_price, expected);
+
+            // whole initial margin is lost at liquidation price
+            let position = match side {
+                OrderSide::Buy => dec!(100),
+                OrderSide::Sell => dec!(-100),
+            };
+            let initial_margin = symbol.position_value(dec!(100), dec!(40000)) / dec!(10);
+            let distance = symbol.liquidation_distance(position, dec!(40000), liquidation_price);
+            assert_eq!(distance.round_dp(12), initial_margin.round_dp(12));
+        }
+
+        #[rstest]
+        #[case(ContractType::Linear, OrderSide::Buy)]
+        #[case(ContractType::Inverse, OrderSide::Sell)]
+        fn no_liquidation_price_without_leverage(
+            #[case] contract_type: ContractType,
+            #[case] side: OrderSide,
+        ) {
+            let symbol = derivative(contract_type, "USD", "BTC", dec!(1));
+
+            let liquidation_price = symbol.liquidation_price(side, dec!(40000), dec!(1), dec!(0));
+            assert_eq!(liquidation_price, None);
+        }
+    }
 }
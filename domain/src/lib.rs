@@ -1,4 +1,5 @@
 pub mod events;
+pub mod events_query;
 pub mod exchanges;
 pub mod market;
 pub mod order;
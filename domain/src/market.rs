@@ -193,6 +193,10 @@ pub enum ExchangeErrorType {
     ParsingError,
     PendingError(Duration),
     ServiceUnavailable,
+    /// Order was rejected locally by pre-trade risk checks
+    RiskCheckFailed,
+    /// Order was rejected locally because it crosses own not finished order
+    SelfTradePrevented,
 }
 
 #[cfg(test)]
@@ -14,12 +14,14 @@ pub enum OrderFillType {
     Liquidation = 2,
     Funding = 3,
     ClosePosition = 4,
+    /// Fill that was detected by reconciliation with exchange but wasn't received from it
+    MissedFill = 5,
 }
 
 impl OrderFillType {
     pub fn is_special(&self) -> bool {
         use OrderFillType::*;
-        matches!(self, Liquidation | ClosePosition)
+        matches!(self, Liquidation | ClosePosition | MissedFill)
     }
 }
 
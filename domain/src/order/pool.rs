@@ -147,6 +147,8 @@ pub struct OrdersPool {
     pub cache_by_client_id: DashMap<ClientOrderId, OrderRef>,
     pub cache_by_exchange_id: DashMap<ExchangeOrderId, OrderRef>,
     pub not_finished: DashMap<ClientOrderId, OrderRef>,
+    /// Legs of one-cancels-the-other orders: each leg is mapped to the opposite one
+    pub linked_orders: DashMap<ClientOrderId, OrderRef>,
 }
 
 impl OrdersPool {
@@ -157,6 +159,7 @@ impl OrdersPool {
             cache_by_client_id: DashMap::with_capacity(ORDERS_INIT_CAPACITY),
             cache_by_exchange_id: DashMap::with_capacity(ORDERS_INIT_CAPACITY),
             not_finished: DashMap::with_capacity(ORDERS_INIT_CAPACITY),
+            linked_orders: DashMap::new(),
         })
     }
 
@@ -209,4 +212,27 @@ impl OrdersPool {
             }
         }
     }
+
+    /// Link two orders so that finishing one of them finalizes the other one (OCO order legs).
+    pub fn add_linked_pair(&self, first: &OrderRef, second: &OrderRef) {
+        let _ = self
+            .linked_orders
+            .insert(first.client_order_id(), second.clone());
+        let _ = self
+            .linked_orders
+            .insert(second.client_order_id(), first.clone());
+    }
+
+    pub fn get_linked_order(&self, client_order_id: &ClientOrderId) -> Option<OrderRef> {
+        self.linked_orders
+            .get(client_order_id)
+            .map(|x| x.value().clone())
+    }
+
+    /// Remove link between orders when both of them are finished
+    pub fn remove_linked_pair(&self, client_order_id: &ClientOrderId) {
+        if let Some((_, linked_order)) = self.linked_orders.remove(client_order_id) {
+            let _ = self.linked_orders.remove(&linked_order.client_order_id());
+        }
+    }
 }
@@ -6,6 +6,7 @@ use chrono::Utc;
 use dyn_clone::{clone_trait_object, DynClone};
 use enum_map::Enum;
 use mmb_database::impl_event;
+use mmb_database::postgres_db::events::EventPriority;
 use mmb_utils::{impl_from_for_str_id, DateTime};
 use mmb_utils::{impl_str_id, impl_u64_id, time::get_atomic_current_secs};
 use once_cell::sync::Lazy;
@@ -123,6 +124,54 @@ impl From<&i32> for ClientOrderId {
     }
 }
 
+const STRATEGY_TAG_SEPARATOR: char = '-';
+
+/// Max length of client order id supported by exchanges (e.g. Binance `newClientOrderId`)
+const MAX_CLIENT_ORDER_ID_LEN: usize = 36;
+/// Max length of `u64` unique part of id
+const MAX_UNIQUE_ID_LEN: usize = 20;
+const MAX_STRATEGY_TAG_LEN: usize = MAX_CLIENT_ORDER_ID_LEN - MAX_UNIQUE_ID_LEN - 1;
+/// Length of hex encoded hash that replaces the end of too long strategy tag
+const STRATEGY_TAG_HASH_LEN: usize = 8;
+
+impl ClientOrderId {
+    /// Unique id with tag of strategy that created order,
+    /// so the order can be adopted by the same strategy after restart
+    pub fn unique_id_with_strategy_tag(strategy_tag: &str) -> Self {
+        let strategy_tag = Self::shorten_strategy_tag(strategy_tag);
+        let unique_id = Self::unique_id();
+        format!("{strategy_tag}{STRATEGY_TAG_SEPARATOR}{unique_id}")
+            .as_str()
+            .into()
+    }
+
+    /// Strategy tag as it is written in client order id. Too long tag is truncated
+    /// and its end is replaced with hash of the whole tag, so the id fits in `MAX_CLIENT_ORDER_ID_LEN`
+    pub fn shorten_strategy_tag(strategy_tag: &str) -> String {
+        if strategy_tag.chars().count() <= MAX_STRATEGY_TAG_LEN {
+            return strategy_tag.to_owned();
+        }
+
+        // FNV-1a is used because the hash has to be the same between runs of engine
+        let hash = strategy_tag.bytes().fold(0x811c9dc5u32, |hash, byte| {
+            (hash ^ byte as u32).wrapping_mul(0x01000193)
+        });
+
+        let prefix: String = strategy_tag
+            .chars()
+            .take(MAX_STRATEGY_TAG_LEN - STRATEGY_TAG_HASH_LEN)
+            .collect();
+        format!("{prefix}{hash:08x}")
+    }
+
+    /// Strategy tag of id created by `unique_id_with_strategy_tag`
+    pub fn strategy_tag(&self) -> Option<&str> {
+        let (strategy_tag, unique_id) = self.as_str().rsplit_once(STRATEGY_TAG_SEPARATOR)?;
+        let is_unique_id = !unique_id.is_empty() && unique_id.chars().all(|x| x.is_ascii_digit());
+        (!strategy_tag.is_empty() && is_unique_id).then_some(strategy_tag)
+    }
+}
+
 impl_str_id!(ClientOrderFillId);
 impl_str_id!(ExchangeOrderId);
 
@@ -240,6 +289,10 @@ impl OrderOptions {
         Self::External(ExternalOrder::ClosePosition { price })
     }
 
+    pub fn missed_fill(price: Price) -> Self {
+        Self::External(ExternalOrder::MissedFill { price })
+    }
+
     pub(crate) fn get_source_price(&self) -> Option<Price> {
         match self {
             OrderOptions::User(UserOrder::Limit { price, .. })
@@ -655,7 +708,7 @@ pub struct OrderSnapshot {
     pub extension_data: Option<Box<dyn OrderInfoExtensionData>>,
 }
 
-impl_event!(&mut OrderSnapshot, "orders");
+impl_event!(&mut OrderSnapshot, "orders", EventPriority::Critical);
 
 impl OrderSnapshot {
     pub fn new(
@@ -792,3 +845,41 @@ fn set_status(
         time,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn strategy_tag_of_unique_id_with_strategy_tag() {
+        let client_order_id = ClientOrderId::unique_id_with_strategy_tag("ExampleStrategy");
+
+        assert_eq!(client_order_id.strategy_tag(), Some("ExampleStrategy"));
+    }
+
+    #[test]
+    fn long_strategy_tag_is_shortened() {
+        let strategy_tag = "CrossExchangeStrategy";
+        let client_order_id = ClientOrderId::unique_id_with_strategy_tag(strategy_tag);
+
+        let shortened_tag = ClientOrderId::shorten_strategy_tag(strategy_tag);
+        assert_eq!(shortened_tag.len(), MAX_STRATEGY_TAG_LEN);
+        assert!(shortened_tag.starts_with("CrossEx"));
+        assert_ne!(
+            shortened_tag,
+            ClientOrderId::shorten_strategy_tag("CrossExchangeStrategy2")
+        );
+        assert_eq!(client_order_id.strategy_tag(), Some(shortened_tag.as_str()));
+        assert!(client_order_id.as_str().len() <= MAX_CLIENT_ORDER_ID_LEN);
+    }
+
+    #[rstest]
+    #[case::unique_id("1668000000")]
+    #[case::empty_tag("-1668000000")]
+    #[case::uuid("550e8400-e29b-41d4-a716-44665544000a")]
+    #[case::not_numeric_unique_id("web-order")]
+    fn no_strategy_tag(#[case] client_order_id: &str) {
+        assert_eq!(ClientOrderId::from(client_order_id).strategy_tag(), None);
+    }
+}
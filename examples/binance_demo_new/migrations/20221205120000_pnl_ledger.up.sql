@@ -0,0 +1,11 @@
+CREATE TABLE pnl_ledger (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX pnl_ledger__exchange_account_id_idx ON pnl_ledger USING btree (((json ->> 'exchange_account_id')::text));
+CREATE INDEX pnl_ledger__currency_pair_idx ON pnl_ledger USING btree (((json ->> 'currency_pair')::text));
+
+-- ledger is a history of realized P&L, so it isn't added to cleanup_settings
@@ -0,0 +1 @@
+-- removed records of version 1 can't be restored
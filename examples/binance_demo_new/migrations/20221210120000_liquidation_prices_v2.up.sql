@@ -0,0 +1,3 @@
+-- Records of version 1 contained only liquidation price without exchange account and currency pair.
+-- They can't be attributed to any market, so they are removed. Records of version 2 contain whole LiquidationPriceEvent
+DELETE FROM liquidation_prices WHERE version IS NULL OR version < 2;
@@ -0,0 +1,12 @@
+ALTER TABLE liquidity_order_books DROP COLUMN event_id;
+ALTER TABLE transactions DROP COLUMN event_id;
+ALTER TABLE balances DROP COLUMN event_id;
+ALTER TABLE disposition_explanations DROP COLUMN event_id;
+ALTER TABLE balance_updates DROP COLUMN event_id;
+ALTER TABLE trades_events DROP COLUMN event_id;
+ALTER TABLE liquidation_prices DROP COLUMN event_id;
+ALTER TABLE orders DROP COLUMN event_id;
+ALTER TABLE prices_sources DROP COLUMN event_id;
+ALTER TABLE profit_loss_balance_changes DROP COLUMN event_id;
+ALTER TABLE metrics_events DROP COLUMN event_id;
+ALTER TABLE pnl_ledger DROP COLUMN event_id;
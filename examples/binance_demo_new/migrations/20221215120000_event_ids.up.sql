@@ -0,0 +1,36 @@
+-- identifier of event assigned before saving, so importing of postponed events skips already saved ones
+ALTER TABLE liquidity_order_books ADD COLUMN event_id uuid;
+CREATE UNIQUE INDEX liquidity_order_books__event_id_idx ON liquidity_order_books USING btree (event_id);
+
+ALTER TABLE transactions ADD COLUMN event_id uuid;
+CREATE UNIQUE INDEX transactions__event_id_idx ON transactions USING btree (event_id);
+
+ALTER TABLE balances ADD COLUMN event_id uuid;
+CREATE UNIQUE INDEX balances__event_id_idx ON balances USING btree (event_id);
+
+ALTER TABLE disposition_explanations ADD COLUMN event_id uuid;
+CREATE UNIQUE INDEX disposition_explanations__event_id_idx ON disposition_explanations USING btree (event_id);
+
+ALTER TABLE balance_updates ADD COLUMN event_id uuid;
+CREATE UNIQUE INDEX balance_updates__event_id_idx ON balance_updates USING btree (event_id);
+
+ALTER TABLE trades_events ADD COLUMN event_id uuid;
+CREATE UNIQUE INDEX trades_events__event_id_idx ON trades_events USING btree (event_id);
+
+ALTER TABLE liquidation_prices ADD COLUMN event_id uuid;
+CREATE UNIQUE INDEX liquidation_prices__event_id_idx ON liquidation_prices USING btree (event_id);
+
+ALTER TABLE orders ADD COLUMN event_id uuid;
+CREATE UNIQUE INDEX orders__event_id_idx ON orders USING btree (event_id);
+
+ALTER TABLE prices_sources ADD COLUMN event_id uuid;
+CREATE UNIQUE INDEX prices_sources__event_id_idx ON prices_sources USING btree (event_id);
+
+ALTER TABLE profit_loss_balance_changes ADD COLUMN event_id uuid;
+CREATE UNIQUE INDEX profit_loss_balance_changes__event_id_idx ON profit_loss_balance_changes USING btree (event_id);
+
+ALTER TABLE metrics_events ADD COLUMN event_id uuid;
+CREATE UNIQUE INDEX metrics_events__event_id_idx ON metrics_events USING btree (event_id);
+
+ALTER TABLE pnl_ledger ADD COLUMN event_id uuid;
+CREATE UNIQUE INDEX pnl_ledger__event_id_idx ON pnl_ledger USING btree (event_id);
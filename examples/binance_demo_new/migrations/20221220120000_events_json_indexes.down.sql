@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS orders__json_idx;
+DROP INDEX IF EXISTS trades_events__json_idx;
+DROP INDEX IF EXISTS liquidation_prices__json_idx;
+DROP INDEX IF EXISTS balance_updates__json_idx;
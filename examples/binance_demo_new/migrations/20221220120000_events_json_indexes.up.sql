@@ -0,0 +1,5 @@
+-- events are filtered by exchange account and currency pair with JSONPath predicates (operator @?) which use these indexes
+CREATE INDEX orders__json_idx ON orders USING gin (json jsonb_path_ops);
+CREATE INDEX trades_events__json_idx ON trades_events USING gin (json jsonb_path_ops);
+CREATE INDEX liquidation_prices__json_idx ON liquidation_prices USING gin (json jsonb_path_ops);
+CREATE INDEX balance_updates__json_idx ON balance_updates USING gin (json jsonb_path_ops);
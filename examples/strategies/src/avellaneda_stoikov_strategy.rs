@@ -0,0 +1,506 @@
+use anyhow::Result;
+use itertools::Itertools;
+use mmb_core::balance::manager::balance_manager::BalanceManager;
+use mmb_core::disposition_execution::strategy::DispositionStrategy;
+use mmb_core::disposition_execution::{
+    PriceSlot, TradeCycle, TradeDisposition, TradingContext, TradingContextBySide,
+};
+use mmb_core::explanation::{Explanation, WithExplanation};
+use mmb_core::lifecycle::trading_engine::EngineContext;
+use mmb_core::order_book::local_snapshot_service::LocalSnapshotsService;
+use mmb_core::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+use mmb_core::settings::{CurrencyPairSetting, DispositionStrategySettings};
+use mmb_domain::events::{ExchangeEvent, TradesEvent};
+use mmb_domain::exchanges::symbol::{Round, Symbol};
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, MarketAccountId, MarketId};
+use mmb_domain::order::snapshot::{Amount, OrderRole, OrderSide, OrderSnapshot, Price};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::WithExpect;
+use mmb_utils::DateTime;
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Min count of mid price samples needed for volatility estimation
+const MIN_MID_PRICES_COUNT: usize = 10;
+/// Min count of trades needed for order arrival intensity estimation
+const MIN_TRADES_COUNT: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AvellanedaStoikovStrategySettings {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPairSetting,
+    pub max_amount: Decimal,
+    /// Risk aversion `gamma`: the bigger value the stronger quotes are skewed by inventory
+    pub risk_aversion: Decimal,
+    /// Trading horizon `T - t` in seconds used in reservation price and spread formulas
+    pub horizon_secs: Decimal,
+    /// Time window in seconds of mid prices used for volatility estimation
+    pub volatility_window_secs: i64,
+    /// Time window in seconds of trades used for order arrival intensity estimation
+    pub intensity_window_secs: i64,
+}
+
+impl DispositionStrategySettings for AvellanedaStoikovStrategySettings {
+    fn exchange_account_id(&self) -> ExchangeAccountId {
+        self.exchange_account_id
+    }
+
+    fn currency_pair(&self) -> CurrencyPair {
+        if let CurrencyPairSetting::Ordinary { base, quote } = self.currency_pair {
+            CurrencyPair::from_codes(base, quote)
+        } else {
+            panic!(
+                "Incorrect currency pair setting enum type {:?}",
+                self.currency_pair
+            );
+        }
+    }
+
+    // Max amount for orders that will be created
+    fn max_amount(&self) -> Amount {
+        self.max_amount
+    }
+}
+
+/// Quotes calculated by Avellaneda-Stoikov model for current market state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Quotes {
+    reservation_price: Price,
+    spread: Price,
+}
+
+impl Quotes {
+    fn price(&self, side: OrderSide) -> Price {
+        match side {
+            OrderSide::Buy => self.reservation_price - self.spread * dec!(0.5),
+            OrderSide::Sell => self.reservation_price + self.spread * dec!(0.5),
+        }
+    }
+}
+
+/// Market maker based on "High-frequency trading in a limit order book" (Avellaneda, Stoikov).
+/// Quotes are placed around reservation price `r = s - q * gamma * sigma^2 * (T - t)`
+/// with total spread `gamma * sigma^2 * (T - t) + 2 / gamma * ln(1 + gamma / k)`, where
+/// `s` is mid price, `q` is inventory, `sigma^2` is mid price variance per second and
+/// `k` is order arrival intensity decay estimated from distances between trade prices and mid price.
+pub struct AvellanedaStoikovStrategy {
+    target_eai: ExchangeAccountId,
+    currency_pair: CurrencyPair,
+    symbol: Arc<Symbol>,
+    risk_aversion: Decimal,
+    horizon_secs: Decimal,
+    volatility_window_secs: i64,
+    intensity_window_secs: i64,
+    engine_context: Arc<EngineContext>,
+    configuration_descriptor: ConfigurationDescriptor,
+    max_amount: Decimal,
+    mid_prices: VecDeque<(DateTime, Price)>,
+    /// Distances between trade price and mid price at the moment of trade
+    trade_distances: VecDeque<(DateTime, Price)>,
+}
+
+impl AvellanedaStoikovStrategy {
+    pub fn new(
+        settings: &AvellanedaStoikovStrategySettings,
+        engine_context: Arc<EngineContext>,
+    ) -> Box<Self> {
+        let target_eai = settings.exchange_account_id;
+        let currency_pair = settings.currency_pair();
+
+        let configuration_descriptor = ConfigurationDescriptor::new(
+            Self::strategy_name().into(),
+            format!("{target_eai};{currency_pair}").as_str().into(),
+        );
+
+        // amount_limit it's a limit for position changing for both sides
+        // it's equal to half of the max amount because an order that can change a position from
+        // a limit by sells to a limit by buys is possible
+        let amount_limit = settings.max_amount * dec!(0.5);
+
+        let symbol = engine_context
+            .exchanges
+            .get(&target_eai)
+            .with_expect(|| format!("failed to get exchange from trading_engine for {target_eai}"))
+            .symbols
+            .get(&currency_pair)
+            .with_expect(|| format!("failed to get symbol from exchange for {currency_pair}"))
+            .clone();
+
+        engine_context
+            .balance_manager
+            .lock()
+            .set_target_amount_limit(
+                configuration_descriptor,
+                target_eai,
+                symbol.clone(),
+                amount_limit,
+            );
+
+        Box::new(AvellanedaStoikovStrategy {
+            target_eai,
+            currency_pair,
+            symbol,
+            risk_aversion: settings.risk_aversion,
+            horizon_secs: settings.horizon_secs,
+            volatility_window_secs: settings.volatility_window_secs,
+            intensity_window_secs: settings.intensity_window_secs,
+            engine_context,
+            configuration_descriptor,
+            max_amount: settings.max_amount,
+            mid_prices: VecDeque::new(),
+            trade_distances: VecDeque::new(),
+        })
+    }
+
+    fn strategy_name() -> &'static str {
+        "AvellanedaStoikovStrategy"
+    }
+
+    fn market_account_id(&self) -> MarketAccountId {
+        MarketAccountId::new(self.target_eai, self.currency_pair)
+    }
+
+    fn market_id(&self) -> MarketId {
+        self.market_account_id().market_id()
+    }
+
+    fn update_mid_price(&mut self, mid_price: Price, now: DateTime) {
+        if self.mid_prices.back().map(|(_, price)| *price) != Some(mid_price) {
+            self.mid_prices.push_back((now, mid_price));
+        }
+
+        // last mid price out of window is kept as a start point of the next price change
+        let window = self.volatility_window_secs;
+        while self
+            .mid_prices
+            .get(1)
+            .map_or(false, |(time, _)| (now - *time).num_seconds() > window)
+        {
+            self.mid_prices.pop_front();
+        }
+    }
+
+    fn handle_trades(&mut self, trades_event: &TradesEvent, now: DateTime) {
+        if trades_event.exchange_account_id != self.target_eai
+            || trades_event.currency_pair != self.currency_pair
+        {
+            return;
+        }
+
+        let mid_price = match self.mid_prices.back() {
+            Some((_, mid_price)) => *mid_price,
+            None => return,
+        };
+
+        for trade in &trades_event.trades {
+            self.trade_distances
+                .push_back((trade.transaction_time, (trade.price - mid_price).abs()));
+        }
+
+        let window = self.intensity_window_secs;
+        while let Some((time, _)) = self.trade_distances.front() {
+            if (now - *time).num_seconds() <= window {
+                break;
+            }
+            self.trade_distances.pop_front();
+        }
+    }
+
+    /// Inventory in amount currency: positive for long position and negative for short one
+    fn get_inventory(&self) -> Amount {
+        // BalanceManager returns position with sign depending on the trade currency of side
+        let side = match self.symbol.is_derivative() {
+            true => OrderSide::Buy,
+            false => OrderSide::Sell,
+        };
+
+        self.engine_context.balance_manager.lock().get_position(
+            self.target_eai,
+            self.currency_pair,
+            side,
+        )
+    }
+
+    fn calc_quotes(&self, mid_price: Price, explanation: &mut Explanation) -> Option<Quotes> {
+        let variance = match estimate_variance(&self.mid_prices) {
+            Some(variance) => variance,
+            None => {
+                explanation.add_reason(format!(
+                    "Not enough mid prices for volatility estimation: {}",
+                    self.mid_prices.len()
+                ));
+                return None;
+            }
+        };
+
+        let intensity = match estimate_intensity(&self.trade_distances) {
+            Some(intensity) => intensity,
+            None => {
+                explanation.add_reason(format!(
+                    "Not enough trades for order arrival intensity estimation: {}",
+                    self.trade_distances.len()
+                ));
+                return None;
+            }
+        };
+
+        let inventory = self.get_inventory();
+        let reservation_price = calc_reservation_price(
+            mid_price,
+            inventory,
+            self.risk_aversion,
+            variance,
+            self.horizon_secs,
+        );
+        let spread =
+            calc_optimal_spread(self.risk_aversion, variance, self.horizon_secs, intensity)?;
+
+        explanation.add_reason(format!(
+            "Mid price {mid_price}, inventory {inventory}, variance {variance}, intensity {intensity}"
+        ));
+        explanation.add_reason(format!(
+            "Reservation price {reservation_price}, optimal spread {spread} (risk aversion {}, horizon {}s)",
+            self.risk_aversion, self.horizon_secs
+        ));
+
+        Some(Quotes {
+            reservation_price,
+            spread,
+        })
+    }
+
+    fn calc_trading_context_by_side(
+        &mut self,
+        side: OrderSide,
+        quotes: Quotes,
+        local_snapshots_service: &LocalSnapshotsService,
+        mut explanation: Explanation,
+    ) -> Option<TradingContextBySide> {
+        let snapshot = local_snapshots_service.get_snapshot(self.market_id())?;
+        let ask_min_price = snapshot.get_top_ask()?.0;
+        let bid_max_price = snapshot.get_top_bid()?.0;
+
+        let symbol = self.symbol.clone();
+        let model_price = quotes.price(side);
+
+        // maker order shouldn't cross the order book so it's moved to the top of own side
+        let price = match side {
+            OrderSide::Buy if model_price >= ask_min_price => bid_max_price,
+            OrderSide::Sell if model_price <= bid_max_price => ask_min_price,
+            OrderSide::Buy => symbol.price_round(model_price, Round::Floor),
+            OrderSide::Sell => symbol.price_round(model_price, Round::Ceiling),
+        };
+        explanation.add_reason(format!(
+            "{side} price {price} from model price {model_price}"
+        ));
+
+        let amount;
+        explanation = {
+            let mut explanation = Some(explanation);
+
+            // TODO: delete deep_clone
+            let orders = self
+                .engine_context
+                .exchanges
+                .iter()
+                .flat_map(|x| {
+                    x.orders
+                        .not_finished
+                        .iter()
+                        .map(|y| y.clone())
+                        .collect_vec()
+                })
+                .collect_vec();
+
+            let balance_manager = BalanceManager::clone_and_subtract_not_approved_data(
+                self.engine_context.balance_manager.clone(),
+                Some(&mut orders.iter()),
+            )
+            .expect("AvellanedaStoikovStrategy::calc_trading_context_by_side: failed to clone and subtract not approved data for BalanceManager");
+
+            amount = balance_manager
+                .lock()
+                .get_leveraged_balance_in_amount_currency_code(
+                    self.configuration_descriptor,
+                    side,
+                    self.target_eai,
+                    symbol.clone(),
+                    price,
+                    &mut explanation,
+                )
+                .with_expect(|| format!("Failed to get balance for {}", self.target_eai));
+
+            // This expect can happened if get_leveraged_balance_in_amount_currency_code() sets the explanation to None
+            explanation.expect(
+                "AvellanedaStoikovStrategy::calc_trading_context_by_side(): Explanation should be non None here"
+            )
+        };
+
+        let amount = symbol.amount_round(amount, Round::Floor);
+
+        Some(TradingContextBySide {
+            max_amount: self.max_amount,
+            estimating: vec![WithExplanation {
+                value: Some(TradeCycle {
+                    order_role: OrderRole::Maker,
+                    strategy_name: Self::strategy_name().to_string(),
+                    disposition: TradeDisposition::new(
+                        self.market_account_id(),
+                        side,
+                        price,
+                        amount,
+                    ),
+                }),
+                explanation,
+            }],
+        })
+    }
+}
+
+impl DispositionStrategy for AvellanedaStoikovStrategy {
+    fn calculate_trading_context(
+        &mut self,
+        event: &ExchangeEvent,
+        now: DateTime,
+        local_snapshots_service: &LocalSnapshotsService,
+        explanation: &mut Explanation,
+    ) -> Option<TradingContext> {
+        let snapshot = local_snapshots_service.get_snapshot(self.market_id())?;
+        let mid_price = (snapshot.get_top_ask()?.0 + snapshot.get_top_bid()?.0) * dec!(0.5);
+        self.update_mid_price(mid_price, now);
+
+        if let ExchangeEvent::Trades(trades_event) = event {
+            self.handle_trades(trades_event, now);
+        }
+
+        let quotes = self.calc_quotes(mid_price, explanation)?;
+
+        let buy_trading_ctx = self.calc_trading_context_by_side(
+            OrderSide::Buy,
+            quotes,
+            local_snapshots_service,
+            explanation.clone(),
+        )?;
+
+        let sell_trading_ctx = self.calc_trading_context_by_side(
+            OrderSide::Sell,
+            quotes,
+            local_snapshots_service,
+            explanation.clone(),
+        )?;
+
+        Some(TradingContext::new(buy_trading_ctx, sell_trading_ctx))
+    }
+
+    fn handle_order_fill(
+        &self,
+        _cloned_order: &Arc<OrderSnapshot>,
+        _price_slot: &PriceSlot,
+        _target_eai: ExchangeAccountId,
+        _cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        // inventory is read from BalanceManager so nothing to do here
+        Ok(())
+    }
+
+    fn configuration_descriptor(&self) -> ConfigurationDescriptor {
+        self.configuration_descriptor
+    }
+}
+
+/// Realized variance of mid price per second
+fn estimate_variance(mid_prices: &VecDeque<(DateTime, Price)>) -> Option<Decimal> {
+    if mid_prices.len() < MIN_MID_PRICES_COUNT {
+        return None;
+    }
+
+    let (first_time, _) = mid_prices.front()?;
+    let (last_time, _) = mid_prices.back()?;
+    let duration_ms = (*last_time - *first_time).num_milliseconds();
+    if duration_ms <= 0 {
+        return None;
+    }
+
+    let squares_sum: Decimal = mid_prices
+        .iter()
+        .tuple_windows()
+        .map(|((_, prev), (_, next))| (next - prev) * (next - prev))
+        .sum();
+
+    Some(squares_sum * dec!(1000) / Decimal::from(duration_ms))
+}
+
+/// Decay `k` of order arrival intensity `A * exp(-k * distance)`.
+/// Distances of trades from mid price are distributed exponentially, so `k` is estimated as reciprocal of mean distance.
+fn estimate_intensity(trade_distances: &VecDeque<(DateTime, Price)>) -> Option<Decimal> {
+    if trade_distances.len() < MIN_TRADES_COUNT {
+        return None;
+    }
+
+    let distances_sum: Decimal = trade_distances.iter().map(|(_, distance)| distance).sum();
+    if distances_sum.is_zero() {
+        return None;
+    }
+
+    Some(Decimal::from(trade_distances.len()) / distances_sum)
+}
+
+fn calc_reservation_price(
+    mid_price: Price,
+    inventory: Amount,
+    risk_aversion: Decimal,
+    variance: Decimal,
+    horizon_secs: Decimal,
+) -> Price {
+    mid_price - inventory * risk_aversion * variance * horizon_secs
+}
+
+fn calc_optimal_spread(
+    risk_aversion: Decimal,
+    variance: Decimal,
+    horizon_secs: Decimal,
+    intensity: Decimal,
+) -> Option<Price> {
+    if risk_aversion <= dec!(0) || intensity <= dec!(0) {
+        return None;
+    }
+
+    let inventory_risk = risk_aversion * variance * horizon_secs;
+    let market_making_profit =
+        dec!(2) / risk_aversion * (dec!(1) + risk_aversion / intensity).checked_ln()?;
+
+    Some(inventory_risk + market_making_profit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reservation_price_is_skewed_by_inventory() {
+        let reservation_price =
+            |inventory| calc_reservation_price(dec!(100), inventory, dec!(0.1), dec!(2), dec!(5));
+
+        assert_eq!(reservation_price(dec!(0)), dec!(100));
+        assert_eq!(reservation_price(dec!(3)), dec!(97));
+        assert_eq!(reservation_price(dec!(-3)), dec!(103));
+    }
+
+    #[test]
+    fn optimal_spread_grows_with_volatility() {
+        let spread = |variance| {
+            calc_optimal_spread(dec!(0.1), variance, dec!(5), dec!(1.5)).expect("in test")
+        };
+
+        // 2 / 0.1 * ln(1 + 0.1 / 1.5)
+        assert_eq!(spread(dec!(0)).round_dp(6), dec!(1.290770));
+        assert_eq!(spread(dec!(2)).round_dp(6), dec!(2.290770));
+        assert_eq!(
+            calc_optimal_spread(dec!(0), dec!(2), dec!(5), dec!(1.5)),
+            None
+        );
+    }
+}
@@ -0,0 +1,786 @@
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+use mmb_core::balance::manager::balance_manager::BalanceManager;
+use mmb_core::disposition_execution::strategy::DispositionStrategy;
+use mmb_core::disposition_execution::{
+    PriceSlot, TradeCycle, TradeDisposition, TradingContext, TradingContextBySide,
+};
+use mmb_core::exchanges::exchange_blocker::{BlockReason, BlockType};
+use mmb_core::explanation::{Explanation, WithExplanation};
+use mmb_core::infrastructure::spawn_future;
+use mmb_core::lifecycle::trading_engine::EngineContext;
+use mmb_core::misc::reserve_parameters::ReserveParameters;
+use mmb_core::order_book::local_snapshot_service::LocalSnapshotsService;
+use mmb_core::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+use mmb_core::settings::{CurrencyPairSetting, DispositionStrategySettings};
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::exchanges::symbol::{Round, Symbol};
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, MarketAccountId, MarketId};
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, OrderHeader, OrderRole, OrderSide, OrderSnapshot, Price, UserOrder,
+};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::{SpawnFutureFlags, WithExpect};
+use mmb_utils::DateTime;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{sleep, timeout};
+
+/// Maker exchange is blocked with this reason while unhedged amount exceeds `max_unhedged_amount`
+pub static UNHEDGED_EXPOSURE: BlockReason = BlockReason::new("UNHEDGED_EXPOSURE");
+
+const HEDGE_ATTEMPTS_COUNT: u32 = 3;
+const HEDGE_RETRY_DELAY: Duration = Duration::from_secs(1);
+const HEDGE_ORDER_FINISH_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CrossExchangeStrategySettings {
+    /// Exchange where maker orders are placed
+    pub exchange_account_id: ExchangeAccountId,
+    /// Exchange where fills of maker orders are hedged by taker orders
+    pub hedge_exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPairSetting,
+    pub max_amount: Decimal,
+    /// Desired profit rate between maker price and hedge price after all fees
+    pub spread: Decimal,
+    /// Fee rate for maker orders on target exchange
+    pub maker_fee: Decimal,
+    /// Fee rate for taker orders on hedge exchange
+    pub hedge_fee: Decimal,
+    /// Max allowed price deterioration rate from top of hedge order book for hedge orders
+    pub hedge_slippage: Decimal,
+    /// Unhedged amount above which quoting on target exchange stops
+    pub max_unhedged_amount: Decimal,
+}
+
+impl DispositionStrategySettings for CrossExchangeStrategySettings {
+    fn exchange_account_id(&self) -> ExchangeAccountId {
+        self.exchange_account_id
+    }
+
+    fn currency_pair(&self) -> CurrencyPair {
+        if let CurrencyPairSetting::Ordinary { base, quote } = self.currency_pair {
+            CurrencyPair::from_codes(base, quote)
+        } else {
+            panic!(
+                "Incorrect currency pair setting enum type {:?}",
+                self.currency_pair
+            );
+        }
+    }
+
+    // Max amount for orders that will be created
+    fn max_amount(&self) -> Amount {
+        self.max_amount
+    }
+}
+
+#[derive(Debug, Default)]
+struct HedgeState {
+    /// Filled amount of maker orders that was already added to `unhedged_amount`
+    accounted_fills: HashMap<ClientOrderId, Amount>,
+    /// Positive value means that bought on target exchange more than sold on hedge exchange
+    unhedged_amount: Amount,
+    is_hedging_in_progress: bool,
+}
+
+impl HedgeState {
+    /// Adds not yet accounted part of order filled amount to `unhedged_amount` and returns it
+    fn account_fill(
+        &mut self,
+        client_order_id: ClientOrderId,
+        side: OrderSide,
+        filled_amount: Amount,
+    ) -> Amount {
+        let accounted_amount = self
+            .accounted_fills
+            .insert(client_order_id, filled_amount)
+            .unwrap_or_default();
+
+        let new_filled_amount = filled_amount - accounted_amount;
+        match side {
+            OrderSide::Buy => self.unhedged_amount += new_filled_amount,
+            OrderSide::Sell => self.unhedged_amount -= new_filled_amount,
+        }
+
+        new_filled_amount
+    }
+}
+
+/// Everything needed for sending hedge orders from spawned futures
+#[derive(Clone)]
+struct Hedger {
+    engine_context: Arc<EngineContext>,
+    target_eai: ExchangeAccountId,
+    hedge_eai: ExchangeAccountId,
+    hedge_symbol: Arc<Symbol>,
+    configuration_descriptor: ConfigurationDescriptor,
+    hedge_slippage: Decimal,
+    max_unhedged_amount: Amount,
+    state: Arc<Mutex<HedgeState>>,
+}
+
+impl Hedger {
+    fn try_start_hedging(&self, cancellation_token: CancellationToken) {
+        {
+            let mut state = self.state.lock();
+            if state.is_hedging_in_progress || !self.is_hedge_needed(state.unhedged_amount) {
+                return;
+            }
+            state.is_hedging_in_progress = true;
+        }
+
+        let hedger = self.clone();
+        spawn_future(
+            "CrossExchangeStrategy hedging",
+            SpawnFutureFlags::empty(),
+            async move {
+                hedger.hedge_with_retries(cancellation_token).await;
+                hedger.state.lock().is_hedging_in_progress = false;
+                Ok(())
+            },
+        );
+    }
+
+    fn is_hedge_needed(&self, unhedged_amount: Amount) -> bool {
+        let amount = self
+            .hedge_symbol
+            .amount_round(unhedged_amount.abs(), Round::Floor);
+
+        !amount.is_zero()
+            && self
+                .hedge_symbol
+                .min_amount
+                .map_or(true, |min| amount >= min)
+    }
+
+    async fn hedge_with_retries(&self, cancellation_token: CancellationToken) {
+        for attempt in 1..=HEDGE_ATTEMPTS_COUNT {
+            if cancellation_token.is_cancellation_requested() {
+                return;
+            }
+
+            let unhedged_amount = self.state.lock().unhedged_amount;
+            if !self.is_hedge_needed(unhedged_amount) {
+                break;
+            }
+
+            let side = match unhedged_amount.is_sign_positive() {
+                true => OrderSide::Sell,
+                false => OrderSide::Buy,
+            };
+
+            match self
+                .send_hedge_order(side, unhedged_amount.abs(), cancellation_token.clone())
+                .await
+            {
+                Ok(filled_amount) => {
+                    let mut state = self.state.lock();
+                    match side {
+                        OrderSide::Sell => state.unhedged_amount -= filled_amount,
+                        OrderSide::Buy => state.unhedged_amount += filled_amount,
+                    }
+                    log::info!(
+                        "Hedged {filled_amount} by {side} on {}, unhedged amount {}",
+                        self.hedge_eai,
+                        state.unhedged_amount
+                    );
+                }
+                Err(err) => {
+                    log::warn!(
+                        "Failed to hedge {unhedged_amount} on {} (attempt {attempt}): {err:?}",
+                        self.hedge_eai
+                    );
+                    sleep(HEDGE_RETRY_DELAY).await;
+                }
+            }
+        }
+
+        self.update_exposure_block();
+    }
+
+    fn update_exposure_block(&self) {
+        let unhedged_amount = self.state.lock().unhedged_amount;
+        let exchange_blocker = &self.engine_context.exchange_blocker;
+
+        let is_blocked = exchange_blocker.is_blocked_by_reason(self.target_eai, UNHEDGED_EXPOSURE);
+        let is_exceeded = unhedged_amount.abs() > self.max_unhedged_amount;
+        match (is_exceeded, is_blocked) {
+            (true, false) => {
+                log::error!(
+                    "Unhedged amount {unhedged_amount} exceeds {} so {} is blocked",
+                    self.max_unhedged_amount,
+                    self.target_eai
+                );
+                exchange_blocker.block(self.target_eai, UNHEDGED_EXPOSURE, BlockType::Manual);
+            }
+            (false, true) => exchange_blocker.unblock(self.target_eai, UNHEDGED_EXPOSURE),
+            _ => {}
+        }
+    }
+
+    /// Send taker order on hedge exchange and wait for its finishing
+    /// Returns filled amount of hedge order
+    async fn send_hedge_order(
+        &self,
+        side: OrderSide,
+        amount: Amount,
+        cancellation_token: CancellationToken,
+    ) -> Result<Amount> {
+        let exchange = self
+            .engine_context
+            .exchanges
+            .get(&self.hedge_eai)
+            .with_context(|| format!("Failed to get hedge exchange {}", self.hedge_eai))?
+            .clone();
+
+        let currency_pair = self.hedge_symbol.currency_pair();
+        let top_price = {
+            let order_book_top = exchange
+                .order_book_top
+                .get(&currency_pair)
+                .with_context(|| format!("There is no order book top for {currency_pair}"))?;
+
+            match side {
+                OrderSide::Buy => order_book_top.ask.as_ref().map(|x| x.price),
+                OrderSide::Sell => order_book_top.bid.as_ref().map(|x| x.price),
+            }
+            .with_context(|| format!("There is no {side} price level for {currency_pair}"))?
+        };
+
+        let price =
+            calc_hedge_order_price(side, top_price, self.hedge_slippage, &self.hedge_symbol);
+        let amount = self.hedge_symbol.amount_round(amount, Round::Floor);
+
+        let reserve_parameters = ReserveParameters::new(
+            self.configuration_descriptor,
+            self.hedge_eai,
+            self.hedge_symbol.clone(),
+            side,
+            price,
+            amount,
+        );
+        let reservation_id = match self
+            .engine_context
+            .balance_manager
+            .lock()
+            .try_reserve(&reserve_parameters, &mut None)
+        {
+            Some(reservation_id) => reservation_id,
+            None => bail!("Can't reserve balance {amount} for hedge order {side} {price}"),
+        };
+
+        let order_header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            self.hedge_eai,
+            currency_pair,
+            side,
+            amount,
+            UserOrder::limit(price),
+            Some(reservation_id),
+            None,
+            CrossExchangeStrategy::strategy_name().to_string(),
+        );
+
+        let order = match exchange
+            .create_order(&order_header, None, cancellation_token.clone())
+            .await
+        {
+            Ok(order) => order,
+            Err(err) => {
+                self.engine_context
+                    .balance_manager
+                    .lock()
+                    .unreserve_rest(reservation_id)
+                    .with_expect(|| format!("Failed to unreserve_rest for {reservation_id:?}"));
+                return Err(err);
+            }
+        };
+
+        let wait_finish =
+            exchange
+                .clone()
+                .wait_order_finish(&order, None, cancellation_token.clone());
+        if timeout(HEDGE_ORDER_FINISH_TIMEOUT, wait_finish)
+            .await
+            .is_err()
+        {
+            log::warn!(
+                "Hedge order {} is not finished in {HEDGE_ORDER_FINISH_TIMEOUT:?}, cancelling it",
+                order.client_order_id()
+            );
+            // Partial fill of the order has to be accounted even if cancellation failed
+            if let Err(err) = exchange
+                .wait_cancel_order(order.clone(), None, true, cancellation_token)
+                .await
+            {
+                log::error!(
+                    "Failed to cancel hedge order {}: {err:?}",
+                    order.client_order_id()
+                );
+            }
+        }
+
+        let order_snapshot = order.deep_clone();
+        let mut balance_manager = self.engine_context.balance_manager.lock();
+        for fill in &order_snapshot.fills.fills {
+            balance_manager.order_was_filled_with_fill(
+                self.configuration_descriptor,
+                &order_snapshot,
+                fill,
+            );
+        }
+        balance_manager
+            .unreserve_by_client_order_id(
+                reservation_id,
+                order_snapshot.client_order_id(),
+                order_snapshot.amount(),
+            )
+            .with_expect(|| {
+                format!(
+                    "Failed to unreserve hedge order {}",
+                    order_snapshot.client_order_id()
+                )
+            });
+
+        Ok(order_snapshot.fills.filled_amount)
+    }
+}
+
+/// Market making strategy which quotes on target exchange with prices based on hedge exchange order book
+/// and hedges every fill with taker order on hedge exchange
+/// NOTE: amounts on both exchanges are expected to be specified in the same currency
+pub struct CrossExchangeStrategy {
+    target_eai: ExchangeAccountId,
+    hedge_eai: ExchangeAccountId,
+    currency_pair: CurrencyPair,
+    spread: Decimal,
+    maker_fee: Decimal,
+    hedge_fee: Decimal,
+    hedge_slippage: Decimal,
+    max_amount: Decimal,
+    max_unhedged_amount: Decimal,
+    engine_context: Arc<EngineContext>,
+    configuration_descriptor: ConfigurationDescriptor,
+    hedger: Hedger,
+}
+
+impl CrossExchangeStrategy {
+    pub fn new(
+        settings: &CrossExchangeStrategySettings,
+        engine_context: Arc<EngineContext>,
+    ) -> Box<Self> {
+        let target_eai = settings.exchange_account_id;
+        let hedge_eai = settings.hedge_exchange_account_id;
+        let currency_pair = settings.currency_pair();
+
+        let configuration_descriptor = ConfigurationDescriptor::new(
+            Self::strategy_name().into(),
+            format!("{target_eai};{hedge_eai};{currency_pair}")
+                .as_str()
+                .into(),
+        );
+
+        let get_symbol = |exchange_account_id: ExchangeAccountId| {
+            engine_context
+                .exchanges
+                .get(&exchange_account_id)
+                .with_expect(|| {
+                    format!("failed to get exchange from trading_engine for {exchange_account_id}")
+                })
+                .get_symbol(currency_pair)
+                .with_expect(|| format!("failed to get symbol from exchange for {currency_pair}"))
+        };
+        let target_symbol = get_symbol(target_eai);
+        let hedge_symbol = get_symbol(hedge_eai);
+
+        // amount_limit it's a limit for position changing for both sides
+        // it's equal to half of the max amount because an order that can change a position from
+        // a limit by sells to a limit by buys is possible
+        let amount_limit = settings.max_amount * dec!(0.5);
+        {
+            let mut balance_manager = engine_context.balance_manager.lock();
+            balance_manager.set_target_amount_limit(
+                configuration_descriptor,
+                target_eai,
+                target_symbol,
+                amount_limit,
+            );
+            balance_manager.set_target_amount_limit(
+                configuration_descriptor,
+                hedge_eai,
+                hedge_symbol.clone(),
+                amount_limit,
+            );
+        }
+
+        let hedger = Hedger {
+            engine_context: engine_context.clone(),
+            target_eai,
+            hedge_eai,
+            hedge_symbol,
+            configuration_descriptor,
+            hedge_slippage: settings.hedge_slippage,
+            max_unhedged_amount: settings.max_unhedged_amount,
+            state: Default::default(),
+        };
+
+        Box::new(CrossExchangeStrategy {
+            target_eai,
+            hedge_eai,
+            currency_pair,
+            spread: settings.spread,
+            maker_fee: settings.maker_fee,
+            hedge_fee: settings.hedge_fee,
+            hedge_slippage: settings.hedge_slippage,
+            max_amount: settings.max_amount,
+            max_unhedged_amount: settings.max_unhedged_amount,
+            engine_context,
+            configuration_descriptor,
+            hedger,
+        })
+    }
+
+    fn strategy_name() -> &'static str {
+        "CrossExchangeStrategy"
+    }
+
+    fn market_account_id(&self) -> MarketAccountId {
+        MarketAccountId::new(self.target_eai, self.currency_pair)
+    }
+
+    fn hedge_market_id(&self) -> MarketId {
+        MarketId::new(self.hedge_eai.exchange_id, self.currency_pair)
+    }
+
+    /// Amount in amount currency code that can be traded by `side` on exchange with current balances
+    fn get_available_amount(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        symbol: Arc<Symbol>,
+        side: OrderSide,
+        price: Price,
+        explanation: Explanation,
+    ) -> (Amount, Explanation) {
+        let mut explanation = Some(explanation);
+
+        // TODO: delete deep_clone
+        let orders = self
+            .engine_context
+            .exchanges
+            .iter()
+            .flat_map(|x| {
+                x.orders
+                    .not_finished
+                    .iter()
+                    .map(|y| y.clone())
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        let balance_manager = BalanceManager::clone_and_subtract_not_approved_data(
+            self.engine_context.balance_manager.clone(),
+            Some(&mut orders.iter()),
+        )
+        .expect("CrossExchangeStrategy::get_available_amount: failed to clone and subtract not approved data for BalanceManager");
+
+        let amount = balance_manager
+            .lock()
+            .get_leveraged_balance_in_amount_currency_code(
+                self.configuration_descriptor,
+                side,
+                exchange_account_id,
+                symbol,
+                price,
+                &mut explanation,
+            )
+            .unwrap_or_default();
+
+        // This expect can happened if get_leveraged_balance_in_amount_currency_code() sets the explanation to None
+        let explanation = explanation.expect(
+            "CrossExchangeStrategy::get_available_amount(): Explanation should be non None here",
+        );
+
+        (amount, explanation)
+    }
+
+    fn calc_trading_context_by_side(
+        &self,
+        side: OrderSide,
+        local_snapshots_service: &LocalSnapshotsService,
+        mut explanation: Explanation,
+    ) -> Option<TradingContextBySide> {
+        let unhedged_amount = self.hedger.state.lock().unhedged_amount;
+        if unhedged_amount.abs() > self.max_unhedged_amount {
+            explanation.add_reason(format!(
+                "Unhedged amount {unhedged_amount} exceeds max unhedged amount {}",
+                self.max_unhedged_amount
+            ));
+            return Some(TradingContextBySide::empty(1, explanation));
+        }
+
+        let hedge_side = side.change_side();
+        let hedge_snapshot = local_snapshots_service.get_snapshot(self.hedge_market_id())?;
+        let (hedge_price, hedge_top_amount) = hedge_snapshot.get_top(hedge_side)?;
+
+        let exchange = self.engine_context.exchanges.get(&self.target_eai)?;
+        let symbol = exchange.get_symbol(self.currency_pair).ok()?;
+        let hedge_symbol = self.hedger.hedge_symbol.clone();
+
+        let price = calc_maker_price(
+            side,
+            hedge_price,
+            self.spread,
+            self.maker_fee,
+            self.hedge_fee,
+            self.hedge_slippage,
+            &symbol,
+        );
+        explanation.add_reason(format!(
+            "Hedge {hedge_side} price {hedge_price} on {}, maker price {price}",
+            self.hedge_eai
+        ));
+
+        let (maker_amount, explanation) =
+            self.get_available_amount(self.target_eai, symbol.clone(), side, price, explanation);
+        let (hedge_amount, mut explanation) = self.get_available_amount(
+            self.hedge_eai,
+            hedge_symbol,
+            hedge_side,
+            hedge_price,
+            explanation,
+        );
+
+        let amount = maker_amount.min(hedge_amount).min(hedge_top_amount);
+        explanation.add_reason(format!(
+            "Maker balance amount {maker_amount}, hedge balance amount {hedge_amount}, hedge top amount {hedge_top_amount}"
+        ));
+
+        let amount = symbol.amount_round(amount, Round::Floor);
+
+        Some(TradingContextBySide {
+            max_amount: self.max_amount,
+            estimating: vec![WithExplanation {
+                value: Some(TradeCycle {
+                    order_role: OrderRole::Maker,
+                    strategy_name: Self::strategy_name().to_string(),
+                    disposition: TradeDisposition::new(
+                        self.market_account_id(),
+                        side,
+                        price,
+                        amount,
+                    ),
+                }),
+                explanation,
+            }],
+        })
+    }
+}
+
+impl DispositionStrategy for CrossExchangeStrategy {
+    fn calculate_trading_context(
+        &mut self,
+        _: &ExchangeEvent,
+        _now: DateTime,
+        local_snapshots_service: &LocalSnapshotsService,
+        explanation: &mut Explanation,
+    ) -> Option<TradingContext> {
+        // Residual exposure after failed hedging is retried on market data updates
+        self.hedger
+            .try_start_hedging(self.engine_context.lifetime_manager.stop_token());
+
+        let buy_trading_ctx = self.calc_trading_context_by_side(
+            OrderSide::Buy,
+            local_snapshots_service,
+            explanation.clone(),
+        )?;
+
+        let sell_trading_ctx = self.calc_trading_context_by_side(
+            OrderSide::Sell,
+            local_snapshots_service,
+            explanation.clone(),
+        )?;
+
+        Some(TradingContext::new(buy_trading_ctx, sell_trading_ctx))
+    }
+
+    fn handle_order_fill(
+        &self,
+        cloned_order: &Arc<OrderSnapshot>,
+        _price_slot: &PriceSlot,
+        target_eai: ExchangeAccountId,
+        cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        if target_eai != self.target_eai {
+            return Ok(());
+        }
+
+        {
+            let mut state = self.hedger.state.lock();
+            let new_filled_amount = state.account_fill(
+                cloned_order.client_order_id(),
+                cloned_order.side(),
+                cloned_order.fills.filled_amount,
+            );
+
+            log::info!(
+                "CrossExchangeStrategy: order {} filled by {new_filled_amount}, unhedged amount {}",
+                cloned_order.client_order_id(),
+                state.unhedged_amount
+            );
+        }
+
+        self.hedger.try_start_hedging(cancellation_token);
+
+        Ok(())
+    }
+
+    fn handle_order_finished(&self, order: &OrderRef, target_eai: ExchangeAccountId) {
+        if target_eai != self.target_eai {
+            return;
+        }
+
+        // Fills of finished order can't arrive to strategy anymore
+        self.hedger
+            .state
+            .lock()
+            .accounted_fills
+            .remove(&order.client_order_id());
+    }
+
+    fn configuration_descriptor(&self) -> ConfigurationDescriptor {
+        self.configuration_descriptor
+    }
+}
+
+/// Price for maker order that leaves `spread` profit after hedging by taker order on hedge exchange
+fn calc_maker_price(
+    side: OrderSide,
+    hedge_price: Price,
+    spread: Decimal,
+    maker_fee: Decimal,
+    hedge_fee: Decimal,
+    hedge_slippage: Decimal,
+    symbol: &Symbol,
+) -> Price {
+    match side {
+        OrderSide::Buy => {
+            let price = hedge_price * (dec!(1) - hedge_fee - hedge_slippage)
+                / (dec!(1) + maker_fee + spread);
+            symbol.price_round(price, Round::Floor)
+        }
+        OrderSide::Sell => {
+            let price = hedge_price * (dec!(1) + hedge_fee + hedge_slippage)
+                / (dec!(1) - maker_fee - spread);
+            symbol.price_round(price, Round::Ceiling)
+        }
+    }
+}
+
+/// Limit price of taker hedge order that is crossing order book no further than `hedge_slippage`
+fn calc_hedge_order_price(
+    side: OrderSide,
+    top_price: Price,
+    hedge_slippage: Decimal,
+    symbol: &Symbol,
+) -> Price {
+    match side {
+        OrderSide::Buy => symbol.price_round(top_price * (dec!(1) + hedge_slippage), Round::Floor),
+        OrderSide::Sell => {
+            symbol.price_round(top_price * (dec!(1) - hedge_slippage), Round::Ceiling)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::exchanges::symbol::Precision;
+
+    fn symbol() -> Symbol {
+        Symbol::new(
+            false,
+            "BTC".into(),
+            "BTC".into(),
+            "USDT".into(),
+            "USDT".into(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            "BTC".into(),
+            None,
+            Precision::ByTick { tick: dec!(0.01) },
+            Precision::ByTick { tick: dec!(0.001) },
+        )
+    }
+
+    #[test]
+    fn maker_price_covers_fees_and_spread() {
+        let symbol = symbol();
+
+        let buy_price = calc_maker_price(
+            OrderSide::Buy,
+            dec!(1000),
+            dec!(0.002),
+            dec!(0.001),
+            dec!(0.001),
+            dec!(0.001),
+            &symbol,
+        );
+        assert_eq!(buy_price, dec!(995.01));
+
+        let sell_price = calc_maker_price(
+            OrderSide::Sell,
+            dec!(1000),
+            dec!(0.002),
+            dec!(0.001),
+            dec!(0.001),
+            dec!(0.001),
+            &symbol,
+        );
+        assert_eq!(sell_price, dec!(1005.02));
+    }
+
+    #[test]
+    fn partial_fills_are_accounted_once() {
+        let client_order_id = ClientOrderId::unique_id();
+        let mut state = HedgeState::default();
+
+        let new_filled_amount =
+            state.account_fill(client_order_id.clone(), OrderSide::Buy, dec!(1));
+        assert_eq!(new_filled_amount, dec!(1));
+
+        let new_filled_amount =
+            state.account_fill(client_order_id.clone(), OrderSide::Buy, dec!(3));
+        assert_eq!(new_filled_amount, dec!(2));
+        assert_eq!(state.unhedged_amount, dec!(3));
+        assert_eq!(state.accounted_fills[&client_order_id], dec!(3));
+
+        let new_filled_amount =
+            state.account_fill(ClientOrderId::unique_id(), OrderSide::Sell, dec!(5));
+        assert_eq!(new_filled_amount, dec!(5));
+        assert_eq!(state.unhedged_amount, dec!(-2));
+    }
+
+    #[test]
+    fn hedge_order_price_is_bounded_by_slippage() {
+        let symbol = symbol();
+
+        assert_eq!(
+            calc_hedge_order_price(OrderSide::Buy, dec!(1000), dec!(0.001), &symbol),
+            dec!(1001)
+        );
+        assert_eq!(
+            calc_hedge_order_price(OrderSide::Sell, dec!(1000), dec!(0.001), &symbol),
+            dec!(999)
+        );
+    }
+}
@@ -16,4 +16,6 @@
     clippy::unwrap_used
 )]
 
+pub mod avellaneda_stoikov_strategy;
+pub mod cross_exchange_strategy;
 pub mod example_strategy;
@@ -2,6 +2,7 @@ use anyhow::{anyhow, bail, Context, Result};
 use chrono::Utc;
 use dashmap::DashMap;
 use function_name::named;
+use futures::future::try_join_all;
 use hmac::digest::generic_array;
 use hmac::{Hmac, Mac};
 use hyper::header::CONTENT_TYPE;
@@ -20,8 +21,10 @@ use std::time::{Duration, UNIX_EPOCH};
 use tokio::sync::broadcast;
 
 use super::support::{
-    BinanceDerivativeAccountInfo, BinanceOrderInfo, BinancePosition, BinanceSpotAccountInfo,
+    BinanceCrossMarginAccountInfo, BinanceDerivativeAccountInfo, BinanceIsolatedMarginAccountInfo,
+    BinanceOrderInfo, BinancePosition, BinanceSpotAccountInfo,
 };
+use super::websocket_api::WebSocketApiSession;
 use mmb_core::exchanges::general::exchange::BoxExchangeClient;
 use mmb_core::exchanges::general::exchange::Exchange;
 use mmb_core::exchanges::general::features::{
@@ -29,6 +32,7 @@ use mmb_core::exchanges::general::features::{
 };
 use mmb_core::exchanges::general::handlers::handle_order_filled::FillAmount;
 use mmb_core::exchanges::general::handlers::handle_order_filled::FillEvent;
+use mmb_core::exchanges::general::market_data_connections::MarketDataShards;
 use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
 use mmb_core::exchanges::hosts::Hosts;
 use mmb_core::exchanges::rest_client::{
@@ -38,17 +42,17 @@ use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
 use mmb_core::exchanges::traits::{ExchangeClientBuilder, ExchangeError, HandleMetricsCb};
 use mmb_core::exchanges::traits::{
     ExchangeClientBuilderResult, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb,
-    OrderCreatedCb, Support,
+    OrderCreatedCb, SendWebsocketMessageCb, Support,
 };
 use mmb_core::exchanges::{
     general::features::{ExchangeFeatures, OpenOrdersType},
     timeouts::requests_timeout_manager_factory::RequestTimeoutArguments,
 };
 use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
-use mmb_core::settings::ExchangeSettings;
+use mmb_core::settings::{ExchangeSettings, MarketType, SelfTradePreventionPolicy};
 use mmb_domain::events::{AllowedEventSourceType, EventSourceType};
-use mmb_domain::events::{ExchangeBalance, ExchangeEvent, TradeId};
-use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::events::{ExchangeBalance, ExchangeEvent, MarginAccount, MarginBalance, TradeId};
+use mmb_domain::exchanges::symbol::{ContractType, Precision, Symbol};
 use mmb_domain::market::{CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId};
 use mmb_domain::market::{ExchangeAccountId, SpecificCurrencyPair};
 use mmb_domain::order::fill::OrderFillType;
@@ -57,17 +61,20 @@ use mmb_domain::order::snapshot::*;
 use mmb_domain::order::snapshot::{Amount, Price};
 use mmb_domain::position::{ActivePosition, DerivativePosition};
 use mmb_utils::value_to_decimal::GetOrErr;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sha2::digest::generic_array::GenericArray;
 
 const LISTEN_KEY: &str = "listenKey";
+/// Futures stop orders are triggered by mark price to avoid triggering on short spikes of last price
+const STOP_ORDER_WORKING_TYPE: &str = "MARK_PRICE";
 
 #[derive(Default)]
 pub struct ErrorHandlerBinance;
 
 pub struct RestHeadersBinance {
     pub api_key: String,
-    pub is_usd_m_futures: bool,
+    pub is_futures: bool,
 }
 
 impl RestHeaders for RestHeadersBinance {
@@ -77,7 +84,7 @@ impl RestHeaders for RestHeadersBinance {
         _uri: &Uri,
         _request_type: RequestType,
     ) -> Builder {
-        match self.is_usd_m_futures {
+        match self.is_futures {
             true => builder.header(CONTENT_TYPE, "application/x-www-form-urlencoded"),
             false => builder,
         }
@@ -140,6 +147,7 @@ const EMPTY_RESPONSE_IS_OK: bool = false;
 
 pub struct Binance {
     pub settings: ExchangeSettings,
+    pub market_type: MarketType,
     pub hosts: Hosts,
     pub id: ExchangeAccountId,
     pub order_created_callback: OrderCreatedCb,
@@ -147,6 +155,7 @@ pub struct Binance {
     pub handle_order_filled_callback: HandleOrderFilledCb,
     pub handle_trade_callback: HandleTradeCb,
     pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(super) websocket_message_callback: SendWebsocketMessageCb,
 
     pub unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
     pub specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
@@ -158,6 +167,8 @@ pub struct Binance {
 
     // Currencies used for trading according to user settings
     pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    // Distribution of traded currencies across market data connections, empty if they aren't used
+    pub(super) market_data_shards: Mutex<MarketDataShards>,
 
     pub(super) last_trade_ids: DashMap<CurrencyPair, TradeId>,
 
@@ -172,6 +183,8 @@ pub struct Binance {
 
     // NOTE: None when websocket is disconnected
     pub(super) listen_key: RwLock<Option<String>>,
+
+    pub(super) websocket_api: WebSocketApiSession,
 }
 
 impl Binance {
@@ -210,7 +223,8 @@ impl Binance {
             .is_reducing_market_data
             .unwrap_or(is_reducing_market_data);
 
-        let hosts = Self::make_hosts(settings.is_margin_trading);
+        let market_type = Self::get_market_type(&settings);
+        let hosts = Self::make_hosts(market_type);
         let exchange_account_id = settings.exchange_account_id;
 
         Self {
@@ -220,11 +234,15 @@ impl Binance {
             handle_order_filled_callback: Box::new(|_| {}),
             handle_trade_callback: Box::new(|_, _| {}),
             handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
             unified_to_specific: Default::default(),
             specific_to_unified: Default::default(),
             supported_currencies: Default::default(),
             working_currencies_ids: Default::default(),
             traded_specific_currencies: Default::default(),
+            market_data_shards: Mutex::new(MarketDataShards::new(
+                settings.market_data_connections.unwrap_or_default(),
+            )),
             last_trade_ids: Default::default(),
             subscribe_to_market_data: settings.subscribe_to_market_data,
             rest_client: RestClient::new(
@@ -235,39 +253,64 @@ impl Binance {
                 ),
                 RestHeadersBinance {
                     api_key: settings.api_key.clone(),
-                    is_usd_m_futures: settings.is_margin_trading,
+                    is_futures: settings.is_margin_trading,
                 },
             ),
             timeout_manager,
             is_reducing_market_data,
             settings,
+            market_type,
             hosts,
             events_channel,
             lifetime_manager,
             listen_key: Default::default(),
+            websocket_api: Default::default(),
         }
     }
 
-    pub fn make_hosts(is_margin_trading: bool) -> Hosts {
-        if is_margin_trading {
-            Hosts {
-                web_socket_host: "wss://fstream.binance.com",
-                web_socket2_host: "wss://fstream.binance.com",
-                rest_host: "https://fapi.binance.com",
-            }
-        } else {
-            Hosts {
+    /// USDⓈ-M futures are used for margin trading if market type isn't specified
+    pub fn get_market_type(settings: &ExchangeSettings) -> MarketType {
+        let market_type = settings
+            .market_type
+            .unwrap_or(match settings.is_margin_trading {
+                true => MarketType::UsdM,
+                false => MarketType::Spot,
+            });
+
+        match (market_type, settings.is_margin_trading) {
+            (MarketType::Spot | MarketType::CrossMargin | MarketType::IsolatedMargin, false)
+            | (MarketType::UsdM | MarketType::CoinM, true) => market_type,
+            _ => panic!(
+                "Binance market type {market_type:?} doesn't match is_margin_trading = {} for {}",
+                settings.is_margin_trading, settings.exchange_account_id
+            ),
+        }
+    }
+
+    pub fn make_hosts(market_type: MarketType) -> Hosts {
+        match market_type {
+            MarketType::Spot | MarketType::CrossMargin | MarketType::IsolatedMargin => Hosts {
                 web_socket_host: "wss://stream.binance.com:9443",
                 web_socket2_host: "wss://stream.binance.com:9443",
                 rest_host: "https://api.binance.com",
-            }
+            },
+            MarketType::UsdM => Hosts {
+                web_socket_host: "wss://fstream.binance.com",
+                web_socket2_host: "wss://fstream.binance.com",
+                rest_host: "https://fapi.binance.com",
+            },
+            MarketType::CoinM => Hosts {
+                web_socket_host: "wss://dstream.binance.com",
+                web_socket2_host: "wss://dstream.binance.com",
+                rest_host: "https://dapi.binance.com",
+            },
         }
     }
 
     #[named]
     pub(super) async fn request_listen_key(&self) -> Result<RestResponse, ExchangeError> {
-        let path = self.get_uri_path("/fapi/v1/listenKey", "/api/v3/userDataStream");
-        let builder = UriBuilder::from_path(path);
+        let mut builder = UriBuilder::from_path(self.get_listen_key_path());
+        self.add_isolated_margin_symbol(&mut builder)?;
         let (uri, query) = builder.build_uri_and_query(self.hosts.rest_uri_host(), false);
 
         self.rest_client
@@ -289,9 +332,9 @@ impl Binance {
 
     #[named]
     pub async fn request_update_listen_key(&self, listen_key: &str) -> Result<(), ExchangeError> {
-        let path = self.get_uri_path("/fapi/v1/listenKey", "/api/v3/userDataStream");
-        let mut builder = UriBuilder::from_path(path);
+        let mut builder = UriBuilder::from_path(self.get_listen_key_path());
         builder.add_kv(LISTEN_KEY, listen_key);
+        self.add_isolated_margin_symbol(&mut builder)?;
         let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
 
         self.rest_client
@@ -305,6 +348,18 @@ impl Binance {
         todo!("reconnect")
     }
 
+    fn get_listen_key_path(&self) -> &'static str {
+        match self.market_type {
+            MarketType::CrossMargin => "/sapi/v1/userDataStream",
+            MarketType::IsolatedMargin => "/sapi/v1/userDataStream/isolated",
+            _ => self.get_uri_path(
+                "/fapi/v1/listenKey",
+                "/dapi/v1/listenKey",
+                "/api/v3/userDataStream",
+            ),
+        }
+    }
+
     pub(super) fn get_stream_name(
         specific_currency_pair: &SpecificCurrencyPair,
         channel: &str,
@@ -316,12 +371,19 @@ impl Binance {
         todo!("is_websocket_reconnecting")
     }
 
-    fn write_signature_to_builder(&self, builder: &mut UriBuilder) {
+    pub(super) fn calculate_hmac(
+        &self,
+        payload: &[u8],
+    ) -> GenericArray<u8, generic_array::typenum::U32> {
         let mut hmac = Hmac::<Sha256>::new_from_slice(self.settings.secret_key.as_bytes())
             .expect("Unable to calculate hmac for Binance signature");
-        hmac.update(builder.query());
+        hmac.update(payload);
 
-        let hmac_bytes = hmac.finalize().into_bytes();
+        hmac.finalize().into_bytes()
+    }
+
+    fn write_signature_to_builder(&self, builder: &mut UriBuilder) {
+        let hmac_bytes = self.calculate_hmac(builder.query());
 
         // hex representation of signature have double size of input data
         builder.ensure_free_size(hmac_bytes.len() * 2);
@@ -381,8 +443,6 @@ impl Binance {
         json_response: Value,
         event_time: DateTime,
     ) -> Result<()> {
-        // TODO need special handler for OCO orders
-
         let exchange_order_id = json_response["i"].to_string();
         let exchange_order_id = exchange_order_id.trim_matches('"');
         let execution_type = json_response["x"]
@@ -400,10 +460,15 @@ impl Binance {
                 "CANCELED" | "EXPIRED" if !self.settings.is_margin_trading => "C",
                 _ => "c",
             };
-            json_response[field_name]
-                .as_str()
-                .ok_or_else(|| anyhow!("Unable to parse client order id"))?
+            match json_response[field_name].as_str() {
+                // Original client order id is empty for orders expired by exchange itself
+                Some("") => json_response["c"].as_str(),
+                client_order_id => client_order_id,
+            }
+            .ok_or_else(|| anyhow!("Unable to parse client order id"))?
         };
+        // Spot orders which are not legs of OCO order have orderListId = -1
+        let is_oco_order_leg = json_response["g"].as_i64().map_or(false, |x| x != -1);
 
         match execution_type {
             "NEW" => match order_status {
@@ -430,7 +495,15 @@ impl Binance {
                 // TODO: May be not handle error in Rest but move it here to make it unified?
                 // We get notification of rejected orders from the rest responses
             }
+            // Leg of OCO order is expired when the other leg is executed
             "EXPIRED" => match time_in_force {
+                _ if is_oco_order_leg => {
+                    (self.order_cancelled_callback)(
+                        client_order_id.into(),
+                        exchange_order_id.into(),
+                        EventSourceType::WebSocket,
+                    );
+                }
                 "GTX" => {
                     (self.order_cancelled_callback)(
                         client_order_id.into(),
@@ -534,17 +607,14 @@ impl Binance {
         }
     }
 
-    pub(super) fn get_order_id(
-        &self,
-        response: &RestResponse,
-    ) -> Result<ExchangeOrderId, ExchangeError> {
+    pub(super) fn get_order_id(&self, content: &str) -> Result<ExchangeOrderId, ExchangeError> {
         #[derive(Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct OrderId {
             order_id: u64,
         }
 
-        let deserialized: OrderId = serde_json::from_str(&response.content)
+        let deserialized: OrderId = serde_json::from_str(content)
             .map_err(|err| ExchangeError::parsing(format!("Unable to parse orderId: {err:?}")))?;
 
         let order_id_str = deserialized.order_id.to_string().into();
@@ -553,12 +623,86 @@ impl Binance {
 
     pub(super) fn get_uri_path<'a>(
         &self,
-        margin_trading_url: &'a str,
-        not_margin_trading_url: &'a str,
+        usd_m_futures_url: &'a str,
+        coin_m_futures_url: &'a str,
+        spot_url: &'a str,
     ) -> &'a str {
-        match self.settings.is_margin_trading {
-            true => margin_trading_url,
-            false => not_margin_trading_url,
+        match self.market_type {
+            MarketType::UsdM => usd_m_futures_url,
+            MarketType::CoinM => coin_m_futures_url,
+            MarketType::Spot | MarketType::CrossMargin | MarketType::IsolatedMargin => spot_url,
+        }
+    }
+
+    /// Path of request related to account, margin accounts have separate requests
+    pub(super) fn get_account_uri_path<'a>(
+        &self,
+        usd_m_futures_url: &'a str,
+        coin_m_futures_url: &'a str,
+        spot_url: &'a str,
+        margin_url: &'a str,
+    ) -> &'a str {
+        match self.market_type {
+            MarketType::CrossMargin | MarketType::IsolatedMargin => margin_url,
+            _ => self.get_uri_path(usd_m_futures_url, coin_m_futures_url, spot_url),
+        }
+    }
+
+    pub(super) fn is_margin_account(&self) -> bool {
+        matches!(
+            self.market_type,
+            MarketType::CrossMargin | MarketType::IsolatedMargin
+        )
+    }
+
+    /// Requests of isolated margin account are distinguished from cross margin ones by `isIsolated` parameter
+    pub(super) fn add_isolated_margin_param(&self, builder: &mut UriBuilder) {
+        if self.market_type == MarketType::IsolatedMargin {
+            builder.add_kv("isIsolated", "TRUE");
+        }
+    }
+
+    /// Isolated margin account of exchange account is used for trading a single currency pair
+    pub(super) fn get_isolated_margin_currency_pair(
+        &self,
+    ) -> Result<SpecificCurrencyPair, ExchangeError> {
+        let traded_specific_currencies = self.traded_specific_currencies.lock();
+        match traded_specific_currencies.as_slice() {
+            [specific_currency_pair] => Ok(*specific_currency_pair),
+            _ => Err(ExchangeError::unknown(&format!(
+                "Isolated margin account {} should be used for exactly one currency pair, but got {traded_specific_currencies:?}",
+                self.settings.exchange_account_id
+            ))),
+        }
+    }
+
+    /// Requests of isolated margin account without currency pair (e.g. user data stream) are specified by its currency pair
+    pub(super) fn add_isolated_margin_symbol(
+        &self,
+        builder: &mut UriBuilder,
+    ) -> Result<(), ExchangeError> {
+        if self.market_type == MarketType::IsolatedMargin {
+            builder.add_kv("symbol", self.get_isolated_margin_currency_pair()?);
+        }
+
+        Ok(())
+    }
+
+    /// Path of request which exists only on futures markets, margin accounts don't have such requests
+    pub(super) fn get_futures_uri_path<'a>(
+        &self,
+        usd_m_futures_url: &'a str,
+        coin_m_futures_url: &'a str,
+    ) -> Result<&'a str, ExchangeError> {
+        match self.market_type {
+            MarketType::CoinM => Ok(coin_m_futures_url),
+            MarketType::UsdM | MarketType::Spot => Ok(usd_m_futures_url),
+            MarketType::CrossMargin | MarketType::IsolatedMargin => {
+                Err(ExchangeError::unknown(&format!(
+                    "Request {usd_m_futures_url} isn't supported for {:?} account {}",
+                    self.market_type, self.id
+                )))
+            }
         }
     }
 
@@ -582,10 +726,11 @@ impl Binance {
         let client_order_id = order.client_order_id();
         let specific_currency_pair = self.get_specific_currency_pair(order.currency_pair());
 
-        let path = self.get_uri_path("/fapi/v1/order", "/api/v3/order");
+        let path = self.get_order_path();
         let mut builder = UriBuilder::from_path(path);
         builder.add_kv("symbol", specific_currency_pair);
         builder.add_kv("origClientOrderId", &client_order_id);
+        self.add_isolated_margin_param(&mut builder);
         self.add_authentification(&mut builder);
         let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
 
@@ -601,12 +746,28 @@ impl Binance {
         self.specific_order_info_to_unified(&specific_order)
     }
 
-    fn get_open_order_path(&self) -> &str {
-        self.get_uri_path("/fapi/v1/openOrders", "/api/v3/openOrders")
+    fn get_order_path(&self) -> &str {
+        self.get_account_uri_path(
+            "/fapi/v1/order",
+            "/dapi/v1/order",
+            "/api/v3/order",
+            "/sapi/v1/margin/order",
+        )
+    }
+
+    pub(super) fn get_open_order_path(&self) -> &str {
+        self.get_account_uri_path(
+            "/fapi/v1/openOrders",
+            "/dapi/v1/openOrders",
+            "/api/v3/openOrders",
+            "/sapi/v1/margin/openOrders",
+        )
     }
 
     pub(super) async fn request_open_orders(&self) -> Result<RestResponse, ExchangeError> {
         let mut builder = UriBuilder::from_path(self.get_open_order_path());
+        self.add_isolated_margin_param(&mut builder);
+        self.add_isolated_margin_symbol(&mut builder)?;
         self.add_authentification(&mut builder);
 
         self.request_open_orders_by_http_header(builder).await
@@ -620,6 +781,7 @@ impl Binance {
 
         let mut builder = UriBuilder::from_path(self.get_open_order_path());
         builder.add_kv("symbol", specific_currency_pair);
+        self.add_isolated_margin_param(&mut builder);
         self.add_authentification(&mut builder);
 
         self.request_open_orders_by_http_header(builder).await
@@ -641,7 +803,8 @@ impl Binance {
         position: &ActivePosition,
         price: Option<Price>,
     ) -> Result<RestResponse, ExchangeError> {
-        let mut builder = UriBuilder::from_path("/fapi/v1/order");
+        let path = self.get_futures_uri_path("/fapi/v1/order", "/dapi/v1/order")?;
+        let mut builder = UriBuilder::from_path(path);
         builder.add_kv("quantity", position.derivative.position.abs());
         let side = position.derivative.get_side().change_side();
         builder.add_kv("side", get_server_order_side(side));
@@ -669,9 +832,31 @@ impl Binance {
             .await
     }
 
+    #[named]
+    pub(super) async fn request_cancel_all_orders_after(
+        &self,
+        currency_pair: CurrencyPair,
+        timeout: Duration,
+    ) -> Result<RestResponse, ExchangeError> {
+        let path = self
+            .get_futures_uri_path("/fapi/v1/countdownCancelAll", "/dapi/v1/countdownCancelAll")?;
+        let mut builder = UriBuilder::from_path(path);
+        builder.add_kv("symbol", self.get_specific_currency_pair(currency_pair));
+        builder.add_kv("countdownTime", timeout.as_millis());
+        self.add_authentification(&mut builder);
+
+        let (uri, query) = builder.build_uri_and_query(self.hosts.rest_uri_host(), false);
+
+        let log_args = format!("Cancel all orders after {timeout:?} for {currency_pair}");
+        self.rest_client
+            .post(uri, Some(query), function_name!(), log_args)
+            .await
+    }
+
     #[named]
     pub(super) async fn request_get_position(&self) -> Result<RestResponse, ExchangeError> {
-        let mut builder = UriBuilder::from_path("/fapi/v2/positionRisk");
+        let path = self.get_futures_uri_path("/fapi/v2/positionRisk", "/dapi/v1/positionRisk")?;
+        let mut builder = UriBuilder::from_path(path);
         self.add_authentification(&mut builder);
 
         let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
@@ -725,8 +910,15 @@ impl Binance {
 
     #[named]
     pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
-        let path = self.get_uri_path("/fapi/v2/account", "/api/v3/account");
+        let path = match self.market_type {
+            MarketType::CrossMargin => "/sapi/v1/margin/account",
+            MarketType::IsolatedMargin => "/sapi/v1/margin/isolated/account",
+            _ => self.get_uri_path("/fapi/v2/account", "/dapi/v1/account", "/api/v3/account"),
+        };
         let mut builder = UriBuilder::from_path(path);
+        if self.market_type == MarketType::IsolatedMargin {
+            builder.add_kv("symbols", self.get_isolated_margin_currency_pair()?);
+        }
         self.add_authentification(&mut builder);
         let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
 
@@ -755,6 +947,105 @@ impl Binance {
             .collect_vec())
     }
 
+    #[named]
+    pub(super) async fn request_max_borrowable(
+        &self,
+        currency_id: CurrencyId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/sapi/v1/margin/maxBorrowable");
+        builder.add_kv("asset", currency_id);
+        if self.market_type == MarketType::IsolatedMargin {
+            builder.add_kv("isolatedSymbol", self.get_isolated_margin_currency_pair()?);
+        }
+        self.add_authentification(&mut builder);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), format!("asset {currency_id}"))
+            .await
+    }
+
+    pub(super) fn parse_max_borrowable(response: &RestResponse) -> Result<Amount> {
+        #[derive(Deserialize)]
+        struct MaxBorrowable {
+            amount: Amount,
+        }
+
+        let max_borrowable: MaxBorrowable = serde_json::from_str(&response.content)
+            .context("Unable to parse max borrowable amount")?;
+
+        Ok(max_borrowable.amount)
+    }
+
+    /// Margin account state with amounts that can be borrowed for working currencies
+    pub(super) async fn get_margin_account(&self) -> Result<MarginAccount> {
+        let working_currencies_ids = self.working_currencies_ids.read().clone();
+        let max_borrowable_requests =
+            working_currencies_ids
+                .iter()
+                .map(|&currency_id| async move {
+                    let response = self.request_max_borrowable(currency_id).await?;
+                    Ok::<_, anyhow::Error>((currency_id, Self::parse_max_borrowable(&response)?))
+                });
+
+        let (balance_response, max_borrowable) = tokio::join!(
+            self.request_get_balance(),
+            try_join_all(max_borrowable_requests)
+        );
+
+        self.parse_margin_account(&balance_response?, &max_borrowable?.into_iter().collect())
+    }
+
+    pub(super) fn parse_margin_account(
+        &self,
+        response: &RestResponse,
+        max_borrowable: &HashMap<CurrencyId, Amount>,
+    ) -> Result<MarginAccount> {
+        let (margin_level, balances) = match self.market_type {
+            MarketType::IsolatedMargin => {
+                let account_info: BinanceIsolatedMarginAccountInfo =
+                    serde_json::from_str(&response.content)
+                        .context("Unable to parse isolated margin account info")?;
+                let pair = account_info
+                    .assets
+                    .into_iter()
+                    .next()
+                    .context("Isolated margin account info doesn't contain currency pair")?;
+                (pair.margin_level, vec![pair.base_asset, pair.quote_asset])
+            }
+            _ => {
+                let account_info: BinanceCrossMarginAccountInfo =
+                    serde_json::from_str(&response.content)
+                        .context("Unable to parse cross margin account info")?;
+                (account_info.margin_level, account_info.user_assets)
+            }
+        };
+
+        let balances = balances
+            .iter()
+            .filter_map(|balance| {
+                let currency_id = balance.asset.into();
+                self.get_currency_code(&currency_id)
+                    .map(|currency_code| MarginBalance {
+                        currency_code,
+                        free: balance.free,
+                        locked: balance.locked,
+                        borrowed: balance.borrowed,
+                        interest: balance.interest,
+                        max_borrowable: max_borrowable
+                            .get(&currency_id)
+                            .copied()
+                            .unwrap_or_default(),
+                    })
+            })
+            .collect_vec();
+
+        Ok(MarginAccount {
+            balances,
+            margin_level,
+        })
+    }
+
     pub(super) fn parse_derivative_balance(
         &self,
         response: &RestResponse,
@@ -775,18 +1066,29 @@ impl Binance {
             .collect_vec())
     }
 
-    #[named]
-    pub(super) async fn request_cancel_order(
+    /// Parameters of cancel order request without authentication
+    pub(super) fn cancel_order_params(
         &self,
         order: &OrderRef,
         exchange_order_id: &ExchangeOrderId,
-    ) -> Result<RestResponse, ExchangeError> {
+    ) -> UriBuilder {
         let specific_currency_pair = self.get_specific_currency_pair(order.currency_pair());
 
-        let path = self.get_uri_path("/fapi/v1/order", "/api/v3/order");
-        let mut builder = UriBuilder::from_path(path);
+        let mut builder = UriBuilder::from_path(self.get_order_path());
         builder.add_kv("symbol", specific_currency_pair);
         builder.add_kv("orderId", exchange_order_id);
+        self.add_isolated_margin_param(&mut builder);
+
+        builder
+    }
+
+    #[named]
+    pub(super) async fn request_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = self.cancel_order_params(order, exchange_order_id);
         self.add_authentification(&mut builder);
 
         let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
@@ -805,7 +1107,12 @@ impl Binance {
     ) -> Result<RestResponse, ExchangeError> {
         let specific_currency_pair = self.get_specific_currency_pair(symbol.currency_pair());
 
-        let path = self.get_uri_path("/fapi/v1/userTrades", "/api/v3/myTrades");
+        let path = self.get_account_uri_path(
+            "/fapi/v1/userTrades",
+            "/dapi/v1/userTrades",
+            "/api/v3/myTrades",
+            "/sapi/v1/margin/myTrades",
+        );
         let mut builder = UriBuilder::from_path(path);
         if let Some(last_date_time_value) = last_date_time {
             builder.add_kv(
@@ -814,6 +1121,7 @@ impl Binance {
             );
         }
         builder.add_kv("symbol", specific_currency_pair);
+        self.add_isolated_margin_param(&mut builder);
         self.add_authentification(&mut builder);
 
         let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
@@ -885,17 +1193,16 @@ impl Binance {
             .collect()
     }
 
-    #[named]
-    pub(super) async fn request_create_order(
+    /// Parameters of create order request without authentication
+    pub(super) fn create_order_params(
         &self,
         order: &OrderRef,
-    ) -> Result<RestResponse, ExchangeError> {
+    ) -> Result<UriBuilder, ExchangeError> {
         let header = order.header();
         let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
         let is_margin_trading = self.settings.is_margin_trading;
 
-        let path = self.get_uri_path("/fapi/v1/order", "/api/v3/order");
-        let mut builder = UriBuilder::from_path(path);
+        let mut builder = UriBuilder::from_path(self.get_order_path());
         builder.add_kv("symbol", specific_currency_pair);
         builder.add_kv("side", get_server_order_side(header.side));
         builder.add_kv("quantity", header.amount);
@@ -951,28 +1258,155 @@ impl Binance {
                 UserOrder::StopLoss { stop_price } => {
                     builder.add_kv("type", "STOP_MARKET");
                     builder.add_kv("stopPrice", stop_price);
+                    builder.add_kv("workingType", STOP_ORDER_WORKING_TYPE);
                     builder.add_kv("timeInForce", "GTC");
                 }
-                UserOrder::TrailingStop { .. } => {
-                    unimplemented!("Trailing stop order not implemented for futures now.")
+                UserOrder::TrailingStop {
+                    trailing_delta,
+                    stop_price,
+                } => {
+                    builder.add_kv("type", "TRAILING_STOP_MARKET");
+                    // Futures callback rate is specified in percents unlike spot trailing delta in BIPS
+                    builder.add_kv("callbackRate", trailing_delta / Decimal::ONE_HUNDRED);
+                    builder.add_kv("workingType", STOP_ORDER_WORKING_TYPE);
+                    builder.add_kv("timeInForce", "GTC");
+
+                    if let Some(stop_price) = stop_price {
+                        builder.add_kv("activationPrice", stop_price)
+                    }
                 }
             },
             _ => return Err(ExchangeError::unknown("Unexpected order type")),
         }
 
+        self.add_margin_order_params(&mut builder);
+
+        if let Some(self_trade_prevention) = &self.settings.self_trade_prevention {
+            if self_trade_prevention.use_exchange_native_mode {
+                builder.add_kv(
+                    "selfTradePreventionMode",
+                    get_self_trade_prevention_mode(self_trade_prevention.policy),
+                );
+            }
+        }
+
+        Ok(builder)
+    }
+
+    #[named]
+    pub(super) async fn request_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = self.create_order_params(order)?;
+        self.add_authentification(&mut builder);
+
+        let (uri, query) = builder.build_uri_and_query(self.hosts.rest_uri_host(), false);
+
+        let log_args = format!("Create order for {:?}", order.header());
+        self.rest_client
+            .post(uri, Some(query), function_name!(), log_args)
+            .await
+    }
+
+    /// Assets are borrowed automatically when balance isn't enough for margin order and repaid when the order is executed
+    fn add_margin_order_params(&self, builder: &mut UriBuilder) {
+        if self.is_margin_account() {
+            builder.add_kv("sideEffectType", "AUTO_BORROW_REPAY");
+            self.add_isolated_margin_param(builder);
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_create_oco_order(
+        &self,
+        limit_order: &OrderRef,
+        stop_loss_order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let limit_header = limit_order.header();
+        let stop_loss_header = stop_loss_order.header();
+        let stop_price = match &stop_loss_header.options {
+            OrderOptions::User(UserOrder::StopLoss { stop_price }) => stop_price,
+            _ => {
+                return Err(ExchangeError::unknown(
+                    "Unexpected stop loss leg of OCO order",
+                ))
+            }
+        };
+
+        let specific_currency_pair = self.get_specific_currency_pair(limit_header.currency_pair);
+
+        let path = match self.is_margin_account() {
+            true => "/sapi/v1/margin/order/oco",
+            false => "/api/v3/order/oco",
+        };
+        let mut builder = UriBuilder::from_path(path);
+        builder.add_kv("symbol", specific_currency_pair);
+        builder.add_kv("side", get_server_order_side(limit_header.side));
+        builder.add_kv("quantity", limit_header.amount);
+        builder.add_kv("limitClientOrderId", &limit_header.client_order_id);
+        builder.add_kv("price", limit_header.price());
+        builder.add_kv("stopClientOrderId", &stop_loss_header.client_order_id);
+        builder.add_kv("stopPrice", stop_price);
+        self.add_margin_order_params(&mut builder);
         self.add_authentification(&mut builder);
 
         let (uri, query) = builder.build_uri_and_query(self.hosts.rest_uri_host(), false);
 
-        let log_args = format!("Create order for {header:?}");
+        let log_args = format!("Create OCO order for {limit_header:?} {stop_loss_header:?}");
         self.rest_client
             .post(uri, Some(query), function_name!(), log_args)
             .await
     }
 
+    /// Returns exchange order ids of limit and stop loss legs of OCO order respectively
+    pub(super) fn get_oco_order_ids(
+        &self,
+        response: &RestResponse,
+        limit_client_order_id: &ClientOrderId,
+        stop_loss_client_order_id: &ClientOrderId,
+    ) -> Result<(ExchangeOrderId, ExchangeOrderId), ExchangeError> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct OcoOrderLeg {
+            order_id: u64,
+            client_order_id: ClientOrderId,
+        }
+
+        #[derive(Deserialize)]
+        struct OcoOrder {
+            orders: Vec<OcoOrderLeg>,
+        }
+
+        let deserialized: OcoOrder = serde_json::from_str(&response.content)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse OCO order: {err:?}")))?;
+
+        let get_order_id = |client_order_id: &ClientOrderId| {
+            deserialized
+                .orders
+                .iter()
+                .find(|x| &x.client_order_id == client_order_id)
+                .map(|x| ExchangeOrderId::new(x.order_id.to_string().into()))
+                .ok_or_else(|| {
+                    ExchangeError::parsing(format!(
+                        "Unable to find order {client_order_id} in OCO order response"
+                    ))
+                })
+        };
+
+        Ok((
+            get_order_id(limit_client_order_id)?,
+            get_order_id(stop_loss_client_order_id)?,
+        ))
+    }
+
     #[named]
     pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
-        let path = self.get_uri_path("/fapi/v1/exchangeInfo", "/api/v3/exchangeInfo");
+        let path = self.get_uri_path(
+            "/fapi/v1/exchangeInfo",
+            "/dapi/v1/exchangeInfo",
+            "/api/v3/exchangeInfo",
+        );
         let builder = UriBuilder::from_path(path);
         let uri = builder.build_uri(self.hosts.rest_uri_host(), false);
 
@@ -991,7 +1425,7 @@ impl Binance {
 
         let mut supported_symbols = Vec::new();
         for symbol in symbols {
-            if Binance::is_unsupported_symbol(symbol) {
+            if self.is_unsupported_symbol(symbol) {
                 continue;
             }
 
@@ -1017,12 +1451,22 @@ impl Binance {
                 .write()
                 .insert(specific_currency_pair, unified_currency_pair);
 
+            // COIN-M contracts are inverse and settled in base currency, amount of their orders is in contracts
+            let contract_type = match self.market_type {
+                MarketType::CoinM => ContractType::Inverse,
+                MarketType::UsdM
+                | MarketType::Spot
+                | MarketType::CrossMargin
+                | MarketType::IsolatedMargin => ContractType::Linear,
+            };
+            // contract size is sent as number unlike other decimal fields
+            let contract_size = symbol["contractSize"].as_u64().map(Decimal::from);
+
             let (amount_currency_code, balance_currency_code) =
-                match self.settings.is_margin_trading {
-                    // TODO need explicit specify type of using Binance futures markets
-                    // true => (quote, Some(base)),
-                    true => (base, Some(quote)),
-                    false => (base, None),
+                match (self.settings.is_margin_trading, contract_type) {
+                    (true, ContractType::Inverse) => (quote, Some(base)),
+                    (true, _) => (base, Some(quote)),
+                    (false, _) => (base, None),
                 };
 
             let mut min_amount = None;
@@ -1076,7 +1520,7 @@ impl Binance {
                 ),
             };
 
-            let symbol = Symbol::new(
+            let mut symbol = Symbol::new(
                 self.settings.is_margin_trading,
                 base_currency_id.as_str().into(),
                 base,
@@ -1092,6 +1536,14 @@ impl Binance {
                 price_precision,
                 amount_precision,
             );
+            symbol.contract_type = contract_type;
+            if let ContractType::Inverse = contract_type {
+                symbol.amount_multiplier = contract_size.with_context(|| {
+                    format!(
+                        "Unable to get contract size from Binance for {specific_currency_pair:?}"
+                    )
+                })?;
+            }
 
             supported_symbols.push(Arc::new(symbol))
         }
@@ -1099,13 +1551,20 @@ impl Binance {
         Ok(supported_symbols)
     }
 
-    fn is_unsupported_symbol(symbol: &Value) -> bool {
+    fn is_unsupported_symbol(&self, symbol: &Value) -> bool {
         let code = &symbol
             .get_as_str("symbol")
             .expect("Unable to get symbol code from Binance");
 
-        // Binance adds "_<NUMBERS>" to old symbol's code
-        code.contains('_') || symbol["status"] != "TRADING"
+        match self.market_type {
+            // Only perpetual COIN-M contracts are supported, delivery ones have "_<DELIVERY DATE>" suffix
+            MarketType::CoinM => !code.ends_with("_PERP") || symbol["contractStatus"] != "TRADING",
+            // Binance adds "_<NUMBERS>" to old symbol's code, margin accounts trade spot symbols
+            MarketType::UsdM
+            | MarketType::Spot
+            | MarketType::CrossMargin
+            | MarketType::IsolatedMargin => code.contains('_') || symbol["status"] != "TRADING",
+        }
     }
 
     pub(super) fn get_event_time(data: &Value) -> Result<DateTime> {
@@ -1118,7 +1577,7 @@ impl Binance {
 
     #[named]
     pub(super) async fn request_get_server_time(&self) -> Result<RestResponse, ExchangeError> {
-        let path = self.get_uri_path("/fapi/v1/time", "/api/v3/time");
+        let path = self.get_uri_path("/fapi/v1/time", "/dapi/v1/time", "/api/v3/time");
         let builder = UriBuilder::from_path(path);
         let uri = builder.build_uri(self.hosts.rest_uri_host(), false);
 
@@ -1147,6 +1606,16 @@ pub(super) fn get_server_order_side(side: OrderSide) -> &'static str {
     }
 }
 
+/// Taker order is expired by exchange if policy prevents creation of new order, otherwise maker order is expired
+fn get_self_trade_prevention_mode(policy: SelfTradePreventionPolicy) -> &'static str {
+    match policy {
+        SelfTradePreventionPolicy::SkipNew | SelfTradePreventionPolicy::RepriceNew => {
+            "EXPIRE_TAKER"
+        }
+        SelfTradePreventionPolicy::CancelResting => "EXPIRE_MAKER",
+    }
+}
+
 pub(super) fn get_local_order_side(side: &str) -> OrderSide {
     match side {
         "BUY" => OrderSide::Buy,
@@ -1155,7 +1624,7 @@ pub(super) fn get_local_order_side(side: &str) -> OrderSide {
     }
 }
 
-fn get_local_order_status(status: &str) -> OrderStatus {
+pub(crate) fn get_local_order_status(status: &str) -> OrderStatus {
     match status {
         "NEW" | "PARTIALLY_FILLED" => OrderStatus::Created,
         "FILLED" => OrderStatus::Completed,
@@ -1177,6 +1646,10 @@ impl ExchangeClientBuilder for BinanceBuilder {
         _orders: Arc<OrdersPool>,
     ) -> ExchangeClientBuilderResult {
         let exchange_account_id = exchange_settings.exchange_account_id;
+        // Countdown cancel all is supported only for futures
+        let supports_cancel_all_after = exchange_settings.is_margin_trading;
+        // OCO orders are supported only for spot
+        let supports_oco_order = !exchange_settings.is_margin_trading;
 
         ExchangeClientBuilderResult {
             client: Box::new(Binance::new(
@@ -1192,6 +1665,8 @@ impl ExchangeClientBuilder for BinanceBuilder {
                 RestFillsFeatures::new(RestFillsType::None),
                 OrderFeatures {
                     supports_get_order_info_by_client_order_id: true,
+                    supports_cancel_all_after,
+                    supports_oco_order,
                     ..OrderFeatures::default()
                 },
                 OrderTradeOption::default(),
@@ -1279,4 +1754,183 @@ mod tests {
 
         assert_eq!(signature_value, expected);
     }
+
+    #[test]
+    fn parse_coin_m_symbols() {
+        let exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+
+        let mut settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), true);
+        settings.market_type = Some(MarketType::CoinM);
+
+        let (tx, _) = broadcast::channel(10);
+        let binance = Binance::new(
+            exchange_account_id,
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+            get_timeout_manager(exchange_account_id),
+            false,
+        );
+        assert_eq!(binance.hosts.rest_host, "https://dapi.binance.com");
+
+        let content = r#"{
+            "symbols": [
+                {
+                    "symbol": "BTCUSD_PERP",
+                    "pair": "BTCUSD",
+                    "contractType": "PERPETUAL",
+                    "contractStatus": "TRADING",
+                    "contractSize": 100,
+                    "baseAsset": "BTC",
+                    "quoteAsset": "USD",
+                    "marginAsset": "BTC",
+                    "filters": [
+                        {"filterType": "PRICE_FILTER", "minPrice": "1000", "maxPrice": "4520958", "tickSize": "0.1"},
+                        {"filterType": "LOT_SIZE", "minQty": "1", "maxQty": "1000000", "stepSize": "1"}
+                    ]
+                },
+                {
+                    "symbol": "BTCUSD_231229",
+                    "pair": "BTCUSD",
+                    "contractType": "CURRENT_QUARTER",
+                    "contractStatus": "TRADING",
+                    "contractSize": 100,
+                    "baseAsset": "BTC",
+                    "quoteAsset": "USD",
+                    "marginAsset": "BTC",
+                    "filters": []
+                }
+            ]
+        }"#;
+        let response = RestResponse {
+            status: hyper::StatusCode::OK,
+            content: content.to_owned(),
+        };
+
+        let symbols = binance
+            .parse_all_symbols(&response)
+            .expect("Failed to parse symbols");
+
+        // delivery contracts are skipped
+        assert_eq!(symbols.len(), 1);
+        let symbol = &symbols[0];
+        assert!(symbol.is_derivative);
+        assert_eq!(symbol.contract_type, ContractType::Inverse);
+        assert_eq!(symbol.amount_multiplier, Decimal::from(100));
+        assert_eq!(symbol.amount_currency_code, "USD".into());
+        assert_eq!(symbol.balance_currency_code, Some("BTC".into()));
+        assert_eq!(
+            binance
+                .get_unified_currency_pair(&"BTCUSD_PERP".into())
+                .ok(),
+            Some(CurrencyPair::from_codes("BTC".into(), "USD".into()))
+        );
+    }
+
+    #[test]
+    fn parse_oco_order_ids() {
+        let exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+        let settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
+
+        let (tx, _) = broadcast::channel(10);
+        let binance = Binance::new(
+            exchange_account_id,
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+            get_timeout_manager(exchange_account_id),
+            false,
+        );
+
+        // Binance returns stop loss leg first for sell OCO orders
+        let content = r#"{
+            "orderListId": 0,
+            "contingencyType": "OCO",
+            "listStatusType": "EXEC_STARTED",
+            "listOrderStatus": "EXECUTING",
+            "listClientOrderId": "JYVpp3F0f5CAG15DhtrqLp",
+            "transactionTime": 1563417480525,
+            "symbol": "LTCBTC",
+            "orders": [
+                {"symbol": "LTCBTC", "orderId": 2, "clientOrderId": "stop_loss_leg"},
+                {"symbol": "LTCBTC", "orderId": 3, "clientOrderId": "limit_leg"}
+            ]
+        }"#;
+        let response = RestResponse {
+            status: hyper::StatusCode::OK,
+            content: content.to_owned(),
+        };
+
+        let order_ids = binance
+            .get_oco_order_ids(&response, &"limit_leg".into(), &"stop_loss_leg".into())
+            .expect("Failed to parse OCO order ids");
+
+        assert_eq!(order_ids, ("3".into(), "2".into()));
+        assert!(binance
+            .get_oco_order_ids(&response, &"unknown_leg".into(), &"stop_loss_leg".into())
+            .is_err());
+    }
+
+    #[test]
+    fn parse_cross_margin_account() {
+        let exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+
+        let mut settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
+        settings.market_type = Some(MarketType::CrossMargin);
+
+        let (tx, _) = broadcast::channel(10);
+        let binance = Binance::new(
+            exchange_account_id,
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+            get_timeout_manager(exchange_account_id),
+            false,
+        );
+        assert_eq!(binance.hosts.rest_host, "https://api.binance.com");
+        for currency in ["BTC", "USDT"] {
+            binance
+                .supported_currencies
+                .insert(currency.into(), currency.into());
+        }
+
+        let content = r#"{
+            "borrowEnabled": true,
+            "marginLevel": "11.64405625",
+            "totalAssetOfBtc": "6.82728457",
+            "totalLiabilityOfBtc": "0.58633215",
+            "totalNetAssetOfBtc": "6.24095242",
+            "tradeEnabled": true,
+            "transferEnabled": true,
+            "userAssets": [
+                {"asset": "BTC", "borrowed": "0.50000000", "free": "0.00499500", "interest": "0.00001000", "locked": "0.10000000", "netAsset": "-0.39501500"},
+                {"asset": "USDT", "borrowed": "0.00000000", "free": "1500.00000000", "interest": "0.00000000", "locked": "0.00000000", "netAsset": "1500.00000000"},
+                {"asset": "BNB", "borrowed": "0.00000000", "free": "1.00000000", "interest": "0.00000000", "locked": "0.00000000", "netAsset": "1.00000000"}
+            ]
+        }"#;
+        let response = RestResponse {
+            status: hyper::StatusCode::OK,
+            content: content.to_owned(),
+        };
+        let max_borrowable = HashMap::from([("BTC".into(), Decimal::new(2, 1))]);
+
+        let margin_account = binance
+            .parse_margin_account(&response, &max_borrowable)
+            .expect("Failed to parse margin account");
+
+        assert_eq!(margin_account.margin_level, Decimal::new(1164405625, 8));
+        // unsupported currencies are skipped
+        assert_eq!(margin_account.balances.len(), 2);
+        let btc = &margin_account.balances[0];
+        assert_eq!(btc.currency_code, "BTC".into());
+        assert_eq!(btc.free, Decimal::new(4995, 6));
+        assert_eq!(btc.locked, Decimal::new(1, 1));
+        assert_eq!(btc.borrowed, Decimal::new(5, 1));
+        assert_eq!(btc.interest, Decimal::new(1, 5));
+        assert_eq!(btc.max_borrowable, Decimal::new(2, 1));
+        assert_eq!(margin_account.balances[1].max_borrowable, Decimal::ZERO);
+    }
 }
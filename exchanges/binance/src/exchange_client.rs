@@ -1,5 +1,8 @@
 use super::binance::Binance;
 use crate::support::BinanceOrderInfo;
+use crate::websocket_api::{
+    resolve_unconfirmed_cancel_order, resolve_unconfirmed_create_order, WebSocketApiOutcome,
+};
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use function_name::named;
@@ -7,29 +10,76 @@ use itertools::Itertools;
 use mmb_core::exchanges::general::exchange::RequestResult;
 use mmb_core::exchanges::general::order::cancel::CancelOrderResult;
 use mmb_core::exchanges::general::order::create::CreateOrderResult;
+use mmb_core::exchanges::general::order::create_oco::CreateOcoOrderResult;
 use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
 use mmb_core::exchanges::general::request_type::RequestType;
 use mmb_core::exchanges::rest_client::UriBuilder;
 use mmb_core::exchanges::traits::{ExchangeClient, ExchangeError, Support};
-use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions};
+use mmb_domain::events::{
+    EventSourceType, ExchangeBalance, ExchangeBalancesAndPositions, MetricsEventInfo,
+    MetricsEventType, MetricsTime,
+};
 use mmb_domain::exchanges::symbol::Symbol;
 use mmb_domain::market::CurrencyPair;
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::snapshot::Price;
 use mmb_domain::order::snapshot::*;
 use mmb_domain::position::{ActivePosition, ClosedPosition};
+use mmb_utils::time::get_current_milliseconds;
 use mmb_utils::DateTime;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[async_trait]
 impl ExchangeClient for Binance {
     async fn create_order(&self, order: &OrderRef) -> CreateOrderResult {
-        match self.request_create_order(order).await {
-            Ok(request_outcome) => match self.get_order_id(&request_outcome) {
-                Ok(order_id) => CreateOrderResult::succeed(&order_id, EventSourceType::Rest),
-                Err(error) => CreateOrderResult::failed(error, EventSourceType::Rest),
+        let start_time = get_current_milliseconds();
+        let (outcome, source_type) = match self.websocket_api_create_order(order).await {
+            Some(outcome) => (outcome, EventSourceType::WebSocket),
+            None => (
+                self.request_create_order(order)
+                    .await
+                    .map(|response| response.content),
+                EventSourceType::Rest,
+            ),
+        };
+        self.handle_request_metrics(
+            start_time,
+            source_type,
+            MetricsEventType::CreateOrderRequest(source_type),
+        );
+
+        match outcome {
+            Ok(content) => match self.get_order_id(&content) {
+                Ok(order_id) => CreateOrderResult::succeed(&order_id, source_type),
+                Err(error) => CreateOrderResult::failed(error, source_type),
             },
-            Err(err) => CreateOrderResult::failed(err, EventSourceType::Rest),
+            Err(err) => CreateOrderResult::failed(err, source_type),
+        }
+    }
+
+    async fn create_oco_order(
+        &self,
+        limit_order: &OrderRef,
+        stop_loss_order: &OrderRef,
+    ) -> CreateOcoOrderResult {
+        match self
+            .request_create_oco_order(limit_order, stop_loss_order)
+            .await
+        {
+            Ok(request_outcome) => match self.get_oco_order_ids(
+                &request_outcome,
+                &limit_order.client_order_id(),
+                &stop_loss_order.client_order_id(),
+            ) {
+                Ok((limit_order_id, stop_loss_order_id)) => CreateOcoOrderResult::succeed(
+                    &limit_order_id,
+                    &stop_loss_order_id,
+                    EventSourceType::Rest,
+                ),
+                Err(error) => CreateOcoOrderResult::failed(error, EventSourceType::Rest),
+            },
+            Err(err) => CreateOcoOrderResult::failed(err, EventSourceType::Rest),
         }
     }
 
@@ -38,11 +88,28 @@ impl ExchangeClient for Binance {
         order: &OrderRef,
         exchange_order_id: &ExchangeOrderId,
     ) -> CancelOrderResult {
-        match self.request_cancel_order(order, exchange_order_id).await {
-            Ok(_) => {
-                CancelOrderResult::succeed(order.client_order_id(), EventSourceType::Rest, None)
-            }
-            Err(err) => CancelOrderResult::failed(err, EventSourceType::Rest),
+        let start_time = get_current_milliseconds();
+        let (outcome, source_type) = match self
+            .websocket_api_cancel_order(order, exchange_order_id)
+            .await
+        {
+            Some(outcome) => (outcome.map(|_| ()), EventSourceType::WebSocket),
+            None => (
+                self.request_cancel_order(order, exchange_order_id)
+                    .await
+                    .map(|_| ()),
+                EventSourceType::Rest,
+            ),
+        };
+        self.handle_request_metrics(
+            start_time,
+            source_type,
+            MetricsEventType::CancelOrderRequest(source_type),
+        );
+
+        match outcome {
+            Ok(()) => CancelOrderResult::succeed(order.client_order_id(), source_type, None),
+            Err(err) => CancelOrderResult::failed(err, source_type),
         }
     }
 
@@ -50,8 +117,15 @@ impl ExchangeClient for Binance {
     async fn cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()> {
         let specific_currency_pair = self.get_specific_currency_pair(currency_pair);
 
-        let mut builder = UriBuilder::from_path("/api/v3/openOrders");
+        let path = self.get_account_uri_path(
+            "/fapi/v1/allOpenOrders",
+            "/dapi/v1/allOpenOrders",
+            "/api/v3/openOrders",
+            "/sapi/v1/margin/openOrders",
+        );
+        let mut builder = UriBuilder::from_path(path);
         builder.add_kv("symbol", specific_currency_pair);
+        self.add_isolated_margin_param(&mut builder);
         self.add_authentification(&mut builder);
 
         let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
@@ -63,6 +137,23 @@ impl ExchangeClient for Binance {
         Ok(())
     }
 
+    async fn cancel_all_orders_after(
+        &self,
+        currency_pairs: &[CurrencyPair],
+        timeout: Duration,
+    ) -> Result<()> {
+        // Binance countdown timer is armed separately for each symbol
+        for currency_pair in currency_pairs {
+            self.request_cancel_all_orders_after(*currency_pair, timeout)
+                .await
+                .with_context(|| {
+                    format!("Failed to arm countdown cancel all for {currency_pair}")
+                })?;
+        }
+
+        Ok(())
+    }
+
     async fn get_open_orders(&self) -> Result<Vec<OrderInfo>> {
         let response = self.request_open_orders().await?;
 
@@ -122,6 +213,23 @@ impl ExchangeClient for Binance {
                             .map(|position| Ok::<_, anyhow::Error>(position?.derivative))
                             .try_collect()?,
                     ),
+                    margin_account: None,
+                }
+            }
+            false if self.is_margin_account() => {
+                let margin_account = self.get_margin_account().await?;
+                ExchangeBalancesAndPositions {
+                    // Assets are borrowed automatically, so borrowable amounts are available for orders too
+                    balances: margin_account
+                        .balances
+                        .iter()
+                        .map(|balance| ExchangeBalance {
+                            currency_code: balance.currency_code,
+                            balance: balance.available_for_orders(),
+                        })
+                        .collect(),
+                    positions: None,
+                    margin_account: Some(margin_account),
                 }
             }
             false => {
@@ -129,6 +237,7 @@ impl ExchangeClient for Binance {
                 ExchangeBalancesAndPositions {
                     balances: self.parse_spot_balance(&balance_response)?,
                     positions: None,
+                    margin_account: None,
                 }
             }
         })
@@ -163,6 +272,64 @@ impl ExchangeClient for Binance {
 }
 
 impl Binance {
+    /// `None` is returned if order should be created via REST
+    async fn websocket_api_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Option<Result<String, ExchangeError>> {
+        let params = match self.create_order_params(order) {
+            Ok(params) => params,
+            Err(err) => return Some(Err(err)),
+        };
+
+        match self
+            .send_websocket_api_request("order.place", RequestType::CreateOrder, params)
+            .await
+        {
+            WebSocketApiOutcome::NotSent => None,
+            WebSocketApiOutcome::Response(outcome) => Some(outcome),
+            WebSocketApiOutcome::NoResponse(error) => {
+                let order_info = self.request_unconfirmed_order_info(order).await;
+                resolve_unconfirmed_create_order(order_info, error)
+            }
+        }
+    }
+
+    /// `None` is returned if order should be cancelled via REST
+    async fn websocket_api_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Option<Result<String, ExchangeError>> {
+        let params = self.cancel_order_params(order, exchange_order_id);
+
+        match self
+            .send_websocket_api_request("order.cancel", RequestType::CancelOrder, params)
+            .await
+        {
+            WebSocketApiOutcome::NotSent => None,
+            WebSocketApiOutcome::Response(outcome) => Some(outcome),
+            WebSocketApiOutcome::NoResponse(error) => {
+                let order_info = self.request_unconfirmed_order_info(order).await;
+                resolve_unconfirmed_cancel_order(order_info, error)
+            }
+        }
+    }
+
+    fn handle_request_metrics(
+        &self,
+        start_time: MetricsTime,
+        source_type: EventSourceType,
+        event_type: MetricsEventType,
+    ) {
+        (self.handle_metrics_callback)(MetricsEventInfo::new(
+            start_time,
+            get_current_milliseconds(),
+            source_type,
+            event_type,
+        ));
+    }
+
     #[named]
     async fn get_listen_key(&self) -> Result<String> {
         let request_outcome = self
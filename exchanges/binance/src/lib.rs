@@ -20,3 +20,4 @@ pub mod binance;
 pub mod exchange_client;
 
 mod support;
+mod websocket_api;
@@ -9,20 +9,22 @@ use itertools::Itertools;
 use mmb_domain::order::snapshot::{Amount, Price};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::sync::Arc;
 use std::time::Duration;
 use url::Url;
 
 use super::binance::Binance;
+use super::websocket_api::{run_websocket_api_session, websocket_api_url};
 use mmb_core::connectivity::WebSocketRole;
 use mmb_core::exchanges::common::send_event;
 use mmb_core::exchanges::general::exchange::Exchange;
+use mmb_core::exchanges::general::market_data_connections::MarketDataShardChanges;
 use mmb_core::exchanges::traits::{HandleMetricsCb, Support};
 use mmb_core::exchanges::traits::{
     HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
 };
-use mmb_core::infrastructure::spawn_by_timer;
+use mmb_core::infrastructure::{spawn_by_timer, spawn_future};
 use mmb_core::settings::ExchangeSettings;
 use mmb_domain::events::{
     EventSourceType, ExchangeEvent, MetricsEventInfo, MetricsEventType, Trade, TradeId,
@@ -70,6 +72,40 @@ pub(super) struct BinanceSpotBalances<'a> {
     pub(super) free: Decimal,
 }
 
+/// Corresponds https://binance-docs.github.io/apidocs/spot/en/#query-cross-margin-account-details-user_data
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[serde(bound(deserialize = "'de: 'a"))]
+pub(crate) struct BinanceCrossMarginAccountInfo<'a> {
+    pub(crate) margin_level: Decimal,
+    pub(crate) user_assets: Vec<BinanceMarginBalance<'a>>,
+}
+
+/// Corresponds https://binance-docs.github.io/apidocs/spot/en/#query-isolated-margin-account-info-user_data
+#[derive(Deserialize, Debug)]
+#[serde(bound(deserialize = "'de: 'a"))]
+pub(crate) struct BinanceIsolatedMarginAccountInfo<'a> {
+    pub(crate) assets: Vec<BinanceIsolatedMarginPair<'a>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[serde(bound(deserialize = "'de: 'a"))]
+pub(crate) struct BinanceIsolatedMarginPair<'a> {
+    pub(crate) base_asset: BinanceMarginBalance<'a>,
+    pub(crate) quote_asset: BinanceMarginBalance<'a>,
+    pub(crate) margin_level: Decimal,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct BinanceMarginBalance<'a> {
+    pub(super) asset: &'a str,
+    pub(super) free: Decimal,
+    pub(super) locked: Decimal,
+    pub(super) borrowed: Decimal,
+    pub(super) interest: Decimal,
+}
+
 /// Corresponds https://binance-docs.github.io/apidocs/futures/en/#account-information-v2-user_data
 /// asset: string,                      // asset name
 /// wallet_balance: Decimal,            // wallet balance
@@ -115,6 +151,10 @@ impl Support for Binance {
         self.initialize_working_currencies(&exchange);
 
         start_updating_listen_key(&exchange);
+
+        if self.settings.websocket_order_entry.is_some() {
+            self.start_websocket_api_session(&exchange);
+        }
     }
 
     fn on_websocket_message(&self, msg: &str) -> Result<()> {
@@ -151,6 +191,15 @@ impl Support for Binance {
             return Ok(());
         }
 
+        // Response to subscription request of market data connection
+        if data.get("e").is_none() && data.get("id").is_some() {
+            if let Some(error) = data.get("error") {
+                log::error!("Market data subscription failed for {}: {error}", self.id);
+            }
+
+            return Ok(());
+        }
+
         // so it is userData stream
         let event_type = data["e"]
             .as_str()
@@ -192,7 +241,9 @@ impl Support for Binance {
         Ok(())
     }
 
-    fn set_send_websocket_message_callback(&mut self, _callback: SendWebsocketMessageCb) {}
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
 
     fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
         self.order_created_callback = callback;
@@ -215,28 +266,55 @@ impl Support for Binance {
     }
 
     fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        let changes = self
+            .market_data_shards
+            .lock()
+            .set_currency_pairs(&currencies);
         *self.traded_specific_currencies.lock() = currencies;
+
+        self.update_market_data_subscriptions(changes);
     }
 
     fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        let has_credentials =
+            !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty();
+
         match role {
-            WebSocketRole::Main => true,
-            WebSocketRole::Secondary => {
-                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
-            }
+            WebSocketRole::Main => !self.is_market_data_sharded() || has_credentials,
+            WebSocketRole::Secondary => !self.is_market_data_sharded() && has_credentials,
+            WebSocketRole::MarketData(index) => index < self.market_data_shards.lock().count(),
         }
     }
 
     async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
         let (host, path) = match role {
-            WebSocketRole::Main => (
-                &self.hosts.web_socket_host,
-                self.build_ws_main_path(&self.settings.websocket_channels[..]),
+            // User data stream is the only stream of main websocket if market data is received via separate connections
+            WebSocketRole::Main if self.is_market_data_sharded() => (
+                &self.hosts.web_socket2_host,
+                self.build_ws_secondary_path().await?,
             ),
+            WebSocketRole::Main => {
+                let currency_pairs = self.traded_specific_currencies.lock().clone();
+                (
+                    &self.hosts.web_socket_host,
+                    self.build_ws_main_path(&currency_pairs),
+                )
+            }
             WebSocketRole::Secondary => (
                 &self.hosts.web_socket2_host,
                 self.build_ws_secondary_path().await?,
             ),
+            WebSocketRole::MarketData(index) => {
+                let currency_pairs = self
+                    .market_data_shards
+                    .lock()
+                    .currency_pairs(index)
+                    .to_vec();
+                (
+                    &self.hosts.web_socket_host,
+                    self.build_ws_main_path(&currency_pairs),
+                )
+            }
         };
 
         Url::parse(&format!("{host}{path}"))
@@ -318,6 +396,7 @@ impl Binance {
                 quantity,
                 side: order_side,
                 transaction_time: Utc.timestamp_millis(datetime),
+                tick_direction: None,
             },
         );
 
@@ -409,22 +488,59 @@ impl Binance {
         self.get_unified_currency_pair(&specific_currency_pair)
     }
 
-    fn build_ws_main_path(&self, websocket_channels: &[String]) -> String {
-        let stream_names = self
-            .traded_specific_currencies
-            .lock()
+    fn get_stream_names(&self, currency_pairs: &[SpecificCurrencyPair]) -> Vec<String> {
+        currency_pairs
             .iter()
             .flat_map(|currency_pair| {
-                let mut results = Vec::new();
-                for channel in websocket_channels {
-                    let result = Self::get_stream_name(currency_pair, channel);
-                    results.push(result);
-                }
-                results
+                self.settings.websocket_channels.iter().map(move |channel| {
+                    Self::get_stream_name(currency_pair, channel).to_lowercase()
+                })
             })
-            .join("/");
-        let ws_path = format!("/stream?streams={stream_names}");
-        ws_path.to_lowercase()
+            .collect_vec()
+    }
+
+    fn build_ws_main_path(&self, currency_pairs: &[SpecificCurrencyPair]) -> String {
+        if currency_pairs.is_empty() {
+            // Streams can be subscribed later if market data connection has no currency pairs yet
+            return "/stream".to_owned();
+        }
+
+        let stream_names = self.get_stream_names(currency_pairs).join("/");
+        format!("/stream?streams={stream_names}")
+    }
+
+    fn is_market_data_sharded(&self) -> bool {
+        self.market_data_shards.lock().count() > 0
+    }
+
+    /// Subscriptions of established market data connections are changed at runtime,
+    /// other connections get actual streams in url on connection
+    fn update_market_data_subscriptions(&self, changes: Vec<MarketDataShardChanges>) {
+        for shard_changes in changes {
+            let role = WebSocketRole::MarketData(shard_changes.shard);
+            let requests = [
+                ("UNSUBSCRIBE", &shard_changes.removed),
+                ("SUBSCRIBE", &shard_changes.added),
+            ];
+            for (method, currency_pairs) in requests {
+                if currency_pairs.is_empty() {
+                    continue;
+                }
+
+                // Responses are only checked for errors, so index of connection is enough as request id
+                let request = json!({
+                    "method": method,
+                    "params": self.get_stream_names(currency_pairs),
+                    "id": shard_changes.shard,
+                });
+                if let Err(err) = (self.websocket_message_callback)(role, request.to_string()) {
+                    log::debug!(
+                        "{method} of {currency_pairs:?} is postponed until {role} websocket of {} is connected: {err}",
+                        self.id
+                    );
+                }
+            }
+        }
     }
 
     async fn build_ws_secondary_path(&self) -> Result<String> {
@@ -436,6 +552,28 @@ impl Binance {
 
         Ok(ws_path)
     }
+
+    fn start_websocket_api_session(&self, exchange: &Arc<Exchange>) {
+        let Some(url) = websocket_api_url(self.market_type) else {
+            log::warn!(
+                "Binance WebSocket API isn't available for {:?}, orders of {} are sent via REST",
+                self.market_type,
+                self.id
+            );
+            return;
+        };
+        let url = Url::parse(url).expect("Invalid Binance WebSocket API url");
+
+        let _ = spawn_future(
+            "Binance WebSocket API session",
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            run_websocket_api_session(
+                Arc::downgrade(exchange),
+                url,
+                self.lifetime_manager.stop_token(),
+            ),
+        );
+    }
 }
 
 fn start_updating_listen_key(exchange: &Arc<Exchange>) {
@@ -0,0 +1,654 @@
+use crate::binance::{get_local_order_status, Binance, ErrorHandlerBinance};
+use anyhow::Result;
+use itertools::Itertools;
+use mmb_core::connectivity::{websocket_open, WebSocketParams, WsSender};
+use mmb_core::exchanges::general::exchange::Exchange;
+use mmb_core::exchanges::general::request_type::RequestType;
+use mmb_core::exchanges::rest_client::{ErrorHandler, UriBuilder};
+use mmb_core::exchanges::traits::ExchangeError;
+use mmb_core::settings::MarketType;
+use mmb_domain::market::ExchangeErrorType;
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::OrderStatus;
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::time::get_current_milliseconds;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Weak;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use url::Url;
+
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Binance WebSocket API is available only for spot and futures accounts, margin orders are sent via REST
+pub(crate) fn websocket_api_url(market_type: MarketType) -> Option<&'static str> {
+    match market_type {
+        MarketType::Spot => Some("wss://ws-api.binance.com:443/ws-api/v3"),
+        MarketType::UsdM => Some("wss://ws-fapi.binance.com/ws-fapi/v1"),
+        MarketType::CoinM | MarketType::CrossMargin | MarketType::IsolatedMargin => None,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct WebSocketApiResponse {
+    /// Is null if request can't be parsed by exchange
+    id: Option<u64>,
+    status: u16,
+    result: Option<Value>,
+    error: Option<WebSocketApiError>,
+    #[serde(rename = "rateLimits", default)]
+    rate_limits: Vec<RateLimit>,
+}
+
+/// Usage of exchange limit after request was handled
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RateLimit {
+    rate_limit_type: String,
+    interval: String,
+    interval_num: u64,
+    limit: u64,
+    count: u64,
+}
+
+impl RateLimit {
+    fn interval_duration(&self) -> Option<Duration> {
+        let interval_secs = match self.interval.as_str() {
+            "SECOND" => 1,
+            "MINUTE" => 60,
+            "HOUR" => 60 * 60,
+            "DAY" => 24 * 60 * 60,
+            _ => return None,
+        };
+
+        Some(Duration::from_secs(interval_secs * self.interval_num))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct WebSocketApiError {
+    code: i64,
+    msg: String,
+}
+
+/// Outcome of request sent via WebSocket API
+pub(crate) enum WebSocketApiOutcome {
+    /// Session isn't established, so request wasn't sent and should be sent via REST
+    NotSent,
+    Response(Result<String, ExchangeError>),
+    /// Request was sent, but it's unknown whether exchange received it
+    NoResponse(ExchangeError),
+}
+
+/// Order entry session over Binance WebSocket API. Responses are matched with requests by id
+#[derive(Default)]
+pub(crate) struct WebSocketApiSession {
+    /// None when session isn't established
+    sender: Mutex<Option<WsSender>>,
+    pending_requests: Mutex<HashMap<u64, oneshot::Sender<WebSocketApiResponse>>>,
+    last_request_id: AtomicU64,
+}
+
+impl WebSocketApiSession {
+    fn on_connected(&self, sender: WsSender) {
+        *self.sender.lock() = Some(sender);
+    }
+
+    fn on_disconnected(&self) {
+        self.sender.lock().take();
+        // Waiting requests are failed because it's unknown whether they reached exchange
+        self.pending_requests.lock().clear();
+    }
+
+    /// Returns `None` if session isn't established, so request wasn't sent
+    fn send(
+        &self,
+        method: &str,
+        params: Map<String, Value>,
+    ) -> Option<(u64, oneshot::Receiver<WebSocketApiResponse>)> {
+        let sender = self.sender.lock();
+        let sender = sender.as_ref()?;
+
+        let id = self.last_request_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let (tx, rx) = oneshot::channel();
+        let _ = self.pending_requests.lock().insert(id, tx);
+
+        let request = json!({ "id": id, "method": method, "params": params });
+        match sender.send_main(request.to_string()) {
+            Ok(()) => Some((id, rx)),
+            Err(err) => {
+                log::warn!("Unable to send Binance WebSocket API request {method}: {err}");
+                let _ = self.pending_requests.lock().remove(&id);
+                None
+            }
+        }
+    }
+
+    fn on_message(&self, msg: &str) -> Result<()> {
+        let response: WebSocketApiResponse = serde_json::from_str(msg)?;
+        let Some(id) = response.id else {
+            log::error!("Binance WebSocket API request wasn't recognized: {msg}");
+            return Ok(());
+        };
+
+        match self.pending_requests.lock().remove(&id) {
+            Some(tx) => {
+                let _ = tx.send(response);
+            }
+            None => log::warn!("Binance WebSocket API response to unknown request {id}: {msg}"),
+        }
+
+        Ok(())
+    }
+}
+
+impl Binance {
+    /// Send signed request via WebSocket API and wait for result content
+    pub(super) async fn send_websocket_api_request(
+        &self,
+        method: &str,
+        request_type: RequestType,
+        mut params: UriBuilder,
+    ) -> WebSocketApiOutcome {
+        let Some(websocket_order_entry) = &self.settings.websocket_order_entry else {
+            return WebSocketApiOutcome::NotSent;
+        };
+        let response_timeout = Duration::from_secs(websocket_order_entry.response_timeout_secs);
+
+        let params = self.sign_websocket_api_params(params.query(), get_current_milliseconds());
+        let Some((id, receiver)) = self.websocket_api.send(method, params) else {
+            return WebSocketApiOutcome::NotSent;
+        };
+
+        let response = match tokio::time::timeout(response_timeout, receiver).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(_)) => {
+                return WebSocketApiOutcome::NoResponse(ExchangeError::new(
+                    ExchangeErrorType::SendError,
+                    format!("Binance WebSocket API session was closed before response to {method}"),
+                    None,
+                ))
+            }
+            Err(_) => {
+                let _ = self.websocket_api.pending_requests.lock().remove(&id);
+                return WebSocketApiOutcome::NoResponse(ExchangeError::new(
+                    ExchangeErrorType::SendError,
+                    format!(
+                        "Binance WebSocket API didn't respond to {method} in {response_timeout:?}"
+                    ),
+                    None,
+                ));
+            }
+        };
+
+        self.sync_websocket_api_rate_limits(request_type, &response.rate_limits);
+
+        WebSocketApiOutcome::Response(parse_websocket_api_response(response))
+    }
+
+    /// Requests made via WebSocket API are counted by exchange, so local timeout manager
+    /// is synchronized with used request weight that is reported in each response
+    fn sync_websocket_api_rate_limits(&self, request_type: RequestType, rate_limits: &[RateLimit]) {
+        let used_requests_count = get_used_requests_count(
+            rate_limits,
+            self.timeout_manager.get_requests_per_period(self.id),
+            self.timeout_manager.get_period_duration(self.id),
+        );
+
+        if let Some(used_requests_count) = used_requests_count {
+            self.timeout_manager.sync_used_requests_count(
+                self.id,
+                request_type,
+                used_requests_count,
+            );
+        }
+    }
+
+    /// Order status on exchange by client order id for request that got no response via WebSocket API
+    pub(super) async fn request_unconfirmed_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<String, ExchangeError> {
+        self.timeout_manager
+            .reserve_when_available(
+                self.id,
+                RequestType::GetOrderInfo,
+                None,
+                self.lifetime_manager.stop_token(),
+            )
+            .await;
+
+        self.request_order_info(order)
+            .await
+            .map(|response| response.content)
+    }
+
+    /// Unlike REST, all parameters including api key are signed in alphabetical order
+    fn sign_websocket_api_params(&self, query: &[u8], timestamp: i64) -> Map<String, Value> {
+        let query = String::from_utf8_lossy(query);
+        let mut params = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| match pair.split_once('=') {
+                Some((key, value)) => (key.to_owned(), value.to_owned()),
+                None => (pair.to_owned(), String::new()),
+            })
+            .collect_vec();
+        params.push(("apiKey".to_owned(), self.settings.api_key.clone()));
+        params.push(("timestamp".to_owned(), timestamp.to_string()));
+        params.sort();
+
+        let payload = params
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .join("&");
+        let signature = format!("{:x}", self.calculate_hmac(payload.as_bytes()));
+        params.push(("signature".to_owned(), signature));
+
+        params
+            .into_iter()
+            .map(|(key, value)| (key, Value::String(value)))
+            .collect()
+    }
+}
+
+fn parse_websocket_api_response(response: WebSocketApiResponse) -> Result<String, ExchangeError> {
+    if let Some(error) = response.error {
+        let mut error = ExchangeError::new(ExchangeErrorType::Unknown, error.msg, Some(error.code));
+        error.error_type = match response.status {
+            // 418 means IP is banned after ignoring of 429 responses
+            429 | 418 => ExchangeErrorType::RateLimit,
+            _ => ErrorHandlerBinance.clarify_error_type(&error),
+        };
+        return Err(error);
+    }
+
+    response
+        .result
+        .map(|result| result.to_string())
+        .ok_or_else(|| {
+            ExchangeError::parsing(format!(
+                "Binance WebSocket API response with status {} doesn't contain result",
+                response.status
+            ))
+        })
+}
+
+/// Request weight used on exchange in terms of local timeout manager that allows
+/// `requests_per_period` requests for `period`. Only limits with the same period are comparable
+fn get_used_requests_count(
+    rate_limits: &[RateLimit],
+    requests_per_period: usize,
+    period: Duration,
+) -> Option<usize> {
+    rate_limits
+        .iter()
+        .filter(|x| {
+            x.rate_limit_type == "REQUEST_WEIGHT"
+                && x.limit > 0
+                && x.interval_duration() == Some(period)
+        })
+        .map(|x| {
+            let used = x.count as u128 * requests_per_period as u128;
+            let limit = x.limit as u128;
+            ((used + limit - 1) / limit) as usize
+        })
+        .max()
+}
+
+/// Order creation without response is resolved by order status on exchange: order is created if
+/// exchange knows it, otherwise it's safe to create it via REST (`None` is returned) because
+/// Binance rejects request with client order id of existing order
+pub(crate) fn resolve_unconfirmed_create_order(
+    order_info: Result<String, ExchangeError>,
+    no_response_error: ExchangeError,
+) -> Option<Result<String, ExchangeError>> {
+    match order_info {
+        Ok(content) => Some(Ok(content)),
+        Err(error) if error.error_type == ExchangeErrorType::OrderNotFound => None,
+        Err(error) => {
+            log::warn!(
+                "Unable to get status of order created via Binance WebSocket API: {error:?}"
+            );
+            Some(Err(no_response_error))
+        }
+    }
+}
+
+/// Order cancellation without response is resolved by order status on exchange: order is cancelled
+/// if exchange reports it, otherwise cancellation should be repeated via REST (`None` is returned)
+pub(crate) fn resolve_unconfirmed_cancel_order(
+    order_info: Result<String, ExchangeError>,
+    no_response_error: ExchangeError,
+) -> Option<Result<String, ExchangeError>> {
+    match order_info {
+        Ok(content) => match parse_order_status(&content) {
+            Ok(OrderStatus::Canceled) => Some(Ok(content)),
+            Ok(_) => None,
+            Err(error) => Some(Err(error)),
+        },
+        Err(error) if error.error_type == ExchangeErrorType::OrderNotFound => Some(Err(error)),
+        Err(error) => {
+            log::warn!(
+                "Unable to get status of order cancelled via Binance WebSocket API: {error:?}"
+            );
+            Some(Err(no_response_error))
+        }
+    }
+}
+
+fn parse_order_status(content: &str) -> Result<OrderStatus, ExchangeError> {
+    #[derive(Deserialize)]
+    struct BinanceOrderStatus {
+        status: String,
+    }
+
+    let order: BinanceOrderStatus = serde_json::from_str(content)
+        .map_err(|err| ExchangeError::parsing(format!("Unable to parse order status: {err:?}")))?;
+
+    Ok(get_local_order_status(&order.status))
+}
+
+/// Keep WebSocket API session and restore it after disconnection until application is stopped
+pub(crate) async fn run_websocket_api_session(
+    exchange: Weak<Exchange>,
+    url: Url,
+    stop_token: CancellationToken,
+) -> Result<()> {
+    while !stop_token.is_cancellation_requested() {
+        // Connection attempts are counted in request weight limit by Binance
+        let Some((exchange_account_id, reservation)) = with_binance(&exchange, |binance| {
+            (
+                binance.id,
+                binance.timeout_manager.reserve_when_available(
+                    binance.id,
+                    RequestType::ConnectWebSocketApi,
+                    None,
+                    stop_token.clone(),
+                ),
+            )
+        }) else {
+            return Ok(());
+        };
+        let _ = reservation.await;
+
+        match websocket_open(exchange_account_id, WebSocketParams::new(url.clone()), None).await {
+            Ok((sender, mut receiver)) => {
+                log::info!("Binance WebSocket API session of {exchange_account_id} is established");
+                if with_binance(&exchange, |binance| {
+                    binance.websocket_api.on_connected(sender)
+                })
+                .is_none()
+                {
+                    return Ok(());
+                }
+
+                loop {
+                    let msg = tokio::select! {
+                        msg = receiver.recv() => msg,
+                        _ = stop_token.when_cancelled() => return Ok(()),
+                    };
+                    let Some(msg) = msg else {
+                        break;
+                    };
+
+                    let handled =
+                        with_binance(&exchange, |binance| binance.websocket_api.on_message(&msg));
+                    match handled {
+                        Some(Ok(())) => (),
+                        Some(Err(err)) => log::error!(
+                            "Unable to handle Binance WebSocket API message {msg}: {err:?}"
+                        ),
+                        None => return Ok(()),
+                    }
+                }
+
+                if with_binance(&exchange, |binance| binance.websocket_api.on_disconnected())
+                    .is_none()
+                {
+                    return Ok(());
+                }
+                log::warn!("Binance WebSocket API session of {exchange_account_id} is closed, orders are sent via REST until reconnection");
+            }
+            Err(err) => log::warn!(
+                "Unable to connect to Binance WebSocket API for {exchange_account_id}: {err}"
+            ),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(RECONNECT_INTERVAL) => (),
+            _ = stop_token.when_cancelled() => (),
+        }
+    }
+
+    Ok(())
+}
+
+fn with_binance<R>(exchange: &Weak<Exchange>, f: impl FnOnce(&Binance) -> R) -> Option<R> {
+    let exchange = exchange.upgrade()?;
+    let binance = exchange
+        .exchange_client
+        .as_any()
+        .downcast_ref::<Binance>()
+        .expect("Exchange client isn't Binance");
+
+    Some(f(binance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::{
+        RequestTimeoutArguments, RequestsTimeoutManagerFactory,
+    };
+    use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+    use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+    use mmb_core::settings::ExchangeSettings;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_utils::hashmap;
+    use tokio::sync::broadcast;
+
+    fn no_response_error() -> ExchangeError {
+        ExchangeError::new(
+            ExchangeErrorType::SendError,
+            "Binance WebSocket API didn't respond".to_owned(),
+            None,
+        )
+    }
+
+    fn order_not_found_error() -> ExchangeError {
+        ExchangeError::new(
+            ExchangeErrorType::OrderNotFound,
+            "Order does not exist.".to_owned(),
+            Some(-2013),
+        )
+    }
+
+    fn order_info(status: &str) -> String {
+        format!(
+            r#"{{"symbol":"BTCUSDT","orderId":12569099453,"clientOrderId":"4d96324ff9d44481926157ec08158a40","price":"23416.10","origQty":"0.00847000","executedQty":"0.00000000","status":"{status}","side":"SELL"}}"#
+        )
+    }
+
+    #[test]
+    fn sign_params_in_alphabetical_order() {
+        // All values gotten from Binance WebSocket API example
+        let exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+        let settings = ExchangeSettings::new_short(
+            exchange_account_id,
+            "vmPUZE6mv9SD5VNHk4HlWFsOr6aKE2zvsw0MuIgwCIPy6utIco14y7Ju91duEh8A".into(),
+            "NhqPtmdSJYdKjVHjA7PZj4Mge3R5YNiP1e3UZjInClVN65XAbvqqM6A7H5fATj0j".into(),
+            false,
+        );
+        let request_timeout_manager = RequestsTimeoutManagerFactory::from_requests_per_period(
+            RequestTimeoutArguments::from_requests_per_minute(1200),
+            exchange_account_id,
+        );
+        let (tx, _) = broadcast::channel(10);
+        let binance = Binance::new(
+            exchange_account_id,
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+            TimeoutManager::new(hashmap![exchange_account_id => request_timeout_manager]),
+            false,
+        );
+
+        let mut builder = UriBuilder::from_path("/test");
+        builder.add_kv("symbol", "BTCUSDT");
+        builder.add_kv("side", "SELL");
+        builder.add_kv("type", "LIMIT");
+        builder.add_kv("timeInForce", "GTC");
+        builder.add_kv("quantity", "0.01000000");
+        builder.add_kv("price", "52000.00");
+        builder.add_kv("newOrderRespType", "ACK");
+        builder.add_kv("recvWindow", "100");
+
+        let params = binance.sign_websocket_api_params(builder.query(), 1645423376532);
+
+        assert_eq!(params.len(), 11);
+        assert_eq!(
+            params["apiKey"],
+            "vmPUZE6mv9SD5VNHk4HlWFsOr6aKE2zvsw0MuIgwCIPy6utIco14y7Ju91duEh8A"
+        );
+        assert_eq!(params["timestamp"], "1645423376532");
+        assert_eq!(
+            params["signature"],
+            "cc15477742bd704c29492d96c7ead9414dfd8e0ec4a00f947bb5bb454ddbd08a"
+        );
+    }
+
+    #[test]
+    fn match_responses_with_requests_by_id() {
+        let session = WebSocketApiSession::default();
+        let (first_tx, mut first_rx) = oneshot::channel();
+        let (second_tx, mut second_rx) = oneshot::channel();
+        let _ = session.pending_requests.lock().insert(1, first_tx);
+        let _ = session.pending_requests.lock().insert(2, second_tx);
+
+        session
+            .on_message(r#"{"id":2,"status":200,"result":{"orderId":2},"rateLimits":[]}"#)
+            .expect("in test");
+        session
+            .on_message(
+                r#"{"id":1,"status":400,"error":{"code":-2013,"msg":"Order does not exist."}}"#,
+            )
+            .expect("in test");
+        // response to unknown request is ignored
+        session
+            .on_message(r#"{"id":3,"status":200,"result":{},"rateLimits":[]}"#)
+            .expect("in test");
+
+        let first_response = first_rx.try_recv().expect("in test");
+        assert_eq!(first_response.id, Some(1));
+        assert_eq!(first_response.error.expect("in test").code, -2013);
+
+        let second_response = second_rx.try_recv().expect("in test");
+        assert_eq!(second_response.id, Some(2));
+        assert_eq!(
+            second_response.result.expect("in test")["orderId"],
+            json!(2)
+        );
+
+        assert!(session.pending_requests.lock().is_empty());
+    }
+
+    #[test]
+    fn used_requests_count_by_rate_limits() {
+        let response: WebSocketApiResponse = serde_json::from_str(
+            r#"{"id":1,"status":200,"result":{},"rateLimits":[
+                {"rateLimitType":"ORDERS","interval":"SECOND","intervalNum":10,"limit":50,"count":1},
+                {"rateLimitType":"ORDERS","interval":"DAY","intervalNum":1,"limit":160000,"count":1},
+                {"rateLimitType":"REQUEST_WEIGHT","interval":"MINUTE","intervalNum":1,"limit":6000,"count":501}
+            ]}"#,
+        )
+        .expect("in test");
+
+        // weight is converted to local limit 1200 per minute and rounded up
+        assert_eq!(
+            get_used_requests_count(&response.rate_limits, 1200, Duration::from_secs(60)),
+            Some(101)
+        );
+        // limits of other periods can't be compared with local one
+        assert_eq!(
+            get_used_requests_count(&response.rate_limits, 1200, Duration::from_secs(1)),
+            None
+        );
+    }
+
+    #[test]
+    fn resolve_unconfirmed_create_order_by_order_status() {
+        let content = resolve_unconfirmed_create_order(Ok(order_info("NEW")), no_response_error())
+            .expect("in test")
+            .expect("in test");
+        assert!(content.contains(r#""orderId":12569099453"#));
+
+        // order didn't reach exchange, so it should be created via REST
+        assert!(resolve_unconfirmed_create_order(
+            Err(order_not_found_error()),
+            no_response_error()
+        )
+        .is_none());
+
+        let error = resolve_unconfirmed_create_order(
+            Err(ExchangeError::new(
+                ExchangeErrorType::ServiceUnavailable,
+                "Service unavailable".to_owned(),
+                None,
+            )),
+            no_response_error(),
+        )
+        .expect("in test")
+        .expect_err("in test");
+        assert_eq!(error.error_type, ExchangeErrorType::SendError);
+    }
+
+    #[test]
+    fn resolve_unconfirmed_cancel_order_by_order_status() {
+        assert!(
+            resolve_unconfirmed_cancel_order(Ok(order_info("CANCELED")), no_response_error())
+                .expect("in test")
+                .is_ok()
+        );
+
+        // order is still open, so it should be cancelled via REST
+        assert!(
+            resolve_unconfirmed_cancel_order(Ok(order_info("NEW")), no_response_error()).is_none()
+        );
+
+        let error =
+            resolve_unconfirmed_cancel_order(Err(order_not_found_error()), no_response_error())
+                .expect("in test")
+                .expect_err("in test");
+        assert_eq!(error.error_type, ExchangeErrorType::OrderNotFound);
+    }
+
+    #[test]
+    fn parse_rate_limit_error() {
+        let response: WebSocketApiResponse = serde_json::from_str(
+            r#"{"id":3,"status":429,"error":{"code":-1015,"msg":"Too many new orders; current limit is 50 orders per 10 SECOND."},"rateLimits":[]}"#,
+        )
+        .expect("in test");
+
+        let error = parse_websocket_api_response(response).expect_err("in test");
+
+        assert_eq!(error.error_type, ExchangeErrorType::RateLimit);
+        assert_eq!(error.code, Some(-1015));
+    }
+
+    #[test]
+    fn parse_order_place_result() {
+        let response: WebSocketApiResponse = serde_json::from_str(
+            r#"{"id":1,"status":200,"result":{"symbol":"BTCUSDT","orderId":12569099453,"clientOrderId":"4d96324ff9d44481926157ec08158a40"},"rateLimits":[]}"#,
+        )
+        .expect("in test");
+
+        let content = parse_websocket_api_response(response).expect("in test");
+
+        assert!(content.contains(r#""orderId":12569099453"#));
+    }
+}
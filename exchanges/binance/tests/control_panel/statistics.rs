@@ -70,7 +70,7 @@ async fn orders_cancelled() {
         let _ = exchange.cancel_all_orders(test_currency_pair).await;
         let (execution_price, min_price) = get_prices(
             get_specific_currency_pair_for_tests(&exchange, test_currency_pair),
-            &Binance::make_hosts(exchange_settings.is_margin_trading),
+            &Binance::make_hosts(Binance::get_market_type(&exchange_settings)),
             &exchange_settings,
             &symbol.price_precision,
         )
@@ -78,7 +78,7 @@ async fn orders_cancelled() {
 
         let amount = get_min_amount(
             get_specific_currency_pair_for_tests(&exchange, test_currency_pair),
-            &Binance::make_hosts(exchange_settings.is_margin_trading),
+            &Binance::make_hosts(Binance::get_market_type(&exchange_settings)),
             &exchange_settings,
             execution_price,
             &symbol,
@@ -1,3 +1,4 @@
+use crate::order_book::OrderBookIds;
 use crate::support::BitmexOrderFill;
 use crate::types::{
     BitmexBalanceInfo, BitmexOrderInfo, BitmexSymbol, BitmexSymbolType, BitmexWalletAsset,
@@ -30,7 +31,7 @@ use mmb_core::exchanges::traits::{
 use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
 use mmb_core::settings::ExchangeSettings;
 use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent};
-use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::exchanges::symbol::{ContractType, Precision, Symbol};
 use mmb_domain::market::{
     CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId, SpecificCurrencyPair,
 };
@@ -50,7 +51,7 @@ use sha2::Sha256;
 use std::collections::HashMap;
 use std::io::Write;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tinyvec::Array;
 use tokio::sync::broadcast;
 use urlencoding_macro::encode;
@@ -182,7 +183,7 @@ pub struct Bitmex {
     pub(crate) handle_trade_callback: HandleTradeCb,
     pub(super) handle_metrics_callback: HandleMetricsCb,
     pub(crate) websocket_message_callback: SendWebsocketMessageCb,
-    pub(super) order_book_ids: Mutex<HashMap<(SpecificCurrencyPair, u64), Price>>,
+    pub(super) order_books: Mutex<HashMap<SpecificCurrencyPair, OrderBookIds>>,
     currency_balance_rates: Mutex<HashMap<CurrencyCode, Decimal>>,
 }
 
@@ -215,7 +216,7 @@ impl Bitmex {
             handle_trade_callback: Box::new(|_, _| {}),
             handle_metrics_callback: Box::new(|_| {}),
             websocket_message_callback: Box::new(|_, _| Ok(())),
-            order_book_ids: Default::default(),
+            order_books: Default::default(),
             currency_balance_rates: Default::default(),
         }
     }
@@ -259,13 +260,22 @@ impl Bitmex {
                         .write()
                         .insert(specific_currency_pair, unified_currency_pair);
 
+                    let contract_type = match (symbol.is_inverse, symbol.is_quanto) {
+                        (true, _) => ContractType::Inverse,
+                        (false, true) => ContractType::Quanto,
+                        (false, false) => ContractType::Linear,
+                    };
+
+                    let settlement_currency = symbol.settlement_currency.map(CurrencyCode::from);
                     let (amount_currency_code, balance_currency_code) =
-                        match self.settings.is_margin_trading {
-                            true => (quote, Some(base)),
-                            false => (base, None),
+                        match (self.settings.is_margin_trading, contract_type) {
+                            (true, ContractType::Inverse) => (quote, Some(base)),
+                            (true, ContractType::Quanto) => (quote, settlement_currency),
+                            (true, ContractType::Linear) => (base, Some(quote)),
+                            (false, _) => (base, None),
                         };
 
-                    Arc::new(Symbol::new(
+                    let mut unified_symbol = Symbol::new(
                         self.settings.is_margin_trading,
                         symbol.base_id.into(),
                         base,
@@ -284,12 +294,41 @@ impl Bitmex {
                         Precision::ByTick {
                             tick: symbol.amount_tick,
                         },
-                    ))
+                    );
+
+                    if self.settings.is_margin_trading {
+                        unified_symbol.contract_type = contract_type;
+                        unified_symbol.amount_multiplier =
+                            self.get_amount_multiplier(symbol, settlement_currency);
+                    }
+
+                    Arc::new(unified_symbol)
                 })
             })
             .collect_vec())
     }
 
+    /// Bitmex specifies contract multiplier in minimal units of settlement currency (e.g. satoshis)
+    fn get_amount_multiplier(
+        &self,
+        symbol: &BitmexSymbol,
+        settlement_currency: Option<CurrencyCode>,
+    ) -> Decimal {
+        let currency_rates = self.currency_balance_rates.lock();
+        let balance_rate = settlement_currency.and_then(|x| currency_rates.get(&x));
+
+        match (symbol.multiplier, balance_rate) {
+            (Some(multiplier), Some(balance_rate)) => multiplier.abs() * balance_rate,
+            _ => {
+                log::warn!(
+                    "Unable to get amount multiplier for Bitmex symbol {}, multiplier 1 is used",
+                    symbol.id
+                );
+                dec!(1)
+            }
+        }
+    }
+
     fn filter_symbol<'a>(&self, symbol: &'a BitmexSymbol<'a>) -> Option<&'a BitmexSymbol<'a>> {
         let symbol_type = BitmexSymbolType::try_from(symbol.symbol_type).ok()?;
 
@@ -533,6 +572,22 @@ impl Bitmex {
             .await
     }
 
+    #[named]
+    pub(super) async fn request_cancel_all_orders_after(
+        &self,
+        timeout: Duration,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v1/order/cancelAllAfter");
+        builder.add_kv("timeout", timeout.as_millis());
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        let log_args = format!("Cancel all orders after {timeout:?}");
+
+        self.rest_client
+            .post(uri, None, function_name!(), log_args)
+            .await
+    }
+
     pub(super) fn create_signature(secret_key: &str, message: &str, expire_time: u64) -> [u8; 64] {
         let mut hmac = Hmac::<Sha256>::new_from_slice(secret_key.as_bytes())
             .expect("Unable to calculate hmac for Bitmex signature");
@@ -796,6 +851,8 @@ impl ExchangeClientBuilder for BitmexBuilder {
                     order_was_completed_error_for_cancellation: true,
                     supports_already_cancelled_order: true,
                     supports_stop_loss_order: true,
+                    supports_cancel_all_after: true,
+                    supports_oco_order: false,
                 },
                 OrderTradeOption {
                     supports_trade_time: true,
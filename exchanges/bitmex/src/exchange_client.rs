@@ -15,6 +15,7 @@ use mmb_domain::order::snapshot::{ExchangeOrderId, OrderInfo, Price};
 use mmb_domain::position::{ActivePosition, ClosedPosition};
 use mmb_utils::DateTime;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[async_trait]
 impl ExchangeClient for Bitmex {
@@ -48,6 +49,18 @@ impl ExchangeClient for Bitmex {
         }
     }
 
+    async fn cancel_all_orders_after(
+        &self,
+        _currency_pairs: &[CurrencyPair],
+        timeout: Duration,
+    ) -> Result<()> {
+        // Bitmex timer is common for all currency pairs
+        match self.request_cancel_all_orders_after(timeout).await {
+            Ok(_) => Ok(()),
+            Err(error) => bail!("Failed to arm cancel all orders after timer: {error:?}"),
+        }
+    }
+
     async fn get_open_orders(&self) -> Result<Vec<OrderInfo>> {
         let response = self.request_open_orders(None).await?;
 
@@ -102,6 +115,7 @@ impl ExchangeClient for Bitmex {
                             .map(|position| Ok::<_, anyhow::Error>(position?.derivative))
                             .try_collect()?,
                     ),
+                    margin_account: None,
                 }
             }
             false => {
@@ -109,6 +123,7 @@ impl ExchangeClient for Bitmex {
                 ExchangeBalancesAndPositions {
                     balances: self.parse_get_balance(&balance_response)?,
                     positions: None,
+                    margin_account: None,
                 }
             }
         })
@@ -133,12 +148,11 @@ impl ExchangeClient for Bitmex {
     }
 
     async fn build_all_symbols(&self) -> Result<Vec<Arc<Symbol>>> {
-        let response = self.request_all_symbols().await?;
-        let symbols = self.parse_all_symbols(&response)?;
-
+        // currency assets are needed for calculation of contracts multipliers
         self.update_currency_assets().await?;
 
-        Ok(symbols)
+        let response = self.request_all_symbols().await?;
+        self.parse_all_symbols(&response)
     }
 
     async fn get_server_time(&self) -> Option<Result<i64>> {
@@ -18,5 +18,6 @@
 
 pub mod bitmex;
 mod exchange_client;
+mod order_book;
 mod support;
 pub mod types;
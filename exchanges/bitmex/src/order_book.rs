@@ -0,0 +1,192 @@
+use crate::types::{BitmexOrderBookDelete, BitmexOrderBookInsert, BitmexOrderBookUpdate};
+use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// State of Bitmex L2 order book of a single symbol. Update and delete actions contain only ids of records,
+/// so prices are restored by ids received in partial and insert actions
+#[derive(Default)]
+pub(crate) enum OrderBookIds {
+    /// Subscription is requested, all deltas are skipped until partial action received
+    #[default]
+    WaitingForPartial,
+    /// Prices of order book records by their ids
+    Synced(HashMap<u64, Price>),
+}
+
+pub(crate) enum DeltaOutcome {
+    Applied(OrderBookData),
+    /// Delta was received before partial action
+    Skipped,
+    /// Delta references record id which is unknown, so order book should be resubscribed
+    UnknownId(u64),
+}
+
+impl OrderBookIds {
+    pub(crate) fn apply_partial(&mut self, records: &[BitmexOrderBookInsert]) -> OrderBookData {
+        let mut prices = HashMap::with_capacity(records.len());
+        let mut order_book_data = OrderBookData::default();
+        for record in records {
+            add_order_book_info(record.price, record.size, record.side, &mut order_book_data);
+            prices.insert(record.id, record.price);
+        }
+
+        *self = OrderBookIds::Synced(prices);
+        order_book_data
+    }
+
+    pub(crate) fn apply_insert(&mut self, records: &[BitmexOrderBookInsert]) -> DeltaOutcome {
+        let prices = match self {
+            OrderBookIds::WaitingForPartial => return DeltaOutcome::Skipped,
+            OrderBookIds::Synced(prices) => prices,
+        };
+
+        let mut order_book_data = OrderBookData::default();
+        for record in records {
+            add_order_book_info(record.price, record.size, record.side, &mut order_book_data);
+            prices.insert(record.id, record.price);
+        }
+
+        DeltaOutcome::Applied(order_book_data)
+    }
+
+    pub(crate) fn apply_update(&mut self, records: &[BitmexOrderBookUpdate]) -> DeltaOutcome {
+        let prices = match self {
+            OrderBookIds::WaitingForPartial => return DeltaOutcome::Skipped,
+            OrderBookIds::Synced(prices) => prices,
+        };
+
+        let mut order_book_data = OrderBookData::default();
+        for record in records {
+            match prices.get(&record.id) {
+                None => return DeltaOutcome::UnknownId(record.id),
+                Some(price) => {
+                    add_order_book_info(*price, record.size, record.side, &mut order_book_data)
+                }
+            }
+        }
+
+        DeltaOutcome::Applied(order_book_data)
+    }
+
+    pub(crate) fn apply_delete(&mut self, records: &[BitmexOrderBookDelete]) -> DeltaOutcome {
+        let prices = match self {
+            OrderBookIds::WaitingForPartial => return DeltaOutcome::Skipped,
+            OrderBookIds::Synced(prices) => prices,
+        };
+
+        // All ids are validated before removing to keep state untouched when delta is rejected
+        if let Some(record) = records.iter().find(|x| !prices.contains_key(&x.id)) {
+            return DeltaOutcome::UnknownId(record.id);
+        }
+
+        let mut order_book_data = OrderBookData::default();
+        for record in records {
+            if let Some(price) = prices.remove(&record.id) {
+                add_order_book_info(price, Decimal::ZERO, record.side, &mut order_book_data);
+            }
+        }
+
+        DeltaOutcome::Applied(order_book_data)
+    }
+}
+
+pub(crate) fn add_order_book_info(
+    price: Price,
+    amount: Amount,
+    side: OrderSide,
+    order_book_map: &mut OrderBookData,
+) {
+    match side {
+        OrderSide::Sell => {
+            order_book_map.asks.insert(price, amount);
+        }
+        OrderSide::Buy => {
+            order_book_map.bids.insert(price, amount);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use std::collections::BTreeMap;
+
+    fn insert(id: u64, side: OrderSide, price: Price) -> BitmexOrderBookInsert {
+        BitmexOrderBookInsert {
+            symbol: "XBTUSD".into(),
+            id,
+            side,
+            size: dec!(100),
+            price,
+        }
+    }
+
+    fn update(id: u64, side: OrderSide, size: Amount) -> BitmexOrderBookUpdate {
+        BitmexOrderBookUpdate {
+            symbol: "XBTUSD".into(),
+            id,
+            side,
+            size,
+        }
+    }
+
+    fn delete(id: u64, side: OrderSide) -> BitmexOrderBookDelete {
+        BitmexOrderBookDelete {
+            symbol: "XBTUSD".into(),
+            id,
+            side,
+        }
+    }
+
+    #[test]
+    fn skip_deltas_before_partial() {
+        let mut order_book = OrderBookIds::default();
+
+        let outcome = order_book.apply_update(&[update(1, OrderSide::Buy, dec!(1))]);
+
+        assert!(matches!(outcome, DeltaOutcome::Skipped));
+    }
+
+    #[test]
+    fn restore_prices_by_ids() {
+        let mut order_book = OrderBookIds::default();
+        order_book.apply_partial(&[
+            insert(1, OrderSide::Sell, dec!(20001)),
+            insert(2, OrderSide::Buy, dec!(20000)),
+        ]);
+
+        let outcome = order_book.apply_update(&[update(2, OrderSide::Buy, dec!(5))]);
+        match outcome {
+            DeltaOutcome::Applied(data) => {
+                assert_eq!(data.bids, BTreeMap::from([(dec!(20000), dec!(5))]))
+            }
+            _ => panic!("Update should be applied"),
+        }
+
+        let outcome = order_book.apply_delete(&[delete(1, OrderSide::Sell)]);
+        match outcome {
+            DeltaOutcome::Applied(data) => {
+                assert_eq!(data.asks, BTreeMap::from([(dec!(20001), dec!(0))]))
+            }
+            _ => panic!("Delete should be applied"),
+        }
+    }
+
+    #[test]
+    fn detect_unknown_id() {
+        let mut order_book = OrderBookIds::default();
+        order_book.apply_partial(&[insert(1, OrderSide::Sell, dec!(20001))]);
+
+        let outcome =
+            order_book.apply_delete(&[delete(1, OrderSide::Sell), delete(3, OrderSide::Buy)]);
+
+        assert!(matches!(outcome, DeltaOutcome::UnknownId(3)));
+        match &order_book {
+            OrderBookIds::Synced(prices) => assert!(prices.contains_key(&1)),
+            OrderBookIds::WaitingForPartial => panic!("Order book should stay synced"),
+        }
+    }
+}
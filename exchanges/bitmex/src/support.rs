@@ -1,7 +1,8 @@
 use crate::bitmex::Bitmex;
+use crate::order_book::{add_order_book_info, DeltaOutcome, OrderBookIds};
 use crate::types::{
-    BitmexOrderBookDelete, BitmexOrderBookInsert, BitmexOrderBookUpdate, BitmexOrderFillDummy,
-    BitmexOrderFillTrade, BitmexOrderStatus, BitmexTradePayload,
+    BitmexOrderBook10, BitmexOrderBookDelete, BitmexOrderBookInsert, BitmexOrderBookUpdate,
+    BitmexOrderFillDummy, BitmexOrderFillTrade, BitmexOrderStatus, BitmexTradePayload,
 };
 use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
@@ -21,10 +22,11 @@ use mmb_core::settings::ExchangeSettings;
 use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade};
 use mmb_domain::market::{CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair};
 use mmb_domain::order::fill::OrderFillType;
-use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
+use mmb_domain::order::snapshot::OrderSide;
 use mmb_domain::order_book::event::{EventType, OrderBookEvent};
 use mmb_domain::order_book::order_book_data::OrderBookData;
-use rust_decimal_macros::dec;
+use serde::de::value::StrDeserializer;
+use serde::de::IntoDeserializer;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::any::Any;
@@ -95,6 +97,8 @@ impl Support for Bitmex {
     }
 
     fn on_disconnected(&self) -> Result<()> {
+        // Order books ids are actual only within websocket session
+        self.order_books.lock().clear();
         Ok(())
     }
 
@@ -131,7 +135,7 @@ impl Support for Bitmex {
             WebSocketRole::Main => {
                 !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
             }
-            WebSocketRole::Secondary => false,
+            WebSocketRole::Secondary | WebSocketRole::MarketData(_) => false,
         }
     }
 
@@ -191,6 +195,7 @@ impl Bitmex {
             BitmexPayloadData::OrderBookL2(data) | BitmexPayloadData::OrderBookL2_25(data) => {
                 self.handle_order_book_data(data)?
             }
+            BitmexPayloadData::OrderBook10 { data } => self.handle_order_book_10(data)?,
             BitmexPayloadData::Trade { action, data } => self.handle_trade(action, data)?,
             BitmexPayloadData::Execution { action, data } => self.handle_execution(action, data)?,
         }
@@ -199,112 +204,87 @@ impl Bitmex {
     }
 
     fn handle_order_book_data(&self, order_book_data: BitmexOrderBookPayload) -> Result<()> {
-        match order_book_data {
-            BitmexOrderBookPayload::Update { data } => {
-                if !data.is_empty() {
-                    // All symbols in event's array are the same
-                    let symbol = data[0].symbol;
-                    let order_book_dictionary = self.order_book_ids.lock();
-                    let mut order_book_data = OrderBookData::default();
-                    for record in data {
-                        let price = order_book_dictionary
-                            .get(&(record.symbol, record.id))
-                            .with_context(|| {
-                                format!(
-                                    "Cannot find order id {} for symbol {}",
-                                    record.id, record.symbol
-                                )
-                            })?;
-
-                        Self::add_order_book_info(
-                            *price,
-                            record.size,
-                            record.side,
-                            &mut order_book_data,
-                        );
-                    }
-                    self.send_order_book_event(symbol, order_book_data, EventType::Update)?;
-                }
-            }
-            BitmexOrderBookPayload::Delete { data } => {
-                if !data.is_empty() {
-                    // All symbols in event's array are the same
-                    let symbol = data[0].symbol;
-                    let mut order_book_dictionary = self.order_book_ids.lock();
-                    let mut order_book_data = OrderBookData::default();
-                    for record in data {
-                        let price = order_book_dictionary
-                            .get(&(record.symbol, record.id))
-                            .with_context(|| {
-                                format!(
-                                    "Cannot find order id {} for symbol {}",
-                                    record.id, record.symbol
-                                )
-                            })?;
-
-                        Self::add_order_book_info(
-                            *price,
-                            dec!(0),
-                            record.side,
-                            &mut order_book_data,
-                        );
+        let symbol = match order_book_data.symbol() {
+            // All symbols in event's array are the same
+            Some(symbol) => symbol,
+            None => return Ok(()),
+        };
 
-                        order_book_dictionary.remove(&(record.symbol, record.id));
-                    }
+        let is_partial = matches!(order_book_data, BitmexOrderBookPayload::Partial { .. });
 
-                    self.send_order_book_event(symbol, order_book_data, EventType::Update)?;
-                }
-            }
-            BitmexOrderBookPayload::Insert { data } => {
-                if !self.is_order_book_initialized() {
-                    // We should skip all data before partial action received
-                    return Ok(());
-                }
-
-                if !data.is_empty() {
-                    // All symbols in event's array are the same
-                    let symbol = data[0].symbol;
-                    let mut order_book_dictionary = self.order_book_ids.lock();
-                    let mut order_book_data = OrderBookData::default();
-                    for record in data {
-                        Self::add_order_book_info(
-                            record.price,
-                            record.size,
-                            record.side,
-                            &mut order_book_data,
-                        );
-                        order_book_dictionary.insert((record.symbol, record.id), record.price);
-                    }
+        let mut order_books = self.order_books.lock();
+        if !is_partial && !order_books.contains_key(&symbol) {
+            drop(order_books);
+            log::warn!("Bitmex order book delta for {symbol} was received without partial action");
+            return self.resubscribe_order_book(symbol);
+        }
 
-                    self.send_order_book_event(symbol, order_book_data, EventType::Update)?;
-                }
-            }
+        let order_book = order_books.entry(symbol).or_default();
+        let outcome = match &order_book_data {
             BitmexOrderBookPayload::Partial { data } => {
-                if !data.is_empty() {
-                    // All symbols in event's array are the same
-                    let symbol = data[0].symbol;
-                    let mut order_book_dictionary = self.order_book_ids.lock();
-                    let mut order_book_data = OrderBookData::default();
-                    for record in data {
-                        Self::add_order_book_info(
-                            record.price,
-                            record.size,
-                            record.side,
-                            &mut order_book_data,
-                        );
-                        order_book_dictionary.insert((record.symbol, record.id), record.price);
-                    }
+                DeltaOutcome::Applied(order_book.apply_partial(data))
+            }
+            BitmexOrderBookPayload::Insert { data } => order_book.apply_insert(data),
+            BitmexOrderBookPayload::Update { data } => order_book.apply_update(data),
+            BitmexOrderBookPayload::Delete { data } => order_book.apply_delete(data),
+        };
+        drop(order_books);
+
+        match outcome {
+            DeltaOutcome::Applied(data) => {
+                let event_type = match is_partial {
+                    true => EventType::Snapshot,
+                    false => EventType::Update,
+                };
+                self.send_order_book_event(symbol, data, event_type)
+            }
+            // We should skip all data before partial action received
+            DeltaOutcome::Skipped => Ok(()),
+            DeltaOutcome::UnknownId(id) => {
+                log::warn!("Bitmex order book delta for {symbol} references unknown id {id}");
+                self.resubscribe_order_book(symbol)
+            }
+        }
+    }
 
-                    self.send_order_book_event(symbol, order_book_data, EventType::Snapshot)?;
-                }
+    fn handle_order_book_10(&self, data: Vec<BitmexOrderBook10>) -> Result<()> {
+        for record in data {
+            let mut order_book_data = OrderBookData::default();
+            for (price, amount) in record.asks {
+                add_order_book_info(price, amount, OrderSide::Sell, &mut order_book_data);
             }
+            for (price, amount) in record.bids {
+                add_order_book_info(price, amount, OrderSide::Buy, &mut order_book_data);
+            }
+
+            self.send_order_book_event(record.symbol, order_book_data, EventType::Snapshot)?;
         }
 
         Ok(())
     }
 
-    fn is_order_book_initialized(&self) -> bool {
-        !self.order_book_ids.lock().is_empty()
+    /// Resubscription is the only way to get new partial action when order book became inconsistent
+    fn resubscribe_order_book(&self, symbol: SpecificCurrencyPair) -> Result<()> {
+        self.order_books
+            .lock()
+            .insert(symbol, OrderBookIds::WaitingForPartial);
+
+        let order_book_subscription = self
+            .get_market_data_subscriptions()?
+            .into_iter()
+            .find(SubscriptionType::is_order_book_l2)
+            .context("Bitmex websocket: there is no L2 order book subscription to resubscribe")?;
+
+        for operation in [
+            SubscriptionOperationType::Unsubscribe,
+            SubscriptionOperationType::Subscribe,
+        ] {
+            let request =
+                Self::create_websocket_request(operation, &[order_book_subscription], &[symbol]);
+            (self.websocket_message_callback)(WebSocketRole::Main, request)?;
+        }
+
+        Ok(())
     }
 
     fn send_order_book_event(
@@ -331,22 +311,6 @@ impl Bitmex {
         )
     }
 
-    fn add_order_book_info(
-        price: Price,
-        amount: Amount,
-        side: OrderSide,
-        order_book_map: &mut OrderBookData,
-    ) {
-        match side {
-            OrderSide::Sell => {
-                order_book_map.asks.insert(price, amount);
-            }
-            OrderSide::Buy => {
-                order_book_map.bids.insert(price, amount);
-            }
-        }
-    }
-
     fn handle_trade(
         &self,
         action: SubscriptionDataAction,
@@ -365,6 +329,7 @@ impl Bitmex {
                     quantity: record.size,
                     side: record.side,
                     transaction_time: record.timestamp,
+                    tick_direction: record.tick_direction,
                 },
             );
         }
@@ -461,26 +426,65 @@ impl Bitmex {
     }
 
     fn on_auth_success(&self) -> Result<()> {
+        let mut subscriptions = self.get_market_data_subscriptions()?;
+        subscriptions.push(SubscriptionType::Execution);
+
         let traded_currencies = self.traded_specific_currencies.lock();
-        let subscriptions = Self::subscribe_to_websocket_events(
-            // Note that OrderBookL2_25 get only top 25 levels
-            vec![
-                SubscriptionType::OrderBookL2_25,
-                SubscriptionType::Trade,
-                SubscriptionType::Execution,
-            ],
+        if subscriptions.iter().any(SubscriptionType::is_order_book_l2) {
+            let mut order_books = self.order_books.lock();
+            for currency_pair in traded_currencies.iter() {
+                order_books.insert(*currency_pair, OrderBookIds::WaitingForPartial);
+            }
+        }
+
+        let request = Self::create_websocket_request(
+            SubscriptionOperationType::Subscribe,
+            &subscriptions,
             traded_currencies.deref(),
         );
 
-        (self.websocket_message_callback)(WebSocketRole::Main, subscriptions)
+        (self.websocket_message_callback)(WebSocketRole::Main, request)
     }
 
-    fn subscribe_to_websocket_events(
-        subscriptions: Vec<SubscriptionType>,
+    /// Public subscriptions are taken from `websocket_channels` setting.
+    /// By default we get only top 25 levels of order book and trades
+    fn get_market_data_subscriptions(&self) -> Result<Vec<SubscriptionType>> {
+        let channels = &self.settings.websocket_channels;
+        if channels.is_empty() {
+            return Ok(vec![
+                SubscriptionType::OrderBookL2_25,
+                SubscriptionType::Trade,
+            ]);
+        }
+
+        let subscriptions = channels
+            .iter()
+            .map(|channel| {
+                let deserializer: StrDeserializer<serde::de::value::Error> =
+                    channel.as_str().into_deserializer();
+                SubscriptionType::deserialize(deserializer)
+                    .with_context(|| format!("Unknown Bitmex websocket channel {channel}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let order_book_subscriptions_count = subscriptions
+            .iter()
+            .filter(|x| x.is_order_book_l2() || **x == SubscriptionType::OrderBook10)
+            .count();
+        if order_book_subscriptions_count > 1 {
+            bail!("Only one Bitmex order book websocket channel can be used, but got {channels:?}");
+        }
+
+        Ok(subscriptions)
+    }
+
+    fn create_websocket_request(
+        operation: SubscriptionOperationType,
+        subscriptions: &[SubscriptionType],
         currency_pairs: &[SpecificCurrencyPair],
     ) -> String {
         let mut request = Request {
-            operation: SubscriptionOperationType::Subscribe,
+            operation,
             args: Vec::with_capacity(subscriptions.len() * currency_pairs.len()),
         };
         for subscription in subscriptions {
@@ -528,7 +532,7 @@ struct Request<T> {
 
 // Enum is for all possible subscription types but just some of them are used for now
 #[allow(dead_code)]
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone, Copy, PartialEq)]
 #[serde(rename_all = "camelCase")]
 enum SubscriptionType {
     // Public types
@@ -566,6 +570,11 @@ enum SubscriptionType {
 }
 
 impl SubscriptionType {
+    /// L2 order book tables send deltas which reference records by ids
+    fn is_order_book_l2(&self) -> bool {
+        matches!(self, Self::OrderBookL2 | Self::OrderBookL2_25)
+    }
+
     fn as_str(&self) -> &str {
         match self {
             Self::Announcement => "announcement",
@@ -614,8 +623,6 @@ impl Debug for SubscriptionType {
     }
 }
 
-// Unsubscribe is not used for now
-#[allow(dead_code)]
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 enum SubscriptionOperationType {
@@ -674,6 +681,10 @@ impl Debug for SubscriptionDataAction {
 enum BitmexPayloadData<'a> {
     OrderBookL2_25(BitmexOrderBookPayload),
     OrderBookL2(BitmexOrderBookPayload),
+    // Each message of this table is a snapshot regardless of action
+    OrderBook10 {
+        data: Vec<BitmexOrderBook10>,
+    },
     Trade {
         action: SubscriptionDataAction,
         data: Vec<BitmexTradePayload>,
@@ -694,6 +705,16 @@ enum BitmexOrderBookPayload {
     Partial { data: Vec<BitmexOrderBookInsert> },
 }
 
+impl BitmexOrderBookPayload {
+    fn symbol(&self) -> Option<SpecificCurrencyPair> {
+        match self {
+            Self::Update { data } => data.first().map(|x| x.symbol),
+            Self::Insert { data } | Self::Partial { data } => data.first().map(|x| x.symbol),
+            Self::Delete { data } => data.first().map(|x| x.symbol),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(bound(deserialize = "'de: 'a"))]
 #[serde(tag = "ordStatus")]
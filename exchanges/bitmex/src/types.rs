@@ -1,5 +1,5 @@
 use anyhow::bail;
-use mmb_domain::events::TradeId;
+use mmb_domain::events::{TickDirection, TradeId};
 use mmb_domain::market::SpecificCurrencyPair;
 use mmb_domain::order::snapshot::{Amount, ClientOrderId, ExchangeOrderId, OrderSide, Price};
 use mmb_utils::DateTime;
@@ -133,6 +133,15 @@ pub(crate) struct BitmexSymbol<'a> {
     pub(crate) max_price: Option<Price>,
     #[serde(rename = "maxOrderQty")]
     pub(crate) max_amount: Option<Amount>,
+    #[serde(rename = "isInverse", default)]
+    pub(crate) is_inverse: bool,
+    #[serde(rename = "isQuanto", default)]
+    pub(crate) is_quanto: bool,
+    /// Value of one contract in settlement currency units (e.g. satoshis for XBt).
+    /// Negative for inverse contracts
+    pub(crate) multiplier: Option<Decimal>,
+    #[serde(rename = "settlCurrency")]
+    pub(crate) settlement_currency: Option<&'a str>,
 }
 
 #[derive(PartialEq)]
@@ -307,6 +316,20 @@ pub(crate) struct BitmexOrderBookUpdate {
     pub(crate) size: Amount,
 }
 
+/// Bitmex top 10 levels of order book description. Each message is a full snapshot
+/// {
+/// symbol: "string" // currency pair
+/// bids: [[0, 0]] // price and amount values of bids
+/// asks: [[0, 0]] // price and amount values of asks
+/// timestamp: "2020-09-14T02:26:42.839Z"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitmexOrderBook10 {
+    pub(crate) symbol: SpecificCurrencyPair,
+    pub(crate) bids: Vec<(Price, Amount)>,
+    pub(crate) asks: Vec<(Price, Amount)>,
+}
+
 /// Bitmex execution response description.
 /// We use it for trades, order fills and order status changing via websocket
 ///{
@@ -368,6 +391,8 @@ pub(crate) struct BitmexTradePayload {
     pub(crate) trade_id: TradeId,
     #[serde(deserialize_with = "deserialize_datetime")]
     pub(crate) timestamp: DateTime,
+    #[serde(rename = "tickDirection")]
+    pub(crate) tick_direction: Option<TickDirection>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -0,0 +1,106 @@
+use crate::fields::{msg_type, tag};
+use crate::message::{FieldAccess, FixMessage, Tag};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FixField {
+    pub tag: Tag,
+    pub value: String,
+}
+
+/// Venue specific deviations from FIX 4.4 which can be configured without code changes
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FixDictionary {
+    pub begin_string: String,
+    /// Constant fields appended to outgoing messages by MsgType(35), e.g. Account(1) or HandlInst(21)
+    pub extra_fields: HashMap<String, Vec<FixField>>,
+    /// Fields which should be present in incoming application messages by MsgType(35)
+    pub required_fields: HashMap<String, Vec<Tag>>,
+    /// MarketDepth(264) of market data subscription, 0 means full book
+    pub market_depth: u32,
+    /// Field with aggressor side of trade entries in market data, trades are skipped if it's missing
+    pub trade_side_tag: Option<Tag>,
+}
+
+impl Default for FixDictionary {
+    fn default() -> Self {
+        FixDictionary {
+            begin_string: "FIX.4.4".to_owned(),
+            extra_fields: HashMap::new(),
+            required_fields: HashMap::from([
+                (
+                    msg_type::EXECUTION_REPORT.to_owned(),
+                    vec![tag::EXEC_ID, tag::EXEC_TYPE, tag::ORD_STATUS],
+                ),
+                (
+                    msg_type::ORDER_CANCEL_REJECT.to_owned(),
+                    vec![tag::CL_ORD_ID, tag::ORD_STATUS, tag::CXL_REJ_RESPONSE_TO],
+                ),
+            ]),
+            market_depth: 0,
+            trade_side_tag: Some(tag::SIDE),
+        }
+    }
+}
+
+impl FixDictionary {
+    pub fn add_extra_fields(&self, message: &mut FixMessage) {
+        let Some(fields) = self.extra_fields.get(message.msg_type()) else {
+            return;
+        };
+
+        for field in fields {
+            message.push(field.tag, &field.value);
+        }
+    }
+
+    pub fn validate(&self, message: &FixMessage) -> Result<()> {
+        let Some(required_fields) = self.required_fields.get(message.msg_type()) else {
+            return Ok(());
+        };
+
+        let missing_fields: Vec<_> = required_fields
+            .iter()
+            .filter(|tag| message.get(**tag).is_none())
+            .collect();
+        if !missing_fields.is_empty() {
+            bail!("Required fields {missing_fields:?} are missing in FIX message {message:?}");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_extra_fields_and_validate() {
+        let dictionary = FixDictionary {
+            extra_fields: HashMap::from([(
+                msg_type::NEW_ORDER_SINGLE.to_owned(),
+                vec![FixField {
+                    tag: tag::HANDL_INST,
+                    value: "1".to_owned(),
+                }],
+            )]),
+            ..FixDictionary::default()
+        };
+
+        let mut new_order = FixMessage::new(msg_type::NEW_ORDER_SINGLE);
+        dictionary.add_extra_fields(&mut new_order);
+        assert_eq!(new_order.get(tag::HANDL_INST), Some("1"));
+
+        let execution_report = FixMessage::new(msg_type::EXECUTION_REPORT)
+            .with(tag::EXEC_ID, "1")
+            .with(tag::EXEC_TYPE, "0");
+        assert!(dictionary.validate(&execution_report).is_err());
+        assert!(dictionary
+            .validate(&execution_report.with(tag::ORD_STATUS, "0"))
+            .is_ok());
+    }
+}
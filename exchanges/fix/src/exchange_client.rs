@@ -0,0 +1,347 @@
+use crate::fields::{exec_inst, exec_type, msg_type, ord_type, tag, time_in_force};
+use crate::fix::{response_types, Fix};
+use crate::message::{FieldAccess, FixMessage, Tag};
+use crate::support::get_exchange_order_id;
+use crate::types::{
+    cancel_reject_error_type, format_utc_timestamp, order_reject_error_type, order_status_from_fix,
+    side_from_fix, side_to_fix,
+};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use mmb_core::exchanges::general::exchange::RequestResult;
+use mmb_core::exchanges::general::order::cancel::CancelOrderResult;
+use mmb_core::exchanges::general::order::create::CreateOrderResult;
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::traits::{ExchangeClient, ExchangeError, Support};
+use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{CurrencyPair, ExchangeErrorType};
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{
+    ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderStatus, Price, UserOrder,
+};
+use mmb_domain::position::{ActivePosition, ClosedPosition};
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+#[async_trait]
+impl ExchangeClient for Fix {
+    async fn create_order(&self, order: &OrderRef) -> CreateOrderResult {
+        let request = match self.create_new_order_single(order) {
+            Ok(request) => request,
+            Err(err) => return CreateOrderResult::failed(err, EventSourceType::WebSocket),
+        };
+
+        let client_order_id = order.client_order_id().to_string();
+        match self
+            .send_single_request(client_order_id, request, response_types::NEW_ORDER)
+            .await
+        {
+            Ok(response) => {
+                match get_rejection(&response, order_reject_error_type, tag::ORD_REJ_REASON) {
+                    Some(error) => CreateOrderResult::failed(error, EventSourceType::WebSocket),
+                    None => match get_exchange_order_id(&response) {
+                        Ok(order_id) => {
+                            CreateOrderResult::succeed(&order_id, EventSourceType::WebSocket)
+                        }
+                        Err(err) => CreateOrderResult::failed(
+                            ExchangeError::parsing(format!("Unable to get order id: {err:?}")),
+                            EventSourceType::WebSocket,
+                        ),
+                    },
+                }
+            }
+            Err(err) => CreateOrderResult::failed(err.into(), EventSourceType::WebSocket),
+        }
+    }
+
+    async fn cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> CancelOrderResult {
+        let header = order.header();
+        let request_id = self.new_request_id();
+        let request = FixMessage::new(msg_type::ORDER_CANCEL_REQUEST)
+            .with(tag::CL_ORD_ID, &request_id)
+            .with(tag::ORIG_CL_ORD_ID, &header.client_order_id)
+            .with(tag::ORDER_ID, exchange_order_id)
+            .with(
+                tag::SYMBOL,
+                self.get_specific_currency_pair(header.currency_pair),
+            )
+            .with(tag::SIDE, side_to_fix(header.side))
+            .with(tag::ORDER_QTY, header.amount)
+            .with(tag::TRANSACT_TIME, format_utc_timestamp(Utc::now()));
+
+        match self
+            .send_single_request(request_id, request, response_types::CANCEL_ORDER)
+            .await
+        {
+            Ok(response) => {
+                match get_rejection(&response, cancel_reject_error_type, tag::CXL_REJ_REASON) {
+                    Some(error) => CancelOrderResult::failed(error, EventSourceType::WebSocket),
+                    None => CancelOrderResult::succeed(
+                        header.client_order_id.clone(),
+                        EventSourceType::WebSocket,
+                        None,
+                    ),
+                }
+            }
+            Err(err) => CancelOrderResult::failed(err.into(), EventSourceType::WebSocket),
+        }
+    }
+
+    async fn cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()> {
+        let request_id = self.new_request_id();
+        let request = FixMessage::new(msg_type::ORDER_MASS_CANCEL_REQUEST)
+            .with(tag::CL_ORD_ID, &request_id)
+            // Cancel orders for a security
+            .with(tag::MASS_CANCEL_REQUEST_TYPE, 1)
+            .with(tag::SYMBOL, self.get_specific_currency_pair(currency_pair))
+            .with(tag::TRANSACT_TIME, format_utc_timestamp(Utc::now()));
+
+        let response = self
+            .send_single_request(request_id, request, response_types::MASS_CANCEL)
+            .await?;
+        match (response.msg_type(), response.get(tag::MASS_CANCEL_RESPONSE)) {
+            (msg_type::ORDER_MASS_CANCEL_REPORT, Some(mass_cancel_response))
+                if mass_cancel_response != "0" =>
+            {
+                Ok(())
+            }
+            _ => bail!(
+                "Failed to cancel all orders, reason {:?}: {:?}",
+                response.get(tag::MASS_CANCEL_REJECT_REASON),
+                response.get(tag::TEXT)
+            ),
+        }
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<OrderInfo>> {
+        let request_id = self.new_request_id();
+        let request = FixMessage::new(msg_type::ORDER_MASS_STATUS_REQUEST)
+            .with(tag::MASS_STATUS_REQ_ID, &request_id)
+            // Status for all orders
+            .with(tag::MASS_STATUS_REQ_TYPE, 7);
+
+        let responses = self
+            .send_request(request_id, request, response_types::STATUS)
+            .await?;
+
+        let mut orders = Vec::new();
+        for response in responses {
+            if response.msg_type() == msg_type::BUSINESS_MESSAGE_REJECT {
+                bail!(
+                    "Order mass status request rejected: {:?}",
+                    response.get(tag::TEXT)
+                );
+            }
+            // Venue sends a single report without order if there are no open orders
+            if get_exchange_order_id(&response).is_err() {
+                continue;
+            }
+
+            let order = self.order_info_from_execution_report(&response)?;
+            if order.order_status == OrderStatus::Created {
+                orders.push(order);
+            }
+        }
+
+        Ok(orders)
+    }
+
+    async fn get_open_orders_by_currency_pair(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<Vec<OrderInfo>> {
+        let mut orders = self.get_open_orders().await?;
+        orders.retain(|order| order.currency_pair == currency_pair);
+
+        Ok(orders)
+    }
+
+    async fn get_order_info(&self, order: &OrderRef) -> Result<OrderInfo, ExchangeError> {
+        let header = order.header();
+        let exchange_order_id = order.exchange_order_id().ok_or_else(|| {
+            ExchangeError::unknown(&format!(
+                "Order {} doesn't have exchange order id",
+                header.client_order_id
+            ))
+        })?;
+        let request_id = self.new_request_id();
+        let request = FixMessage::new(msg_type::ORDER_STATUS_REQUEST)
+            .with(tag::ORDER_ID, exchange_order_id)
+            .with(tag::CL_ORD_ID, &header.client_order_id)
+            .with(tag::ORD_STATUS_REQ_ID, &request_id)
+            .with(
+                tag::SYMBOL,
+                self.get_specific_currency_pair(header.currency_pair),
+            )
+            .with(tag::SIDE, side_to_fix(header.side));
+
+        let response = self
+            .send_single_request(request_id, request, response_types::STATUS)
+            .await?;
+        if let Some(error) = get_rejection(&response, order_reject_error_type, tag::ORD_REJ_REASON)
+        {
+            return Err(error);
+        }
+
+        self.order_info_from_execution_report(&response)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse order info: {err:?}")))
+    }
+
+    async fn close_position(
+        &self,
+        _position: &ActivePosition,
+        _price: Option<Price>,
+    ) -> Result<ClosedPosition> {
+        bail!("FIX connector doesn't support positions")
+    }
+
+    async fn get_active_positions(&self) -> Result<Vec<ActivePosition>> {
+        bail!("FIX connector doesn't support positions")
+    }
+
+    async fn get_balance_and_positions(&self) -> Result<ExchangeBalancesAndPositions> {
+        // Balances aren't a part of FIX 4.4 order routing, venues provide them by own extensions
+        bail!("FIX connector doesn't support balances")
+    }
+
+    async fn get_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> RequestResult<Vec<OrderTrade>> {
+        RequestResult::Success(self.get_my_trades_since(symbol.currency_pair(), last_date_time))
+    }
+
+    async fn build_all_symbols(&self) -> Result<Vec<Arc<Symbol>>> {
+        Ok(self
+            .venue
+            .instruments
+            .iter()
+            .map(|instrument| {
+                for currency_code in [instrument.base, instrument.quote] {
+                    self.supported_currencies
+                        .insert(currency_code.as_str().into(), currency_code);
+                }
+                self.add_currency_pair(
+                    instrument.symbol,
+                    CurrencyPair::from_codes(instrument.base, instrument.quote),
+                );
+
+                Arc::new(Symbol::new(
+                    false,
+                    instrument.base.as_str().into(),
+                    instrument.base,
+                    instrument.quote.as_str().into(),
+                    instrument.quote,
+                    None,
+                    None,
+                    instrument.min_amount,
+                    None,
+                    None,
+                    instrument.base,
+                    None,
+                    Precision::ByTick {
+                        tick: instrument.price_tick,
+                    },
+                    Precision::ByTick {
+                        tick: instrument.amount_tick,
+                    },
+                ))
+            })
+            .collect())
+    }
+
+    async fn get_server_time(&self) -> Option<Result<i64>> {
+        None
+    }
+}
+
+impl Fix {
+    fn create_new_order_single(&self, order: &OrderRef) -> Result<FixMessage, ExchangeError> {
+        let header = order.header();
+        let mut request = FixMessage::new(msg_type::NEW_ORDER_SINGLE)
+            .with(tag::CL_ORD_ID, &header.client_order_id)
+            .with(
+                tag::SYMBOL,
+                self.get_specific_currency_pair(header.currency_pair),
+            )
+            .with(tag::SIDE, side_to_fix(header.side))
+            .with(tag::TRANSACT_TIME, format_utc_timestamp(Utc::now()))
+            .with(tag::ORDER_QTY, header.amount);
+
+        match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => {
+                request.push(tag::ORD_TYPE, ord_type::LIMIT);
+                request.push(tag::PRICE, price);
+                request.push(tag::TIME_IN_FORCE, time_in_force::GOOD_TILL_CANCEL);
+                match execution_type {
+                    OrderExecutionType::MakerOnly => {
+                        request.push(tag::EXEC_INST, exec_inst::PARTICIPATE_DONT_INITIATE)
+                    }
+                    OrderExecutionType::None => (),
+                }
+            }
+            OrderOptions::User(UserOrder::Market) => {
+                request.push(tag::ORD_TYPE, ord_type::MARKET);
+                request.push(tag::TIME_IN_FORCE, time_in_force::IMMEDIATE_OR_CANCEL);
+            }
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        }
+
+        Ok(request)
+    }
+
+    fn order_info_from_execution_report(&self, message: &FixMessage) -> Result<OrderInfo> {
+        let currency_pair =
+            self.get_unified_currency_pair(&message.get_required(tag::SYMBOL)?.into())?;
+
+        Ok(OrderInfo::new(
+            currency_pair,
+            get_exchange_order_id(message)?,
+            message.get_required(tag::CL_ORD_ID)?.into(),
+            side_from_fix(message.get_required(tag::SIDE)?)?,
+            order_status_from_fix(message.get_required(tag::ORD_STATUS)?)?,
+            // Market orders don't have price
+            message.parse(tag::PRICE)?.unwrap_or_default(),
+            message.parse_required(tag::ORDER_QTY)?,
+            message.parse::<Decimal>(tag::AVG_PX)?.unwrap_or_default(),
+            message.parse::<Decimal>(tag::CUM_QTY)?.unwrap_or_default(),
+            // Commission is charged in quote currency by default
+            Some(currency_pair.to_codes().quote.to_string()),
+            None,
+            message.parse(tag::COMMISSION)?,
+        ))
+    }
+}
+
+/// Error of rejected ExecutionReport, OrderCancelReject or BusinessMessageReject
+fn get_rejection(
+    response: &FixMessage,
+    get_error_type: fn(Option<&str>) -> ExchangeErrorType,
+    reason_tag: Tag,
+) -> Option<ExchangeError> {
+    let error_type = match response.msg_type() {
+        msg_type::EXECUTION_REPORT if response.get(tag::EXEC_TYPE) == Some(exec_type::REJECTED) => {
+            get_error_type(response.get(reason_tag))
+        }
+        msg_type::ORDER_CANCEL_REJECT => get_error_type(response.get(reason_tag)),
+        msg_type::BUSINESS_MESSAGE_REJECT => ExchangeErrorType::Unknown,
+        _ => return None,
+    };
+
+    Some(ExchangeError::new(
+        error_type,
+        response.get(tag::TEXT).unwrap_or_default().to_owned(),
+        None,
+    ))
+}
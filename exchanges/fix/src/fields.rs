@@ -0,0 +1,179 @@
+/// Numbers of FIX 4.4 fields which are used by connector
+pub mod tag {
+    use crate::message::Tag;
+
+    pub const ACCOUNT: Tag = 1;
+    pub const AVG_PX: Tag = 6;
+    pub const BEGIN_SEQ_NO: Tag = 7;
+    pub const BEGIN_STRING: Tag = 8;
+    pub const BODY_LENGTH: Tag = 9;
+    pub const CHECK_SUM: Tag = 10;
+    pub const CL_ORD_ID: Tag = 11;
+    pub const COMMISSION: Tag = 12;
+    pub const CUM_QTY: Tag = 14;
+    pub const END_SEQ_NO: Tag = 16;
+    pub const EXEC_ID: Tag = 17;
+    pub const EXEC_INST: Tag = 18;
+    pub const HANDL_INST: Tag = 21;
+    pub const LAST_PX: Tag = 31;
+    pub const LAST_QTY: Tag = 32;
+    pub const MSG_SEQ_NUM: Tag = 34;
+    pub const MSG_TYPE: Tag = 35;
+    pub const NEW_SEQ_NO: Tag = 36;
+    pub const ORDER_ID: Tag = 37;
+    pub const ORDER_QTY: Tag = 38;
+    pub const ORD_STATUS: Tag = 39;
+    pub const ORD_TYPE: Tag = 40;
+    pub const ORIG_CL_ORD_ID: Tag = 41;
+    pub const POSS_DUP_FLAG: Tag = 43;
+    pub const PRICE: Tag = 44;
+    pub const REF_SEQ_NUM: Tag = 45;
+    pub const SENDER_COMP_ID: Tag = 49;
+    pub const SENDING_TIME: Tag = 52;
+    pub const SIDE: Tag = 54;
+    pub const SYMBOL: Tag = 55;
+    pub const TARGET_COMP_ID: Tag = 56;
+    pub const TEXT: Tag = 58;
+    pub const TIME_IN_FORCE: Tag = 59;
+    pub const TRANSACT_TIME: Tag = 60;
+    pub const ENCRYPT_METHOD: Tag = 98;
+    pub const CXL_REJ_REASON: Tag = 102;
+    pub const ORD_REJ_REASON: Tag = 103;
+    pub const HEART_BT_INT: Tag = 108;
+    pub const TEST_REQ_ID: Tag = 112;
+    pub const ORIG_SENDING_TIME: Tag = 122;
+    pub const GAP_FILL_FLAG: Tag = 123;
+    pub const RESET_SEQ_NUM_FLAG: Tag = 141;
+    pub const NO_RELATED_SYM: Tag = 146;
+    pub const EXEC_TYPE: Tag = 150;
+    pub const LEAVES_QTY: Tag = 151;
+    pub const MD_REQ_ID: Tag = 262;
+    pub const SUBSCRIPTION_REQUEST_TYPE: Tag = 263;
+    pub const MARKET_DEPTH: Tag = 264;
+    pub const MD_UPDATE_TYPE: Tag = 265;
+    pub const NO_MD_ENTRY_TYPES: Tag = 267;
+    pub const NO_MD_ENTRIES: Tag = 268;
+    pub const MD_ENTRY_TYPE: Tag = 269;
+    pub const MD_ENTRY_PX: Tag = 270;
+    pub const MD_ENTRY_SIZE: Tag = 271;
+    pub const MD_ENTRY_DATE: Tag = 272;
+    pub const MD_ENTRY_TIME: Tag = 273;
+    pub const MD_ENTRY_ID: Tag = 278;
+    pub const MD_UPDATE_ACTION: Tag = 279;
+    pub const MD_REQ_REJ_REASON: Tag = 281;
+    pub const BUSINESS_REJECT_REF_ID: Tag = 379;
+    pub const BUSINESS_REJECT_REASON: Tag = 380;
+    pub const CXL_REJ_RESPONSE_TO: Tag = 434;
+    pub const MASS_CANCEL_REQUEST_TYPE: Tag = 530;
+    pub const MASS_CANCEL_RESPONSE: Tag = 531;
+    pub const MASS_CANCEL_REJECT_REASON: Tag = 532;
+    pub const USERNAME: Tag = 553;
+    pub const PASSWORD: Tag = 554;
+    pub const MASS_STATUS_REQ_ID: Tag = 584;
+    pub const MASS_STATUS_REQ_TYPE: Tag = 585;
+    pub const ORD_STATUS_REQ_ID: Tag = 790;
+    pub const LAST_LIQUIDITY_IND: Tag = 851;
+    pub const LAST_RPT_REQUESTED: Tag = 912;
+    pub const TRADE_ID: Tag = 1003;
+    pub const AGGRESSOR_INDICATOR: Tag = 1057;
+}
+
+/// Values of MsgType(35)
+pub mod msg_type {
+    pub const HEARTBEAT: &str = "0";
+    pub const TEST_REQUEST: &str = "1";
+    pub const RESEND_REQUEST: &str = "2";
+    pub const REJECT: &str = "3";
+    pub const SEQUENCE_RESET: &str = "4";
+    pub const LOGOUT: &str = "5";
+    pub const EXECUTION_REPORT: &str = "8";
+    pub const ORDER_CANCEL_REJECT: &str = "9";
+    pub const LOGON: &str = "A";
+    pub const NEW_ORDER_SINGLE: &str = "D";
+    pub const ORDER_CANCEL_REQUEST: &str = "F";
+    pub const ORDER_STATUS_REQUEST: &str = "H";
+    pub const BUSINESS_MESSAGE_REJECT: &str = "j";
+    pub const ORDER_MASS_CANCEL_REQUEST: &str = "q";
+    pub const ORDER_MASS_CANCEL_REPORT: &str = "r";
+    pub const MARKET_DATA_REQUEST: &str = "V";
+    pub const MARKET_DATA_SNAPSHOT_FULL_REFRESH: &str = "W";
+    pub const MARKET_DATA_INCREMENTAL_REFRESH: &str = "X";
+    pub const MARKET_DATA_REQUEST_REJECT: &str = "Y";
+    pub const ORDER_MASS_STATUS_REQUEST: &str = "AF";
+
+    /// Session level messages which are never resent
+    pub fn is_admin(msg_type: &str) -> bool {
+        matches!(
+            msg_type,
+            HEARTBEAT | TEST_REQUEST | RESEND_REQUEST | REJECT | SEQUENCE_RESET | LOGOUT | LOGON
+        )
+    }
+}
+
+/// Values of ExecType(150)
+pub mod exec_type {
+    pub const NEW: &str = "0";
+    pub const CANCELED: &str = "4";
+    pub const PENDING_CANCEL: &str = "6";
+    pub const REJECTED: &str = "8";
+    pub const PENDING_NEW: &str = "A";
+    pub const EXPIRED: &str = "C";
+    pub const TRADE: &str = "F";
+    pub const ORDER_STATUS: &str = "I";
+}
+
+/// Values of OrdStatus(39)
+pub mod ord_status {
+    pub const NEW: &str = "0";
+    pub const PARTIALLY_FILLED: &str = "1";
+    pub const FILLED: &str = "2";
+    pub const DONE_FOR_DAY: &str = "3";
+    pub const CANCELED: &str = "4";
+    pub const PENDING_CANCEL: &str = "6";
+    pub const STOPPED: &str = "7";
+    pub const REJECTED: &str = "8";
+    pub const SUSPENDED: &str = "9";
+    pub const PENDING_NEW: &str = "A";
+    pub const CALCULATED: &str = "B";
+    pub const EXPIRED: &str = "C";
+    pub const ACCEPTED_FOR_BIDDING: &str = "D";
+    pub const PENDING_REPLACE: &str = "E";
+}
+
+/// Values of Side(54)
+pub mod side {
+    pub const BUY: &str = "1";
+    pub const SELL: &str = "2";
+}
+
+/// Values of OrdType(40)
+pub mod ord_type {
+    pub const MARKET: &str = "1";
+    pub const LIMIT: &str = "2";
+}
+
+/// Values of TimeInForce(59)
+pub mod time_in_force {
+    pub const GOOD_TILL_CANCEL: &str = "1";
+    pub const IMMEDIATE_OR_CANCEL: &str = "3";
+}
+
+/// Values of ExecInst(18)
+pub mod exec_inst {
+    /// Post only order
+    pub const PARTICIPATE_DONT_INITIATE: &str = "6";
+}
+
+/// Values of MDEntryType(269)
+pub mod md_entry_type {
+    pub const BID: &str = "0";
+    pub const OFFER: &str = "1";
+    pub const TRADE: &str = "2";
+}
+
+/// Values of MDUpdateAction(279)
+pub mod md_update_action {
+    pub const NEW: &str = "0";
+    pub const CHANGE: &str = "1";
+    pub const DELETE: &str = "2";
+}
@@ -0,0 +1,461 @@
+use crate::fields::msg_type;
+use crate::message::FixMessage;
+use crate::session::{FixSession, SessionSettings, SessionState};
+use crate::settings::FixVenueSettings;
+use crate::store::{FileSequenceStore, MemorySequenceStore, SequenceStore};
+use anyhow::{bail, Context, Result};
+use dashmap::DashMap;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, HandleMetricsCb, HandleOrderFilledCb,
+    HandleTradeCb, OrderCancelledCb, OrderCreatedCb, OrderCreationFailedCb, SendWebsocketMessageCb,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeEvent};
+use mmb_domain::market::{
+    CurrencyCode, CurrencyId, CurrencyPair, ExchangeId, SpecificCurrencyPair,
+};
+use mmb_domain::order::pool::OrdersPool;
+use mmb_utils::infrastructure::WithExpect;
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+/// Max time of waiting for response to FIX request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// FIX venues usually don't provide trades history, so own trades are kept since start
+const MY_TRADES_CAPACITY: usize = 1000;
+
+/// Request which is waiting for responses with specified MsgType(35)
+struct PendingRequest {
+    msg_types: &'static [&'static str],
+    responses: Vec<FixMessage>,
+    sender: oneshot::Sender<Vec<FixMessage>>,
+}
+
+pub struct Fix {
+    pub(crate) settings: ExchangeSettings,
+    pub(crate) venue: FixVenueSettings,
+    session: Mutex<FixSession>,
+    /// Channel of TCP connection writer, it exists only while connection is established
+    writer: Mutex<Option<mpsc::UnboundedSender<String>>>,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) order_creation_failed_callback: OrderCreationFailedCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    /// Requests are matched with responses by ClOrdID(11) or other request specific id
+    pending_requests: Mutex<HashMap<String, PendingRequest>>,
+    my_trades: Mutex<VecDeque<(CurrencyPair, OrderTrade)>>,
+    last_request_id: AtomicU64,
+}
+
+impl Fix {
+    pub fn new(
+        settings: ExchangeSettings,
+        venue: FixVenueSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Fix {
+        let store: Box<dyn SequenceStore> = match &venue.sequence_store_path {
+            Some(path) => Box::new(FileSequenceStore::new(path.clone())),
+            None => Box::new(MemorySequenceStore::default()),
+        };
+        let session_settings = SessionSettings {
+            begin_string: venue.dictionary.begin_string.clone(),
+            sender_comp_id: venue.sender_comp_id.clone(),
+            target_comp_id: venue.target_comp_id.clone(),
+            heartbeat_interval: Duration::from_secs(venue.heartbeat_interval_secs),
+            reset_seq_num_on_logon: venue.reset_seq_num_on_logon,
+            username: (!settings.api_key.is_empty()).then(|| settings.api_key.clone()),
+            password: (!settings.secret_key.is_empty()).then(|| settings.secret_key.clone()),
+        };
+        let session = FixSession::new(session_settings, store).with_expect(|| {
+            format!(
+                "Unable to load FIX sequence numbers of {}",
+                venue.exchange_id
+            )
+        });
+
+        Self {
+            settings,
+            venue,
+            session: Mutex::new(session),
+            writer: Default::default(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            order_creation_failed_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+            pending_requests: Default::default(),
+            my_trades: Default::default(),
+            // Request ids should be unique between restarts of the application
+            last_request_id: AtomicU64::new(
+                u64::try_from(chrono::Utc::now().timestamp_millis()).unwrap_or_default(),
+            ),
+        }
+    }
+
+    pub fn is_logged_on(&self) -> bool {
+        self.session.lock().state() == SessionState::LoggedOn
+    }
+
+    pub(crate) fn new_request_id(&self) -> String {
+        (self.last_request_id.fetch_add(1, Ordering::Relaxed) + 1).to_string()
+    }
+
+    /// Send application message, it's allowed only after logon
+    pub(crate) fn send_message(&self, mut message: FixMessage) -> Result<()> {
+        self.venue.dictionary.add_extra_fields(&mut message);
+
+        // Messages are written under session lock to keep order of sequence numbers
+        let mut session = self.session.lock();
+        if session.state() != SessionState::LoggedOn {
+            bail!(
+                "FIX session of {} isn't logged on",
+                self.settings.exchange_account_id
+            );
+        }
+
+        let encoded = session.send(&message, Instant::now())?;
+        self.write(encoded)
+    }
+
+    /// Send application message and wait for all responses with specified MsgType(35)
+    /// which are delivered by `add_response` under `request_id`
+    pub(crate) async fn send_request(
+        &self,
+        request_id: String,
+        message: FixMessage,
+        msg_types: &'static [&'static str],
+    ) -> Result<Vec<FixMessage>> {
+        let (sender, receiver) = oneshot::channel();
+        let pending_request = PendingRequest {
+            msg_types,
+            responses: Vec::new(),
+            sender,
+        };
+        let _ = self
+            .pending_requests
+            .lock()
+            .insert(request_id.clone(), pending_request);
+
+        if let Err(err) = self.send_message(message) {
+            let _ = self.pending_requests.lock().remove(&request_id);
+            return Err(err);
+        }
+
+        match tokio::time::timeout(REQUEST_TIMEOUT, receiver).await {
+            Ok(Ok(responses)) => Ok(responses),
+            Ok(Err(_)) => {
+                bail!("FIX session was disconnected before response to request {request_id}")
+            }
+            Err(_) => {
+                let _ = self.pending_requests.lock().remove(&request_id);
+                bail!("Response to FIX request {request_id} wasn't received in {REQUEST_TIMEOUT:?}")
+            }
+        }
+    }
+
+    /// Same as `send_request` for requests with a single response
+    pub(crate) async fn send_single_request(
+        &self,
+        request_id: String,
+        message: FixMessage,
+        msg_types: &'static [&'static str],
+    ) -> Result<FixMessage> {
+        self.send_request(request_id, message, msg_types)
+            .await?
+            .into_iter()
+            .next()
+            .context("No one response to FIX request received")
+    }
+
+    /// Returns `false` if there is no request with such id waiting for message of this type
+    pub(crate) fn add_response(
+        &self,
+        request_id: &str,
+        message: FixMessage,
+        is_last: bool,
+    ) -> bool {
+        let mut pending_requests = self.pending_requests.lock();
+        let Some(pending_request) = pending_requests.get_mut(request_id) else {
+            return false;
+        };
+        if !pending_request.msg_types.contains(&message.msg_type()) {
+            return false;
+        }
+
+        pending_request.responses.push(message);
+        if is_last {
+            if let Some(pending_request) = pending_requests.remove(request_id) {
+                // Requester could stop waiting by timeout
+                let _ = pending_request.sender.send(pending_request.responses);
+            }
+        }
+
+        true
+    }
+
+    pub(crate) fn add_my_trade(&self, currency_pair: CurrencyPair, trade: OrderTrade) {
+        let mut my_trades = self.my_trades.lock();
+        if my_trades.len() == MY_TRADES_CAPACITY {
+            let _ = my_trades.pop_front();
+        }
+        my_trades.push_back((currency_pair, trade));
+    }
+
+    pub(crate) fn get_my_trades_since(
+        &self,
+        currency_pair: CurrencyPair,
+        from_datetime: Option<DateTime>,
+    ) -> Vec<OrderTrade> {
+        self.my_trades
+            .lock()
+            .iter()
+            .filter(|(trade_currency_pair, trade)| {
+                *trade_currency_pair == currency_pair
+                    && match from_datetime {
+                        Some(from_datetime) => trade.datetime >= from_datetime,
+                        None => true,
+                    }
+            })
+            .map(|(_, trade)| trade.clone())
+            .collect()
+    }
+
+    pub(crate) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    pub(crate) fn add_currency_pair(
+        &self,
+        specific_currency_pair: SpecificCurrencyPair,
+        currency_pair: CurrencyPair,
+    ) {
+        self.unified_to_specific
+            .write()
+            .insert(currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, currency_pair);
+    }
+
+    /// Called by transport on each established TCP connection
+    pub(crate) fn on_transport_connected(
+        &self,
+        writer: mpsc::UnboundedSender<String>,
+    ) -> Result<()> {
+        let mut session = self.session.lock();
+        *self.writer.lock() = Some(writer);
+
+        let logon = session.logon(Instant::now())?;
+        self.write(logon)
+    }
+
+    /// Returns `false` if connection should be closed
+    pub(crate) fn on_fix_message(&self, frame: &[u8]) -> Result<bool> {
+        let (begin_string, message) = FixMessage::decode(frame)?;
+        if begin_string != self.venue.dictionary.begin_string {
+            bail!("Unexpected BeginString {begin_string} of FIX message");
+        }
+
+        let output = {
+            let mut session = self.session.lock();
+            let output = session.on_message(message, Instant::now())?;
+            for message in output.outgoing.iter() {
+                self.write(message.clone())?;
+            }
+
+            output
+        };
+
+        // Errors of application layer don't break the session
+        if output.logged_on {
+            if let Err(err) = self.on_logged_on() {
+                log::error!(
+                    "Failed to handle FIX logon of {}: {err:?}",
+                    self.settings.exchange_account_id
+                );
+            }
+        }
+        if let Some(message) = output.application {
+            if let Err(err) = self.handle_application_message(message) {
+                log::error!(
+                    "Failed to handle FIX message of {}: {err:?}",
+                    self.settings.exchange_account_id
+                );
+            }
+        }
+
+        Ok(!output.disconnect)
+    }
+
+    /// Returns `false` if connection should be closed
+    pub(crate) fn on_timer(&self) -> Result<bool> {
+        let mut session = self.session.lock();
+        let output = session.on_timer(Instant::now())?;
+        for message in output.outgoing {
+            self.write(message)?;
+        }
+
+        Ok(!output.disconnect)
+    }
+
+    /// Logout is sent on graceful shutdown, the connection is closed by transport after that
+    pub(crate) fn logout(&self) -> Result<()> {
+        let mut session = self.session.lock();
+        if session.state() != SessionState::LoggedOn {
+            return Ok(());
+        }
+
+        let logout = session.logout(None, Instant::now())?;
+        self.write(logout)
+    }
+
+    pub(crate) fn on_transport_disconnected(&self) {
+        self.session.lock().on_disconnected(Instant::now());
+        *self.writer.lock() = None;
+        // Waiting requests are failed immediately because responses are lost
+        self.pending_requests.lock().clear();
+    }
+
+    fn write(&self, message: String) -> Result<()> {
+        match self.writer.lock().as_ref() {
+            Some(writer) => writer
+                .send(message)
+                .context("FIX connection writer is closed"),
+            None => bail!(
+                "FIX connection of {} isn't established",
+                self.settings.exchange_account_id
+            ),
+        }
+    }
+}
+
+pub struct FixBuilder {
+    venue: FixVenueSettings,
+}
+
+impl FixBuilder {
+    pub fn new(venue: FixVenueSettings) -> Self {
+        FixBuilder { venue }
+    }
+}
+
+impl ExchangeClientBuilder for FixBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Fix::new(
+                exchange_settings,
+                self.venue.clone(),
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    ..OrderFeatures::default()
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: false,
+                    supports_subscription_response: false,
+                },
+                false,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        // FIX venues throttle messages per session instead of REST-like request weights
+        RequestTimeoutArguments::from_requests_per_minute(600)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        self.venue.exchange_id.as_str().into()
+    }
+}
+
+/// MsgTypes(35) of responses to order requests
+pub(crate) mod response_types {
+    use super::msg_type;
+
+    pub const NEW_ORDER: &[&str] = &[
+        msg_type::EXECUTION_REPORT,
+        msg_type::BUSINESS_MESSAGE_REJECT,
+    ];
+    pub const CANCEL_ORDER: &[&str] = &[
+        msg_type::EXECUTION_REPORT,
+        msg_type::ORDER_CANCEL_REJECT,
+        msg_type::BUSINESS_MESSAGE_REJECT,
+    ];
+    pub const MASS_CANCEL: &[&str] = &[
+        msg_type::ORDER_MASS_CANCEL_REPORT,
+        msg_type::BUSINESS_MESSAGE_REJECT,
+    ];
+    pub const STATUS: &[&str] = &[
+        msg_type::EXECUTION_REPORT,
+        msg_type::BUSINESS_MESSAGE_REJECT,
+    ];
+}
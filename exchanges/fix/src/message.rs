@@ -0,0 +1,352 @@
+use crate::fields::tag;
+use anyhow::{bail, Context, Result};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Field delimiter of tag=value encoding
+pub const SOH: char = '\u{1}';
+
+pub type Tag = u32;
+
+/// Length of `10=XXX<SOH>` trailer
+const TRAILER_LENGTH: usize = 7;
+
+/// Read access to fields of message or entry of repeating group
+pub trait FieldAccess {
+    fn fields(&self) -> &[(Tag, String)];
+
+    /// Value of the first field with specified tag
+    fn get(&self, tag: Tag) -> Option<&str> {
+        self.fields()
+            .iter()
+            .find(|(field_tag, _)| *field_tag == tag)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn get_required(&self, tag: Tag) -> Result<&str> {
+        self.get(tag)
+            .with_context(|| format!("Field {tag} is missing in FIX message"))
+    }
+
+    fn parse<T>(&self, tag: Tag) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.get(tag)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|err| anyhow::anyhow!("Unable to parse field {tag}={value}: {err}"))
+            })
+            .transpose()
+    }
+
+    fn parse_required<T>(&self, tag: Tag) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.parse(tag)?
+            .with_context(|| format!("Field {tag} is missing in FIX message"))
+    }
+
+    /// Boolean field is set if its value is `Y`
+    fn has_flag(&self, tag: Tag) -> bool {
+        self.get(tag) == Some("Y")
+    }
+}
+
+/// FIX message without BeginString(8), BodyLength(9) and CheckSum(10) which are added on encoding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixMessage {
+    msg_type: String,
+    /// Fields following MsgType(35) in order of appearance, including rest of standard header
+    fields: Vec<(Tag, String)>,
+}
+
+impl FieldAccess for FixMessage {
+    fn fields(&self) -> &[(Tag, String)] {
+        &self.fields
+    }
+}
+
+impl FixMessage {
+    pub fn new(msg_type: &str) -> Self {
+        FixMessage {
+            msg_type: msg_type.to_owned(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn msg_type(&self) -> &str {
+        &self.msg_type
+    }
+
+    pub fn with(mut self, tag: Tag, value: impl ToString) -> Self {
+        self.push(tag, value);
+        self
+    }
+
+    pub fn push(&mut self, tag: Tag, value: impl ToString) {
+        self.fields.push((tag, value.to_string()));
+    }
+
+    /// Entries of repeating group with specified NoXXX field. Each entry starts with the first field
+    /// following the counter, so fields which are placed after the group are included into the last entry
+    pub fn groups(&self, count_tag: Tag) -> Result<Vec<FixGroup<'_>>> {
+        let Some(count_position) = self.fields.iter().position(|(tag, _)| *tag == count_tag) else {
+            return Ok(Vec::new());
+        };
+        let count: usize = self.fields[count_position]
+            .1
+            .parse()
+            .with_context(|| format!("Unable to parse counter of repeating group {count_tag}"))?;
+        let fields = &self.fields[count_position + 1..];
+        let Some((delimiter, _)) = fields.first() else {
+            return match count {
+                0 => Ok(Vec::new()),
+                _ => bail!("Repeating group {count_tag} is empty, but {count} entries expected"),
+            };
+        };
+
+        let mut groups = Vec::with_capacity(count);
+        let mut start = 0;
+        for (index, (tag, _)) in fields.iter().enumerate().skip(1) {
+            if tag == delimiter {
+                groups.push(FixGroup(&fields[start..index]));
+                start = index;
+            }
+        }
+        groups.push(FixGroup(&fields[start..]));
+
+        if groups.len() != count {
+            bail!(
+                "Repeating group {count_tag} contains {} entries, but {count} expected",
+                groups.len()
+            );
+        }
+
+        Ok(groups)
+    }
+
+    /// Encode message with standard header fields which should follow MsgType(35)
+    pub fn encode(&self, begin_string: &str, header: &[(Tag, String)]) -> String {
+        let mut body = format!("{}={}{SOH}", tag::MSG_TYPE, self.msg_type);
+        for (tag, value) in header.iter().chain(&self.fields) {
+            body.push_str(&format!("{tag}={value}{SOH}"));
+        }
+
+        let mut message = format!(
+            "{}={begin_string}{SOH}{}={}{SOH}{body}",
+            tag::BEGIN_STRING,
+            tag::BODY_LENGTH,
+            body.len()
+        );
+        let checksum = calculate_checksum(message.as_bytes());
+        message.push_str(&format!("{}={checksum:03}{SOH}", tag::CHECK_SUM));
+
+        message
+    }
+
+    /// Decode a single complete message, returns BeginString(8) and the message
+    pub fn decode(frame: &[u8]) -> Result<(String, FixMessage)> {
+        let frame = std::str::from_utf8(frame).context("FIX message isn't valid UTF-8")?;
+        let (body, trailer) = frame
+            .len()
+            .checked_sub(TRAILER_LENGTH)
+            .filter(|position| frame.is_char_boundary(*position))
+            .map(|position| frame.split_at(position))
+            .with_context(|| format!("FIX message is too short: {frame}"))?;
+
+        let expected_checksum: u32 = trailer
+            .strip_prefix("10=")
+            .and_then(|x| x.strip_suffix(SOH))
+            .and_then(|x| x.parse().ok())
+            .with_context(|| format!("Invalid CheckSum of FIX message: {frame}"))?;
+        let checksum = calculate_checksum(body.as_bytes());
+        if checksum != expected_checksum {
+            bail!("CheckSum of FIX message {checksum} doesn't match {expected_checksum}: {frame}");
+        }
+
+        let mut fields = body
+            .strip_suffix(SOH)
+            .with_context(|| format!("FIX message should end with delimiter: {frame}"))?
+            .split(SOH)
+            .map(|field| {
+                let (tag, value) = field
+                    .split_once('=')
+                    .with_context(|| format!("Invalid field '{field}' of FIX message"))?;
+                let tag: Tag = tag
+                    .parse()
+                    .with_context(|| format!("Invalid tag of field '{field}' of FIX message"))?;
+                Ok((tag, value.to_owned()))
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter();
+
+        let (begin_string, body_length, msg_type) =
+            match (fields.next(), fields.next(), fields.next()) {
+                (
+                    Some((tag::BEGIN_STRING, begin_string)),
+                    Some((tag::BODY_LENGTH, body_length)),
+                    Some((tag::MSG_TYPE, msg_type)),
+                ) => (begin_string, body_length, msg_type),
+                _ => bail!(
+                    "FIX message should start with BeginString, BodyLength and MsgType: {frame}"
+                ),
+            };
+
+        let body_start = format!("8={begin_string}{SOH}9={body_length}{SOH}").len();
+        let body_length: usize = body_length
+            .parse()
+            .with_context(|| format!("Invalid BodyLength of FIX message: {frame}"))?;
+        if body.len() - body_start != body_length {
+            bail!("BodyLength of FIX message doesn't match {body_length}: {frame}");
+        }
+
+        Ok((
+            begin_string,
+            FixMessage {
+                msg_type,
+                fields: fields.collect(),
+            },
+        ))
+    }
+}
+
+/// Entry of repeating group
+#[derive(Debug, Clone, Copy)]
+pub struct FixGroup<'a>(&'a [(Tag, String)]);
+
+impl FieldAccess for FixGroup<'_> {
+    fn fields(&self) -> &[(Tag, String)] {
+        self.0
+    }
+}
+
+/// Length of the first complete message in buffer or `None` if it isn't received completely yet
+pub fn find_frame_end(buffer: &[u8]) -> Result<Option<usize>> {
+    const BEGIN_STRING_PREFIX: &[u8] = b"8=";
+    const BODY_LENGTH_PREFIX: &[u8] = b"9=";
+
+    if buffer.len() < BEGIN_STRING_PREFIX.len() {
+        return Ok(None);
+    }
+    if !buffer.starts_with(BEGIN_STRING_PREFIX) {
+        bail!("FIX message should start with BeginString");
+    }
+
+    let delimiter = SOH as u8;
+    let Some(begin_string_end) = buffer.iter().position(|x| *x == delimiter) else {
+        return Ok(None);
+    };
+    let body_length_start = begin_string_end + 1;
+    let Some(body_length_field) = buffer.get(body_length_start..) else {
+        return Ok(None);
+    };
+    let Some(body_length_end) = body_length_field.iter().position(|x| *x == delimiter) else {
+        return Ok(None);
+    };
+
+    let body_length_field = &body_length_field[..body_length_end];
+    let body_length: usize = body_length_field
+        .strip_prefix(BODY_LENGTH_PREFIX)
+        .and_then(|x| std::str::from_utf8(x).ok())
+        .and_then(|x| x.parse().ok())
+        .context("FIX message should contain valid BodyLength after BeginString")?;
+
+    let frame_length = body_length_start + body_length_end + 1 + body_length + TRAILER_LENGTH;
+    match buffer.len() >= frame_length {
+        true => Ok(Some(frame_length)),
+        false => Ok(None),
+    }
+}
+
+fn calculate_checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().map(|x| *x as u32).sum::<u32>() % 256
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fields::msg_type;
+
+    fn to_fix(message: &str) -> String {
+        message.replace('|', &SOH.to_string())
+    }
+
+    #[test]
+    fn encode_message() {
+        let message = FixMessage::new(msg_type::HEARTBEAT).with(tag::TEST_REQ_ID, "TEST");
+        let header = [
+            (tag::SENDER_COMP_ID, "CLIENT".to_owned()),
+            (tag::TARGET_COMP_ID, "VENUE".to_owned()),
+            (tag::MSG_SEQ_NUM, "2".to_owned()),
+        ];
+
+        let encoded = message.encode("FIX.4.4", &header);
+
+        assert_eq!(
+            encoded,
+            to_fix("8=FIX.4.4|9=38|35=0|49=CLIENT|56=VENUE|34=2|112=TEST|10=088|")
+        );
+    }
+
+    #[test]
+    fn decode_encoded_message() {
+        let message = FixMessage::new(msg_type::NEW_ORDER_SINGLE)
+            .with(tag::CL_ORD_ID, "123")
+            .with(tag::SYMBOL, "BTC/USD");
+        let encoded = message.encode("FIX.4.4", &[(tag::MSG_SEQ_NUM, "5".to_owned())]);
+
+        let (begin_string, decoded) =
+            FixMessage::decode(encoded.as_bytes()).expect("Failed to decode message");
+
+        assert_eq!(begin_string, "FIX.4.4");
+        assert_eq!(decoded.msg_type(), msg_type::NEW_ORDER_SINGLE);
+        assert_eq!(decoded.get(tag::MSG_SEQ_NUM), Some("5"));
+        assert_eq!(decoded.get(tag::SYMBOL), Some("BTC/USD"));
+    }
+
+    #[test]
+    fn reject_invalid_checksum() {
+        let message = to_fix("8=FIX.4.4|9=38|35=0|49=CLIENT|56=VENUE|34=2|112=TEST|10=089|");
+
+        assert!(FixMessage::decode(message.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn find_end_of_complete_frames_only() {
+        let frame = to_fix("8=FIX.4.4|9=38|35=0|49=CLIENT|56=VENUE|34=2|112=TEST|10=088|");
+        let buffer = format!("{frame}8=FIX.4.4|9=");
+
+        let frame_length = find_frame_end(buffer.as_bytes()).expect("Failed to find frame");
+        assert_eq!(frame_length, Some(frame.len()));
+
+        let frame_length =
+            find_frame_end(&buffer.as_bytes()[..frame.len() - 1]).expect("Failed to find frame");
+        assert_eq!(frame_length, None);
+    }
+
+    #[test]
+    fn split_repeating_groups() {
+        let message = FixMessage::new(msg_type::MARKET_DATA_INCREMENTAL_REFRESH)
+            .with(tag::NO_MD_ENTRIES, 2)
+            .with(tag::MD_UPDATE_ACTION, 0)
+            .with(tag::MD_ENTRY_TYPE, 0)
+            .with(tag::MD_ENTRY_PX, "100.5")
+            .with(tag::MD_UPDATE_ACTION, 2)
+            .with(tag::MD_ENTRY_TYPE, 1)
+            .with(tag::MD_ENTRY_PX, "101");
+
+        let groups = message
+            .groups(tag::NO_MD_ENTRIES)
+            .expect("Failed to split groups");
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].get(tag::MD_ENTRY_PX), Some("100.5"));
+        assert_eq!(groups[1].get(tag::MD_UPDATE_ACTION), Some("2"));
+        assert_eq!(groups[1].get(tag::MD_ENTRY_PX), Some("101"));
+    }
+}
@@ -0,0 +1,715 @@
+use crate::fields::{msg_type, tag};
+use crate::message::{FieldAccess, FixMessage, Tag};
+use crate::store::{SequenceNumbers, SequenceStore};
+use crate::types::format_utc_timestamp;
+use anyhow::{bail, Result};
+use chrono::Utc;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Sent application messages which are kept for answering resend requests
+const MAX_SENT_MESSAGES_COUNT: usize = 10_000;
+
+pub struct SessionSettings {
+    pub begin_string: String,
+    pub sender_comp_id: String,
+    pub target_comp_id: String,
+    pub heartbeat_interval: Duration,
+    /// Both sides start sequence numbers from 1 on each logon
+    pub reset_seq_num_on_logon: bool,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SessionState {
+    Disconnected,
+    LogonSent,
+    LoggedOn,
+    LogoutSent,
+}
+
+/// Result of processing of incoming message or timer
+#[derive(Debug, Default)]
+pub struct SessionOutput {
+    /// Encoded messages which should be sent to counterparty in the same order
+    pub outgoing: Vec<String>,
+    /// Message which should be handled by application layer
+    pub application: Option<FixMessage>,
+    pub logged_on: bool,
+    /// Connection should be closed after sending of outgoing messages
+    pub disconnect: bool,
+}
+
+/// Application message which can be resent with its original sending time
+struct SentMessage {
+    message: FixMessage,
+    sending_time: String,
+}
+
+/// Session layer of FIX initiator: logon, heartbeats and sequence numbers.
+/// Counterparty's resend requests are answered by resending of application messages with
+/// PossDupFlag(43) = Y. Session level messages and application messages which aren't kept
+/// anymore (sent before restart or evicted) are replaced by gap fill
+pub struct FixSession {
+    settings: SessionSettings,
+    store: Box<dyn SequenceStore>,
+    sequence_numbers: SequenceNumbers,
+    state: SessionState,
+    state_changed: Instant,
+    last_sent: Instant,
+    last_received: Instant,
+    /// TestReqID and time of test request which is waiting for heartbeat
+    test_request: Option<(String, Instant)>,
+    /// Sequence number of the message which revealed a gap, messages are expected to be resent up to it
+    resend_range_end: Option<u64>,
+    /// Outbound application messages by sequence number
+    sent_messages: BTreeMap<u64, SentMessage>,
+}
+
+impl FixSession {
+    pub fn new(settings: SessionSettings, mut store: Box<dyn SequenceStore>) -> Result<Self> {
+        let sequence_numbers = store.load()?;
+        let now = Instant::now();
+
+        Ok(FixSession {
+            settings,
+            store,
+            sequence_numbers,
+            state: SessionState::Disconnected,
+            state_changed: now,
+            last_sent: now,
+            last_received: now,
+            test_request: None,
+            resend_range_end: None,
+            sent_messages: BTreeMap::new(),
+        })
+    }
+
+    pub fn state(&self) -> SessionState {
+        self.state
+    }
+
+    pub fn sequence_numbers(&self) -> SequenceNumbers {
+        self.sequence_numbers
+    }
+
+    pub fn logon(&mut self, now: Instant) -> Result<String> {
+        if self.settings.reset_seq_num_on_logon {
+            self.save_sequence_numbers(SequenceNumbers::default())?;
+            self.sent_messages.clear();
+        }
+
+        let mut logon = FixMessage::new(msg_type::LOGON)
+            .with(tag::ENCRYPT_METHOD, 0)
+            .with(
+                tag::HEART_BT_INT,
+                self.settings.heartbeat_interval.as_secs(),
+            );
+        if self.settings.reset_seq_num_on_logon {
+            logon.push(tag::RESET_SEQ_NUM_FLAG, "Y");
+        }
+        if let Some(username) = &self.settings.username {
+            logon.push(tag::USERNAME, username);
+        }
+        if let Some(password) = &self.settings.password {
+            logon.push(tag::PASSWORD, password);
+        }
+
+        self.test_request = None;
+        self.resend_range_end = None;
+        self.last_received = now;
+        self.set_state(SessionState::LogonSent, now);
+        self.send(&logon, now)
+    }
+
+    pub fn logout(&mut self, text: Option<&str>, now: Instant) -> Result<String> {
+        let mut logout = FixMessage::new(msg_type::LOGOUT);
+        if let Some(text) = text {
+            logout.push(tag::TEXT, text);
+        }
+
+        self.set_state(SessionState::LogoutSent, now);
+        self.send(&logout, now)
+    }
+
+    /// Assign the next sequence number to message and encode it
+    pub fn send(&mut self, message: &FixMessage, now: Instant) -> Result<String> {
+        let seq_num = self.sequence_numbers.next_sender_seq_num;
+        let sending_time = format_utc_timestamp(Utc::now());
+        let encoded = self.encode(message, seq_num, &sending_time, None);
+
+        self.save_sequence_numbers(SequenceNumbers {
+            next_sender_seq_num: seq_num + 1,
+            ..self.sequence_numbers
+        })?;
+        self.last_sent = now;
+
+        if !msg_type::is_admin(message.msg_type()) {
+            let sent_message = SentMessage {
+                message: message.clone(),
+                sending_time,
+            };
+            let _ = self.sent_messages.insert(seq_num, sent_message);
+            if self.sent_messages.len() > MAX_SENT_MESSAGES_COUNT {
+                let _ = self.sent_messages.pop_first();
+            }
+        }
+
+        Ok(encoded)
+    }
+
+    pub fn on_message(&mut self, message: FixMessage, now: Instant) -> Result<SessionOutput> {
+        let mut output = SessionOutput::default();
+        self.last_received = now;
+
+        let sender_comp_id = message.get_required(tag::SENDER_COMP_ID)?;
+        let target_comp_id = message.get_required(tag::TARGET_COMP_ID)?;
+        if sender_comp_id != self.settings.target_comp_id
+            || target_comp_id != self.settings.sender_comp_id
+        {
+            bail!(
+                "Unexpected CompIDs {sender_comp_id}->{target_comp_id} of FIX message {message:?}"
+            );
+        }
+
+        let seq_num: u64 = message.parse_required(tag::MSG_SEQ_NUM)?;
+        let msg_type = message.msg_type();
+
+        if msg_type == msg_type::LOGON && message.has_flag(tag::RESET_SEQ_NUM_FLAG) {
+            self.save_sequence_numbers(SequenceNumbers {
+                next_target_seq_num: 1,
+                ..self.sequence_numbers
+            })?;
+        }
+
+        // Sequence number of SequenceReset in reset mode is ignored
+        if msg_type == msg_type::SEQUENCE_RESET && !message.has_flag(tag::GAP_FILL_FLAG) {
+            self.apply_new_seq_no(&message)?;
+            return Ok(output);
+        }
+
+        let next_target_seq_num = self.sequence_numbers.next_target_seq_num;
+        match seq_num.cmp(&next_target_seq_num) {
+            Ordering::Less => {
+                if !message.has_flag(tag::POSS_DUP_FLAG) {
+                    let text = format!(
+                        "MsgSeqNum too low, expecting {next_target_seq_num} but received {seq_num}"
+                    );
+                    log::error!("FIX session with {}: {text}", self.settings.target_comp_id);
+                    output.outgoing.push(self.logout(Some(&text), now)?);
+                    output.disconnect = true;
+                }
+                // Possible duplicates were already processed
+
+                return Ok(output);
+            }
+            Ordering::Greater => {
+                if self.resend_range_end.is_none() {
+                    log::warn!(
+                        "FIX session with {}: gap of sequence numbers {next_target_seq_num}-{seq_num}, resend is requested",
+                        self.settings.target_comp_id
+                    );
+                    let resend_request = FixMessage::new(msg_type::RESEND_REQUEST)
+                        .with(tag::BEGIN_SEQ_NO, next_target_seq_num)
+                        // Up to infinity
+                        .with(tag::END_SEQ_NO, 0);
+                    output.outgoing.push(self.send(&resend_request, now)?);
+                    self.resend_range_end = Some(seq_num);
+                }
+
+                // Other messages will be received again after resend request
+                match msg_type {
+                    msg_type::LOGON => self.handle_logon(&mut output, now),
+                    msg_type::LOGOUT => self.handle_logout(&mut output, now)?,
+                    msg_type::RESEND_REQUEST => {
+                        self.handle_resend_request(&message, &mut output)?
+                    }
+                    _ => (),
+                }
+
+                return Ok(output);
+            }
+            Ordering::Equal => (),
+        }
+
+        if msg_type == msg_type::SEQUENCE_RESET {
+            self.apply_new_seq_no(&message)?;
+        } else {
+            self.save_sequence_numbers(SequenceNumbers {
+                next_target_seq_num: next_target_seq_num + 1,
+                ..self.sequence_numbers
+            })?;
+        }
+
+        if let Some(resend_range_end) = self.resend_range_end {
+            if self.sequence_numbers.next_target_seq_num > resend_range_end {
+                self.resend_range_end = None;
+            }
+        }
+
+        match msg_type {
+            msg_type::LOGON => self.handle_logon(&mut output, now),
+            msg_type::LOGOUT => self.handle_logout(&mut output, now)?,
+            msg_type::HEARTBEAT => {
+                let test_req_id = message.get(tag::TEST_REQ_ID);
+                if matches!(&self.test_request, Some((id, _)) if Some(id.as_str()) == test_req_id) {
+                    self.test_request = None;
+                }
+            }
+            msg_type::TEST_REQUEST => {
+                let heartbeat = FixMessage::new(msg_type::HEARTBEAT)
+                    .with(tag::TEST_REQ_ID, message.get_required(tag::TEST_REQ_ID)?);
+                output.outgoing.push(self.send(&heartbeat, now)?);
+            }
+            msg_type::RESEND_REQUEST => self.handle_resend_request(&message, &mut output)?,
+            msg_type::REJECT => log::warn!(
+                "FIX session with {} rejected message {:?}: {:?}",
+                self.settings.target_comp_id,
+                message.get(tag::REF_SEQ_NUM),
+                message.get(tag::TEXT)
+            ),
+            msg_type::SEQUENCE_RESET => (),
+            _ => output.application = Some(message),
+        }
+
+        Ok(output)
+    }
+
+    /// Should be called periodically to send heartbeats and detect dead connection
+    pub fn on_timer(&mut self, now: Instant) -> Result<SessionOutput> {
+        let mut output = SessionOutput::default();
+        let heartbeat_interval = self.settings.heartbeat_interval;
+
+        match self.state {
+            SessionState::Disconnected => (),
+            SessionState::LogonSent | SessionState::LogoutSent => {
+                if now - self.state_changed >= heartbeat_interval {
+                    log::error!(
+                        "FIX session with {} wasn't answered in {state:?} state",
+                        self.settings.target_comp_id,
+                        state = self.state
+                    );
+                    output.disconnect = true;
+                }
+            }
+            SessionState::LoggedOn => {
+                match &self.test_request {
+                    Some((_, sent)) => {
+                        if now - *sent >= heartbeat_interval {
+                            log::error!(
+                                "FIX session with {} didn't answer test request",
+                                self.settings.target_comp_id
+                            );
+                            output.disconnect = true;
+                            return Ok(output);
+                        }
+                    }
+                    None => {
+                        // Some transmission time is allowed according to FIX specification
+                        if now - self.last_received >= heartbeat_interval + heartbeat_interval / 5 {
+                            let test_req_id =
+                                format!("TEST{}", self.sequence_numbers.next_sender_seq_num);
+                            let test_request = FixMessage::new(msg_type::TEST_REQUEST)
+                                .with(tag::TEST_REQ_ID, &test_req_id);
+                            output.outgoing.push(self.send(&test_request, now)?);
+                            self.test_request = Some((test_req_id, now));
+                        }
+                    }
+                }
+
+                if now - self.last_sent >= heartbeat_interval {
+                    let heartbeat = FixMessage::new(msg_type::HEARTBEAT);
+                    output.outgoing.push(self.send(&heartbeat, now)?);
+                }
+            }
+        }
+
+        Ok(output)
+    }
+
+    pub fn on_disconnected(&mut self, now: Instant) {
+        self.test_request = None;
+        self.resend_range_end = None;
+        self.set_state(SessionState::Disconnected, now);
+    }
+
+    fn handle_logon(&mut self, output: &mut SessionOutput, now: Instant) {
+        if self.state != SessionState::LogonSent {
+            log::warn!(
+                "Unexpected Logon from FIX session with {} in {:?} state",
+                self.settings.target_comp_id,
+                self.state
+            );
+            return;
+        }
+
+        log::info!(
+            "FIX session with {} is logged on",
+            self.settings.target_comp_id
+        );
+        self.set_state(SessionState::LoggedOn, now);
+        output.logged_on = true;
+    }
+
+    fn handle_logout(&mut self, output: &mut SessionOutput, now: Instant) -> Result<()> {
+        log::info!(
+            "FIX session with {} is logged out",
+            self.settings.target_comp_id
+        );
+        if self.state != SessionState::LogoutSent {
+            output.outgoing.push(self.logout(None, now)?);
+        }
+
+        output.disconnect = true;
+        Ok(())
+    }
+
+    /// Kept application messages are resent as possible duplicates,
+    /// each range of other requested messages is replaced by a single gap fill
+    fn handle_resend_request(
+        &mut self,
+        message: &FixMessage,
+        output: &mut SessionOutput,
+    ) -> Result<()> {
+        let begin_seq_no: u64 = message.parse_required(tag::BEGIN_SEQ_NO)?;
+        let end_seq_no: u64 = message.parse_required(tag::END_SEQ_NO)?;
+        let last_sender_seq_num = self.sequence_numbers.next_sender_seq_num.saturating_sub(1);
+        // Zero means up to infinity
+        let end_seq_no = match end_seq_no {
+            0 => last_sender_seq_num,
+            _ => end_seq_no.min(last_sender_seq_num),
+        };
+        if begin_seq_no > end_seq_no {
+            return Ok(());
+        }
+
+        let sending_time = format_utc_timestamp(Utc::now());
+        let mut gap_begin_seq_no = begin_seq_no;
+        for (&seq_num, sent_message) in self.sent_messages.range(begin_seq_no..=end_seq_no) {
+            if seq_num > gap_begin_seq_no {
+                output.outgoing.push(self.encode_gap_fill(
+                    gap_begin_seq_no,
+                    seq_num,
+                    &sending_time,
+                ));
+            }
+
+            output.outgoing.push(self.encode(
+                &sent_message.message,
+                seq_num,
+                &sending_time,
+                Some(&sent_message.sending_time),
+            ));
+            gap_begin_seq_no = seq_num + 1;
+        }
+
+        if gap_begin_seq_no <= end_seq_no {
+            output.outgoing.push(self.encode_gap_fill(
+                gap_begin_seq_no,
+                end_seq_no + 1,
+                &sending_time,
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn encode_gap_fill(&self, seq_num: u64, new_seq_no: u64, sending_time: &str) -> String {
+        let gap_fill = FixMessage::new(msg_type::SEQUENCE_RESET)
+            .with(tag::GAP_FILL_FLAG, "Y")
+            .with(tag::NEW_SEQ_NO, new_seq_no);
+
+        self.encode(&gap_fill, seq_num, sending_time, Some(sending_time))
+    }
+
+    fn apply_new_seq_no(&mut self, message: &FixMessage) -> Result<()> {
+        let new_seq_no: u64 = message.parse_required(tag::NEW_SEQ_NO)?;
+        if new_seq_no < self.sequence_numbers.next_target_seq_num {
+            bail!(
+                "SequenceReset of FIX session with {} can't decrease sequence number from {} to {new_seq_no}",
+                self.settings.target_comp_id,
+                self.sequence_numbers.next_target_seq_num
+            );
+        }
+
+        self.save_sequence_numbers(SequenceNumbers {
+            next_target_seq_num: new_seq_no,
+            ..self.sequence_numbers
+        })
+    }
+
+    /// Message is encoded as possible duplicate if original sending time is specified
+    fn encode(
+        &self,
+        message: &FixMessage,
+        seq_num: u64,
+        sending_time: &str,
+        orig_sending_time: Option<&str>,
+    ) -> String {
+        let mut header: Vec<(Tag, String)> = vec![
+            (tag::SENDER_COMP_ID, self.settings.sender_comp_id.clone()),
+            (tag::TARGET_COMP_ID, self.settings.target_comp_id.clone()),
+            (tag::MSG_SEQ_NUM, seq_num.to_string()),
+        ];
+        if let Some(orig_sending_time) = orig_sending_time {
+            header.push((tag::POSS_DUP_FLAG, "Y".to_owned()));
+            header.push((tag::ORIG_SENDING_TIME, orig_sending_time.to_owned()));
+        }
+        header.push((tag::SENDING_TIME, sending_time.to_owned()));
+
+        message.encode(&self.settings.begin_string, &header)
+    }
+
+    fn save_sequence_numbers(&mut self, sequence_numbers: SequenceNumbers) -> Result<()> {
+        self.store.save(sequence_numbers)?;
+        self.sequence_numbers = sequence_numbers;
+
+        Ok(())
+    }
+
+    fn set_state(&mut self, state: SessionState, now: Instant) {
+        self.state = state;
+        self.state_changed = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{FileSequenceStore, MemorySequenceStore};
+
+    const BEGIN_STRING: &str = "FIX.4.4";
+
+    fn session_settings() -> SessionSettings {
+        SessionSettings {
+            begin_string: BEGIN_STRING.to_owned(),
+            sender_comp_id: "CLIENT".to_owned(),
+            target_comp_id: "VENUE".to_owned(),
+            heartbeat_interval: Duration::from_secs(30),
+            reset_seq_num_on_logon: false,
+            username: None,
+            password: None,
+        }
+    }
+
+    fn create_session() -> FixSession {
+        FixSession::new(session_settings(), Box::new(MemorySequenceStore::default()))
+            .expect("Failed to create session")
+    }
+
+    fn incoming(msg_type: &str, seq_num: u64) -> FixMessage {
+        FixMessage::new(msg_type)
+            .with(tag::SENDER_COMP_ID, "VENUE")
+            .with(tag::TARGET_COMP_ID, "CLIENT")
+            .with(tag::MSG_SEQ_NUM, seq_num)
+    }
+
+    fn decode(message: &str) -> FixMessage {
+        FixMessage::decode(message.as_bytes())
+            .expect("Failed to decode message")
+            .1
+    }
+
+    fn logged_on_session(now: Instant) -> FixSession {
+        let mut session = create_session();
+        session.logon(now).expect("Failed to logon");
+        let output = session
+            .on_message(incoming(msg_type::LOGON, 1), now)
+            .expect("Failed to handle logon");
+        assert!(output.logged_on);
+
+        session
+    }
+
+    #[test]
+    fn request_resend_on_sequence_gap() {
+        let now = Instant::now();
+        let mut session = logged_on_session(now);
+
+        let output = session
+            .on_message(incoming(msg_type::EXECUTION_REPORT, 5), now)
+            .expect("Failed to handle message");
+
+        assert!(output.application.is_none());
+        assert_eq!(output.outgoing.len(), 1);
+        let resend_request = decode(&output.outgoing[0]);
+        assert_eq!(resend_request.msg_type(), msg_type::RESEND_REQUEST);
+        assert_eq!(resend_request.get(tag::BEGIN_SEQ_NO), Some("2"));
+        assert_eq!(resend_request.get(tag::END_SEQ_NO), Some("0"));
+
+        // Gap is filled by counterparty
+        let gap_fill = incoming(msg_type::SEQUENCE_RESET, 2)
+            .with(tag::GAP_FILL_FLAG, "Y")
+            .with(tag::NEW_SEQ_NO, 5);
+        session
+            .on_message(gap_fill, now)
+            .expect("Failed to handle gap fill");
+        let output = session
+            .on_message(incoming(msg_type::EXECUTION_REPORT, 5), now)
+            .expect("Failed to handle message");
+
+        assert!(output.application.is_some());
+        assert_eq!(session.sequence_numbers().next_target_seq_num, 6);
+    }
+
+    #[test]
+    fn answer_resend_request_by_resending_application_messages() {
+        let now = Instant::now();
+        let mut session = logged_on_session(now);
+        let order = FixMessage::new(msg_type::NEW_ORDER_SINGLE).with(tag::CL_ORD_ID, "ORDER_1");
+        let sent_order = decode(&session.send(&order, now).expect("Failed to send"));
+        let _ = session
+            .send(&FixMessage::new(msg_type::HEARTBEAT), now)
+            .expect("Failed to send");
+
+        let resend_request = incoming(msg_type::RESEND_REQUEST, 2)
+            .with(tag::BEGIN_SEQ_NO, 1)
+            .with(tag::END_SEQ_NO, 0);
+        let output = session
+            .on_message(resend_request, now)
+            .expect("Failed to handle resend request");
+
+        let resent: Vec<_> = output.outgoing.iter().map(|x| decode(x)).collect();
+        assert_eq!(resent.len(), 3);
+
+        // Logon is replaced by gap fill
+        assert_eq!(resent[0].msg_type(), msg_type::SEQUENCE_RESET);
+        assert_eq!(resent[0].get(tag::MSG_SEQ_NUM), Some("1"));
+        assert_eq!(resent[0].get(tag::NEW_SEQ_NO), Some("2"));
+        assert!(resent[0].has_flag(tag::GAP_FILL_FLAG));
+        assert!(resent[0].has_flag(tag::POSS_DUP_FLAG));
+
+        assert_eq!(resent[1].msg_type(), msg_type::NEW_ORDER_SINGLE);
+        assert_eq!(resent[1].get(tag::MSG_SEQ_NUM), Some("2"));
+        assert_eq!(resent[1].get(tag::CL_ORD_ID), Some("ORDER_1"));
+        assert!(resent[1].has_flag(tag::POSS_DUP_FLAG));
+        assert_eq!(
+            resent[1].get(tag::ORIG_SENDING_TIME),
+            sent_order.get(tag::SENDING_TIME)
+        );
+
+        // Heartbeat is replaced by gap fill
+        assert_eq!(resent[2].msg_type(), msg_type::SEQUENCE_RESET);
+        assert_eq!(resent[2].get(tag::MSG_SEQ_NUM), Some("3"));
+        assert_eq!(resent[2].get(tag::NEW_SEQ_NO), Some("4"));
+
+        assert_eq!(session.sequence_numbers().next_sender_seq_num, 4);
+    }
+
+    #[test]
+    fn answer_resend_request_of_limited_range() {
+        let now = Instant::now();
+        let mut session = logged_on_session(now);
+        for cl_ord_id in ["ORDER_1", "ORDER_2", "ORDER_3"] {
+            let order = FixMessage::new(msg_type::NEW_ORDER_SINGLE).with(tag::CL_ORD_ID, cl_ord_id);
+            let _ = session.send(&order, now).expect("Failed to send");
+        }
+
+        let resend_request = incoming(msg_type::RESEND_REQUEST, 2)
+            .with(tag::BEGIN_SEQ_NO, 3)
+            .with(tag::END_SEQ_NO, 3);
+        let output = session
+            .on_message(resend_request, now)
+            .expect("Failed to handle resend request");
+
+        assert_eq!(output.outgoing.len(), 1);
+        let resent = decode(&output.outgoing[0]);
+        assert_eq!(resent.get(tag::MSG_SEQ_NUM), Some("3"));
+        assert_eq!(resent.get(tag::CL_ORD_ID), Some("ORDER_2"));
+        assert!(resent.has_flag(tag::POSS_DUP_FLAG));
+    }
+
+    #[test]
+    fn answer_resend_request_by_gap_fill_after_restart() {
+        let path = std::env::temp_dir().join(format!(
+            "fix_session_sequence_numbers_{}.txt",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let now = Instant::now();
+        let mut session = FixSession::new(
+            session_settings(),
+            Box::new(FileSequenceStore::new(path.clone())),
+        )
+        .expect("Failed to create session");
+        let _ = session.logon(now).expect("Failed to logon");
+        let _ = session
+            .send(&FixMessage::new(msg_type::NEW_ORDER_SINGLE), now)
+            .expect("Failed to send");
+
+        // Messages sent before restart aren't kept
+        let mut session = FixSession::new(
+            session_settings(),
+            Box::new(FileSequenceStore::new(path.clone())),
+        )
+        .expect("Failed to create session");
+        let _ = std::fs::remove_file(&path);
+
+        let resend_request = incoming(msg_type::RESEND_REQUEST, 1)
+            .with(tag::BEGIN_SEQ_NO, 1)
+            .with(tag::END_SEQ_NO, 0);
+        let output = session
+            .on_message(resend_request, now)
+            .expect("Failed to handle resend request");
+
+        assert_eq!(output.outgoing.len(), 1);
+        let gap_fill = decode(&output.outgoing[0]);
+        assert_eq!(gap_fill.msg_type(), msg_type::SEQUENCE_RESET);
+        assert_eq!(gap_fill.get(tag::MSG_SEQ_NUM), Some("1"));
+        assert_eq!(gap_fill.get(tag::NEW_SEQ_NO), Some("3"));
+        assert!(gap_fill.has_flag(tag::GAP_FILL_FLAG));
+    }
+
+    #[test]
+    fn logout_on_too_low_sequence_number() {
+        let now = Instant::now();
+        let mut session = logged_on_session(now);
+
+        let duplicate = incoming(msg_type::EXECUTION_REPORT, 1).with(tag::POSS_DUP_FLAG, "Y");
+        let output = session
+            .on_message(duplicate, now)
+            .expect("Failed to handle duplicate");
+        assert!(output.outgoing.is_empty() && output.application.is_none());
+
+        let output = session
+            .on_message(incoming(msg_type::EXECUTION_REPORT, 1), now)
+            .expect("Failed to handle message");
+        assert!(output.disconnect);
+        assert_eq!(decode(&output.outgoing[0]).msg_type(), msg_type::LOGOUT);
+    }
+
+    #[test]
+    fn heartbeats_and_test_requests() {
+        let now = Instant::now();
+        let mut session = logged_on_session(now);
+
+        let test_request = incoming(msg_type::TEST_REQUEST, 2).with(tag::TEST_REQ_ID, "ID");
+        let output = session
+            .on_message(test_request, now)
+            .expect("Failed to handle test request");
+        let heartbeat = decode(&output.outgoing[0]);
+        assert_eq!(heartbeat.msg_type(), msg_type::HEARTBEAT);
+        assert_eq!(heartbeat.get(tag::TEST_REQ_ID), Some("ID"));
+
+        // Counterparty is silent longer than heartbeat interval
+        let output = session
+            .on_timer(now + Duration::from_secs(40))
+            .expect("Failed to handle timer");
+        let msg_types: Vec<_> = output
+            .outgoing
+            .iter()
+            .map(|x| decode(x).msg_type().to_owned())
+            .collect();
+        // Test request replaces heartbeat
+        assert_eq!(msg_types, [msg_type::TEST_REQUEST]);
+        assert!(!output.disconnect);
+
+        let output = session
+            .on_timer(now + Duration::from_secs(70))
+            .expect("Failed to handle timer");
+        assert!(output.disconnect);
+    }
+}
@@ -0,0 +1,48 @@
+use crate::dictionary::FixDictionary;
+use mmb_domain::market::{CurrencyCode, SpecificCurrencyPair};
+use mmb_domain::order::snapshot::{Amount, Price};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Connection and protocol settings of a FIX venue.
+/// Credentials are taken from `api_key` and `secret_key` of `ExchangeSettings`
+/// and sent as Username(553) and Password(554) of Logon message if specified
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FixVenueSettings {
+    pub exchange_id: String,
+    /// TCP address of FIX acceptor in `host:port` format
+    pub address: String,
+    pub sender_comp_id: String,
+    pub target_comp_id: String,
+    #[serde(default = "default_heartbeat_interval_secs")]
+    pub heartbeat_interval_secs: u64,
+    #[serde(default = "default_reconnect_interval_secs")]
+    pub reconnect_interval_secs: u64,
+    #[serde(default)]
+    pub reset_seq_num_on_logon: bool,
+    /// File for persisting of sequence numbers between restarts. They are kept in memory if not specified
+    pub sequence_store_path: Option<PathBuf>,
+    #[serde(default)]
+    pub dictionary: FixDictionary,
+    /// FIX venues don't provide a standard way to request instruments, so they are configured
+    pub instruments: Vec<FixInstrument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FixInstrument {
+    /// Value of Symbol(55) field
+    pub symbol: SpecificCurrencyPair,
+    pub base: CurrencyCode,
+    pub quote: CurrencyCode,
+    pub price_tick: Price,
+    pub amount_tick: Amount,
+    pub min_amount: Option<Amount>,
+}
+
+fn default_heartbeat_interval_secs() -> u64 {
+    30
+}
+
+fn default_reconnect_interval_secs() -> u64 {
+    5
+}
@@ -0,0 +1,120 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+/// Sequence numbers of the next outgoing and incoming messages of FIX session
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SequenceNumbers {
+    pub next_sender_seq_num: u64,
+    pub next_target_seq_num: u64,
+}
+
+impl Default for SequenceNumbers {
+    fn default() -> Self {
+        SequenceNumbers {
+            next_sender_seq_num: 1,
+            next_target_seq_num: 1,
+        }
+    }
+}
+
+/// Storage of sequence numbers which allows to continue FIX session after reconnection or restart
+pub trait SequenceStore: Send {
+    fn load(&mut self) -> Result<SequenceNumbers>;
+
+    fn save(&mut self, sequence_numbers: SequenceNumbers) -> Result<()>;
+}
+
+/// Sequence numbers are lost on restart, so the session should be reset on logon
+#[derive(Default)]
+pub struct MemorySequenceStore(SequenceNumbers);
+
+impl SequenceStore for MemorySequenceStore {
+    fn load(&mut self) -> Result<SequenceNumbers> {
+        Ok(self.0)
+    }
+
+    fn save(&mut self, sequence_numbers: SequenceNumbers) -> Result<()> {
+        self.0 = sequence_numbers;
+        Ok(())
+    }
+}
+
+/// Sequence numbers are stored in text file as `<next sender seq num> <next target seq num>`
+pub struct FileSequenceStore {
+    path: PathBuf,
+}
+
+impl FileSequenceStore {
+    pub fn new(path: PathBuf) -> Self {
+        FileSequenceStore { path }
+    }
+}
+
+impl SequenceStore for FileSequenceStore {
+    fn load(&mut self) -> Result<SequenceNumbers> {
+        if !self.path.exists() {
+            return Ok(SequenceNumbers::default());
+        }
+
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Unable to read FIX sequence numbers from {:?}", self.path))?;
+        let (sender, target) = content.trim().split_once(' ').with_context(|| {
+            format!("Invalid FIX sequence numbers {content} in {:?}", self.path)
+        })?;
+
+        Ok(SequenceNumbers {
+            next_sender_seq_num: sender
+                .parse()
+                .with_context(|| format!("Invalid FIX sender sequence number {sender}"))?,
+            next_target_seq_num: target
+                .parse()
+                .with_context(|| format!("Invalid FIX target sequence number {target}"))?,
+        })
+    }
+
+    fn save(&mut self, sequence_numbers: SequenceNumbers) -> Result<()> {
+        // File is replaced by renaming to avoid partially written content on crash
+        let temp_path = self.path.with_extension("tmp");
+        fs::write(
+            &temp_path,
+            format!(
+                "{} {}",
+                sequence_numbers.next_sender_seq_num, sequence_numbers.next_target_seq_num
+            ),
+        )
+        .with_context(|| format!("Unable to write FIX sequence numbers to {temp_path:?}"))?;
+
+        fs::rename(&temp_path, &self.path)
+            .with_context(|| format!("Unable to save FIX sequence numbers to {:?}", self.path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_store_keeps_sequence_numbers() {
+        let path =
+            std::env::temp_dir().join(format!("fix_sequence_numbers_{}.txt", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut store = FileSequenceStore::new(path.clone());
+        assert_eq!(
+            store.load().expect("Failed to load"),
+            SequenceNumbers::default()
+        );
+
+        let sequence_numbers = SequenceNumbers {
+            next_sender_seq_num: 15,
+            next_target_seq_num: 42,
+        };
+        store.save(sequence_numbers).expect("Failed to save");
+
+        let mut store = FileSequenceStore::new(path.clone());
+        assert_eq!(store.load().expect("Failed to load"), sequence_numbers);
+
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,503 @@
+use crate::fields::{exec_type, md_entry_type, md_update_action, msg_type, tag};
+use crate::fix::Fix;
+use crate::message::{FieldAccess, FixMessage};
+use crate::transport::run_session;
+use crate::types::{order_reject_error_type, parse_utc_timestamp, side_from_fix};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::WebSocketRole;
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::exchange::Exchange;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::traits::{
+    ExchangeError, HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb,
+    OrderCreatedCb, OrderCreationFailedCb, SendWebsocketMessageCb, Support,
+};
+use mmb_core::infrastructure::spawn_future;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::snapshot::{Amount, ClientOrderId, ExchangeOrderId, OrderRole, Price};
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+/// Placeholder of OrderID(37) which is sent if there are no orders in response to status request
+const NO_ORDER_ID: &str = "NONE";
+
+#[async_trait]
+impl Support for Fix {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    async fn initialized(&self, exchange: Arc<Exchange>) {
+        let _ = spawn_future(
+            "FIX session",
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            run_session(
+                Arc::downgrade(&exchange),
+                self.venue.address.clone(),
+                Duration::from_secs(self.venue.reconnect_interval_secs),
+                self.lifetime_manager.stop_token(),
+            ),
+        );
+    }
+
+    fn on_websocket_message(&self, _msg: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_order_creation_failed_callback(&mut self, callback: OrderCreationFailedCb) {
+        self.order_creation_failed_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, _role: WebSocketRole) -> bool {
+        false
+    }
+
+    fn is_connection_managed_by_client(&self) -> bool {
+        true
+    }
+
+    async fn create_ws_url(&self, _role: WebSocketRole) -> Result<Url> {
+        bail!("FIX venues don't use websocket connection")
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        [
+            msg_type::EXECUTION_REPORT,
+            msg_type::ORDER_CANCEL_REJECT,
+            msg_type::BUSINESS_MESSAGE_REJECT,
+            msg_type::REJECT,
+        ]
+        .iter()
+        .any(|msg_type| message.contains(&format!("\u{1}35={msg_type}\u{1}")))
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+}
+
+impl Fix {
+    pub(crate) fn on_logged_on(&self) -> Result<()> {
+        match self.settings.subscribe_to_market_data {
+            true => self.subscribe_to_market_data(),
+            false => Ok(()),
+        }
+    }
+
+    pub(crate) fn handle_application_message(&self, message: FixMessage) -> Result<()> {
+        self.venue.dictionary.validate(&message)?;
+
+        match message.msg_type() {
+            msg_type::EXECUTION_REPORT => self.handle_execution_report(message),
+            msg_type::ORDER_CANCEL_REJECT | msg_type::ORDER_MASS_CANCEL_REPORT => {
+                let client_order_id = message.get_required(tag::CL_ORD_ID)?.to_owned();
+                if !self.add_response(&client_order_id, message, true) {
+                    log::warn!("Unexpected FIX response to request {client_order_id}");
+                }
+                Ok(())
+            }
+            msg_type::BUSINESS_MESSAGE_REJECT => {
+                let text = message.get(tag::TEXT).unwrap_or_default().to_owned();
+                let reject_ref_id = message.get(tag::BUSINESS_REJECT_REF_ID).map(str::to_owned);
+                let is_handled = reject_ref_id
+                    .as_ref()
+                    .map(|request_id| self.add_response(request_id, message, true))
+                    .unwrap_or_default();
+                if !is_handled {
+                    log::error!("FIX business message reject of {reject_ref_id:?}: {text}");
+                }
+                Ok(())
+            }
+            msg_type::MARKET_DATA_SNAPSHOT_FULL_REFRESH => {
+                self.handle_market_data_snapshot(&message)
+            }
+            msg_type::MARKET_DATA_INCREMENTAL_REFRESH => {
+                self.handle_market_data_incremental_refresh(&message)
+            }
+            msg_type::MARKET_DATA_REQUEST_REJECT => bail!(
+                "FIX market data request {:?} rejected with reason {:?}: {:?}",
+                message.get(tag::MD_REQ_ID),
+                message.get(tag::MD_REQ_REJ_REASON),
+                message.get(tag::TEXT)
+            ),
+            _ => {
+                self.log_unknown_message(
+                    self.settings.exchange_account_id,
+                    &format!("{message:?}"),
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Execution reports are responses to requests or unsolicited order updates.
+    /// Callbacks are called only for the latter, responses are handled by requester
+    fn handle_execution_report(&self, message: FixMessage) -> Result<()> {
+        if let Some(mass_status_req_id) = message.get(tag::MASS_STATUS_REQ_ID) {
+            let request_id = mass_status_req_id.to_owned();
+            let is_last = message.has_flag(tag::LAST_RPT_REQUESTED);
+            let _ = self.add_response(&request_id, message, is_last);
+            return Ok(());
+        }
+
+        let client_order_id = message.get_required(tag::CL_ORD_ID)?.to_owned();
+        match message.get_required(tag::EXEC_TYPE)? {
+            exec_type::ORDER_STATUS => {
+                let request_id = message
+                    .get(tag::ORD_STATUS_REQ_ID)
+                    .map(str::to_owned)
+                    .unwrap_or(client_order_id);
+                let _ = self.add_response(&request_id, message, true);
+            }
+            exec_type::NEW => {
+                let exchange_order_id = get_exchange_order_id(&message)?;
+                if !self.add_response(&client_order_id, message, true) {
+                    (self.order_created_callback)(
+                        client_order_id.as_str().into(),
+                        exchange_order_id,
+                        EventSourceType::WebSocket,
+                    );
+                }
+            }
+            exec_type::REJECTED => {
+                let exchange_error = ExchangeError::new(
+                    order_reject_error_type(message.get(tag::ORD_REJ_REASON)),
+                    message.get(tag::TEXT).unwrap_or_default().to_owned(),
+                    None,
+                );
+                if !self.add_response(&client_order_id, message, true) {
+                    log::warn!("FIX order {client_order_id} was rejected: {exchange_error:?}");
+                    (self.order_creation_failed_callback)(
+                        client_order_id.as_str().into(),
+                        exchange_error,
+                        EventSourceType::WebSocket,
+                    );
+                }
+            }
+            exec_type::CANCELED | exec_type::EXPIRED => {
+                let exchange_order_id = get_exchange_order_id(&message)?;
+                // Cancellation by request has ClOrdID of cancel request and OrigClOrdID of order
+                let order_client_order_id = message
+                    .get(tag::ORIG_CL_ORD_ID)
+                    .unwrap_or(&client_order_id)
+                    .to_owned();
+                if !self.add_response(&client_order_id, message, true) {
+                    (self.order_cancelled_callback)(
+                        order_client_order_id.as_str().into(),
+                        exchange_order_id,
+                        EventSourceType::WebSocket,
+                    );
+                }
+            }
+            exec_type::TRADE => {
+                let fill_event = self.create_fill_event(&message)?;
+                // Order can be filled immediately without ExecutionReport with ExecType New
+                let _ = self.add_response(&client_order_id, message, true);
+                (self.handle_order_filled_callback)(fill_event);
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    fn create_fill_event(&self, message: &FixMessage) -> Result<FillEvent> {
+        let specific_currency_pair = message.get_required(tag::SYMBOL)?.into();
+        let currency_pair = self.get_unified_currency_pair(&specific_currency_pair)?;
+        let exchange_order_id = get_exchange_order_id(message)?;
+        let trade_id = match message.get(tag::TRADE_ID) {
+            Some(trade_id) => TradeId::from(trade_id.to_owned()),
+            None => TradeId::from(message.get_required(tag::EXEC_ID)?.to_owned()),
+        };
+        let fill_price: Price = message.parse_required(tag::LAST_PX)?;
+        let fill_amount: Amount = message.parse_required(tag::LAST_QTY)?;
+        let commission_amount: Option<Amount> = message.parse(tag::COMMISSION)?;
+        let fill_date = match message.get(tag::TRANSACT_TIME) {
+            Some(transact_time) => parse_utc_timestamp(transact_time)?,
+            None => Utc::now(),
+        };
+        let order_role = get_order_role(message);
+        // Commission is charged in quote currency by default
+        let commission_currency_code = currency_pair.to_codes().quote;
+
+        self.add_my_trade(
+            currency_pair,
+            OrderTrade::new(
+                exchange_order_id.clone(),
+                trade_id.clone(),
+                fill_date,
+                fill_price,
+                fill_amount,
+                order_role,
+                commission_currency_code,
+                None,
+                commission_amount,
+                OrderFillType::UserTrade,
+            ),
+        );
+
+        Ok(FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(trade_id),
+            client_order_id: Some(ClientOrderId::from(message.get_required(tag::CL_ORD_ID)?)),
+            exchange_order_id,
+            fill_price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount,
+                total_filled_amount: message.parse(tag::CUM_QTY)?,
+            },
+            order_role: Some(order_role),
+            commission_currency_code: Some(commission_currency_code),
+            commission_rate: None,
+            commission_amount,
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date: Some(fill_date),
+        })
+    }
+
+    fn subscribe_to_market_data(&self) -> Result<()> {
+        let traded_currencies = self.traded_specific_currencies.lock().clone();
+        if traded_currencies.is_empty() {
+            return Ok(());
+        }
+
+        let mut entry_types = vec![md_entry_type::BID, md_entry_type::OFFER];
+        if self.settings.request_trades {
+            entry_types.push(md_entry_type::TRADE);
+        }
+
+        let mut request = FixMessage::new(msg_type::MARKET_DATA_REQUEST)
+            .with(tag::MD_REQ_ID, self.new_request_id())
+            // Snapshot and updates
+            .with(tag::SUBSCRIPTION_REQUEST_TYPE, 1)
+            .with(tag::MARKET_DEPTH, self.venue.dictionary.market_depth)
+            // Incremental refresh
+            .with(tag::MD_UPDATE_TYPE, 1)
+            .with(tag::NO_MD_ENTRY_TYPES, entry_types.len());
+        for entry_type in entry_types {
+            request.push(tag::MD_ENTRY_TYPE, entry_type);
+        }
+        request.push(tag::NO_RELATED_SYM, traded_currencies.len());
+        for specific_currency_pair in traded_currencies {
+            request.push(tag::SYMBOL, specific_currency_pair);
+        }
+
+        self.send_message(request)
+    }
+
+    fn handle_market_data_snapshot(&self, message: &FixMessage) -> Result<()> {
+        let specific_currency_pair = message.get_required(tag::SYMBOL)?.into();
+        let currency_pair = self.get_unified_currency_pair(&specific_currency_pair)?;
+
+        let mut order_book_data = OrderBookData::default();
+        for entry in message.groups(tag::NO_MD_ENTRIES)? {
+            let price: Price = entry.parse_required(tag::MD_ENTRY_PX)?;
+            let amount: Amount = entry.parse_required(tag::MD_ENTRY_SIZE)?;
+            match entry.get_required(tag::MD_ENTRY_TYPE)? {
+                md_entry_type::BID => {
+                    let _ = order_book_data.bids.insert(price, amount);
+                }
+                md_entry_type::OFFER => {
+                    let _ = order_book_data.asks.insert(price, amount);
+                }
+                // Trades before subscription aren't needed
+                _ => (),
+            }
+        }
+
+        self.send_order_book_event(currency_pair, EventType::Snapshot, order_book_data)
+    }
+
+    /// Entries of incremental refresh can belong to different instruments,
+    /// Symbol(55) of entry is inherited from the previous one if it's omitted
+    fn handle_market_data_incremental_refresh(&self, message: &FixMessage) -> Result<()> {
+        let mut updates: HashMap<SpecificCurrencyPair, OrderBookData> = HashMap::new();
+        let mut symbol: Option<SpecificCurrencyPair> = message.get(tag::SYMBOL).map(Into::into);
+
+        for entry in message.groups(tag::NO_MD_ENTRIES)? {
+            if let Some(entry_symbol) = entry.get(tag::SYMBOL) {
+                symbol = Some(entry_symbol.into());
+            }
+            let specific_currency_pair = symbol
+                .with_context(|| format!("Symbol is missing in FIX market data entry {entry:?}"))?;
+
+            match entry.get_required(tag::MD_ENTRY_TYPE)? {
+                md_entry_type::TRADE => self.handle_trade(specific_currency_pair, &entry)?,
+                entry_type @ (md_entry_type::BID | md_entry_type::OFFER) => {
+                    let price: Price = entry.parse_required(tag::MD_ENTRY_PX)?;
+                    let amount = match entry.get_required(tag::MD_UPDATE_ACTION)? {
+                        md_update_action::DELETE => Amount::ZERO,
+                        md_update_action::NEW | md_update_action::CHANGE => {
+                            entry.parse_required(tag::MD_ENTRY_SIZE)?
+                        }
+                        action => bail!("Unsupported FIX market data update action {action}"),
+                    };
+
+                    let update = updates.entry(specific_currency_pair).or_default();
+                    let _ = match entry_type {
+                        md_entry_type::BID => update.bids.insert(price, amount),
+                        _ => update.asks.insert(price, amount),
+                    };
+                }
+                _ => (),
+            }
+        }
+
+        for (specific_currency_pair, update) in updates {
+            let currency_pair = self.get_unified_currency_pair(&specific_currency_pair)?;
+            self.send_order_book_event(currency_pair, EventType::Update, update)?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_trade(
+        &self,
+        specific_currency_pair: SpecificCurrencyPair,
+        entry: &impl FieldAccess,
+    ) -> Result<()> {
+        let side = match self
+            .venue
+            .dictionary
+            .trade_side_tag
+            .and_then(|side_tag| entry.get(side_tag))
+        {
+            Some(side) => side_from_fix(side)?,
+            None => {
+                log::warn!(
+                    "FIX trade of {specific_currency_pair} without aggressor side is skipped"
+                );
+                return Ok(());
+            }
+        };
+
+        let transaction_time = match (entry.get(tag::MD_ENTRY_DATE), entry.get(tag::MD_ENTRY_TIME))
+        {
+            (Some(date), Some(time)) => parse_utc_timestamp(&format!("{date}-{time}"))?,
+            _ => Utc::now(),
+        };
+        let price = entry.parse_required(tag::MD_ENTRY_PX)?;
+
+        (self.handle_trade_callback)(
+            self.get_unified_currency_pair(&specific_currency_pair)?,
+            Trade {
+                trade_id: TradeId::from(
+                    entry
+                        .get(tag::MD_ENTRY_ID)
+                        .map(str::to_owned)
+                        .unwrap_or_else(|| transaction_time.timestamp_micros().to_string()),
+                ),
+                price,
+                quantity: entry.parse_required(tag::MD_ENTRY_SIZE)?,
+                side,
+                transaction_time,
+                tick_direction: None,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn send_order_book_event(
+        &self,
+        currency_pair: CurrencyPair,
+        event_type: EventType,
+        order_book_data: OrderBookData,
+    ) -> Result<()> {
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            String::default(),
+            event_type,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+}
+
+pub(crate) fn get_exchange_order_id(message: &impl FieldAccess) -> Result<ExchangeOrderId> {
+    match message.get_required(tag::ORDER_ID)? {
+        NO_ORDER_ID => bail!("FIX message doesn't contain order: {:?}", message.fields()),
+        order_id => Ok(order_id.into()),
+    }
+}
+
+/// Role is taken from LastLiquidityInd(851) or AggressorIndicator(1057), taker is assumed by default
+fn get_order_role(message: &impl FieldAccess) -> OrderRole {
+    match (
+        message.get(tag::LAST_LIQUIDITY_IND),
+        message.get(tag::AGGRESSOR_INDICATOR),
+    ) {
+        (Some("1"), _) | (None, Some("N")) => OrderRole::Maker,
+        _ => OrderRole::Taker,
+    }
+}
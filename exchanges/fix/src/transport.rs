@@ -0,0 +1,120 @@
+use crate::fix::Fix;
+use crate::message::find_frame_end;
+use anyhow::{bail, Context, Result};
+use mmb_core::exchanges::general::exchange::Exchange;
+use mmb_utils::cancellation_token::CancellationToken;
+use std::sync::Weak;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+
+/// Period of session timer which checks heartbeats and timeouts
+const TIMER_PERIOD: Duration = Duration::from_secs(1);
+const READ_BUFFER_CAPACITY: usize = 64 * 1024;
+
+/// Keep TCP connection with FIX acceptor and restore it after failures until application is stopped
+pub(crate) async fn run_session(
+    exchange: Weak<Exchange>,
+    address: String,
+    reconnect_interval: Duration,
+    stop_token: CancellationToken,
+) -> Result<()> {
+    while !stop_token.is_cancellation_requested() {
+        match TcpStream::connect(&address).await {
+            Ok(stream) => {
+                log::info!("Connected to FIX acceptor {address}");
+                if let Err(err) = run_connection(&exchange, stream, &stop_token).await {
+                    log::error!("FIX connection to {address} failed: {err:?}");
+                }
+
+                if with_fix(&exchange, |fix| fix.on_transport_disconnected()).is_none() {
+                    return Ok(());
+                }
+                log::info!("Disconnected from FIX acceptor {address}");
+            }
+            Err(err) => log::warn!("Unable to connect to FIX acceptor {address}: {err}"),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(reconnect_interval) => (),
+            _ = stop_token.when_cancelled() => (),
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_connection(
+    exchange: &Weak<Exchange>,
+    stream: TcpStream,
+    stop_token: &CancellationToken,
+) -> Result<()> {
+    let (mut reader, mut writer) = stream.into_split();
+    let (sender, mut receiver) = mpsc::unbounded_channel::<String>();
+    with_fix(exchange, |fix| fix.on_transport_connected(sender))
+        .context("Exchange was dropped")??;
+
+    let mut buffer = Vec::with_capacity(READ_BUFFER_CAPACITY);
+    let mut timer = tokio::time::interval(TIMER_PERIOD);
+
+    loop {
+        tokio::select! {
+            message = receiver.recv() => match message {
+                Some(message) => writer.write_all(message.as_bytes()).await?,
+                None => bail!("FIX connection writer was closed"),
+            },
+            read = reader.read_buf(&mut buffer) => {
+                if read? == 0 {
+                    bail!("FIX connection was closed by counterparty");
+                }
+
+                while let Some(frame_length) = find_frame_end(&buffer)? {
+                    let frame: Vec<u8> = buffer.drain(..frame_length).collect();
+                    let is_connected = with_fix(exchange, |fix| fix.on_fix_message(&frame))
+                        .context("Exchange was dropped")??;
+                    if !is_connected {
+                        return flush(&mut receiver, &mut writer).await;
+                    }
+                }
+            }
+            _ = timer.tick() => {
+                let is_connected = with_fix(exchange, |fix| fix.on_timer())
+                    .context("Exchange was dropped")??;
+                if !is_connected {
+                    return flush(&mut receiver, &mut writer).await;
+                }
+            }
+            _ = stop_token.when_cancelled() => {
+                with_fix(exchange, |fix| fix.logout())
+                    .context("Exchange was dropped")??;
+                return flush(&mut receiver, &mut writer).await;
+            }
+        }
+    }
+}
+
+/// Write messages which were queued before closing of connection, e.g. Logout
+async fn flush(
+    receiver: &mut mpsc::UnboundedReceiver<String>,
+    writer: &mut OwnedWriteHalf,
+) -> Result<()> {
+    while let Ok(message) = receiver.try_recv() {
+        writer.write_all(message.as_bytes()).await?;
+    }
+
+    Ok(())
+}
+
+/// Returns `None` if exchange was dropped
+fn with_fix<R>(exchange: &Weak<Exchange>, action: impl FnOnce(&Fix) -> R) -> Option<R> {
+    let exchange = exchange.upgrade()?;
+    let fix = exchange
+        .exchange_client
+        .as_any()
+        .downcast_ref::<Fix>()
+        .expect("received non Fix exchange client in FIX transport");
+
+    Some(action(fix))
+}
@@ -0,0 +1,90 @@
+use crate::fields::{ord_status, side};
+use anyhow::{bail, Context, Result};
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use mmb_domain::market::ExchangeErrorType;
+use mmb_domain::order::snapshot::{OrderSide, OrderStatus};
+use mmb_utils::DateTime;
+
+/// UTCTimestamp format with milliseconds, e.g. 20230102-15:04:05.123
+const UTC_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H:%M:%S%.3f";
+/// Fractional seconds are optional in received timestamps
+const UTC_TIMESTAMP_PARSE_FORMAT: &str = "%Y%m%d-%H:%M:%S%.f";
+
+pub(crate) fn format_utc_timestamp(date_time: DateTime) -> String {
+    date_time.format(UTC_TIMESTAMP_FORMAT).to_string()
+}
+
+pub(crate) fn parse_utc_timestamp(value: &str) -> Result<DateTime> {
+    let date_time = NaiveDateTime::parse_from_str(value, UTC_TIMESTAMP_PARSE_FORMAT)
+        .with_context(|| format!("Unable to parse FIX timestamp {value}"))?;
+
+    Ok(Utc.from_utc_datetime(&date_time))
+}
+
+pub(crate) fn side_to_fix(order_side: OrderSide) -> &'static str {
+    match order_side {
+        OrderSide::Buy => side::BUY,
+        OrderSide::Sell => side::SELL,
+    }
+}
+
+pub(crate) fn side_from_fix(value: &str) -> Result<OrderSide> {
+    Ok(match value {
+        side::BUY => OrderSide::Buy,
+        side::SELL => OrderSide::Sell,
+        _ => bail!("Unsupported FIX side {value}"),
+    })
+}
+
+pub(crate) fn order_status_from_fix(value: &str) -> Result<OrderStatus> {
+    use ord_status::*;
+
+    Ok(match value {
+        NEW | PARTIALLY_FILLED | PENDING_NEW | PENDING_REPLACE | PENDING_CANCEL | STOPPED
+        | SUSPENDED | ACCEPTED_FOR_BIDDING => OrderStatus::Created,
+        FILLED | CALCULATED => OrderStatus::Completed,
+        CANCELED | EXPIRED | DONE_FOR_DAY => OrderStatus::Canceled,
+        REJECTED => OrderStatus::FailedToCreate,
+        _ => bail!("Unsupported FIX order status {value}"),
+    })
+}
+
+/// OrdRejReason(103) of rejected ExecutionReport
+pub(crate) fn order_reject_error_type(reason: Option<&str>) -> ExchangeErrorType {
+    match reason {
+        // Unknown symbol, unsupported order characteristic, incorrect quantity, invalid price increment
+        Some("1" | "11" | "13" | "18") => ExchangeErrorType::InvalidOrder,
+        // Exchange closed
+        Some("2") => ExchangeErrorType::ServiceUnavailable,
+        // Order exceeds limit
+        Some("3") => ExchangeErrorType::InsufficientFunds,
+        // Unknown order
+        Some("5") => ExchangeErrorType::OrderNotFound,
+        _ => ExchangeErrorType::Unknown,
+    }
+}
+
+/// CxlRejReason(102) of OrderCancelReject
+pub(crate) fn cancel_reject_error_type(reason: Option<&str>) -> ExchangeErrorType {
+    match reason {
+        // Too late to cancel
+        Some("0") => ExchangeErrorType::OrderCompleted,
+        // Unknown order
+        Some("1") => ExchangeErrorType::OrderNotFound,
+        _ => ExchangeErrorType::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_and_parse_timestamp() {
+        let date_time = parse_utc_timestamp("20230102-15:04:05.123").expect("Failed to parse");
+        assert_eq!(format_utc_timestamp(date_time), "20230102-15:04:05.123");
+
+        let date_time = parse_utc_timestamp("20230102-15:04:05").expect("Failed to parse");
+        assert_eq!(format_utc_timestamp(date_time), "20230102-15:04:05.000");
+    }
+}
@@ -0,0 +1,372 @@
+use chrono::Utc;
+use fix::fields::{exec_type, msg_type, ord_status, tag};
+use fix::message::{find_frame_end, FieldAccess, FixMessage, Tag};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+pub(crate) const BEGIN_STRING: &str = "FIX.4.4";
+pub(crate) const ACCEPTOR_COMP_ID: &str = "VENUE";
+pub(crate) const INITIATOR_COMP_ID: &str = "CLIENT";
+pub(crate) const SYMBOL: &str = "BTC/USD";
+
+const WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Behavior of acceptor which can be changed by test
+#[derive(Default, Clone, Copy)]
+pub(crate) struct AcceptorBehavior {
+    /// Orders are filled completely right after creation
+    pub(crate) fill_orders: bool,
+    /// Cancel requests are rejected with unknown order reason
+    pub(crate) reject_cancels: bool,
+}
+
+#[derive(Default)]
+struct AcceptorState {
+    next_seq_num: u64,
+    received: Vec<FixMessage>,
+    writer: Option<mpsc::UnboundedSender<Option<String>>>,
+    behavior: AcceptorBehavior,
+    /// NewOrderSingle messages of orders which are still open with their OrderID(37)
+    open_orders: Vec<(String, FixMessage)>,
+    last_id: u64,
+}
+
+/// Minimal in-process FIX 4.4 acceptor (venue side of FIX session) for offline tests
+pub(crate) struct FixAcceptor {
+    pub(crate) address: String,
+    state: Arc<Mutex<AcceptorState>>,
+}
+
+impl FixAcceptor {
+    pub(crate) async fn start() -> FixAcceptor {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind FIX acceptor");
+        let address = listener
+            .local_addr()
+            .expect("Failed to get FIX acceptor address")
+            .to_string();
+        let state = Arc::new(Mutex::new(AcceptorState {
+            next_seq_num: 1,
+            ..AcceptorState::default()
+        }));
+
+        let _ = tokio::spawn({
+            let state = state.clone();
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let (mut reader, mut writer) = stream.into_split();
+                    let (sender, mut receiver) = mpsc::unbounded_channel::<Option<String>>();
+                    state.lock().writer = Some(sender);
+
+                    let _ = tokio::spawn(async move {
+                        // None is a command to close connection
+                        while let Some(Some(message)) = receiver.recv().await {
+                            if writer.write_all(message.as_bytes()).await.is_err() {
+                                break;
+                            }
+                        }
+                    });
+
+                    let state = state.clone();
+                    let _ = tokio::spawn(async move {
+                        let mut buffer = Vec::new();
+                        while let Ok(read) = reader.read_buf(&mut buffer).await {
+                            if read == 0 {
+                                break;
+                            }
+                            while let Some(frame_length) =
+                                find_frame_end(&buffer).expect("Invalid FIX frame")
+                            {
+                                let frame: Vec<u8> = buffer.drain(..frame_length).collect();
+                                let (_, message) =
+                                    FixMessage::decode(&frame).expect("Invalid FIX message");
+                                handle_message(&mut state.lock(), message);
+                            }
+                        }
+                    });
+                }
+            }
+        });
+
+        FixAcceptor { address, state }
+    }
+
+    pub(crate) fn set_behavior(&self, behavior: AcceptorBehavior) {
+        self.state.lock().behavior = behavior;
+    }
+
+    /// Send application message to initiator
+    pub(crate) fn send(&self, message: FixMessage) {
+        send(&mut self.state.lock(), message);
+    }
+
+    /// Next messages are sent with a gap of sequence numbers
+    pub(crate) fn skip_sequence_numbers(&self, count: u64) {
+        self.state.lock().next_seq_num += count;
+    }
+
+    pub(crate) fn disconnect(&self) {
+        if let Some(writer) = self.state.lock().writer.take() {
+            let _ = writer.send(None);
+        }
+    }
+
+    pub(crate) fn received(&self, msg_type: &str) -> Vec<FixMessage> {
+        self.state
+            .lock()
+            .received
+            .iter()
+            .filter(|message| message.msg_type() == msg_type)
+            .cloned()
+            .collect()
+    }
+
+    /// Wait until the specified count of messages with MsgType(35) is received
+    pub(crate) async fn wait_for(&self, msg_type: &str, count: usize) -> Vec<FixMessage> {
+        tokio::time::timeout(WAIT_TIMEOUT, async {
+            loop {
+                let received = self.received(msg_type);
+                if received.len() >= count {
+                    return received;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("FIX message with MsgType {msg_type} wasn't received"))
+    }
+}
+
+fn handle_message(state: &mut AcceptorState, message: FixMessage) {
+    state.received.push(message.clone());
+
+    match message.msg_type() {
+        msg_type::LOGON => {
+            let mut logon = FixMessage::new(msg_type::LOGON)
+                .with(tag::ENCRYPT_METHOD, 0)
+                .with(tag::HEART_BT_INT, get(&message, tag::HEART_BT_INT));
+            if message.has_flag(tag::RESET_SEQ_NUM_FLAG) {
+                state.next_seq_num = 1;
+                logon.push(tag::RESET_SEQ_NUM_FLAG, "Y");
+            }
+            send(state, logon);
+        }
+        msg_type::TEST_REQUEST => {
+            let heartbeat = FixMessage::new(msg_type::HEARTBEAT)
+                .with(tag::TEST_REQ_ID, get(&message, tag::TEST_REQ_ID));
+            send(state, heartbeat);
+        }
+        msg_type::RESEND_REQUEST => {
+            let begin_seq_no = get(&message, tag::BEGIN_SEQ_NO);
+            let gap_fill = FixMessage::new(msg_type::SEQUENCE_RESET)
+                .with(tag::GAP_FILL_FLAG, "Y")
+                .with(tag::NEW_SEQ_NO, state.next_seq_num);
+            write(state, &gap_fill, &begin_seq_no, true);
+        }
+        msg_type::LOGOUT => {
+            send(state, FixMessage::new(msg_type::LOGOUT));
+            if let Some(writer) = state.writer.take() {
+                let _ = writer.send(None);
+            }
+        }
+        msg_type::NEW_ORDER_SINGLE => {
+            let order_id = format!("ORD{}", next_id(state));
+            send(
+                state,
+                execution_report(&message, &order_id, exec_type::NEW, ord_status::NEW),
+            );
+
+            match state.behavior.fill_orders {
+                true => {
+                    let amount = get(&message, tag::ORDER_QTY);
+                    let price = message.get(tag::PRICE).unwrap_or("100").to_owned();
+                    let trade_id = format!("T{}", next_id(state));
+                    let fill =
+                        execution_report(&message, &order_id, exec_type::TRADE, ord_status::FILLED)
+                            .with(tag::LAST_PX, price)
+                            .with(tag::LAST_QTY, &amount)
+                            .with(tag::CUM_QTY, &amount)
+                            .with(tag::LAST_LIQUIDITY_IND, 2)
+                            .with(tag::TRADE_ID, trade_id)
+                            .with(tag::COMMISSION, "0.01");
+                    send(state, fill);
+                }
+                false => state.open_orders.push((order_id, message)),
+            }
+        }
+        msg_type::ORDER_CANCEL_REQUEST => {
+            let orig_cl_ord_id = get(&message, tag::ORIG_CL_ORD_ID);
+            let position = state
+                .open_orders
+                .iter()
+                .position(|(_, order)| order.get(tag::CL_ORD_ID) == Some(&orig_cl_ord_id));
+
+            match (position, state.behavior.reject_cancels) {
+                (Some(position), false) => {
+                    let (order_id, order) = state.open_orders.remove(position);
+                    let report = execution_report(
+                        &order,
+                        &order_id,
+                        exec_type::CANCELED,
+                        ord_status::CANCELED,
+                    );
+                    send(state, with_cl_ord_id(report, &message));
+                }
+                _ => {
+                    let reject = FixMessage::new(msg_type::ORDER_CANCEL_REJECT)
+                        .with(tag::ORDER_ID, get(&message, tag::ORDER_ID))
+                        .with(tag::CL_ORD_ID, get(&message, tag::CL_ORD_ID))
+                        .with(tag::ORIG_CL_ORD_ID, orig_cl_ord_id)
+                        .with(tag::ORD_STATUS, ord_status::NEW)
+                        .with(tag::CXL_REJ_RESPONSE_TO, 1)
+                        // Unknown order
+                        .with(tag::CXL_REJ_REASON, 1)
+                        .with(tag::TEXT, "Unknown order");
+                    send(state, reject);
+                }
+            }
+        }
+        msg_type::ORDER_STATUS_REQUEST => {
+            let order_id = get(&message, tag::ORDER_ID);
+            let report = match state.open_orders.iter().find(|(id, _)| *id == order_id) {
+                Some((order_id, order)) => {
+                    execution_report(order, order_id, exec_type::ORDER_STATUS, ord_status::NEW)
+                }
+                None => execution_report(
+                    &message,
+                    &order_id,
+                    exec_type::ORDER_STATUS,
+                    ord_status::REJECTED,
+                )
+                // Unknown order
+                .with(tag::ORD_REJ_REASON, 5),
+            };
+            let report = report.with(
+                tag::ORD_STATUS_REQ_ID,
+                get(&message, tag::ORD_STATUS_REQ_ID),
+            );
+            send(state, report);
+        }
+        msg_type::ORDER_MASS_STATUS_REQUEST => {
+            let mass_status_req_id = get(&message, tag::MASS_STATUS_REQ_ID);
+            let mut reports: Vec<_> = state
+                .open_orders
+                .iter()
+                .map(|(order_id, order)| {
+                    execution_report(order, order_id, exec_type::ORDER_STATUS, ord_status::NEW)
+                })
+                .collect();
+            if reports.is_empty() {
+                reports.push(
+                    FixMessage::new(msg_type::EXECUTION_REPORT)
+                        .with(tag::ORDER_ID, "NONE")
+                        .with(tag::EXEC_ID, format!("E{}", next_id(state)))
+                        .with(tag::EXEC_TYPE, exec_type::ORDER_STATUS)
+                        .with(tag::ORD_STATUS, ord_status::REJECTED),
+                );
+            }
+
+            let count = reports.len();
+            for (index, report) in reports.into_iter().enumerate() {
+                let is_last = if index + 1 == count { "Y" } else { "N" };
+                let report = report
+                    .with(tag::MASS_STATUS_REQ_ID, &mass_status_req_id)
+                    .with(tag::LAST_RPT_REQUESTED, is_last);
+                send(state, report);
+            }
+        }
+        msg_type::MARKET_DATA_REQUEST => {
+            let snapshot = FixMessage::new(msg_type::MARKET_DATA_SNAPSHOT_FULL_REFRESH)
+                .with(tag::MD_REQ_ID, get(&message, tag::MD_REQ_ID))
+                .with(tag::SYMBOL, get(&message, tag::SYMBOL))
+                .with(tag::NO_MD_ENTRIES, 2)
+                .with(tag::MD_ENTRY_TYPE, 0)
+                .with(tag::MD_ENTRY_PX, "100")
+                .with(tag::MD_ENTRY_SIZE, "1.5")
+                .with(tag::MD_ENTRY_TYPE, 1)
+                .with(tag::MD_ENTRY_PX, "101")
+                .with(tag::MD_ENTRY_SIZE, "2");
+            send(state, snapshot);
+        }
+        _ => (),
+    }
+}
+
+fn execution_report(
+    order: &FixMessage,
+    order_id: &str,
+    exec_type: &str,
+    ord_status: &str,
+) -> FixMessage {
+    let mut report = FixMessage::new(msg_type::EXECUTION_REPORT)
+        .with(tag::ORDER_ID, order_id)
+        .with(tag::CL_ORD_ID, get(order, tag::CL_ORD_ID))
+        .with(tag::EXEC_ID, format!("{order_id}-{exec_type}"))
+        .with(tag::EXEC_TYPE, exec_type)
+        .with(tag::ORD_STATUS, ord_status)
+        .with(tag::SYMBOL, get(order, tag::SYMBOL))
+        .with(tag::SIDE, get(order, tag::SIDE))
+        .with(tag::ORDER_QTY, get(order, tag::ORDER_QTY))
+        .with(tag::TRANSACT_TIME, Utc::now().format("%Y%m%d-%H:%M:%S%.3f"));
+    if let Some(price) = order.get(tag::PRICE) {
+        report.push(tag::PRICE, price);
+    }
+
+    report
+}
+
+/// Cancellation by request is reported with ClOrdID of the request
+fn with_cl_ord_id(report: FixMessage, request: &FixMessage) -> FixMessage {
+    let mut result = FixMessage::new(report.msg_type());
+    for (tag, value) in report.fields() {
+        match *tag {
+            tag::CL_ORD_ID => result.push(*tag, get(request, tag::CL_ORD_ID)),
+            _ => result.push(*tag, value),
+        }
+    }
+    result.push(tag::ORIG_CL_ORD_ID, get(request, tag::ORIG_CL_ORD_ID));
+
+    result
+}
+
+fn get(message: &FixMessage, tag: Tag) -> String {
+    message
+        .get(tag)
+        .unwrap_or_else(|| panic!("Field {tag} is missing in {message:?}"))
+        .to_owned()
+}
+
+fn next_id(state: &mut AcceptorState) -> u64 {
+    state.last_id += 1;
+    state.last_id
+}
+
+fn send(state: &mut AcceptorState, message: FixMessage) {
+    let seq_num = state.next_seq_num.to_string();
+    state.next_seq_num += 1;
+    write(state, &message, &seq_num, false);
+}
+
+fn write(state: &AcceptorState, message: &FixMessage, seq_num: &str, poss_dup: bool) {
+    let mut header = vec![
+        (tag::SENDER_COMP_ID, ACCEPTOR_COMP_ID.to_owned()),
+        (tag::TARGET_COMP_ID, INITIATOR_COMP_ID.to_owned()),
+        (tag::MSG_SEQ_NUM, seq_num.to_owned()),
+        (
+            tag::SENDING_TIME,
+            Utc::now().format("%Y%m%d-%H:%M:%S%.3f").to_string(),
+        ),
+    ];
+    if poss_dup {
+        header.push((tag::POSS_DUP_FLAG, "Y".to_owned()));
+    }
+
+    if let Some(writer) = &state.writer {
+        let _ = writer.send(Some(message.encode(BEGIN_STRING, &header)));
+    }
+}
@@ -0,0 +1,176 @@
+use crate::fix::acceptor::{FixAcceptor, ACCEPTOR_COMP_ID, INITIATOR_COMP_ID, SYMBOL};
+use fix::dictionary::FixDictionary;
+use fix::fix::{Fix, FixBuilder};
+use fix::settings::{FixInstrument, FixVenueSettings};
+use mmb_core::balance::manager::balance_manager::BalanceManager;
+use mmb_core::database::events::recorder::EventRecorder;
+use mmb_core::exchanges::exchange_blocker::ExchangeBlocker;
+use mmb_core::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
+use mmb_core::exchanges::general::exchange::Exchange;
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestsTimeoutManagerFactory;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::ExchangeClientBuilder;
+use mmb_core::infrastructure::init_lifetime_manager;
+use mmb_core::settings::{CurrencyPairSetting, ExchangeSettings};
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::exchanges::commission::Commission;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::pool::OrdersPool;
+use mmb_utils::hashmap;
+use rust_decimal_macros::dec;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+pub(crate) fn exchange_account_id() -> ExchangeAccountId {
+    ExchangeAccountId::new("FixVenue", 0)
+}
+
+pub(crate) fn currency_pair() -> CurrencyPair {
+    CurrencyPair::from_codes("btc".into(), "usd".into())
+}
+
+pub(crate) fn venue_settings(address: &str) -> FixVenueSettings {
+    FixVenueSettings {
+        exchange_id: "FixVenue".to_owned(),
+        address: address.to_owned(),
+        sender_comp_id: INITIATOR_COMP_ID.to_owned(),
+        target_comp_id: ACCEPTOR_COMP_ID.to_owned(),
+        heartbeat_interval_secs: 1,
+        reconnect_interval_secs: 1,
+        reset_seq_num_on_logon: false,
+        sequence_store_path: None,
+        dictionary: FixDictionary::default(),
+        instruments: vec![FixInstrument {
+            symbol: SYMBOL.into(),
+            base: "btc".into(),
+            quote: "usd".into(),
+            price_tick: dec!(0.1),
+            amount_tick: dec!(0.001),
+            min_amount: Some(dec!(0.001)),
+        }],
+    }
+}
+
+pub(crate) fn exchange_settings(subscribe_to_market_data: bool) -> ExchangeSettings {
+    let mut settings = ExchangeSettings::new_short(
+        exchange_account_id(),
+        "user".to_owned(),
+        "password".to_owned(),
+        false,
+    );
+    settings.subscribe_to_market_data = subscribe_to_market_data;
+    settings.currency_pairs = Some(vec![CurrencyPairSetting::Ordinary {
+        base: "btc".into(),
+        quote: "usd".into(),
+    }]);
+
+    settings
+}
+
+/// Temporary file for persisting of sequence numbers
+pub(crate) fn sequence_store_path(test_name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("fix_{test_name}_{}.seqnums", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    path
+}
+
+pub(crate) struct TestFix {
+    pub(crate) acceptor: FixAcceptor,
+    pub(crate) exchange: Arc<Exchange>,
+    pub(crate) events: broadcast::Receiver<ExchangeEvent>,
+}
+
+impl TestFix {
+    pub(crate) async fn start(subscribe_to_market_data: bool) -> TestFix {
+        let acceptor = FixAcceptor::start().await;
+        let venue = venue_settings(&acceptor.address);
+
+        Self::start_with_settings(acceptor, venue, exchange_settings(subscribe_to_market_data))
+            .await
+    }
+
+    pub(crate) async fn start_with_settings(
+        acceptor: FixAcceptor,
+        venue: FixVenueSettings,
+        settings: ExchangeSettings,
+    ) -> TestFix {
+        let lifetime_manager = init_lifetime_manager();
+        let (tx, events) = broadcast::channel(100);
+        let orders = OrdersPool::new();
+
+        let builder = FixBuilder::new(venue);
+        let timeout_manager = get_timeout_manager(&builder);
+        let client = builder.create_exchange_client(
+            settings.clone(),
+            tx.clone(),
+            lifetime_manager.clone(),
+            timeout_manager.clone(),
+            orders.clone(),
+        );
+
+        let exchange_blocker = ExchangeBlocker::new(vec![settings.exchange_account_id]);
+        let event_recorder = EventRecorder::start(None, None)
+            .await
+            .expect("Failure start EventRecorder");
+        let exchange = Exchange::new(
+            settings.exchange_account_id,
+            client.client,
+            orders,
+            client.features,
+            builder.get_timeout_arguments(),
+            tx,
+            lifetime_manager,
+            timeout_manager,
+            Arc::downgrade(&exchange_blocker),
+            Commission::default(),
+            event_recorder,
+        );
+        exchange.build_symbols(&settings.currency_pairs).await;
+        exchange.exchange_client.initialized(exchange.clone()).await;
+
+        let currency_pair_to_symbol_converter = CurrencyPairToSymbolConverter::new(
+            hashmap![ settings.exchange_account_id => exchange.clone() ],
+        );
+        exchange
+            .setup_balance_manager(BalanceManager::new(currency_pair_to_symbol_converter, None));
+
+        let test_fix = TestFix {
+            acceptor,
+            exchange,
+            events,
+        };
+        test_fix.wait_for_logon().await;
+
+        test_fix
+    }
+
+    pub(crate) fn fix(&self) -> &Fix {
+        self.exchange
+            .exchange_client
+            .as_any()
+            .downcast_ref::<Fix>()
+            .expect("Exchange client isn't Fix")
+    }
+
+    pub(crate) async fn wait_for_logon(&self) {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !self.fix().is_logged_on() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("FIX session wasn't logged on");
+    }
+}
+
+fn get_timeout_manager(builder: &FixBuilder) -> Arc<TimeoutManager> {
+    let request_timeout_manager = RequestsTimeoutManagerFactory::from_requests_per_period(
+        builder.get_timeout_arguments(),
+        exchange_account_id(),
+    );
+
+    TimeoutManager::new(hashmap![exchange_account_id() => request_timeout_manager])
+}
@@ -0,0 +1,62 @@
+use crate::fix::acceptor::SYMBOL;
+use crate::fix::common::{currency_pair, TestFix};
+use fix::fields::{msg_type, tag};
+use fix::message::{FieldAccess, FixMessage};
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use rust_decimal_macros::dec;
+use std::time::Duration;
+
+async fn next_order_book_event(test_fix: &mut TestFix) -> OrderBookEvent {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            match test_fix.events.recv().await {
+                Ok(ExchangeEvent::OrderBookEvent(event)) => return event,
+                Ok(_) => continue,
+                Err(err) => panic!("Failed to receive exchange event: {err:?}"),
+            }
+        }
+    })
+    .await
+    .expect("Order book event wasn't received")
+}
+
+#[tokio::test]
+async fn snapshot_and_incremental_refresh() {
+    let mut test_fix = TestFix::start(true).await;
+
+    let request = &test_fix
+        .acceptor
+        .wait_for(msg_type::MARKET_DATA_REQUEST, 1)
+        .await[0];
+    assert_eq!(request.get(tag::SYMBOL), Some(SYMBOL));
+    // Snapshot + updates
+    assert_eq!(request.get(tag::SUBSCRIPTION_REQUEST_TYPE), Some("1"));
+
+    let snapshot = next_order_book_event(&mut test_fix).await;
+    assert!(matches!(snapshot.event_type, EventType::Snapshot));
+    assert_eq!(snapshot.currency_pair, currency_pair());
+    assert_eq!(snapshot.data.bids.get(&dec!(100)), Some(&dec!(1.5)));
+    assert_eq!(snapshot.data.asks.get(&dec!(101)), Some(&dec!(2)));
+
+    // Symbol of the first entry is inherited by the next one
+    test_fix.acceptor.send(
+        FixMessage::new(msg_type::MARKET_DATA_INCREMENTAL_REFRESH)
+            .with(tag::NO_MD_ENTRIES, 2)
+            .with(tag::MD_UPDATE_ACTION, 2)
+            .with(tag::MD_ENTRY_TYPE, 0)
+            .with(tag::SYMBOL, SYMBOL)
+            .with(tag::MD_ENTRY_PX, "100")
+            .with(tag::MD_UPDATE_ACTION, 0)
+            .with(tag::MD_ENTRY_TYPE, 1)
+            .with(tag::MD_ENTRY_PX, "100.5")
+            .with(tag::MD_ENTRY_SIZE, "0.3"),
+    );
+
+    let update = next_order_book_event(&mut test_fix).await;
+    assert!(matches!(update.event_type, EventType::Update));
+    assert_eq!(update.currency_pair, currency_pair());
+    // Zero amount means deletion of price level
+    assert_eq!(update.data.bids.get(&dec!(100)), Some(&dec!(0)));
+    assert_eq!(update.data.asks.get(&dec!(100.5)), Some(&dec!(0.3)));
+}
@@ -0,0 +1,5 @@
+pub(crate) mod acceptor;
+pub(crate) mod common;
+mod market_data;
+mod orders;
+mod session;
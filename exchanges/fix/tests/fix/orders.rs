@@ -0,0 +1,197 @@
+use crate::fix::acceptor::AcceptorBehavior;
+use crate::fix::common::{currency_pair, exchange_account_id, TestFix};
+use chrono::Utc;
+use core_tests::order::OrderProxyBuilder;
+use fix::fields::{exec_type, msg_type, ord_status, tag};
+use fix::message::{FieldAccess, FixMessage};
+use mmb_core::exchanges::general::exchange::RequestResult;
+use mmb_domain::exchanges::symbol::Symbol;
+use mmb_domain::market::ExchangeErrorType;
+use mmb_domain::order::snapshot::{OrderRole, OrderStatus};
+use mmb_utils::cancellation_token::CancellationToken;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn symbol(test_fix: &TestFix) -> Arc<Symbol> {
+    test_fix
+        .exchange
+        .get_symbol(currency_pair())
+        .expect("Symbol isn't found")
+}
+
+#[tokio::test]
+async fn create_and_cancel_order() {
+    let test_fix = TestFix::start(false).await;
+    let order_proxy = OrderProxyBuilder::new(
+        exchange_account_id(),
+        Some("FromCreateAndCancelOrderTest".to_owned()),
+        dec!(99),
+        dec!(0.01),
+        currency_pair(),
+    )
+    .build();
+
+    let order_ref = order_proxy
+        .create_order(test_fix.exchange.clone())
+        .await
+        .expect("Create order failed");
+    assert_eq!(order_ref.status(), OrderStatus::Created);
+    assert_eq!(order_ref.exchange_order_id(), Some("ORD1".into()));
+
+    let new_order_single = &test_fix.acceptor.received(msg_type::NEW_ORDER_SINGLE)[0];
+    assert_eq!(new_order_single.get(tag::SYMBOL), Some("BTC/USD"));
+    assert_eq!(new_order_single.get(tag::PRICE), Some("99"));
+    assert_eq!(new_order_single.get(tag::ORDER_QTY), Some("0.01"));
+    // Maker only order is sent as participate don't initiate
+    assert_eq!(new_order_single.get(tag::EXEC_INST), Some("6"));
+
+    let open_orders = test_fix
+        .exchange
+        .get_open_orders(false)
+        .await
+        .expect("Failed to get open orders");
+    assert_eq!(open_orders.len(), 1);
+    assert_eq!(open_orders[0].client_order_id, order_proxy.client_order_id);
+
+    let order_info = test_fix
+        .exchange
+        .get_order_info(&order_ref)
+        .await
+        .expect("Failed to get order info");
+    assert_eq!(order_info.order_status, OrderStatus::Created);
+
+    order_proxy
+        .cancel_order_or_fail(&order_ref, test_fix.exchange.clone())
+        .await;
+
+    let open_orders = test_fix
+        .exchange
+        .get_open_orders(false)
+        .await
+        .expect("Failed to get open orders");
+    assert!(open_orders.is_empty());
+}
+
+#[tokio::test]
+async fn cancel_rejected_for_unknown_order() {
+    let test_fix = TestFix::start(false).await;
+    test_fix.acceptor.set_behavior(AcceptorBehavior {
+        reject_cancels: true,
+        ..AcceptorBehavior::default()
+    });
+    let order_proxy = OrderProxyBuilder::new(
+        exchange_account_id(),
+        Some("FromCancelRejectedTest".to_owned()),
+        dec!(99),
+        dec!(0.01),
+        currency_pair(),
+    )
+    .build();
+
+    let order_ref = order_proxy
+        .create_order(test_fix.exchange.clone())
+        .await
+        .expect("Create order failed");
+
+    let cancel_outcome = test_fix
+        .exchange
+        .cancel_order(&order_ref, CancellationToken::default())
+        .await
+        .expect("in test");
+
+    match cancel_outcome.outcome {
+        RequestResult::Error(error) => {
+            assert_eq!(error.error_type, ExchangeErrorType::OrderNotFound)
+        }
+        RequestResult::Success(_) => panic!("Cancellation should be rejected"),
+    }
+}
+
+#[tokio::test]
+async fn unsolicited_rejection_fails_order_creation() {
+    let test_fix = TestFix::start(false).await;
+    let order_proxy = OrderProxyBuilder::new(
+        exchange_account_id(),
+        Some("FromUnsolicitedRejectionTest".to_owned()),
+        dec!(99),
+        dec!(0.01),
+        currency_pair(),
+    )
+    .build();
+
+    // Order which request is already timed out, so rejection isn't a response to it
+    let order_ref = order_proxy.created_order_ref_stub(test_fix.exchange.orders.clone());
+    order_ref.fn_mut(|order| order.set_status(OrderStatus::Creating, Utc::now()));
+
+    test_fix.acceptor.send(
+        FixMessage::new(msg_type::EXECUTION_REPORT)
+            .with(tag::ORDER_ID, "NONE")
+            .with(tag::CL_ORD_ID, order_proxy.client_order_id.as_str())
+            .with(tag::EXEC_ID, "REJ1")
+            .with(tag::EXEC_TYPE, exec_type::REJECTED)
+            .with(tag::ORD_STATUS, ord_status::REJECTED)
+            .with(tag::ORD_REJ_REASON, "1")
+            .with(tag::TEXT, "Unknown symbol"),
+    );
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while order_ref.status() != OrderStatus::FailedToCreate {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("Order creation wasn't failed");
+
+    let error_type = order_ref.fn_ref(|order| order.internal_props.last_creation_error_type);
+    assert_eq!(error_type, Some(ExchangeErrorType::InvalidOrder));
+}
+
+#[tokio::test]
+async fn filled_order_is_in_my_trades() {
+    let test_fix = TestFix::start(false).await;
+    test_fix.acceptor.set_behavior(AcceptorBehavior {
+        fill_orders: true,
+        ..AcceptorBehavior::default()
+    });
+    let order_proxy = OrderProxyBuilder::new(
+        exchange_account_id(),
+        Some("FromFilledOrderTest".to_owned()),
+        dec!(100),
+        dec!(0.02),
+        currency_pair(),
+    )
+    .build();
+
+    let order_ref = order_proxy
+        .create_order(test_fix.exchange.clone())
+        .await
+        .expect("Create order failed");
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while order_ref.status() != OrderStatus::Completed {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("Order wasn't filled");
+
+    let trades = match test_fix
+        .exchange
+        .exchange_client
+        .get_my_trades(&symbol(&test_fix), None)
+        .await
+    {
+        RequestResult::Success(trades) => trades,
+        RequestResult::Error(error) => panic!("Failed to get my trades: {error:?}"),
+    };
+    assert_eq!(trades.len(), 1);
+    let trade = &trades[0];
+    assert_eq!(trade.exchange_order_id, "ORD1".into());
+    assert_eq!(trade.trade_id, "T2".to_owned().into());
+    assert_eq!(trade.price, dec!(100));
+    assert_eq!(trade.amount, dec!(0.02));
+    assert_eq!(trade.fee_amount, Some(dec!(0.01)));
+    // LastLiquidityInd(851) = 2 means removed liquidity
+    assert_eq!(trade.order_role, OrderRole::Taker);
+}
@@ -0,0 +1,83 @@
+use crate::fix::acceptor::FixAcceptor;
+use crate::fix::common::{exchange_settings, sequence_store_path, venue_settings, TestFix};
+use fix::fields::{msg_type, tag};
+use fix::message::{FieldAccess, FixMessage};
+
+fn seq_num(message: &FixMessage) -> u64 {
+    message
+        .parse_required(tag::MSG_SEQ_NUM)
+        .expect("Invalid MsgSeqNum")
+}
+
+#[tokio::test]
+async fn logon_with_credentials_and_heartbeats() {
+    let test_fix = TestFix::start(false).await;
+
+    let logon = &test_fix.acceptor.received(msg_type::LOGON)[0];
+    assert_eq!(logon.get(tag::USERNAME), Some("user"));
+    assert_eq!(logon.get(tag::PASSWORD), Some("password"));
+    assert_eq!(logon.get(tag::HEART_BT_INT), Some("1"));
+
+    // Heartbeat interval is 1 second
+    let heartbeats = test_fix.acceptor.wait_for(msg_type::HEARTBEAT, 2).await;
+    assert!(seq_num(&heartbeats[1]) > seq_num(&heartbeats[0]));
+}
+
+#[tokio::test]
+async fn sequence_numbers_are_kept_after_reconnection() {
+    let acceptor = FixAcceptor::start().await;
+    let mut venue = venue_settings(&acceptor.address);
+    let path = sequence_store_path("reconnection");
+    venue.sequence_store_path = Some(path.clone());
+    let test_fix = TestFix::start_with_settings(acceptor, venue, exchange_settings(false)).await;
+
+    test_fix.acceptor.disconnect();
+    let logons = test_fix.acceptor.wait_for(msg_type::LOGON, 2).await;
+    test_fix.wait_for_logon().await;
+
+    // Session is continued instead of reset
+    assert!(seq_num(&logons[1]) > seq_num(&logons[0]));
+    assert_eq!(logons[1].get(tag::RESET_SEQ_NUM_FLAG), None);
+    let stored = std::fs::read_to_string(&path).expect("Sequence numbers aren't stored");
+    assert!(!stored.is_empty());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn sequence_gap_is_filled_after_resend_request() {
+    let test_fix = TestFix::start(false).await;
+
+    test_fix.acceptor.skip_sequence_numbers(3);
+    test_fix
+        .acceptor
+        .send(FixMessage::new(msg_type::TEST_REQUEST).with(tag::TEST_REQ_ID, "AFTER_GAP"));
+
+    let resend_request = &test_fix
+        .acceptor
+        .wait_for(msg_type::RESEND_REQUEST, 1)
+        .await[0];
+    // Logon of acceptor was the only message before the gap
+    assert_eq!(resend_request.get(tag::BEGIN_SEQ_NO), Some("2"));
+    assert_eq!(resend_request.get(tag::END_SEQ_NO), Some("0"));
+
+    // Messages are processed again after the gap fill
+    test_fix
+        .acceptor
+        .send(FixMessage::new(msg_type::TEST_REQUEST).with(tag::TEST_REQ_ID, "SYNCED"));
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        loop {
+            let is_answered = test_fix
+                .acceptor
+                .received(msg_type::HEARTBEAT)
+                .iter()
+                .any(|heartbeat| heartbeat.get(tag::TEST_REQ_ID) == Some("SYNCED"));
+            if is_answered {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("Test request wasn't answered after gap fill");
+}
@@ -0,0 +1,140 @@
+use crate::kraken::Kraken;
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use mmb_core::exchanges::general::exchange::RequestResult;
+use mmb_core::exchanges::general::order::cancel::CancelOrderResult;
+use mmb_core::exchanges::general::order::create::CreateOrderResult;
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::traits::{ExchangeClient, ExchangeError};
+use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions};
+use mmb_domain::exchanges::symbol::Symbol;
+use mmb_domain::market::CurrencyPair;
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{ExchangeOrderId, OrderInfo, Price};
+use mmb_domain::position::{ActivePosition, ClosedPosition};
+use mmb_utils::DateTime;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[async_trait]
+impl ExchangeClient for Kraken {
+    async fn create_order(&self, order: &OrderRef) -> CreateOrderResult {
+        match self.request_create_order(order).await {
+            Ok(request_outcome) => match self.get_order_id(&request_outcome) {
+                Ok(order_id) => CreateOrderResult::succeed(&order_id, EventSourceType::Rest),
+                Err(error) => CreateOrderResult::failed(error, EventSourceType::Rest),
+            },
+            Err(err) => CreateOrderResult::failed(err, EventSourceType::Rest),
+        }
+    }
+
+    async fn cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> CancelOrderResult {
+        let client_order_id = order.client_order_id();
+        match self
+            .request_cancel_order(&client_order_id, exchange_order_id)
+            .await
+        {
+            Ok(_) => CancelOrderResult::succeed(client_order_id, EventSourceType::Rest, None),
+            Err(err) => CancelOrderResult::failed(err, EventSourceType::Rest),
+        }
+    }
+
+    async fn cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()> {
+        match self.do_cancel_all_orders(currency_pair).await {
+            Ok(_) => Ok(()),
+            Err(error) => bail!("Failed to cancel all orders: {error:?}"),
+        }
+    }
+
+    async fn cancel_all_orders_after(
+        &self,
+        _currency_pairs: &[CurrencyPair],
+        timeout: Duration,
+    ) -> Result<()> {
+        // Kraken timer is common for all currency pairs
+        match self.request_cancel_all_orders_after(timeout).await {
+            Ok(_) => Ok(()),
+            Err(error) => bail!("Failed to arm cancel all orders after timer: {error:?}"),
+        }
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<OrderInfo>> {
+        let response = self.request_open_orders().await?;
+
+        self.parse_open_orders(&response)
+    }
+
+    async fn get_open_orders_by_currency_pair(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<Vec<OrderInfo>> {
+        let response = self.request_open_orders().await?;
+
+        let mut orders = self.parse_open_orders(&response)?;
+        orders.retain(|order| order.currency_pair == currency_pair);
+
+        Ok(orders)
+    }
+
+    async fn get_order_info(&self, order: &OrderRef) -> Result<OrderInfo, ExchangeError> {
+        let response = self.request_order_info(order).await?;
+
+        self.parse_order_info(&response)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse order info: {err:?}")))
+    }
+
+    async fn close_position(
+        &self,
+        _position: &ActivePosition,
+        _price: Option<Price>,
+    ) -> Result<ClosedPosition> {
+        bail!("Kraken spot trading doesn't support positions")
+    }
+
+    async fn get_active_positions(&self) -> Result<Vec<ActivePosition>> {
+        bail!("Kraken spot trading doesn't support positions")
+    }
+
+    async fn get_balance_and_positions(&self) -> Result<ExchangeBalancesAndPositions> {
+        let response = self.request_get_balance().await?;
+
+        Ok(ExchangeBalancesAndPositions {
+            balances: self.parse_get_balance(&response)?,
+            positions: None,
+            margin_account: None,
+        })
+    }
+
+    async fn get_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> RequestResult<Vec<OrderTrade>> {
+        match self.request_my_trades(last_date_time).await {
+            Ok(response) => match self.parse_my_trades(&response, symbol.currency_pair()) {
+                Ok(data) => RequestResult::Success(data),
+                Err(err) => RequestResult::Error(ExchangeError::parsing(format!(
+                    "Unable to parse trades: {err:?}"
+                ))),
+            },
+            Err(err) => RequestResult::Error(err),
+        }
+    }
+
+    async fn build_all_symbols(&self) -> Result<Vec<Arc<Symbol>>> {
+        let response = self.request_all_symbols().await?;
+
+        self.parse_all_symbols(&response)
+    }
+
+    async fn get_server_time(&self) -> Option<Result<i64>> {
+        match self.request_get_server_time().await {
+            Ok(response) => Some(self.parse_get_server_time(&response)),
+            Err(err) => Some(Err(anyhow!("Get server time request failed: {err:?}"))),
+        }
+    }
+}
@@ -0,0 +1,813 @@
+use crate::order_book::LocalOrderBook;
+use crate::types::{
+    KrakenAssetPair, KrakenBalance, KrakenOpenOrders, KrakenOrder, KrakenOrderSide, KrakenResponse,
+    KrakenTradesHistory,
+};
+use anyhow::{bail, Context, Result};
+use dashmap::DashMap;
+use function_name::named;
+use hmac::{Hmac, Mac};
+use hyper::body::Bytes;
+use hyper::http::request::Builder;
+use hyper::{StatusCode, Uri};
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    ClientOrderId, ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole,
+    OrderStatus, UserOrder,
+};
+use mmb_utils::infrastructure::WithExpect;
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use serde::Deserialize;
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use url::form_urlencoded;
+
+#[derive(Default)]
+pub struct ErrorHandlerKraken;
+
+impl ErrorHandler for ErrorHandlerKraken {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        #[derive(Deserialize)]
+        struct Error {
+            #[serde(default)]
+            error: Vec<String>,
+        }
+
+        let error: Error = serde_json::from_str(&response.content).map_err(|err| {
+            ExchangeError::parsing(format!(
+                "Unable to parse response.content: {err:?}\n{}",
+                response.content
+            ))
+        })?;
+
+        if error.error.is_empty() && response.status == StatusCode::OK {
+            return Ok(());
+        }
+
+        Err(ExchangeError::new(
+            ExchangeErrorType::Unknown,
+            error.error.join("; "),
+            None,
+        ))
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        use ExchangeErrorType::*;
+        // https://docs.kraken.com/rest/#section/General-Usage/Error-Messages
+        // Error type is determined by the first error
+        match error.message.split("; ").next().unwrap_or_default() {
+            "EOrder:Unknown order" => OrderNotFound,
+            "EOrder:Insufficient funds" | "EOrder:Insufficient margin" => InsufficientFunds,
+            "EGeneral:Invalid arguments"
+            | "EOrder:Invalid order"
+            | "EOrder:Invalid price"
+            | "EOrder:Order minimum not met"
+            | "EOrder:Cost minimum not met"
+            | "EOrder:Tick size check failed"
+            | "EOrder:Post only order" => InvalidOrder,
+            "EAPI:Rate limit exceeded"
+            | "EOrder:Rate limit exceeded"
+            | "EGeneral:Too many requests" => RateLimit,
+            "EService:Unavailable" | "EService:Busy" | "EService:Deadline elapsed" => {
+                ServiceUnavailable
+            }
+            "EAPI:Invalid key"
+            | "EAPI:Invalid signature"
+            | "EAPI:Invalid nonce"
+            | "EGeneral:Permission denied" => Authentication,
+            _ => Unknown,
+        }
+    }
+}
+
+pub struct RestHeadersKraken {
+    api_key: String,
+    secret_key: Vec<u8>,
+}
+
+impl RestHeadersKraken {
+    pub fn new(api_key: String, secret_key: &str) -> Self {
+        Self {
+            api_key,
+            secret_key: base64::decode(secret_key)
+                .with_expect(|| "Kraken secret key should be base64 encoded"),
+        }
+    }
+}
+
+impl RestHeaders for RestHeadersKraken {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+    ) -> Builder {
+        // Only public endpoints are requested without body
+        builder
+    }
+
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        _request_type: RequestType,
+        body: &[u8],
+    ) -> Builder {
+        let builder = builder.header(
+            hyper::header::CONTENT_TYPE,
+            "application/x-www-form-urlencoded",
+        );
+
+        if self.api_key.is_empty() {
+            return builder;
+        }
+
+        // Nonce is a part of signed body, so it is taken from there
+        let nonce = form_urlencoded::parse(body)
+            .find(|(key, _)| key == "nonce")
+            .map(|(_, value)| value)
+            .unwrap_or_default();
+        let signature = Kraken::create_signature(&self.secret_key, uri.path(), &nonce, body);
+
+        builder
+            .header("API-Key", &self.api_key)
+            .header("API-Sign", signature)
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+
+pub struct Kraken {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerKraken, RestHeadersKraken>,
+    /// Currency pairs are specified by `wsname` of Kraken asset pair, e.g. XBT/USD
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    /// All names of asset pair (key, `altname` and `wsname`) are mapped to unified currency pair,
+    /// because different Kraken requests and channels use different names
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    /// `altname` of asset pair which is used for REST requests, e.g. XBTUSD
+    unified_to_rest_pair: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    /// Both REST (e.g. XXBT) and websocket (e.g. XBT) asset names are mapped to unified currency codes
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    /// Token for private websocket channels, it is requested on each connection
+    pub(super) websocket_token: RwLock<Option<String>>,
+    /// Order books which are needed to verify checksums of `book` channel updates
+    pub(super) order_books: Mutex<HashMap<SpecificCurrencyPair, LocalOrderBook>>,
+    last_nonce: AtomicU64,
+}
+
+impl Kraken {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Kraken {
+        Self {
+            rest_client: RestClient::new(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerKraken::default(),
+                ),
+                RestHeadersKraken::new(settings.api_key.clone(), &settings.secret_key),
+            ),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            unified_to_rest_pair: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+            websocket_token: Default::default(),
+            order_books: Default::default(),
+            last_nonce: Default::default(),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        Hosts {
+            web_socket_host: "wss://ws.kraken.com",
+            web_socket2_host: "wss://ws-auth.kraken.com",
+            rest_host: "https://api.kraken.com",
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/0/public/AssetPairs");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let asset_pairs: KrakenResponse<HashMap<SpecificCurrencyPair, KrakenAssetPair>> =
+            serde_json::from_str(&response.content)
+                .context("Unable to deserialize response from Kraken")?;
+
+        asset_pairs
+            .result
+            .iter()
+            .filter(|(_, asset_pair)| asset_pair.status == "online")
+            .filter_map(|(pair, asset_pair)| {
+                // Dark pool pairs can't be traded via websocket
+                let wsname = asset_pair.wsname?;
+                Some(self.specific_symbol_to_unified(*pair, wsname, asset_pair))
+            })
+            .try_collect()
+    }
+
+    fn specific_symbol_to_unified(
+        &self,
+        pair: SpecificCurrencyPair,
+        wsname: SpecificCurrencyPair,
+        asset_pair: &KrakenAssetPair,
+    ) -> Result<Arc<Symbol>> {
+        let (base_name, quote_name) = wsname
+            .as_str()
+            .split_once('/')
+            .with_context(|| format!("Unable to get currencies of Kraken pair {asset_pair:?}"))?;
+        let base = get_unified_currency_code(base_name);
+        let quote = get_unified_currency_code(quote_name);
+
+        for (currency_id, currency_code) in [
+            (asset_pair.base.as_str(), base),
+            (base_name, base),
+            (asset_pair.quote.as_str(), quote),
+            (quote_name, quote),
+        ] {
+            self.supported_currencies
+                .insert(currency_id.into(), currency_code);
+        }
+
+        let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, wsname);
+        self.unified_to_rest_pair
+            .write()
+            .insert(unified_currency_pair, asset_pair.altname);
+        let mut specific_to_unified = self.specific_to_unified.write();
+        for specific_currency_pair in [pair, asset_pair.altname, wsname] {
+            specific_to_unified.insert(specific_currency_pair, unified_currency_pair);
+        }
+
+        Ok(Arc::new(Symbol::new(
+            false,
+            asset_pair.base.as_str().into(),
+            base,
+            asset_pair.quote.as_str().into(),
+            quote,
+            None,
+            None,
+            asset_pair.ordermin,
+            None,
+            asset_pair.costmin,
+            base,
+            None,
+            Precision::tick_from_precision(asset_pair.pair_decimals),
+            Precision::tick_from_precision(asset_pair.lot_decimals),
+        )))
+    }
+
+    #[named]
+    pub(super) async fn request_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let side: KrakenOrderSide = header.side.into();
+        let mut params = vec![
+            ("pair", self.get_rest_pair(header.currency_pair).to_string()),
+            ("type", side.as_str().to_owned()),
+            ("volume", header.amount.to_string()),
+            ("cl_ord_id", header.client_order_id.to_string()),
+        ];
+
+        match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => {
+                params.push(("ordertype", "limit".to_owned()));
+                params.push(("price", price.to_string()));
+                match execution_type {
+                    OrderExecutionType::MakerOnly => params.push(("oflags", "post".to_owned())),
+                    OrderExecutionType::None => (),
+                }
+            }
+            OrderOptions::User(UserOrder::Market) => {
+                params.push(("ordertype", "market".to_owned()))
+            }
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        }
+
+        let log_args = format!("Create order for {header:?}");
+        self.post("/0/private/AddOrder", params, function_name!(), log_args)
+            .await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        #[derive(Deserialize)]
+        struct OrderIds {
+            txid: Vec<ExchangeOrderId>,
+        }
+
+        let deserialized: KrakenResponse<OrderIds> = serde_json::from_str(&response.content)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse txid: {err:?}")))?;
+
+        deserialized
+            .result
+            .txid
+            .into_iter()
+            .next()
+            .ok_or_else(|| ExchangeError::parsing("No one order id received".to_owned()))
+    }
+
+    #[named]
+    pub(super) async fn request_cancel_order(
+        &self,
+        client_order_id: &ClientOrderId,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let params = vec![("txid", exchange_order_id.to_string())];
+
+        let log_args = format!("Cancel order for {client_order_id} {exchange_order_id}");
+        self.post("/0/private/CancelOrder", params, function_name!(), log_args)
+            .await
+    }
+
+    /// Kraken `CancelAll` request cancels orders of all pairs, so open orders of the pair are cancelled one by one
+    pub(super) async fn do_cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()> {
+        let response = self.request_open_orders().await?;
+        let open_orders = self.parse_open_orders(&response)?;
+
+        for order in open_orders
+            .iter()
+            .filter(|order| order.currency_pair == currency_pair)
+        {
+            self.request_cancel_order(&order.client_order_id, &order.exchange_order_id)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    #[named]
+    pub(super) async fn request_cancel_all_orders_after(
+        &self,
+        timeout: Duration,
+    ) -> Result<RestResponse, ExchangeError> {
+        let params = vec![("timeout", timeout.as_secs().to_string())];
+
+        let log_args = format!("Cancel all orders after {timeout:?}");
+        self.post(
+            "/0/private/CancelAllOrdersAfter",
+            params,
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(&self) -> Result<RestResponse, ExchangeError> {
+        self.post(
+            "/0/private/OpenOrders",
+            Vec::new(),
+            function_name!(),
+            "".to_string(),
+        )
+        .await
+    }
+
+    pub fn parse_open_orders(&self, response: &RestResponse) -> Result<Vec<OrderInfo>> {
+        let orders: KrakenResponse<KrakenOpenOrders> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_open_orders request")?;
+
+        orders
+            .result
+            .open
+            .into_iter()
+            .map(|(exchange_order_id, order)| {
+                self.specific_order_info_to_unified(exchange_order_id, order)
+            })
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+        let exchange_order_id = order.exchange_order_id().ok_or_else(|| {
+            ExchangeError::unknown(&format!(
+                "Order {client_order_id} doesn't have exchange order id"
+            ))
+        })?;
+        let params = vec![("txid", exchange_order_id.to_string())];
+
+        let log_args = format!("order {client_order_id}");
+        self.post("/0/private/QueryOrders", params, function_name!(), log_args)
+            .await
+    }
+
+    pub fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let orders: KrakenResponse<HashMap<ExchangeOrderId, KrakenOrder>> =
+            serde_json::from_str(&response.content)
+                .context("Unable to parse response content for get_order_info request")?;
+
+        let (exchange_order_id, order) = orders
+            .result
+            .into_iter()
+            .next()
+            .context("No one order info received")?;
+
+        self.specific_order_info_to_unified(exchange_order_id, order)
+    }
+
+    fn specific_order_info_to_unified(
+        &self,
+        exchange_order_id: ExchangeOrderId,
+        order: KrakenOrder,
+    ) -> Result<OrderInfo> {
+        let currency_pair = self.get_unified_currency_pair(&order.descr.pair)?;
+        // Orders created not by us can be without client order id
+        let client_order_id = order.cl_ord_id.unwrap_or_else(|| "".into());
+
+        Ok(OrderInfo::new(
+            currency_pair,
+            exchange_order_id,
+            client_order_id,
+            order.descr.side.into(),
+            Self::get_local_order_status(&order.status)?,
+            order.descr.price,
+            order.vol,
+            order.price,
+            order.vol_exec,
+            // Fee is charged in quote currency by default
+            Some(currency_pair.to_codes().quote.to_string()),
+            None,
+            Some(order.fee),
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    fn get_rest_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_rest_pair.read()[&currency_pair]
+    }
+
+    pub(super) fn get_local_order_status(status: &str) -> Result<OrderStatus> {
+        Ok(match status {
+            "pending" | "open" => OrderStatus::Created,
+            "closed" => OrderStatus::Completed,
+            "canceled" | "expired" => OrderStatus::Canceled,
+            _ => bail!("Kraken: unexpected order status {status}"),
+        })
+    }
+
+    pub(super) fn get_order_role(maker: Option<bool>) -> Option<OrderRole> {
+        maker.map(|maker| match maker {
+            true => OrderRole::Maker,
+            false => OrderRole::Taker,
+        })
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut params = Vec::new();
+        if let Some(date_time) = last_date_time {
+            params.push(("start", date_time.timestamp().to_string()));
+        }
+
+        self.post(
+            "/0/private/TradesHistory",
+            params,
+            function_name!(),
+            "".to_string(),
+        )
+        .await
+    }
+
+    /// Kraken trades history can't be filtered by pair, so trades of other pairs are skipped
+    pub fn parse_my_trades(
+        &self,
+        response: &RestResponse,
+        currency_pair: CurrencyPair,
+    ) -> Result<Vec<OrderTrade>> {
+        let trades: KrakenResponse<KrakenTradesHistory> =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+
+        trades
+            .result
+            .trades
+            .into_iter()
+            .filter(|(_, trade)| {
+                self.get_unified_currency_pair(&trade.pair).ok() == Some(currency_pair)
+            })
+            .map(|(trade_id, trade)| {
+                let order_role = Self::get_order_role(trade.maker)
+                    .with_context(|| format!("Unknown order role of trade {trade:?}"))?;
+
+                Result::<_, anyhow::Error>::Ok(OrderTrade::new(
+                    trade.ordertxid,
+                    TradeId::from(trade_id),
+                    trade.time,
+                    trade.price,
+                    trade.vol,
+                    order_role,
+                    currency_pair.to_codes().quote,
+                    None,
+                    Some(trade.fee),
+                    OrderFillType::UserTrade,
+                ))
+            })
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        self.post(
+            "/0/private/BalanceEx",
+            Vec::new(),
+            function_name!(),
+            "".to_string(),
+        )
+        .await
+    }
+
+    pub fn parse_get_balance(&self, response: &RestResponse) -> Result<Vec<ExchangeBalance>> {
+        let balances: KrakenResponse<HashMap<String, KrakenBalance>> =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        // Assets which aren't used in any pair are skipped, e.g. staked assets with suffix `.S`
+        Ok(balances
+            .result
+            .into_iter()
+            .filter_map(|(asset, balance)| {
+                let currency_code = *self
+                    .supported_currencies
+                    .get(&CurrencyId::from(asset.as_str()))?;
+                Some(ExchangeBalance {
+                    currency_code,
+                    balance: balance.balance - balance.hold_trade,
+                })
+            })
+            .collect())
+    }
+
+    #[named]
+    pub(super) async fn request_get_server_time(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/0/public/Time");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_server_time(&self, response: &RestResponse) -> Result<i64> {
+        #[derive(Deserialize)]
+        struct ServerTime {
+            unixtime: i64,
+        }
+
+        let server_time: KrakenResponse<ServerTime> = serde_json::from_str(&response.content)
+            .context("Failed to parse Kraken get time response")?;
+
+        Ok(server_time.result.unixtime * 1000)
+    }
+
+    #[named]
+    pub(super) async fn request_websocket_token(&self) -> Result<RestResponse, ExchangeError> {
+        self.post(
+            "/0/private/GetWebSocketsToken",
+            Vec::new(),
+            function_name!(),
+            "".to_string(),
+        )
+        .await
+    }
+
+    pub(super) fn parse_websocket_token(response: &RestResponse) -> Result<String> {
+        #[derive(Deserialize)]
+        struct WebsocketToken {
+            token: String,
+        }
+
+        let token: KrakenResponse<WebsocketToken> = serde_json::from_str(&response.content)
+            .context("Failed to parse Kraken websocket token")?;
+
+        Ok(token.result.token)
+    }
+
+    /// All private Kraken requests are POST requests with form encoded parameters including nonce
+    async fn post(
+        &self,
+        path: &str,
+        params: Vec<(&str, String)>,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let nonce = self.generate_nonce().to_string();
+        let body = form_urlencoded::Serializer::new(String::new())
+            .append_pair("nonce", &nonce)
+            .extend_pairs(params)
+            .finish();
+        let uri = UriBuilder::from_path(path).build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .post(uri, Some(Bytes::from(body)), action_name, log_args)
+            .await
+    }
+
+    /// Nonce should increase with each private request, so requests sent within the same millisecond get different nonces
+    fn generate_nonce(&self) -> u64 {
+        let now = u64::try_from(chrono::Utc::now().timestamp_millis()).unwrap_or_default();
+        let previous = self
+            .last_nonce
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |last| {
+                Some(now.max(last + 1))
+            })
+            .unwrap_or_else(|last| last);
+
+        now.max(previous + 1)
+    }
+
+    /// Base64 encoded HMAC SHA512 of URI path and SHA256 of nonce and POST data with base64 decoded secret key
+    pub fn create_signature(secret_key: &[u8], path: &str, nonce: &str, body: &[u8]) -> String {
+        let mut sha256 = Sha256::new();
+        sha256.update(nonce.as_bytes());
+        sha256.update(body);
+
+        let mut hmac = Hmac::<Sha512>::new_from_slice(secret_key)
+            .expect("Unable to calculate hmac for Kraken signature");
+        hmac.update(path.as_bytes());
+        hmac.update(&sha256.finalize());
+
+        base64::encode(hmac.finalize().into_bytes())
+    }
+}
+
+/// Kraken uses legacy names for some currencies
+fn get_unified_currency_code(currency: &str) -> CurrencyCode {
+    match currency {
+        "XBT" => "BTC",
+        "XDG" => "DOGE",
+        _ => currency,
+    }
+    .into()
+}
+
+pub struct KrakenBuilder;
+
+impl ExchangeClientBuilder for KrakenBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Kraken::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_cancel_all_after: true,
+                    ..OrderFeatures::default()
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: true,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: true,
+                    supports_subscription_response: false,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        // Kraken uses counter of requests which is decreased by 0.33-1 per second depending on verification tier
+        RequestTimeoutArguments::from_requests_per_minute(60)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Kraken".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_signature() {
+        let secret_key = base64::decode(
+            "kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==",
+        )
+        .expect("Failed to decode secret key");
+
+        let signature = Kraken::create_signature(
+            &secret_key,
+            "/0/private/AddOrder",
+            "1616492376594",
+            b"nonce=1616492376594&ordertype=limit&pair=XBTUSD&price=37500&type=buy&volume=1.25",
+        );
+
+        assert_eq!(
+            signature,
+            "4/dpxb3iT4tp/ZCVEwSnEsLxx0bqyhLpdfOpc6fn7OR8+UClSV5n9E6aSS8MPtnRfp32bAb0nmbRn6H8ndwLUQ=="
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use crate::types::KrakenBookLevel;
+use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+/// Depth of subscription to `book` channel. Checksum is calculated by top 10 levels too
+pub(crate) const BOOK_DEPTH: usize = 10;
+
+/// Local copy of subscribed top of Kraken order book. It is needed to calculate checksum
+/// after each update, because levels which go out of subscribed depth aren't deleted by Kraken explicitly
+#[derive(Default)]
+pub(crate) struct LocalOrderBook {
+    asks: BTreeMap<Price, Amount>,
+    bids: BTreeMap<Price, Amount>,
+}
+
+impl LocalOrderBook {
+    pub(crate) fn from_snapshot(asks: &[KrakenBookLevel], bids: &[KrakenBookLevel]) -> Self {
+        let mut order_book = LocalOrderBook::default();
+        order_book.apply_update(asks, bids);
+        order_book
+    }
+
+    pub(crate) fn to_order_book_data(&self) -> OrderBookData {
+        OrderBookData::new(self.asks.clone(), self.bids.clone())
+    }
+
+    /// Returns update for unified order book including levels removed due to truncation by subscribed depth
+    pub(crate) fn apply_update(
+        &mut self,
+        asks: &[KrakenBookLevel],
+        bids: &[KrakenBookLevel],
+    ) -> OrderBookData {
+        let mut update = OrderBookData::default();
+
+        for KrakenBookLevel(price, amount, ..) in asks {
+            update_level(&mut self.asks, *price, *amount);
+            update.asks.insert(*price, *amount);
+        }
+        for KrakenBookLevel(price, amount, ..) in bids {
+            update_level(&mut self.bids, *price, *amount);
+            update.bids.insert(*price, *amount);
+        }
+
+        // Asks are truncated from the highest prices and bids from the lowest ones
+        while self.asks.len() > BOOK_DEPTH {
+            if let Some((price, _)) = self.asks.pop_last() {
+                update.asks.insert(price, Decimal::ZERO);
+            }
+        }
+        while self.bids.len() > BOOK_DEPTH {
+            if let Some((price, _)) = self.bids.pop_first() {
+                update.bids.insert(price, Decimal::ZERO);
+            }
+        }
+
+        update
+    }
+
+    /// CRC32 of concatenated prices and amounts of top 10 asks from the lowest price and top 10 bids
+    /// from the highest price. Decimal point and leading zeros are removed from each value
+    pub(crate) fn checksum(&self) -> u32 {
+        let asks = self.asks.iter().take(BOOK_DEPTH);
+        let bids = self.bids.iter().rev().take(BOOK_DEPTH);
+
+        let mut hasher = crc32fast::Hasher::new();
+        for (price, amount) in asks.chain(bids) {
+            hasher.update(checksum_value(price).as_bytes());
+            hasher.update(checksum_value(amount).as_bytes());
+        }
+
+        hasher.finalize()
+    }
+}
+
+fn update_level(levels: &mut BTreeMap<Price, Amount>, price: Price, amount: Amount) {
+    match amount.is_zero() {
+        true => levels.remove(&price),
+        false => levels.insert(price, amount),
+    };
+}
+
+/// Kraken sends values with fixed precision of the pair and `Decimal` keeps that scale
+fn checksum_value(value: &Decimal) -> String {
+    value
+        .to_string()
+        .replace('.', "")
+        .trim_start_matches('0')
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn levels(prices: &[Decimal], amount: Decimal) -> Vec<KrakenBookLevel> {
+        prices
+            .iter()
+            .map(|price| KrakenBookLevel(*price, amount, String::new(), None))
+            .collect()
+    }
+
+    #[test]
+    fn checksum() {
+        let asks = levels(
+            &[
+                dec!(0.05005),
+                dec!(0.05010),
+                dec!(0.05015),
+                dec!(0.05020),
+                dec!(0.05025),
+                dec!(0.05030),
+                dec!(0.05035),
+                dec!(0.05040),
+                dec!(0.05045),
+                dec!(0.05050),
+            ],
+            dec!(0.00000500),
+        );
+        let bids = levels(
+            &[
+                dec!(0.05000),
+                dec!(0.04995),
+                dec!(0.04990),
+                dec!(0.04980),
+                dec!(0.04975),
+                dec!(0.04970),
+                dec!(0.04965),
+                dec!(0.04960),
+                dec!(0.04955),
+                dec!(0.04950),
+            ],
+            dec!(0.00000500),
+        );
+
+        let order_book = LocalOrderBook::from_snapshot(&asks, &bids);
+
+        assert_eq!(order_book.checksum(), 974947235);
+    }
+
+    #[test]
+    fn truncate_by_depth() {
+        let asks = levels(
+            &(1..=BOOK_DEPTH)
+                .map(|x| Decimal::from(100 + x))
+                .collect::<Vec<_>>(),
+            dec!(1),
+        );
+        let mut order_book = LocalOrderBook::from_snapshot(&asks, &[]);
+
+        let update = order_book.apply_update(&levels(&[dec!(100)], dec!(2)), &[]);
+
+        assert_eq!(
+            update.asks,
+            BTreeMap::from([(dec!(100), dec!(2)), (dec!(110), dec!(0))])
+        );
+        assert_eq!(order_book.asks.len(), BOOK_DEPTH);
+        assert_eq!(
+            order_book.asks.first_key_value(),
+            Some((&dec!(100), &dec!(2)))
+        );
+    }
+}
@@ -0,0 +1,511 @@
+use crate::kraken::Kraken;
+use crate::order_book::{LocalOrderBook, BOOK_DEPTH};
+use crate::types::{
+    parse_timestamp, KrakenBookData, KrakenOrderUpdate, KrakenPublicTrade, KrakenTrade,
+};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::WebSocketRole;
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::snapshot::ExchangeOrderId;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+use url::Url;
+
+#[async_trait]
+impl Support for Kraken {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: Value = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        match message {
+            // Events are objects and channel messages are arrays
+            Value::Object(_) => self.handle_event(
+                serde_json::from_value(message).context("Unable to parse websocket event")?,
+            ),
+            Value::Array(items) => self.handle_channel_message(items),
+            _ => bail!("Unexpected Kraken websocket message {msg}"),
+        }
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        self.subscribe_to_public_channels()?;
+
+        match self.is_websocket_enabled(WebSocketRole::Secondary) {
+            true => self.subscribe_to_private_channels(),
+            false => Ok(()),
+        }
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        *self.websocket_token.write() = None;
+        // Snapshots are received again after reconnection
+        self.order_books.lock().clear();
+
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => true,
+            WebSocketRole::Secondary => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+            WebSocketRole::MarketData(_) => false,
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => {
+                // Private channels are authenticated by token which is valid for 15 minutes
+                // until connection is established and until disconnection after that
+                let response = self.request_websocket_token().await?;
+                *self.websocket_token.write() = Some(Kraken::parse_websocket_token(&response)?);
+
+                self.hosts.web_socket2_host
+            }
+            WebSocketRole::MarketData(_) => {
+                bail!("Market data connections aren't supported by Kraken")
+            }
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains("ownTrades") || message.contains("openOrders")
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+}
+
+impl Kraken {
+    fn handle_event(&self, event: EventMessage) -> Result<()> {
+        match event.event.as_str() {
+            "heartbeat" | "pong" | "systemStatus" => Ok(()),
+            "subscriptionStatus" if event.status.as_deref() == Some("error") => {
+                let err = format!(
+                    "Kraken websocket subscription error for {:?}: {}",
+                    event.pair,
+                    event.error_message.unwrap_or_default()
+                );
+                log::error!("{err}");
+                bail!(err)
+            }
+            _ => {
+                log::info!("Kraken websocket: {event:?}");
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_channel_message(&self, mut items: Vec<Value>) -> Result<()> {
+        match items.last() {
+            // Private messages: [data, channel name, {"sequence": 1}]
+            Some(Value::Object(_)) => {
+                let channel = items
+                    .get(1)
+                    .and_then(Value::as_str)
+                    .context("Channel name is missing in Kraken private message")?
+                    .to_owned();
+                let data = items.swap_remove(0);
+
+                match channel.as_str() {
+                    "ownTrades" => {
+                        let trades: Vec<HashMap<String, KrakenTrade>> =
+                            serde_json::from_value(data).context("Unable to parse own trades")?;
+                        for (trade_id, trade) in trades.into_iter().flatten() {
+                            self.handle_order_fill(trade_id, trade)?;
+                        }
+                    }
+                    "openOrders" => {
+                        let orders: Vec<HashMap<ExchangeOrderId, KrakenOrderUpdate>> =
+                            serde_json::from_value(data).context("Unable to parse open orders")?;
+                        for (exchange_order_id, order) in orders.into_iter().flatten() {
+                            self.handle_order_update(exchange_order_id, order);
+                        }
+                    }
+                    channel => bail!("Unsupported Kraken private channel {channel}"),
+                }
+            }
+            // Public messages: [channel id, data (1 or 2 items), channel name, pair]
+            Some(Value::String(pair)) if items.len() >= 4 => {
+                let specific_currency_pair = SpecificCurrencyPair::from(pair.as_str());
+                let channel_index = items.len() - 2;
+                let channel = items[channel_index]
+                    .as_str()
+                    .context("Channel name is missing in Kraken public message")?
+                    .to_owned();
+                let data = items.drain(1..channel_index).collect();
+
+                match channel.as_str() {
+                    channel if channel.starts_with("book") => {
+                        let data: Vec<KrakenBookData> = serde_json::from_value(Value::Array(data))
+                            .context("Unable to parse order book data")?;
+                        self.handle_order_book(specific_currency_pair, data)?;
+                    }
+                    "trade" => {
+                        let trades: Vec<Vec<KrakenPublicTrade>> =
+                            serde_json::from_value(Value::Array(data))
+                                .context("Unable to parse trades data")?;
+                        for trade in trades.into_iter().flatten() {
+                            self.handle_trade(specific_currency_pair, trade)?;
+                        }
+                    }
+                    channel => bail!("Unsupported Kraken public channel {channel}"),
+                }
+            }
+            _ => bail!("Unexpected Kraken channel message {items:?}"),
+        }
+
+        Ok(())
+    }
+
+    /// Checksum of the local order book is verified after each update. In case of mismatch
+    /// subscription is renewed, so the order book is resynchronized by a new snapshot
+    fn handle_order_book(
+        &self,
+        specific_currency_pair: SpecificCurrencyPair,
+        data: Vec<KrakenBookData>,
+    ) -> Result<()> {
+        let currency_pair = self.get_unified_currency_pair(&specific_currency_pair)?;
+
+        let is_snapshot = data
+            .iter()
+            .any(|x| x.snapshot_asks.is_some() || x.snapshot_bids.is_some());
+        if is_snapshot {
+            let mut asks = Vec::new();
+            let mut bids = Vec::new();
+            for book_data in data {
+                asks.extend(book_data.snapshot_asks.unwrap_or_default());
+                bids.extend(book_data.snapshot_bids.unwrap_or_default());
+            }
+
+            let order_book = LocalOrderBook::from_snapshot(&asks, &bids);
+            let order_book_data = order_book.to_order_book_data();
+            self.order_books
+                .lock()
+                .insert(specific_currency_pair, order_book);
+
+            return self.send_order_book_event(currency_pair, EventType::Snapshot, order_book_data);
+        }
+
+        let mut asks = Vec::new();
+        let mut bids = Vec::new();
+        let mut checksum = None;
+        for book_data in data {
+            asks.extend(book_data.asks);
+            bids.extend(book_data.bids);
+            checksum = checksum.or(book_data.checksum);
+        }
+        let checksum: u32 = checksum
+            .context("Checksum is missing in Kraken order book update")?
+            .parse()
+            .context("Unable to parse Kraken order book checksum")?;
+
+        let update = {
+            let mut order_books = self.order_books.lock();
+            // Updates are skipped until a new snapshot is received after resubscription
+            let Some(order_book) = order_books.get_mut(&specific_currency_pair) else {
+                return Ok(());
+            };
+
+            let update = order_book.apply_update(&asks, &bids);
+            match order_book.checksum() == checksum {
+                true => Some(update),
+                false => {
+                    let _ = order_books.remove(&specific_currency_pair);
+                    None
+                }
+            }
+        };
+
+        match update {
+            Some(update) => self.send_order_book_event(currency_pair, EventType::Update, update),
+            None => {
+                log::warn!(
+                    "Kraken order book checksum mismatch for {specific_currency_pair}, order book will be resubscribed"
+                );
+                self.resubscribe_to_order_book(specific_currency_pair)
+            }
+        }
+    }
+
+    fn send_order_book_event(
+        &self,
+        currency_pair: CurrencyPair,
+        event_type: EventType,
+        order_book_data: OrderBookData,
+    ) -> Result<()> {
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            String::default(),
+            event_type,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trade(
+        &self,
+        specific_currency_pair: SpecificCurrencyPair,
+        trade: KrakenPublicTrade,
+    ) -> Result<()> {
+        let KrakenPublicTrade(price, quantity, time, side, ..) = trade;
+
+        (self.handle_trade_callback)(
+            self.get_unified_currency_pair(&specific_currency_pair)?,
+            Trade {
+                // Kraken doesn't send ids of public trades
+                trade_id: TradeId::from(time.clone()),
+                price,
+                quantity,
+                side: side.into(),
+                transaction_time: parse_timestamp(&time)?,
+                tick_direction: None,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn handle_order_fill(&self, trade_id: String, trade: KrakenTrade) -> Result<()> {
+        let currency_pair = self.get_unified_currency_pair(&trade.pair)?;
+
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::from(trade_id)),
+            client_order_id: None,
+            exchange_order_id: trade.ordertxid,
+            fill_price: trade.price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: trade.vol,
+                total_filled_amount: None,
+            },
+            order_role: Kraken::get_order_role(trade.maker),
+            // Fee is charged in quote currency by default
+            commission_currency_code: Some(currency_pair.to_codes().quote),
+            commission_rate: None,
+            commission_amount: Some(trade.fee),
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date: Some(trade.time),
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+
+        Ok(())
+    }
+
+    fn handle_order_update(&self, exchange_order_id: ExchangeOrderId, order: KrakenOrderUpdate) {
+        match (order.status.as_deref(), order.cl_ord_id) {
+            // New orders are sent with full description including client order id
+            (Some("pending" | "open"), Some(client_order_id)) => (self.order_created_callback)(
+                client_order_id,
+                exchange_order_id,
+                EventSourceType::WebSocket,
+            ),
+            (Some("canceled" | "expired"), client_order_id) => (self.order_cancelled_callback)(
+                // Updates contain only changed fields, cancellation is matched by exchange order id
+                client_order_id.unwrap_or_else(|| "".into()),
+                exchange_order_id,
+                EventSourceType::WebSocket,
+            ),
+            _ => (),
+        }
+    }
+
+    fn subscribe_to_public_channels(&self) -> Result<()> {
+        let traded_currencies = self.traded_specific_currencies.lock().clone();
+        if traded_currencies.is_empty() {
+            return Ok(());
+        }
+
+        for subscription in [Subscription::book(), Subscription::trade()] {
+            self.send_request(
+                WebSocketRole::Main,
+                "subscribe",
+                Some(traded_currencies.clone()),
+                subscription,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn subscribe_to_private_channels(&self) -> Result<()> {
+        let token = self
+            .websocket_token
+            .read()
+            .clone()
+            .context("Kraken websocket token isn't received")?;
+
+        for name in ["ownTrades", "openOrders"] {
+            let subscription = Subscription {
+                name,
+                depth: None,
+                token: Some(&token),
+                // Only new trades are needed, the older ones are requested via REST
+                snapshot: (name == "ownTrades").then_some(false),
+            };
+            self.send_request(WebSocketRole::Secondary, "subscribe", None, subscription)?;
+        }
+
+        Ok(())
+    }
+
+    fn resubscribe_to_order_book(
+        &self,
+        specific_currency_pair: SpecificCurrencyPair,
+    ) -> Result<()> {
+        for event in ["unsubscribe", "subscribe"] {
+            self.send_request(
+                WebSocketRole::Main,
+                event,
+                Some(vec![specific_currency_pair]),
+                Subscription::book(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn send_request(
+        &self,
+        role: WebSocketRole,
+        event: &'static str,
+        pair: Option<Vec<SpecificCurrencyPair>>,
+        subscription: Subscription,
+    ) -> Result<()> {
+        let request = Request {
+            event,
+            pair,
+            subscription,
+        };
+        let message =
+            serde_json::to_string(&request).expect("Failed to serialize subscription message");
+
+        (self.websocket_message_callback)(role, message)
+    }
+}
+
+/// Responses to subscriptions, heartbeats and system status
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EventMessage {
+    event: String,
+    status: Option<String>,
+    error_message: Option<String>,
+    pair: Option<SpecificCurrencyPair>,
+}
+
+#[derive(Serialize)]
+struct Request<'a> {
+    event: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pair: Option<Vec<SpecificCurrencyPair>>,
+    subscription: Subscription<'a>,
+}
+
+#[derive(Serialize)]
+struct Subscription<'a> {
+    name: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    depth: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snapshot: Option<bool>,
+}
+
+impl Subscription<'static> {
+    fn book() -> Self {
+        Subscription {
+            name: "book",
+            depth: Some(BOOK_DEPTH),
+            token: None,
+            snapshot: None,
+        }
+    }
+
+    fn trade() -> Self {
+        Subscription {
+            name: "trade",
+            depth: None,
+            token: None,
+            snapshot: None,
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use chrono::{TimeZone, Utc};
+use mmb_domain::market::SpecificCurrencyPair;
+use mmb_domain::order::snapshot::{Amount, ClientOrderId, ExchangeOrderId, OrderSide, Price};
+use mmb_utils::DateTime;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+
+/// Common envelope of all Kraken REST responses
+/// {
+/// "error": [], // List of errors in format "<severity><category>:<message>", e.g. "EOrder:Unknown order"
+/// "result": {} // Response payload, is missing in case of errors
+/// }
+#[derive(Deserialize, Debug)]
+pub struct KrakenResponse<T> {
+    #[serde(default)]
+    pub error: Vec<String>,
+    pub result: T,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KrakenOrderSide {
+    #[serde(alias = "b")]
+    Buy,
+    #[serde(alias = "s")]
+    Sell,
+}
+
+impl KrakenOrderSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KrakenOrderSide::Buy => "buy",
+            KrakenOrderSide::Sell => "sell",
+        }
+    }
+}
+
+impl From<KrakenOrderSide> for OrderSide {
+    fn from(side: KrakenOrderSide) -> Self {
+        match side {
+            KrakenOrderSide::Buy => OrderSide::Buy,
+            KrakenOrderSide::Sell => OrderSide::Sell,
+        }
+    }
+}
+
+impl From<OrderSide> for KrakenOrderSide {
+    fn from(side: OrderSide) -> Self {
+        match side {
+            OrderSide::Buy => KrakenOrderSide::Buy,
+            OrderSide::Sell => KrakenOrderSide::Sell,
+        }
+    }
+}
+
+/// Kraken asset pair description. Pair has 3 names: key of the pair in response (e.g. XXBTZUSD),
+/// `altname` (e.g. XBTUSD) and `wsname` (e.g. XBT/USD). Only used fields are described
+/// "XXBTZUSD": {
+/// "altname": "XBTUSD",
+/// "wsname": "XBT/USD", // Is missing for dark pool pairs
+/// "base": "XXBT", // Asset name of base currency
+/// "quote": "ZUSD", // Asset name of quote currency
+/// "pair_decimals": 1, // Scaling decimal places for price
+/// "lot_decimals": 8, // Scaling decimal places for volume
+/// "ordermin": "0.0001", // Minimum order size in base currency
+/// "costmin": "0.5", // Minimum order cost in quote currency
+/// "tick_size": "0.1",
+/// "status": "online" // online, cancel_only, post_only, limit_only, reduce_only
+/// }
+#[derive(Deserialize, Debug)]
+pub struct KrakenAssetPair {
+    pub altname: SpecificCurrencyPair,
+    pub wsname: Option<SpecificCurrencyPair>,
+    pub base: String,
+    pub quote: String,
+    pub pair_decimals: i8,
+    pub lot_decimals: i8,
+    pub ordermin: Option<Amount>,
+    pub costmin: Option<Price>,
+    #[serde(default)]
+    pub status: String,
+}
+
+/// Kraken order description. It is used for REST `OpenOrders` and `QueryOrders` responses
+/// {
+/// "refid": null,
+/// "userref": 0,
+/// "cl_ord_id": "1665480000123", // Is missing if order was created without client order id
+/// "status": "open", // pending, open, closed, canceled, expired
+/// "opentm": 1688666559.8974,
+/// "descr": {
+///     "pair": "XBTUSD", // Altname of the pair
+///     "type": "buy",
+///     "ordertype": "limit",
+///     "price": "30010.0", // Limit price, zero for market orders
+///     "order": "buy 1.25000000 XBTUSD @ limit 30010.0"
+/// },
+/// "vol": "1.25000000", // Volume of order
+/// "vol_exec": "0.37500000", // Volume executed
+/// "cost": "11253.7", // Total cost in quote currency
+/// "fee": "0.00000", // Total fee in quote currency
+/// "price": "30010.0", // Average price
+/// "oflags": "fciq"
+/// }
+#[derive(Deserialize, Debug)]
+pub struct KrakenOrder {
+    pub cl_ord_id: Option<ClientOrderId>,
+    pub status: String,
+    pub descr: KrakenOrderDescription,
+    pub vol: Amount,
+    pub vol_exec: Amount,
+    pub fee: Amount,
+    pub price: Price,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct KrakenOrderDescription {
+    pub pair: SpecificCurrencyPair,
+    #[serde(rename = "type")]
+    pub side: KrakenOrderSide,
+    pub price: Price,
+}
+
+/// Result of `OpenOrders` request
+#[derive(Deserialize, Debug)]
+pub struct KrakenOpenOrders {
+    pub open: HashMap<ExchangeOrderId, KrakenOrder>,
+}
+
+/// Kraken trade of user. It is used for REST `TradesHistory` response and for `ownTrades` websocket channel
+/// "TDLH43-DVQXD-2KHVYY": {
+/// "ordertxid": "OQCLML-BW3P3-BUCMWZ", // Order id
+/// "postxid": "TKH2SE-M7IF5-CFI7LT", // Position id
+/// "pair": "XXBTZUSD", // Pair key for REST and wsname for websocket
+/// "time": 1688667796.8802, // Unix timestamp in seconds, number for REST and string for websocket
+/// "type": "buy",
+/// "ordertype": "limit",
+/// "price": "30010.00000",
+/// "cost": "600.20000", // Total cost in quote currency
+/// "fee": "0.00000", // Fee in quote currency
+/// "vol": "0.02000000",
+/// "margin": "0.00000",
+/// "maker": true // Whether user was a maker of the trade
+/// }
+#[derive(Deserialize, Debug)]
+pub struct KrakenTrade {
+    pub ordertxid: ExchangeOrderId,
+    pub pair: SpecificCurrencyPair,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub time: DateTime,
+    #[serde(rename = "type")]
+    pub side: KrakenOrderSide,
+    pub price: Price,
+    pub fee: Amount,
+    pub vol: Amount,
+    pub maker: Option<bool>,
+}
+
+/// Result of `TradesHistory` request
+#[derive(Deserialize, Debug)]
+pub struct KrakenTradesHistory {
+    pub trades: HashMap<String, KrakenTrade>,
+}
+
+/// Data of `openOrders` websocket channel. The first message contains full descriptions of open orders
+/// and further messages contain only changed fields of orders
+/// "OGTT3Y-C6I3P-XRI6HX": {
+/// "status": "canceled", // pending, open, closed, canceled, expired
+/// "cancel_reason": "User requested"
+/// }
+#[derive(Deserialize, Debug)]
+pub struct KrakenOrderUpdate {
+    pub status: Option<String>,
+    pub cl_ord_id: Option<ClientOrderId>,
+}
+
+/// Kraken extended balance of asset. Assets are named in Kraken format, e.g. XXBT or ZUSD
+/// "XXBT": {
+/// "balance": "1.2300000000", // Total balance of asset
+/// "hold_trade": "0.2300000000" // Balance reserved by open orders
+/// }
+#[derive(Deserialize, Debug)]
+pub struct KrakenBalance {
+    pub balance: Amount,
+    #[serde(default)]
+    pub hold_trade: Amount,
+}
+
+/// Data of `book` websocket channel. Each level is [price, volume, timestamp] with optional
+/// 4th element "r" for republished updates. Snapshot contains `as` and `bs` fields and update
+/// contains `a` and/or `b` fields. Checksum is sent in the last data object of an update
+/// {
+/// "a": [["5541.30000", "2.50700000", "1534614248.456738"]],
+/// "c": "974942666"
+/// }
+#[derive(Deserialize, Debug)]
+pub struct KrakenBookData {
+    #[serde(rename = "as", default)]
+    pub snapshot_asks: Option<Vec<KrakenBookLevel>>,
+    #[serde(rename = "bs", default)]
+    pub snapshot_bids: Option<Vec<KrakenBookLevel>>,
+    #[serde(rename = "a", default)]
+    pub asks: Vec<KrakenBookLevel>,
+    #[serde(rename = "b", default)]
+    pub bids: Vec<KrakenBookLevel>,
+    #[serde(rename = "c")]
+    pub checksum: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct KrakenBookLevel(
+    pub Price,
+    pub Amount,
+    pub String,
+    #[serde(default)] pub Option<String>,
+);
+
+/// Data of `trade` websocket channel: [price, volume, time, side, order type, miscellaneous]
+/// ["5541.20000", "0.15850568", "1534614057.321597", "s", "l", ""]
+#[derive(Deserialize, Debug)]
+pub struct KrakenPublicTrade(
+    pub Price,
+    pub Amount,
+    pub String,
+    pub KrakenOrderSide,
+    pub String,
+    pub String,
+);
+
+/// Kraken timestamps are Unix time in seconds with fractional part, sent either as strings or as numbers
+fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<DateTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Timestamp {
+        Text(String),
+        Number(f64),
+    }
+
+    match Timestamp::deserialize(deserializer)? {
+        Timestamp::Text(value) => parse_timestamp(&value),
+        Timestamp::Number(value) => Decimal::try_from(value)
+            .map_err(anyhow::Error::from)
+            .and_then(to_date_time),
+    }
+    .map_err(de::Error::custom)
+}
+
+pub(crate) fn parse_timestamp(value: &str) -> anyhow::Result<DateTime> {
+    to_date_time(value.parse()?)
+}
+
+fn to_date_time(seconds: Decimal) -> anyhow::Result<DateTime> {
+    let nanos = (seconds * dec!(1_000_000_000))
+        .trunc()
+        .to_i64()
+        .ok_or_else(|| anyhow::anyhow!("Invalid timestamp {seconds}"))?;
+
+    Ok(Utc.timestamp_nanos(nanos))
+}
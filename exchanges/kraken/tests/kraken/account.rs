@@ -0,0 +1,19 @@
+use crate::kraken::common::{create_kraken_with_symbols, response};
+use rust_decimal_macros::dec;
+
+#[test]
+fn parse_balance() {
+    let (kraken, _) = create_kraken_with_symbols();
+
+    let mut balances = kraken
+        .parse_get_balance(&response(include_str!("fixtures/balance.json")))
+        .expect("Failed to parse balance");
+    balances.sort_by_key(|x| x.currency_code.as_str().to_owned());
+
+    // balances of earn assets with suffix are skipped
+    assert_eq!(balances.len(), 2);
+    assert_eq!(balances[0].currency_code, "btc".into());
+    assert_eq!(balances[0].balance, dec!(1.499));
+    assert_eq!(balances[1].currency_code, "usd".into());
+    assert_eq!(balances[1].balance, dec!(1000));
+}
@@ -0,0 +1,47 @@
+use hyper::StatusCode;
+use kraken::kraken::Kraken;
+use mmb_core::exchanges::rest_client::RestResponse;
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_utils::cancellation_token::CancellationToken;
+use tokio::sync::broadcast;
+
+pub(crate) fn spot_currency_pair() -> CurrencyPair {
+    CurrencyPair::from_codes("btc".into(), "usd".into())
+}
+
+pub(crate) fn response(content: &str) -> RestResponse {
+    RestResponse {
+        status: StatusCode::OK,
+        content: content.to_owned(),
+    }
+}
+
+pub(crate) fn create_kraken() -> (Kraken, broadcast::Receiver<ExchangeEvent>) {
+    let settings = ExchangeSettings::new_short(
+        ExchangeAccountId::new("Kraken", 0),
+        "".to_owned(),
+        "".to_owned(),
+        false,
+    );
+    let (events_sender, events_receiver) = broadcast::channel(10);
+    let kraken = Kraken::new(
+        settings,
+        events_sender,
+        AppLifetimeManager::new(CancellationToken::default()),
+    );
+
+    (kraken, events_receiver)
+}
+
+/// Symbols are needed for mapping of Kraken pairs and assets to unified ones
+pub(crate) fn create_kraken_with_symbols() -> (Kraken, broadcast::Receiver<ExchangeEvent>) {
+    let (kraken, events_receiver) = create_kraken();
+    kraken
+        .parse_all_symbols(&response(include_str!("fixtures/asset_pairs.json")))
+        .expect("Failed to parse Kraken asset pairs");
+
+    (kraken, events_receiver)
+}
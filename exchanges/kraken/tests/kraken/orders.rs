@@ -0,0 +1,70 @@
+use crate::kraken::common::{create_kraken_with_symbols, response, spot_currency_pair};
+use mmb_domain::events::TradeId;
+use mmb_domain::order::snapshot::{OrderRole, OrderSide, OrderStatus};
+use rust_decimal_macros::dec;
+
+#[test]
+fn parse_open_orders() {
+    let (kraken, _) = create_kraken_with_symbols();
+
+    let orders = kraken
+        .parse_open_orders(&response(include_str!("fixtures/open_orders.json")))
+        .expect("Failed to parse open orders");
+
+    assert_eq!(orders.len(), 2);
+
+    let partially_filled = orders
+        .iter()
+        .find(|x| x.exchange_order_id == "OQCLML-BW3P3-BUCMWZ".into())
+        .expect("Partially filled order is missing");
+    assert_eq!(partially_filled.currency_pair, spot_currency_pair());
+    assert_eq!(partially_filled.client_order_id, "1665480000123".into());
+    assert_eq!(partially_filled.order_side, OrderSide::Buy);
+    assert_eq!(partially_filled.order_status, OrderStatus::Created);
+    assert_eq!(partially_filled.price, dec!(30000.1));
+    assert_eq!(partially_filled.amount, dec!(0.002));
+    assert_eq!(partially_filled.average_fill_price, dec!(30000.1));
+    assert_eq!(partially_filled.filled_amount, dec!(0.001));
+    assert_eq!(
+        partially_filled.commission_currency_code,
+        Some("usd".to_owned())
+    );
+    assert_eq!(partially_filled.commission_amount, Some(dec!(0.048)));
+
+    // order without client order id, e.g. created manually
+    let live = orders
+        .iter()
+        .find(|x| x.exchange_order_id == "OB5VMB-B4U2U-DK2WRW".into())
+        .expect("Live order is missing");
+    assert_eq!(live.client_order_id, "".into());
+    assert_eq!(live.order_side, OrderSide::Sell);
+    assert_eq!(live.average_fill_price, dec!(0));
+    assert_eq!(live.filled_amount, dec!(0));
+}
+
+#[test]
+fn parse_my_trades() {
+    let (kraken, _) = create_kraken_with_symbols();
+
+    let trades = kraken
+        .parse_my_trades(
+            &response(include_str!("fixtures/trades_history.json")),
+            spot_currency_pair(),
+        )
+        .expect("Failed to parse trades history");
+
+    // trades of other pairs are skipped
+    assert_eq!(trades.len(), 1);
+    let trade = &trades[0];
+    assert_eq!(trade.exchange_order_id, "OQCLML-BW3P3-BUCMWZ".into());
+    assert_eq!(
+        trade.trade_id,
+        TradeId::from("THVRQM-33VKH-UCI7BS".to_owned())
+    );
+    assert_eq!(trade.price, dec!(30000.1));
+    assert_eq!(trade.amount, dec!(0.001));
+    assert_eq!(trade.order_role, OrderRole::Maker);
+    assert_eq!(trade.fee_currency_code, "usd".into());
+    assert_eq!(trade.fee_amount, Some(dec!(0.048)));
+    assert_eq!(trade.datetime.timestamp_millis(), 1665480001000);
+}
@@ -0,0 +1,60 @@
+use crate::kraken::common::{create_kraken, response};
+use mmb_core::exchanges::traits::Support;
+use mmb_domain::exchanges::symbol::Precision;
+use mmb_domain::market::CurrencyCode;
+use rust_decimal_macros::dec;
+
+#[test]
+fn parse_symbols() {
+    let (kraken, _) = create_kraken();
+
+    let mut symbols = kraken
+        .parse_all_symbols(&response(include_str!("fixtures/asset_pairs.json")))
+        .expect("Failed to parse symbols");
+    symbols.sort_by_key(|symbol| symbol.base_currency_code.as_str().to_owned());
+
+    // dark pool and not online pairs are skipped
+    assert_eq!(symbols.len(), 2);
+
+    let btc = &symbols[0];
+    assert!(!btc.is_derivative);
+    assert_eq!(btc.base_currency_id, "XXBT".into());
+    assert_eq!(btc.base_currency_code, "btc".into());
+    assert_eq!(btc.quote_currency_id, "ZUSD".into());
+    assert_eq!(btc.quote_currency_code, "usd".into());
+    assert_eq!(btc.amount_currency_code, "btc".into());
+    assert_eq!(btc.min_amount, Some(dec!(0.0001)));
+    assert_eq!(btc.min_cost, Some(dec!(0.5)));
+    assert_eq!(btc.price_precision, Precision::ByTick { tick: dec!(0.1) });
+    assert_eq!(
+        btc.amount_precision,
+        Precision::ByTick {
+            tick: dec!(0.00000001)
+        }
+    );
+
+    let doge = &symbols[1];
+    assert_eq!(doge.base_currency_code, "doge".into());
+    assert_eq!(doge.quote_currency_code, "usd".into());
+}
+
+#[test]
+fn map_asset_names() {
+    let (kraken, _) = create_kraken();
+
+    kraken
+        .parse_all_symbols(&response(include_str!("fixtures/asset_pairs.json")))
+        .expect("Failed to parse symbols");
+
+    let currency_code = |asset: &str| -> Option<CurrencyCode> {
+        kraken
+            .get_supported_currencies()
+            .get(&asset.into())
+            .map(|x| *x.value())
+    };
+    assert_eq!(currency_code("XXBT"), Some("btc".into()));
+    assert_eq!(currency_code("XBT"), Some("btc".into()));
+    assert_eq!(currency_code("XXDG"), Some("doge".into()));
+    assert_eq!(currency_code("ZUSD"), Some("usd".into()));
+    assert_eq!(currency_code("USD"), Some("usd".into()));
+}
@@ -0,0 +1,199 @@
+use crate::kraken::common::{create_kraken_with_symbols, spot_currency_pair};
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::traits::Support;
+use mmb_domain::events::{ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::CurrencyPair;
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::snapshot::{ClientOrderId, ExchangeOrderId, OrderRole, OrderSide};
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use parking_lot::Mutex;
+use rust_decimal_macros::dec;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+fn receive_order_book_event(events: &mut broadcast::Receiver<ExchangeEvent>) -> OrderBookEvent {
+    match events.try_recv().expect("Order book event wasn't sent") {
+        ExchangeEvent::OrderBookEvent(event) => event,
+        event => panic!("Unexpected event {event:?}"),
+    }
+}
+
+#[test]
+fn order_book_snapshot_and_update() {
+    let (kraken, mut events) = create_kraken_with_symbols();
+
+    kraken
+        .on_websocket_message(include_str!("fixtures/ws_book_snapshot.json"))
+        .expect("Failed to handle order book snapshot");
+    kraken
+        .on_websocket_message(include_str!("fixtures/ws_book_update.json"))
+        .expect("Failed to handle order book update");
+
+    let snapshot = receive_order_book_event(&mut events);
+    assert_eq!(snapshot.currency_pair, spot_currency_pair());
+    assert!(matches!(snapshot.event_type, EventType::Snapshot));
+    assert_eq!(
+        snapshot.data.asks,
+        BTreeMap::from([(dec!(30001.5), dec!(0.5)), (dec!(30002), dec!(1.2))])
+    );
+    assert_eq!(
+        snapshot.data.bids,
+        BTreeMap::from([(dec!(30000.1), dec!(0.8))])
+    );
+
+    // asks and bids of update are sent in separate objects of the same message
+    let update = receive_order_book_event(&mut events);
+    assert!(matches!(update.event_type, EventType::Update));
+    assert_eq!(update.data.asks, BTreeMap::from([(dec!(30001.5), dec!(0))]));
+    assert_eq!(
+        update.data.bids,
+        BTreeMap::from([(dec!(30000.5), dec!(0.3))])
+    );
+}
+
+#[test]
+fn order_book_resubscription_on_checksum_mismatch() {
+    let (mut kraken, mut events) = create_kraken_with_symbols();
+    let sent_messages = Arc::new(Mutex::new(Vec::<String>::new()));
+    kraken.set_send_websocket_message_callback({
+        let sent_messages = sent_messages.clone();
+        Box::new(move |_, message| {
+            sent_messages.lock().push(message);
+            Ok(())
+        })
+    });
+
+    kraken
+        .on_websocket_message(include_str!("fixtures/ws_book_snapshot.json"))
+        .expect("Failed to handle order book snapshot");
+    let _ = receive_order_book_event(&mut events);
+
+    kraken
+        .on_websocket_message(include_str!(
+            "fixtures/ws_book_update_invalid_checksum.json"
+        ))
+        .expect("Failed to handle order book update");
+
+    assert!(events.try_recv().is_err());
+    assert_eq!(
+        *sent_messages.lock(),
+        vec![
+            r#"{"event":"unsubscribe","pair":["XBT/USD"],"subscription":{"name":"book","depth":10}}"#,
+            r#"{"event":"subscribe","pair":["XBT/USD"],"subscription":{"name":"book","depth":10}}"#,
+        ]
+    );
+
+    // updates are ignored until a new snapshot is received
+    kraken
+        .on_websocket_message(include_str!("fixtures/ws_book_update.json"))
+        .expect("Failed to handle order book update");
+    assert!(events.try_recv().is_err());
+
+    kraken
+        .on_websocket_message(include_str!("fixtures/ws_book_snapshot.json"))
+        .expect("Failed to handle order book snapshot");
+    let snapshot = receive_order_book_event(&mut events);
+    assert!(matches!(snapshot.event_type, EventType::Snapshot));
+}
+
+#[test]
+fn trades() {
+    let (mut kraken, _events) = create_kraken_with_symbols();
+    let trades = Arc::new(Mutex::new(Vec::<(CurrencyPair, Trade)>::new()));
+    kraken.set_handle_trade_callback({
+        let trades = trades.clone();
+        Box::new(move |currency_pair, trade| trades.lock().push((currency_pair, trade)))
+    });
+
+    kraken
+        .on_websocket_message(include_str!("fixtures/ws_trades.json"))
+        .expect("Failed to handle trades");
+
+    let trades = trades.lock();
+    assert_eq!(trades.len(), 1);
+    let (currency_pair, trade) = &trades[0];
+    assert_eq!(*currency_pair, spot_currency_pair());
+    assert_eq!(
+        trade.trade_id,
+        TradeId::from("1665480000.200000".to_owned())
+    );
+    assert_eq!(trade.price, dec!(30000.1));
+    assert_eq!(trade.quantity, dec!(0.12));
+    assert_eq!(trade.side, OrderSide::Sell);
+    assert_eq!(trade.transaction_time.timestamp_millis(), 1665480000200);
+}
+
+#[test]
+fn order_fill() {
+    let (mut kraken, _events) = create_kraken_with_symbols();
+    let fills = Arc::new(Mutex::new(Vec::<FillEvent>::new()));
+    kraken.set_handle_order_filled_callback({
+        let fills = fills.clone();
+        Box::new(move |fill_event| fills.lock().push(fill_event))
+    });
+
+    kraken
+        .on_websocket_message(include_str!("fixtures/ws_own_trades.json"))
+        .expect("Failed to handle order fill");
+
+    let fills = fills.lock();
+    assert_eq!(fills.len(), 1);
+    let fill = &fills[0];
+    assert_eq!(
+        fill.trade_id,
+        Some(TradeId::from("THVRQM-33VKH-UCI7BT".to_owned()))
+    );
+    assert_eq!(fill.client_order_id, None);
+    assert_eq!(fill.exchange_order_id, "OQCLML-BW3P3-BUCMWZ".into());
+    assert_eq!(fill.fill_price, dec!(30000.1));
+    assert!(matches!(
+        fill.fill_amount,
+        FillAmount::Incremental {
+            fill_amount,
+            total_filled_amount: None,
+        } if fill_amount == dec!(0.001)
+    ));
+    assert_eq!(fill.order_role, Some(OrderRole::Taker));
+    assert_eq!(fill.commission_currency_code, Some("usd".into()));
+    assert_eq!(fill.commission_amount, Some(dec!(0.078)));
+    assert_eq!(fill.fill_type, OrderFillType::UserTrade);
+    assert_eq!(
+        fill.fill_date.map(|x| x.timestamp_millis()),
+        Some(1665480001000)
+    );
+    assert!(fill.special_order_data.is_none());
+}
+
+#[test]
+fn order_cancellation() {
+    let (mut kraken, _events) = create_kraken_with_symbols();
+    let cancelled_orders = Arc::new(Mutex::new(Vec::<(ClientOrderId, ExchangeOrderId)>::new()));
+    kraken.set_order_cancelled_callback({
+        let cancelled_orders = cancelled_orders.clone();
+        Box::new(move |client_order_id, exchange_order_id, _| {
+            cancelled_orders
+                .lock()
+                .push((client_order_id, exchange_order_id))
+        })
+    });
+
+    kraken
+        .on_websocket_message(include_str!("fixtures/ws_order_canceled.json"))
+        .expect("Failed to handle order cancellation");
+
+    // updates of orders contain only changed fields without client order id
+    assert_eq!(
+        *cancelled_orders.lock(),
+        vec![("".into(), "OB5VMB-B4U2U-DK2WRW".into())]
+    );
+}
+
+#[test]
+fn heartbeat() {
+    let (kraken, _events) = create_kraken_with_symbols();
+
+    kraken
+        .on_websocket_message(r#"{"event":"heartbeat"}"#)
+        .expect("Failed to handle heartbeat");
+}
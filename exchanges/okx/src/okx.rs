@@ -0,0 +1,926 @@
+use crate::types::{
+    OkxBalance, OkxFill, OkxInstrument, OkxOrder, OkxOrderSide, OkxPosition, OkxResponse,
+};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use dashmap::DashMap;
+use function_name::named;
+use hmac::{Hmac, Mac};
+use hyper::body::Bytes;
+use hyper::http::request::Builder;
+use hyper::{StatusCode, Uri};
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{ContractType, Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    Amount, ExchangeOrderId, ExternalOrder, OrderExecutionType, OrderInfo, OrderOptions, OrderRole,
+    OrderStatus, Price, UserOrder,
+};
+use mmb_domain::position::{ActivePosition, ClosedPosition, DerivativePosition};
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Max count of orders in OKX batch requests
+const BATCH_ORDERS_LIMIT: usize = 20;
+
+#[derive(Default)]
+pub struct ErrorHandlerOkx;
+
+impl ErrorHandler for ErrorHandlerOkx {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        #[derive(Deserialize)]
+        struct Error {
+            code: String,
+            msg: String,
+            #[serde(default)]
+            data: Vec<ErrorData>,
+        }
+        // Errors of order requests are specified for each order separately
+        #[derive(Deserialize)]
+        struct ErrorData {
+            #[serde(rename = "sCode", default)]
+            code: Option<String>,
+            #[serde(rename = "sMsg", default)]
+            message: Option<String>,
+        }
+
+        let error: Error = serde_json::from_str(&response.content).map_err(|err| {
+            ExchangeError::parsing(format!(
+                "Unable to parse response.content: {err:?}\n{}",
+                response.content
+            ))
+        })?;
+
+        if error.code == "0" && response.status == StatusCode::OK {
+            return Ok(());
+        }
+
+        let order_error = error
+            .data
+            .into_iter()
+            .find_map(|x| match (x.code, x.message) {
+                (Some(code), Some(message)) if code != "0" => Some((code, message)),
+                _ => None,
+            });
+        let (code, message) = order_error.unwrap_or((error.code, error.msg));
+
+        Err(ExchangeError::new(
+            ExchangeErrorType::Unknown,
+            message,
+            code.parse().ok(),
+        ))
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        use ExchangeErrorType::*;
+        // https://www.okx.com/docs-v5/en/#error-code
+        match error.code {
+            Some(51400 | 51401 | 51603) => OrderNotFound,
+            Some(51402) => OrderCompleted,
+            Some(51008) => InsufficientFunds,
+            Some(51000 | 51006 | 51020 | 51121) => InvalidOrder,
+            Some(50011) => RateLimit,
+            Some(50001 | 50013) => ServiceUnavailable,
+            Some(50100..=50113) => Authentication,
+            _ => Unknown,
+        }
+    }
+}
+
+pub struct RestHeadersOkx {
+    api_key: String,
+    secret_key: String,
+    passphrase: String,
+}
+
+impl RestHeadersOkx {
+    pub fn new(api_key: String, secret_key: String, passphrase: String) -> Self {
+        Self {
+            api_key,
+            secret_key,
+            passphrase,
+        }
+    }
+}
+
+impl RestHeaders for RestHeadersOkx {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        request_type: RequestType,
+    ) -> Builder {
+        self.add_specific_headers_with_body(builder, uri, request_type, &[])
+    }
+
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        request_type: RequestType,
+        body: &[u8],
+    ) -> Builder {
+        let builder = builder.header(hyper::header::CONTENT_TYPE, "application/json");
+
+        // Public endpoints don't need authentication
+        if self.api_key.is_empty() {
+            return builder;
+        }
+
+        let path_and_query = match uri.path_and_query() {
+            Some(path_and_query) => path_and_query.as_str(),
+            None => uri.path(),
+        };
+        let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+        let signature = Okx::create_signature(
+            &self.secret_key,
+            &timestamp,
+            request_type.as_str(),
+            path_and_query,
+            body,
+        );
+
+        builder
+            .header("OK-ACCESS-KEY", &self.api_key)
+            .header("OK-ACCESS-SIGN", signature)
+            .header("OK-ACCESS-TIMESTAMP", timestamp)
+            .header("OK-ACCESS-PASSPHRASE", &self.passphrase)
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CreateOrderRequest<'a> {
+    inst_id: SpecificCurrencyPair,
+    td_mode: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cl_ord_id: Option<&'a str>,
+    side: OkxOrderSide,
+    ord_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    px: Option<Price>,
+    sz: Amount,
+    /// Currency of `sz` for spot market orders, by default it is quote currency for buy orders
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tgt_ccy: Option<&'static str>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    reduce_only: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CancelOrderRequest<'a> {
+    inst_id: SpecificCurrencyPair,
+    ord_id: &'a ExchangeOrderId,
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+
+pub struct Okx {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerOkx, RestHeadersOkx>,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+}
+
+impl Okx {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Okx {
+        Self {
+            rest_client: RestClient::new(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerOkx::default(),
+                ),
+                RestHeadersOkx::new(
+                    settings.api_key.clone(),
+                    settings.secret_key.clone(),
+                    settings.api_passphrase.clone().unwrap_or_default(),
+                ),
+            ),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        Hosts {
+            web_socket_host: "wss://ws.okx.com:8443/ws/v5/public",
+            web_socket2_host: "wss://ws.okx.com:8443/ws/v5/private",
+            rest_host: "https://www.okx.com",
+        }
+    }
+
+    /// Spot instruments are traded in non-derivative mode and perpetual swaps in derivative mode
+    pub(crate) fn instrument_type(&self) -> &'static str {
+        match self.settings.is_margin_trading {
+            true => "SWAP",
+            false => "SPOT",
+        }
+    }
+
+    fn trade_mode(&self) -> &'static str {
+        match self.settings.is_margin_trading {
+            true => "cross",
+            false => "cash",
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/public/instruments");
+        builder.add_kv("instType", self.instrument_type());
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let instruments: OkxResponse<OkxInstrument> = serde_json::from_str(&response.content)
+            .context("Unable to deserialize response from OKX")?;
+
+        instruments
+            .data
+            .iter()
+            .filter(|instrument| instrument.state == "live")
+            .map(|instrument| self.specific_symbol_to_unified(instrument))
+            .try_collect()
+    }
+
+    fn specific_symbol_to_unified(&self, instrument: &OkxInstrument) -> Result<Arc<Symbol>> {
+        let (base_id, quote_id) = match self.settings.is_margin_trading {
+            // Swap instruments have base and quote currencies only in underlying, e.g. BTC-USDT
+            true => instrument.uly.split_once('-').with_context(|| {
+                format!("Unable to get currencies of OKX instrument {instrument:?}")
+            })?,
+            false => (instrument.base_ccy.as_str(), instrument.quote_ccy.as_str()),
+        };
+        let base: CurrencyCode = base_id.into();
+        let quote: CurrencyCode = quote_id.into();
+
+        let specific_currency_pair = instrument.inst_id;
+        let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, unified_currency_pair);
+
+        let contract_type = match instrument.ct_type.as_str() {
+            "inverse" => ContractType::Inverse,
+            _ => ContractType::Linear,
+        };
+
+        let settlement_currency: CurrencyCode = instrument.settle_ccy.as_str().into();
+        let (amount_currency_code, balance_currency_code) =
+            match (self.settings.is_margin_trading, contract_type) {
+                (true, ContractType::Inverse) => (quote, Some(settlement_currency)),
+                (true, _) => (base, Some(settlement_currency)),
+                (false, _) => (base, None),
+            };
+
+        let mut symbol = Symbol::new(
+            self.settings.is_margin_trading,
+            base_id.into(),
+            base,
+            quote_id.into(),
+            quote,
+            None,
+            None,
+            Some(instrument.min_sz),
+            instrument.max_lmt_sz,
+            None,
+            amount_currency_code,
+            balance_currency_code,
+            Precision::ByTick {
+                tick: instrument.tick_sz,
+            },
+            Precision::ByTick {
+                tick: instrument.lot_sz,
+            },
+        );
+
+        if self.settings.is_margin_trading {
+            // Swap amounts are specified in contracts
+            symbol.contract_type = contract_type;
+            symbol.amount_multiplier = instrument.ct_val.unwrap_or(dec!(1));
+        }
+
+        Ok(Arc::new(symbol))
+    }
+
+    #[named]
+    pub(super) async fn request_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let mut request = CreateOrderRequest {
+            inst_id: self.get_specific_currency_pair(header.currency_pair),
+            td_mode: self.trade_mode(),
+            cl_ord_id: Some(header.client_order_id.as_str()),
+            side: header.side.into(),
+            ord_type: "market",
+            px: None,
+            sz: header.amount,
+            tgt_ccy: None,
+            reduce_only: false,
+        };
+
+        match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => {
+                request.ord_type = match execution_type {
+                    OrderExecutionType::MakerOnly => "post_only",
+                    OrderExecutionType::None => "limit",
+                };
+                request.px = Some(price);
+            }
+            OrderOptions::User(UserOrder::Market) => {
+                if !self.settings.is_margin_trading {
+                    request.tgt_ccy = Some("base_ccy");
+                }
+            }
+            OrderOptions::External(ExternalOrder::ClosePosition { .. }) => {
+                request.reduce_only = true
+            }
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        }
+
+        let log_args = format!("Create order for {header:?}");
+        self.post("/api/v5/trade/order", &request, function_name!(), log_args)
+            .await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct OrderId {
+            ord_id: ExchangeOrderId,
+        }
+
+        let deserialized: OkxResponse<OrderId> = serde_json::from_str(&response.content)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse ordId: {err:?}")))?;
+
+        deserialized
+            .data
+            .into_iter()
+            .next()
+            .map(|x| x.ord_id)
+            .ok_or_else(|| ExchangeError::parsing("No one order id received".to_owned()))
+    }
+
+    #[named]
+    pub(super) async fn request_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let request = CancelOrderRequest {
+            inst_id: self.get_specific_currency_pair(order.currency_pair()),
+            ord_id: exchange_order_id,
+        };
+
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+        self.post(
+            "/api/v5/trade/cancel-order",
+            &request,
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    /// OKX doesn't have request for cancellation of all orders, so open orders are cancelled by batches
+    pub(super) async fn do_cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()> {
+        let response = self.request_open_orders(Some(currency_pair)).await?;
+        let open_orders: OkxResponse<OkxOrder> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_open_orders request")?;
+
+        for orders in open_orders.data.chunks(BATCH_ORDERS_LIMIT) {
+            let requests = orders
+                .iter()
+                .map(|order| CancelOrderRequest {
+                    inst_id: order.inst_id,
+                    ord_id: &order.ord_id,
+                })
+                .collect_vec();
+
+            self.request_cancel_orders_batch(&requests).await?;
+        }
+
+        Ok(())
+    }
+
+    #[named]
+    async fn request_cancel_orders_batch(
+        &self,
+        requests: &[CancelOrderRequest<'_>],
+    ) -> Result<RestResponse, ExchangeError> {
+        let log_args = format!("Cancel orders batch {requests:?}");
+        self.post(
+            "/api/v5/trade/cancel-batch-orders",
+            requests,
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    #[named]
+    pub(super) async fn request_cancel_all_orders_after(
+        &self,
+        timeout: Duration,
+    ) -> Result<RestResponse, ExchangeError> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct CancelAllAfterRequest {
+            time_out: String,
+        }
+
+        let request = CancelAllAfterRequest {
+            time_out: timeout.as_secs().to_string(),
+        };
+
+        let log_args = format!("Cancel all orders after {timeout:?}");
+        self.post(
+            "/api/v5/trade/cancel-all-after",
+            &request,
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(
+        &self,
+        currency_pair: Option<CurrencyPair>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/trade/orders-pending");
+        builder.add_kv("instType", self.instrument_type());
+        if let Some(pair) = currency_pair {
+            builder.add_kv("instId", self.get_specific_currency_pair(pair));
+        }
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub fn parse_open_orders(&self, response: &RestResponse) -> Result<Vec<OrderInfo>> {
+        let orders: OkxResponse<OkxOrder> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_open_orders request")?;
+
+        orders
+            .data
+            .iter()
+            .map(|order| self.specific_order_info_to_unified(order))
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+
+        let mut builder = UriBuilder::from_path("/api/v5/trade/order");
+        builder.add_kv(
+            "instId",
+            self.get_specific_currency_pair(order.currency_pair()),
+        );
+        builder.add_kv("clOrdId", &client_order_id);
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        let log_args = format!("order {client_order_id}");
+
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    pub fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let orders: OkxResponse<OkxOrder> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_order_info request")?;
+
+        let order = orders.data.first().context("No one order info received")?;
+
+        self.specific_order_info_to_unified(order)
+    }
+
+    fn specific_order_info_to_unified(&self, order: &OkxOrder) -> Result<OrderInfo> {
+        Ok(OrderInfo::new(
+            self.get_unified_currency_pair(&order.inst_id)?,
+            order.ord_id.clone(),
+            order.cl_ord_id.clone(),
+            order.side.into(),
+            Self::get_local_order_status(&order.state)?,
+            order.px.unwrap_or_default(),
+            order.sz,
+            order.avg_px.unwrap_or_default(),
+            order.acc_fill_sz,
+            (!order.fee_ccy.is_empty()).then(|| order.fee_ccy.clone()),
+            None,
+            // OKX fee is negative when it is charged
+            order.fee.map(|fee| -fee),
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    pub(super) fn get_local_order_status(status: &str) -> Result<OrderStatus> {
+        Ok(match status {
+            "live" | "partially_filled" => OrderStatus::Created,
+            "filled" => OrderStatus::Completed,
+            "canceled" | "mmp_canceled" => OrderStatus::Canceled,
+            _ => bail!("OKX: unexpected order status {status}"),
+        })
+    }
+
+    pub(super) fn get_order_role(exec_type: &str) -> Option<OrderRole> {
+        match exec_type {
+            "M" => Some(OrderRole::Maker),
+            "T" => Some(OrderRole::Taker),
+            _ => None,
+        }
+    }
+
+    pub(super) fn get_order_fill_type(category: &str) -> OrderFillType {
+        match category {
+            "full_liquidation" | "partial_liquidation" => OrderFillType::Liquidation,
+            _ => OrderFillType::UserTrade,
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/trade/fills");
+        builder.add_kv("instType", self.instrument_type());
+        builder.add_kv(
+            "instId",
+            self.get_specific_currency_pair(symbol.currency_pair()),
+        );
+        if let Some(date_time) = last_date_time {
+            builder.add_kv("begin", date_time.timestamp_millis());
+        }
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub fn parse_my_trades(&self, response: &RestResponse) -> Result<Vec<OrderTrade>> {
+        let fills: OkxResponse<OkxFill> =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+
+        fills
+            .data
+            .into_iter()
+            .map(|fill| {
+                let order_role = Self::get_order_role(&fill.exec_type)
+                    .with_context(|| format!("Unknown execution type of fill {fill:?}"))?;
+
+                Result::<_, anyhow::Error>::Ok(OrderTrade::new(
+                    fill.ord_id,
+                    TradeId::from(fill.trade_id),
+                    fill.ts,
+                    fill.fill_px,
+                    fill.fill_sz,
+                    order_role,
+                    fill.fee_ccy.as_str().into(),
+                    None,
+                    Some(-fill.fee),
+                    OrderFillType::UserTrade,
+                ))
+            })
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_get_position(&self) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/account/positions");
+        builder.add_kv("instType", "SWAP");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub fn parse_active_positions(&self, response: &RestResponse) -> Result<Vec<ActivePosition>> {
+        let positions: OkxResponse<OkxPosition> =
+            serde_json::from_str(&response.content).context("Failed to parse positions")?;
+
+        positions
+            .data
+            .into_iter()
+            .filter(|position| !position.pos.unwrap_or_default().is_zero())
+            .map(|position| {
+                let derivative_position = DerivativePosition::new(
+                    self.get_unified_currency_pair(&position.inst_id)?,
+                    position.pos.unwrap_or_default(),
+                    position.avg_px.unwrap_or_default(),
+                    position.liq_px.unwrap_or_default(),
+                    position.lever.unwrap_or_default(),
+                );
+
+                Result::<_, anyhow::Error>::Ok(ActivePosition::new(
+                    derivative_position,
+                    position.u_time,
+                ))
+            })
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_close_position(
+        &self,
+        position: &ActivePosition,
+        price: Option<Price>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let request = CreateOrderRequest {
+            inst_id: self.get_specific_currency_pair(position.derivative.currency_pair),
+            td_mode: self.trade_mode(),
+            cl_ord_id: None,
+            side: position.derivative.get_side().change_side().into(),
+            ord_type: match price {
+                Some(_) => "limit",
+                None => "market",
+            },
+            px: price,
+            sz: position.derivative.position.abs(),
+            tgt_ccy: None,
+            reduce_only: true,
+        };
+
+        let log_args = format!("Close position response for {position:?} {price:?}");
+        self.post("/api/v5/trade/order", &request, function_name!(), log_args)
+            .await
+    }
+
+    pub(super) fn parse_close_position(
+        &self,
+        response: &RestResponse,
+        position: &ActivePosition,
+    ) -> Result<ClosedPosition> {
+        let exchange_order_id = self.get_order_id(response)?;
+
+        Ok(ClosedPosition::new(
+            exchange_order_id,
+            position.derivative.position.abs(),
+        ))
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/api/v5/account/balance");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub fn parse_get_balance(&self, response: &RestResponse) -> Result<Vec<ExchangeBalance>> {
+        let balances: OkxResponse<OkxBalance> =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        Ok(balances
+            .data
+            .into_iter()
+            .flat_map(|balance| balance.details)
+            .map(|details| ExchangeBalance {
+                currency_code: details.ccy.as_str().into(),
+                balance: details.avail_bal.unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    #[named]
+    pub(super) async fn request_get_server_time(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/api/v5/public/time");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_server_time(&self, response: &RestResponse) -> Result<i64> {
+        #[derive(Deserialize)]
+        struct ServerTime {
+            ts: String,
+        }
+
+        let server_time: OkxResponse<ServerTime> = serde_json::from_str(&response.content)
+            .context("Failed to parse OKX get time response")?;
+        let server_time = server_time
+            .data
+            .first()
+            .context("No server time received")?;
+
+        server_time
+            .ts
+            .parse()
+            .context("Failed to parse OKX server time")
+    }
+
+    async fn post(
+        &self,
+        path: &str,
+        request: &(impl Serialize + ?Sized),
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let body = serde_json::to_vec(request).map_err(|err| {
+            ExchangeError::unknown(&format!("Unable to serialize request: {err}"))
+        })?;
+        let uri = UriBuilder::from_path(path).build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .post(uri, Some(Bytes::from(body)), action_name, log_args)
+            .await
+    }
+
+    /// Base64 encoded HMAC SHA256 of request description
+    pub fn create_signature(
+        secret_key: &str,
+        timestamp: &str,
+        method: &str,
+        path_and_query: &str,
+        body: &[u8],
+    ) -> String {
+        let mut hmac = Hmac::<Sha256>::new_from_slice(secret_key.as_bytes())
+            .expect("Unable to calculate hmac for OKX signature");
+        hmac.update(timestamp.as_bytes());
+        hmac.update(method.as_bytes());
+        hmac.update(path_and_query.as_bytes());
+        hmac.update(body);
+
+        base64::encode(hmac.finalize().into_bytes())
+    }
+}
+
+pub struct OkxBuilder;
+
+impl ExchangeClientBuilder for OkxBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Okx::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    supports_cancel_all_after: true,
+                    ..OrderFeatures::default()
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: true,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: true,
+                    supports_subscription_response: false,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        // OKX limits are specified per endpoint, e.g. 60 requests per 2 seconds for order placement
+        RequestTimeoutArguments::from_requests_per_minute(600)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Okx".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_signature() {
+        let signature = Okx::create_signature(
+            "22582BD0CFF14C41EDBF1AB98506286D",
+            "2020-12-08T09:08:57.715Z",
+            "POST",
+            "/api/v5/trade/order",
+            br#"{"instId":"BTC-USDT","tdMode":"cash","side":"buy","ordType":"limit","px":"1","sz":"1"}"#,
+        );
+
+        assert_eq!(signature, "NYFBVLke8BcN08ls/bRkL/RolQzI8CZFRBuenUTGGGw=");
+    }
+}
@@ -0,0 +1,430 @@
+use crate::okx::Okx;
+use crate::types::{OkxOrder, OkxOrderBook, OkxTrade};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::WebSocketRole;
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{
+    FillAmount, FillEvent, SpecialOrderData,
+};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair};
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::Any;
+use std::sync::Arc;
+use url::Url;
+
+#[async_trait]
+impl Support for Okx {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        // Reply to text ping which keeps connection alive
+        if msg == "pong" {
+            return Ok(());
+        }
+
+        let message: WebsocketMessage = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        match message {
+            WebsocketMessage::Event(event) => self.handle_event(event),
+            WebsocketMessage::Push(push) => self.handle_push(push),
+        }
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        self.subscribe_to_public_channels()?;
+
+        // Private channels are subscribed after successful login
+        match self.is_websocket_enabled(WebSocketRole::Secondary) {
+            true => self.login(),
+            false => Ok(()),
+        }
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => true,
+            WebSocketRole::Secondary => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+            WebSocketRole::MarketData(_) => false,
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => self.hosts.web_socket2_host,
+            WebSocketRole::MarketData(_) => {
+                bail!("Market data connections aren't supported by OKX")
+            }
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains(r#""channel":"orders""#)
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+}
+
+impl Okx {
+    fn handle_event(&self, event: EventMessage) -> Result<()> {
+        match event.event.as_str() {
+            "login" if event.code == "0" => {
+                log::info!("OKX websocket: successful login");
+                self.subscribe_to_private_channels()
+            }
+            "login" | "error" => {
+                let err = format!("OKX websocket error {}: {}", event.code, event.msg);
+                log::error!("{err}");
+                bail!(err)
+            }
+            _ => {
+                log::info!("OKX websocket: {event:?}");
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_push(&self, push: PushMessage) -> Result<()> {
+        match push.arg.channel.as_str() {
+            "books" => {
+                let specific_currency_pair = push
+                    .arg
+                    .inst_id
+                    .context("Instrument id is missing in order book message")?;
+                let event_type = match push.action.as_deref() {
+                    Some("snapshot") => EventType::Snapshot,
+                    _ => EventType::Update,
+                };
+
+                let order_books: Vec<OkxOrderBook> =
+                    serde_json::from_value(push.data).context("Unable to parse order book data")?;
+                for order_book in order_books {
+                    self.handle_order_book(specific_currency_pair, order_book, event_type)?;
+                }
+            }
+            "trades" => {
+                let trades: Vec<OkxTrade> =
+                    serde_json::from_value(push.data).context("Unable to parse trades data")?;
+                for trade in trades {
+                    self.handle_trade(trade)?;
+                }
+            }
+            "orders" => {
+                let orders: Vec<OkxOrder> =
+                    serde_json::from_value(push.data).context("Unable to parse orders data")?;
+                for order in orders {
+                    self.handle_order(order)?;
+                }
+            }
+            channel => bail!("Unsupported OKX websocket channel {channel}"),
+        }
+
+        Ok(())
+    }
+
+    /// Order book checksum isn't verified, missed updates are fixed by resubscription on reconnect only
+    fn handle_order_book(
+        &self,
+        specific_currency_pair: SpecificCurrencyPair,
+        order_book: OkxOrderBook,
+        event_type: EventType,
+    ) -> Result<()> {
+        let mut order_book_data = OrderBookData::default();
+        // Zero amount means that price level should be removed
+        for (price, amount, ..) in order_book.asks {
+            order_book_data.asks.insert(price, amount);
+        }
+        for (price, amount, ..) in order_book.bids {
+            order_book_data.bids.insert(price, amount);
+        }
+
+        let currency_pair = self.get_unified_currency_pair(&specific_currency_pair)?;
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            String::default(),
+            event_type,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trade(&self, trade: OkxTrade) -> Result<()> {
+        (self.handle_trade_callback)(
+            self.get_unified_currency_pair(&trade.inst_id)?,
+            Trade {
+                trade_id: trade.trade_id,
+                price: trade.px,
+                quantity: trade.sz,
+                side: trade.side.into(),
+                transaction_time: trade.ts,
+                tick_direction: None,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn handle_order(&self, order: OkxOrder) -> Result<()> {
+        let fill_amount = order.fill_sz.unwrap_or_default();
+        if !fill_amount.is_zero() {
+            self.handle_order_fill(&order)?;
+        }
+
+        match order.state.as_str() {
+            // Order is live after amendment too, but it isn't a problem to handle creation twice
+            "live" if fill_amount.is_zero() => (self.order_created_callback)(
+                order.cl_ord_id,
+                order.ord_id,
+                EventSourceType::WebSocket,
+            ),
+            "canceled" | "mmp_canceled" => (self.order_cancelled_callback)(
+                order.cl_ord_id,
+                order.ord_id,
+                EventSourceType::WebSocket,
+            ),
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    fn handle_order_fill(&self, order: &OkxOrder) -> Result<()> {
+        let order_data = SpecialOrderData {
+            currency_pair: self.get_unified_currency_pair(&order.inst_id)?,
+            order_side: order.side.into(),
+            order_amount: order.sz,
+        };
+        let client_order_id = match order.cl_ord_id.as_str().is_empty() {
+            true => None,
+            false => Some(order.cl_ord_id.clone()),
+        };
+        let commission_currency_code = match order.fill_fee_ccy.is_empty() {
+            true => None,
+            false => Some(order.fill_fee_ccy.as_str().into()),
+        };
+
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::from(order.trade_id.clone())),
+            client_order_id,
+            exchange_order_id: order.ord_id.clone(),
+            fill_price: order
+                .fill_px
+                .context("Fill price is missing in OKX order")?,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: order.fill_sz.unwrap_or_default(),
+                total_filled_amount: Some(order.acc_fill_sz),
+            },
+            order_role: Okx::get_order_role(&order.exec_type),
+            commission_currency_code,
+            commission_rate: None,
+            // OKX fee is negative when it is charged
+            commission_amount: order.fill_fee.map(|fee| -fee),
+            fill_type: Okx::get_order_fill_type(&order.category),
+            special_order_data: Some(order_data),
+            fill_date: order.fill_time,
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+
+        Ok(())
+    }
+
+    fn subscribe_to_public_channels(&self) -> Result<()> {
+        let traded_currencies = self.traded_specific_currencies.lock();
+        if traded_currencies.is_empty() {
+            return Ok(());
+        }
+
+        let args = ["books", "trades"]
+            .into_iter()
+            .flat_map(|channel| {
+                traded_currencies
+                    .iter()
+                    .map(move |currency_pair| SubscriptionArg {
+                        channel,
+                        inst_id: Some(*currency_pair),
+                        inst_type: None,
+                    })
+            })
+            .collect();
+
+        let request = Request {
+            op: "subscribe",
+            args,
+        };
+        let subscriptions =
+            serde_json::to_string(&request).expect("Failed to serialize subscription message");
+
+        (self.websocket_message_callback)(WebSocketRole::Main, subscriptions)
+    }
+
+    fn subscribe_to_private_channels(&self) -> Result<()> {
+        let request = Request {
+            op: "subscribe",
+            args: vec![SubscriptionArg {
+                channel: "orders",
+                inst_id: None,
+                inst_type: Some(self.instrument_type()),
+            }],
+        };
+        let subscriptions =
+            serde_json::to_string(&request).expect("Failed to serialize subscription message");
+
+        (self.websocket_message_callback)(WebSocketRole::Secondary, subscriptions)
+    }
+
+    fn login(&self) -> Result<()> {
+        let timestamp = Utc::now().timestamp().to_string();
+        let sign = Okx::create_signature(
+            &self.settings.secret_key,
+            &timestamp,
+            "GET",
+            "/users/self/verify",
+            &[],
+        );
+
+        let request = Request {
+            op: "login",
+            args: vec![LoginArg {
+                api_key: &self.settings.api_key,
+                passphrase: self.settings.api_passphrase.as_deref().unwrap_or_default(),
+                timestamp,
+                sign,
+            }],
+        };
+        let login = serde_json::to_string(&request).expect("Failed to serialize OKX login message");
+
+        (self.websocket_message_callback)(WebSocketRole::Secondary, login)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum WebsocketMessage {
+    Event(EventMessage),
+    Push(PushMessage),
+}
+
+/// Responses to login and subscriptions and errors
+#[derive(Deserialize, Debug)]
+struct EventMessage {
+    event: String,
+    #[serde(default)]
+    code: String,
+    #[serde(default)]
+    msg: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct PushMessage {
+    arg: PushArg,
+    /// Only for order book channels: `snapshot` or `update`
+    action: Option<String>,
+    data: Value,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PushArg {
+    channel: String,
+    inst_id: Option<SpecificCurrencyPair>,
+}
+
+#[derive(Serialize)]
+struct Request<T> {
+    op: &'static str,
+    args: Vec<T>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SubscriptionArg {
+    channel: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inst_id: Option<SpecificCurrencyPair>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inst_type: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LoginArg<'a> {
+    api_key: &'a str,
+    passphrase: &'a str,
+    timestamp: String,
+    sign: String,
+}
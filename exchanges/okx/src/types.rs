@@ -0,0 +1,318 @@
+use chrono::{TimeZone, Utc};
+use mmb_domain::events::TradeId;
+use mmb_domain::market::SpecificCurrencyPair;
+use mmb_domain::order::snapshot::{Amount, ClientOrderId, ExchangeOrderId, OrderSide, Price};
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::str::FromStr;
+
+/// Common envelope of all OKX REST responses
+/// {
+/// "code": "0", // "0" on success, error code otherwise
+/// "msg": "", // Error message
+/// "data": [] // Response payload
+/// }
+#[derive(Deserialize, Debug)]
+pub struct OkxResponse<T> {
+    pub code: String,
+    pub msg: String,
+    pub data: Vec<T>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OkxOrderSide {
+    Buy,
+    Sell,
+}
+
+impl From<OkxOrderSide> for OrderSide {
+    fn from(side: OkxOrderSide) -> Self {
+        match side {
+            OkxOrderSide::Buy => OrderSide::Buy,
+            OkxOrderSide::Sell => OrderSide::Sell,
+        }
+    }
+}
+
+impl From<OrderSide> for OkxOrderSide {
+    fn from(side: OrderSide) -> Self {
+        match side {
+            OrderSide::Buy => OkxOrderSide::Buy,
+            OrderSide::Sell => OkxOrderSide::Sell,
+        }
+    }
+}
+
+/// OKX instrument description. Fields which aren't applicable to instrument type are empty strings
+/// {
+/// "instType": "SWAP", // Instrument type: SPOT, MARGIN, SWAP, FUTURES, OPTION
+/// "instId": "BTC-USDT-SWAP", // Instrument ID
+/// "uly": "BTC-USDT", // Underlying, only applicable to FUTURES/SWAP/OPTION
+/// "baseCcy": "", // Base currency, only applicable to SPOT/MARGIN
+/// "quoteCcy": "", // Quote currency, only applicable to SPOT/MARGIN
+/// "settleCcy": "USDT", // Settlement and margin currency, only applicable to FUTURES/SWAP/OPTION
+/// "ctVal": "0.01", // Contract value, only applicable to FUTURES/SWAP/OPTION
+/// "ctMult": "1", // Contract multiplier, only applicable to FUTURES/SWAP/OPTION
+/// "ctValCcy": "BTC", // Contract value currency, only applicable to FUTURES/SWAP/OPTION
+/// "ctType": "linear", // linear or inverse, only applicable to FUTURES/SWAP
+/// "tickSz": "0.1", // Tick size of price
+/// "lotSz": "1", // Lot size of amount. Contracts for FUTURES/SWAP/OPTION and base currency for SPOT/MARGIN
+/// "minSz": "1", // Minimum order size
+/// "maxLmtSz": "100000000", // The maximum order quantity of the limit order
+/// "state": "live" // live, suspend, preopen or test
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OkxInstrument {
+    pub inst_type: String,
+    pub inst_id: SpecificCurrencyPair,
+    pub uly: String,
+    pub base_ccy: String,
+    pub quote_ccy: String,
+    pub settle_ccy: String,
+    #[serde(deserialize_with = "deserialize_optional_decimal")]
+    pub ct_val: Option<Decimal>,
+    pub ct_val_ccy: String,
+    pub ct_type: String,
+    pub tick_sz: Price,
+    pub lot_sz: Amount,
+    pub min_sz: Amount,
+    #[serde(deserialize_with = "deserialize_optional_decimal")]
+    pub max_lmt_sz: Option<Amount>,
+    pub state: String,
+}
+
+/// OKX order description. It is used for REST order requests and for `orders` websocket channel.
+/// Fields with prefix `fill` are related to the latest fill and are sent only via websocket
+/// {
+/// "instType": "SPOT",
+/// "instId": "BTC-USDT",
+/// "ordId": "312269865356374016", // Order ID
+/// "clOrdId": "b1", // Client Order ID, empty if it isn't specified
+/// "px": "999", // Price, empty for market orders
+/// "sz": "3", // Quantity to buy or sell
+/// "ordType": "limit", // market, limit, post_only, fok, ioc
+/// "side": "buy",
+/// "accFillSz": "0", // Accumulated fill quantity
+/// "avgPx": "", // Average filled price, empty if order isn't filled
+/// "state": "live", // canceled, live, partially_filled, filled
+/// "fee": "0", // Accumulated fee, negative means deduction
+/// "feeCcy": "BTC",
+/// "tradeId": "", // Last trade ID
+/// "fillPx": "", // Last filled price
+/// "fillSz": "0", // Last filled quantity
+/// "fillFee": "0", // Last fill fee, negative means deduction
+/// "fillFeeCcy": "",
+/// "fillTime": "", // Last fill time, Unix timestamp in milliseconds
+/// "execType": "", // Liquidity taker or maker of the last fill, T or M
+/// "category": "normal", // normal, twap, adl, full_liquidation, partial_liquidation, delivery, ddh
+/// "uTime": "1597026383085", // Update time, Unix timestamp in milliseconds
+/// "cTime": "1597026383085" // Creation time, Unix timestamp in milliseconds
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OkxOrder {
+    pub inst_id: SpecificCurrencyPair,
+    pub ord_id: ExchangeOrderId,
+    pub cl_ord_id: ClientOrderId,
+    #[serde(deserialize_with = "deserialize_optional_decimal")]
+    pub px: Option<Price>,
+    pub sz: Amount,
+    pub side: OkxOrderSide,
+    pub acc_fill_sz: Amount,
+    #[serde(deserialize_with = "deserialize_optional_decimal")]
+    pub avg_px: Option<Price>,
+    pub state: String,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub fee: Option<Decimal>,
+    #[serde(default)]
+    pub fee_ccy: String,
+    #[serde(default)]
+    pub trade_id: String,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub fill_px: Option<Price>,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub fill_sz: Option<Amount>,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub fill_fee: Option<Decimal>,
+    #[serde(default)]
+    pub fill_fee_ccy: String,
+    #[serde(default, deserialize_with = "deserialize_optional_timestamp")]
+    pub fill_time: Option<DateTime>,
+    #[serde(default)]
+    pub exec_type: String,
+    #[serde(default)]
+    pub category: String,
+}
+
+/// OKX transaction details of the last 3 days
+/// {
+/// "instType": "SPOT",
+/// "instId": "BTC-USDT",
+/// "tradeId": "123",
+/// "ordId": "312269865356374016",
+/// "clOrdId": "b16",
+/// "billId": "1111",
+/// "tag": "",
+/// "fillPx": "999",
+/// "fillSz": "3",
+/// "side": "buy",
+/// "posSide": "net",
+/// "execType": "M", // Liquidity taker or maker, T or M
+/// "feeCcy": "BTC",
+/// "fee": "-0.003", // Negative means deduction
+/// "ts": "1597026383085"
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OkxFill {
+    pub inst_id: SpecificCurrencyPair,
+    pub trade_id: String,
+    pub ord_id: ExchangeOrderId,
+    pub fill_px: Price,
+    pub fill_sz: Amount,
+    pub exec_type: String,
+    pub fee_ccy: String,
+    pub fee: Decimal,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub ts: DateTime,
+}
+
+/// OKX trading account balance. Only used fields of currency details are described
+/// {
+/// "totalEq": "41624.32", // The total amount of equity in USD
+/// "details": [
+///     {
+///         "ccy": "USDT",
+///         "eq": "4992.89", // Equity of the currency
+///         "cashBal": "4850.98", // Cash balance
+///         "availBal": "4834.32", // Available balance of the currency
+///         "frozenBal": "16.66" // Frozen balance of the currency
+///     }
+/// ]
+/// }
+#[derive(Deserialize, Debug)]
+pub struct OkxBalance {
+    pub details: Vec<OkxBalanceDetails>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OkxBalanceDetails {
+    pub ccy: String,
+    #[serde(deserialize_with = "deserialize_optional_decimal")]
+    pub avail_bal: Option<Amount>,
+}
+
+/// OKX position description. Only used fields are described
+/// {
+/// "instType": "SWAP",
+/// "instId": "BTC-USDT-SWAP",
+/// "mgnMode": "cross",
+/// "posSide": "net", // Position side: long, short or net. Positive `pos` means long for net mode
+/// "pos": "10", // Quantity of positions in contracts
+/// "avgPx": "41000", // Average open price
+/// "liqPx": "30000", // Estimated liquidation price
+/// "lever": "10", // Leverage
+/// "uTime": "1614859751636" // Latest time position was adjusted, Unix timestamp in milliseconds
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OkxPosition {
+    pub inst_id: SpecificCurrencyPair,
+    #[serde(deserialize_with = "deserialize_optional_decimal")]
+    pub pos: Option<Amount>,
+    #[serde(deserialize_with = "deserialize_optional_decimal")]
+    pub avg_px: Option<Price>,
+    #[serde(deserialize_with = "deserialize_optional_decimal")]
+    pub liq_px: Option<Price>,
+    #[serde(deserialize_with = "deserialize_optional_decimal")]
+    pub lever: Option<Decimal>,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub u_time: DateTime,
+}
+
+/// Data of `books` websocket channel. Each level is [price, amount, deprecated, orders count]
+/// {
+/// "asks": [["8476.98", "415", "0", "13"]],
+/// "bids": [["8476.97", "256", "0", "12"]],
+/// "ts": "1597026383085",
+/// "checksum": -855196043
+/// }
+#[derive(Deserialize, Debug)]
+pub struct OkxOrderBook {
+    pub asks: Vec<(Price, Amount, String, String)>,
+    pub bids: Vec<(Price, Amount, String, String)>,
+}
+
+/// Data of `trades` websocket channel
+/// {
+/// "instId": "BTC-USDT",
+/// "tradeId": "130639474",
+/// "px": "42219.9",
+/// "sz": "0.12060306",
+/// "side": "buy", // Taker side
+/// "ts": "1630048897897"
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OkxTrade {
+    pub inst_id: SpecificCurrencyPair,
+    #[serde(deserialize_with = "deserialize_trade_id")]
+    pub trade_id: TradeId,
+    pub px: Price,
+    pub sz: Amount,
+    pub side: OkxOrderSide,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub ts: DateTime,
+}
+
+/// OKX sends empty strings instead of nulls for missing numeric values
+fn deserialize_optional_decimal<'de, D>(deserializer: D) -> Result<Option<Decimal>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    match value.is_empty() {
+        true => Ok(None),
+        false => Decimal::from_str(&value)
+            .map(Some)
+            .map_err(de::Error::custom),
+    }
+}
+
+/// OKX timestamps are strings with Unix time in milliseconds
+fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<DateTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    parse_timestamp(&value).map_err(de::Error::custom)
+}
+
+fn deserialize_optional_timestamp<'de, D>(deserializer: D) -> Result<Option<DateTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    match value.is_empty() {
+        true => Ok(None),
+        false => parse_timestamp(&value).map(Some).map_err(de::Error::custom),
+    }
+}
+
+fn parse_timestamp(value: &str) -> anyhow::Result<DateTime> {
+    let millis = value.parse::<i64>()?;
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .ok_or_else(|| anyhow::anyhow!("Invalid timestamp {millis}"))
+}
+
+fn deserialize_trade_id<'de, D>(deserializer: D) -> Result<TradeId, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    String::deserialize(deserializer).map(TradeId::from)
+}
@@ -0,0 +1,39 @@
+use crate::okx::common::{create_okx_with_symbols, response};
+use mmb_domain::market::CurrencyPair;
+use rust_decimal_macros::dec;
+
+#[test]
+fn parse_balance() {
+    let (okx, _) = create_okx_with_symbols(false);
+
+    let balances = okx
+        .parse_get_balance(&response(include_str!("fixtures/balance.json")))
+        .expect("Failed to parse balance");
+
+    assert_eq!(balances.len(), 2);
+    assert_eq!(balances[0].currency_code, "btc".into());
+    assert_eq!(balances[0].balance, dec!(1.5));
+    assert_eq!(balances[1].currency_code, "usdt".into());
+    assert_eq!(balances[1].balance, dec!(1000));
+}
+
+#[test]
+fn parse_positions() {
+    let (okx, _) = create_okx_with_symbols(true);
+
+    let positions = okx
+        .parse_active_positions(&response(include_str!("fixtures/positions.json")))
+        .expect("Failed to parse positions");
+
+    // closed positions are skipped
+    assert_eq!(positions.len(), 1);
+    let position = &positions[0].derivative;
+    assert_eq!(
+        position.currency_pair,
+        CurrencyPair::from_codes("btc".into(), "usdt".into())
+    );
+    assert_eq!(position.position, dec!(-10));
+    assert_eq!(position.average_entry_price, dec!(30000));
+    assert_eq!(position.liquidation_price, dec!(27500.5));
+    assert_eq!(position.leverage, dec!(10));
+}
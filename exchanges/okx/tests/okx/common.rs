@@ -0,0 +1,52 @@
+use hyper::StatusCode;
+use mmb_core::exchanges::rest_client::RestResponse;
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_utils::cancellation_token::CancellationToken;
+use okx::okx::Okx;
+use tokio::sync::broadcast;
+
+pub(crate) fn spot_currency_pair() -> CurrencyPair {
+    CurrencyPair::from_codes("btc".into(), "usdt".into())
+}
+
+pub(crate) fn response(content: &str) -> RestResponse {
+    RestResponse {
+        status: StatusCode::OK,
+        content: content.to_owned(),
+    }
+}
+
+pub(crate) fn create_okx(is_margin_trading: bool) -> (Okx, broadcast::Receiver<ExchangeEvent>) {
+    let settings = ExchangeSettings::new_short(
+        ExchangeAccountId::new("Okx", 0),
+        "".to_owned(),
+        "".to_owned(),
+        is_margin_trading,
+    );
+    let (events_sender, events_receiver) = broadcast::channel(10);
+    let okx = Okx::new(
+        settings,
+        events_sender,
+        AppLifetimeManager::new(CancellationToken::default()),
+    );
+
+    (okx, events_receiver)
+}
+
+/// Symbols are needed for mapping of OKX instruments to currency pairs
+pub(crate) fn create_okx_with_symbols(
+    is_margin_trading: bool,
+) -> (Okx, broadcast::Receiver<ExchangeEvent>) {
+    let (okx, events_receiver) = create_okx(is_margin_trading);
+    let instruments = match is_margin_trading {
+        true => include_str!("fixtures/swap_instruments.json"),
+        false => include_str!("fixtures/spot_instruments.json"),
+    };
+    okx.parse_all_symbols(&response(instruments))
+        .expect("Failed to parse OKX instruments");
+
+    (okx, events_receiver)
+}
@@ -0,0 +1,5 @@
+mod account;
+pub(crate) mod common;
+mod orders;
+mod symbols;
+mod websocket;
@@ -0,0 +1,54 @@
+use crate::okx::common::{create_okx_with_symbols, response, spot_currency_pair};
+use mmb_domain::events::TradeId;
+use mmb_domain::order::snapshot::{OrderRole, OrderSide, OrderStatus};
+use rust_decimal_macros::dec;
+
+#[test]
+fn parse_open_orders() {
+    let (okx, _) = create_okx_with_symbols(false);
+
+    let orders = okx
+        .parse_open_orders(&response(include_str!("fixtures/open_orders.json")))
+        .expect("Failed to parse open orders");
+
+    assert_eq!(orders.len(), 2);
+
+    let partially_filled = &orders[0];
+    assert_eq!(partially_filled.currency_pair, spot_currency_pair());
+    assert_eq!(
+        partially_filled.exchange_order_id,
+        "505073046126960640".into()
+    );
+    assert_eq!(partially_filled.client_order_id, "1665480000123".into());
+    assert_eq!(partially_filled.order_side, OrderSide::Buy);
+    assert_eq!(partially_filled.order_status, OrderStatus::Created);
+    assert_eq!(partially_filled.price, dec!(30000.1));
+    assert_eq!(partially_filled.amount, dec!(0.002));
+    assert_eq!(partially_filled.filled_amount, dec!(0.001));
+    assert_eq!(partially_filled.commission_amount, Some(dec!(0.0000008)));
+
+    let live = &orders[1];
+    assert_eq!(live.order_side, OrderSide::Sell);
+    assert_eq!(live.average_fill_price, dec!(0));
+    assert_eq!(live.filled_amount, dec!(0));
+}
+
+#[test]
+fn parse_my_trades() {
+    let (okx, _) = create_okx_with_symbols(false);
+
+    let trades = okx
+        .parse_my_trades(&response(include_str!("fixtures/fills.json")))
+        .expect("Failed to parse fills");
+
+    assert_eq!(trades.len(), 1);
+    let trade = &trades[0];
+    assert_eq!(trade.exchange_order_id, "505073046126960640".into());
+    assert_eq!(trade.trade_id, TradeId::from("242589207".to_owned()));
+    assert_eq!(trade.price, dec!(30000.1));
+    assert_eq!(trade.amount, dec!(0.001));
+    assert_eq!(trade.order_role, OrderRole::Maker);
+    assert_eq!(trade.fee_currency_code, "btc".into());
+    assert_eq!(trade.fee_amount, Some(dec!(0.0000008)));
+    assert_eq!(trade.datetime.timestamp_millis(), 1665480001000);
+}
@@ -0,0 +1,60 @@
+use crate::okx::common::{create_okx, response};
+use mmb_domain::exchanges::symbol::{ContractType, Precision};
+use rust_decimal_macros::dec;
+
+#[test]
+fn parse_spot_symbols() {
+    let (okx, _) = create_okx(false);
+
+    let symbols = okx
+        .parse_all_symbols(&response(include_str!("fixtures/spot_instruments.json")))
+        .expect("Failed to parse symbols");
+
+    // suspended instruments are skipped
+    assert_eq!(symbols.len(), 1);
+    let symbol = &symbols[0];
+    assert!(!symbol.is_derivative);
+    assert_eq!(symbol.base_currency_code, "btc".into());
+    assert_eq!(symbol.quote_currency_code, "usdt".into());
+    assert_eq!(symbol.amount_currency_code, "btc".into());
+    assert_eq!(symbol.balance_currency_code, None);
+    assert_eq!(symbol.min_amount, Some(dec!(0.00001)));
+    assert_eq!(symbol.max_amount, Some(dec!(9999999999)));
+    assert_eq!(
+        symbol.price_precision,
+        Precision::ByTick { tick: dec!(0.1) }
+    );
+    assert_eq!(
+        symbol.amount_precision,
+        Precision::ByTick {
+            tick: dec!(0.00000001)
+        }
+    );
+}
+
+#[test]
+fn parse_swap_symbols() {
+    let (okx, _) = create_okx(true);
+
+    let symbols = okx
+        .parse_all_symbols(&response(include_str!("fixtures/swap_instruments.json")))
+        .expect("Failed to parse symbols");
+
+    assert_eq!(symbols.len(), 2);
+
+    let linear = &symbols[0];
+    assert!(linear.is_derivative);
+    assert_eq!(linear.base_currency_code, "btc".into());
+    assert_eq!(linear.quote_currency_code, "usdt".into());
+    assert_eq!(linear.contract_type, ContractType::Linear);
+    assert_eq!(linear.amount_multiplier, dec!(0.01));
+    assert_eq!(linear.amount_currency_code, "btc".into());
+    assert_eq!(linear.balance_currency_code, Some("usdt".into()));
+
+    let inverse = &symbols[1];
+    assert_eq!(inverse.quote_currency_code, "usd".into());
+    assert_eq!(inverse.contract_type, ContractType::Inverse);
+    assert_eq!(inverse.amount_multiplier, dec!(100));
+    assert_eq!(inverse.amount_currency_code, "usd".into());
+    assert_eq!(inverse.balance_currency_code, Some("btc".into()));
+}
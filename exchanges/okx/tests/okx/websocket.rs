@@ -0,0 +1,147 @@
+use crate::okx::common::{create_okx_with_symbols, spot_currency_pair};
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::traits::Support;
+use mmb_domain::events::{ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::CurrencyPair;
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::snapshot::{ClientOrderId, ExchangeOrderId, OrderRole, OrderSide};
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use parking_lot::Mutex;
+use rust_decimal_macros::dec;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+fn receive_order_book_event(events: &mut broadcast::Receiver<ExchangeEvent>) -> OrderBookEvent {
+    match events.try_recv().expect("Order book event wasn't sent") {
+        ExchangeEvent::OrderBookEvent(event) => event,
+        event => panic!("Unexpected event {event:?}"),
+    }
+}
+
+#[test]
+fn order_book_snapshot_and_update() {
+    let (okx, mut events) = create_okx_with_symbols(false);
+
+    okx.on_websocket_message(include_str!("fixtures/ws_books_snapshot.json"))
+        .expect("Failed to handle order book snapshot");
+    okx.on_websocket_message(include_str!("fixtures/ws_books_update.json"))
+        .expect("Failed to handle order book update");
+
+    let snapshot = receive_order_book_event(&mut events);
+    assert_eq!(snapshot.currency_pair, spot_currency_pair());
+    assert!(matches!(snapshot.event_type, EventType::Snapshot));
+    assert_eq!(
+        snapshot.data.asks,
+        BTreeMap::from([(dec!(30001.5), dec!(0.5)), (dec!(30002), dec!(1.2))])
+    );
+    assert_eq!(
+        snapshot.data.bids,
+        BTreeMap::from([(dec!(30000.1), dec!(0.8))])
+    );
+
+    let update = receive_order_book_event(&mut events);
+    assert!(matches!(update.event_type, EventType::Update));
+    assert_eq!(update.data.asks, BTreeMap::from([(dec!(30001.5), dec!(0))]));
+    assert_eq!(
+        update.data.bids,
+        BTreeMap::from([(dec!(30000.5), dec!(0.3))])
+    );
+}
+
+#[test]
+fn trades() {
+    let (mut okx, _events) = create_okx_with_symbols(false);
+    let trades = Arc::new(Mutex::new(Vec::<(CurrencyPair, Trade)>::new()));
+    okx.set_handle_trade_callback({
+        let trades = trades.clone();
+        Box::new(move |currency_pair, trade| trades.lock().push((currency_pair, trade)))
+    });
+
+    okx.on_websocket_message(include_str!("fixtures/ws_trades.json"))
+        .expect("Failed to handle trades");
+
+    let trades = trades.lock();
+    assert_eq!(trades.len(), 1);
+    let (currency_pair, trade) = &trades[0];
+    assert_eq!(*currency_pair, spot_currency_pair());
+    assert_eq!(trade.trade_id, TradeId::from("130639474".to_owned()));
+    assert_eq!(trade.price, dec!(30000.1));
+    assert_eq!(trade.quantity, dec!(0.12));
+    assert_eq!(trade.side, OrderSide::Sell);
+    assert_eq!(trade.transaction_time.timestamp_millis(), 1665480000200);
+}
+
+#[test]
+fn order_fill() {
+    let (mut okx, _events) = create_okx_with_symbols(false);
+    let fills = Arc::new(Mutex::new(Vec::<FillEvent>::new()));
+    okx.set_handle_order_filled_callback({
+        let fills = fills.clone();
+        Box::new(move |fill_event| fills.lock().push(fill_event))
+    });
+
+    okx.on_websocket_message(include_str!("fixtures/ws_order_fill.json"))
+        .expect("Failed to handle order fill");
+
+    let fills = fills.lock();
+    assert_eq!(fills.len(), 1);
+    let fill = &fills[0];
+    assert_eq!(fill.trade_id, Some(TradeId::from("242589208".to_owned())));
+    assert_eq!(fill.client_order_id, Some("1665480000123".into()));
+    assert_eq!(fill.exchange_order_id, "505073046126960640".into());
+    assert_eq!(fill.fill_price, dec!(30000.1));
+    assert!(matches!(
+        fill.fill_amount,
+        FillAmount::Incremental {
+            fill_amount,
+            total_filled_amount: Some(total_filled_amount),
+        } if fill_amount == dec!(0.001) && total_filled_amount == dec!(0.001)
+    ));
+    assert_eq!(fill.order_role, Some(OrderRole::Taker));
+    assert_eq!(fill.commission_currency_code, Some("btc".into()));
+    assert_eq!(fill.commission_amount, Some(dec!(0.0000015)));
+    assert_eq!(fill.fill_type, OrderFillType::UserTrade);
+    assert_eq!(
+        fill.fill_date.map(|x| x.timestamp_millis()),
+        Some(1665480001000)
+    );
+
+    let special_order_data = fill
+        .special_order_data
+        .as_ref()
+        .expect("Special order data is missing");
+    assert_eq!(special_order_data.currency_pair, spot_currency_pair());
+    assert_eq!(special_order_data.order_side, OrderSide::Buy);
+    assert_eq!(special_order_data.order_amount, dec!(0.002));
+}
+
+#[test]
+fn order_cancellation() {
+    let (mut okx, _events) = create_okx_with_symbols(false);
+    let cancelled_orders = Arc::new(Mutex::new(Vec::<(ClientOrderId, ExchangeOrderId)>::new()));
+    okx.set_order_cancelled_callback({
+        let cancelled_orders = cancelled_orders.clone();
+        Box::new(move |client_order_id, exchange_order_id, _| {
+            cancelled_orders
+                .lock()
+                .push((client_order_id, exchange_order_id))
+        })
+    });
+
+    okx.on_websocket_message(include_str!("fixtures/ws_order_canceled.json"))
+        .expect("Failed to handle order cancellation");
+
+    assert_eq!(
+        *cancelled_orders.lock(),
+        vec![("1665480000124".into(), "505073046126960642".into())]
+    );
+}
+
+#[test]
+fn pong() {
+    let (okx, _events) = create_okx_with_symbols(false);
+
+    okx.on_websocket_message("pong")
+        .expect("Failed to handle pong");
+}
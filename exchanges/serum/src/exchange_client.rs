@@ -151,15 +151,32 @@ impl ExchangeClient for Serum {
         Ok(ExchangeBalancesAndPositions {
             balances,
             positions: None,
+            margin_account: None,
         })
     }
 
     async fn get_my_trades(
         &self,
-        _symbol: &Symbol,
-        _last_date_time: Option<DateTime>,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
     ) -> RequestResult<Vec<OrderTrade>> {
-        todo!()
+        let trades = self
+            .my_trades
+            .lock()
+            .get(&symbol.currency_pair())
+            .map(|trades| {
+                trades
+                    .iter()
+                    .filter(|trade| match last_date_time {
+                        None => true,
+                        Some(from_datetime) => trade.datetime >= from_datetime,
+                    })
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        RequestResult::Success(trades)
     }
 
     async fn build_all_symbols(&self) -> Result<Vec<Arc<Symbol>>> {
@@ -15,21 +15,21 @@ use serum_dex::instruction::{cancel_order, MarketInstruction};
 use serum_dex::matching::Side;
 use serum_dex::state::OpenOrders;
 use serum_dex::state::{
-    gen_vault_signer_key, strip_header, Event, EventQueueHeader, Market, MarketState,
+    gen_vault_signer_key, strip_header, Event, EventQueueHeader, EventView, Market, MarketState,
 };
 use solana_account_decoder::UiAccount;
 use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
 use solana_client::rpc_filter::{Memcmp, RpcFilterType};
-use solana_client_helpers::spl_associated_token_account::get_associated_token_address;
 use solana_program::account_info::IntoAccountInfo;
 use solana_program::instruction::{AccountMeta, Instruction};
 use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
 use solana_sdk::account::Account;
 use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
 use spl_token::state::Mint;
 use std::any::Any;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::Debug;
 use std::mem::size_of;
 use std::num::NonZeroU64;
@@ -44,12 +44,13 @@ use tokio::time::sleep;
 use crate::helpers::{FromU64Array, ToOrderSide, ToSerumSide, ToU128};
 use crate::market::{MarketData, MarketInfo, MarketMetaData, OpenOrderData};
 use crate::solana_client::{NetworkType, SolanaClient};
-use crate::support::FillEventView;
+use crate::support::{get_out_order_ids, EventQueueData, FillEventView};
 use mmb_core::exchanges::general::exchange::BoxExchangeClient;
 use mmb_core::exchanges::general::features::{
     ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
     RestFillsType, WebSocketOptions,
 };
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
 use mmb_core::exchanges::rest_client::{
     ErrorHandlerData, ErrorHandlerEmpty, RestClient, RestHeadersEmpty,
 };
@@ -154,6 +155,8 @@ pub struct Serum {
     pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
     pub(super) lifetime_manager: Arc<AppLifetimeManager>,
     pub(super) fill_events_cache: Mutex<FillEventsCache>,
+    /// Serum doesn't store trades history, so we keep the latest fills of our orders received from event queue
+    pub(super) my_trades: Mutex<HashMap<CurrencyPair, VecDeque<OrderTrade>>>,
     trade_id_seed: AtomicU64,
 }
 
@@ -196,6 +199,7 @@ impl Serum {
             events_channel,
             lifetime_manager,
             fill_events_cache: FillEventsCache::new().into(),
+            my_trades: Default::default(),
             trade_id_seed: AtomicU64::new(
                 SystemTime::now()
                     .duration_since(UNIX_EPOCH)
@@ -304,14 +308,10 @@ impl Serum {
         metadata: &MarketMetaData,
         open_order_account: Pubkey,
         order: &OrderRef,
+        price: u64,
     ) -> Result<Instruction> {
         let header = order.header();
         let side = header.side;
-        // TODO implement Market order support for Serum
-        let source_price = header
-            .source_price
-            .expect("Price should be specified for Limit order");
-        let price = metadata.make_price(source_price);
         let amount = metadata.make_size(header.amount);
         let max_native_price = metadata.make_max_native(price, amount);
         let client_order_id =
@@ -338,7 +338,9 @@ impl Serum {
             self_trade_behavior: serum_dex::instruction::SelfTradeBehavior::DecrementTake,
             order_type: match header.order_type {
                 OrderType::Limit => serum_dex::matching::OrderType::Limit,
-                _ => unimplemented!(),
+                // Market order never rests in order book so unfilled part of it is cancelled at once
+                OrderType::Market => serum_dex::matching::OrderType::ImmediateOrCancel,
+                order_type => bail!("Order type {order_type:?} isn't supported by Serum"),
             },
             client_order_id,
             limit: u16::MAX,
@@ -373,6 +375,44 @@ impl Serum {
         })
     }
 
+    /// Market order is placed as IOC order with price of the best opposite level
+    async fn get_order_limit_price(
+        &self,
+        order: &OrderRef,
+        market_data: &MarketData,
+    ) -> Result<u64> {
+        let header = order.header();
+        match header.order_type {
+            OrderType::Limit => {
+                let source_price = header
+                    .source_price
+                    .context("Price should be specified for Limit order")?;
+                Ok(market_data.metadata.make_price(source_price))
+            }
+            OrderType::Market => {
+                let (opposite_side, opposite_address) = match header.side {
+                    OrderSide::Buy => (Side::Ask, market_data.metadata.asks_address),
+                    OrderSide::Sell => (Side::Bid, market_data.metadata.bids_address),
+                };
+                let mut account = self.rpc_client.get_account(&opposite_address).await?;
+
+                get_best_level_price(
+                    &mut account,
+                    &opposite_address,
+                    &market_data.metadata,
+                    opposite_side,
+                )?
+                .with_context(|| {
+                    format!(
+                        "There are no opposite orders to fill market order {} for {}",
+                        header.client_order_id, header.currency_pair
+                    )
+                })
+            }
+            order_type => bail!("Order type {order_type:?} isn't supported by Serum"),
+        }
+    }
+
     pub fn create_settle_funds_instructions(
         &self,
         open_order_accounts: &[Pubkey],
@@ -491,13 +531,13 @@ impl Serum {
         &self,
         ui_account: UiAccount,
         market_info: &MarketMetaData,
-    ) -> Result<HashSet<FillEventView>> {
+    ) -> Result<EventQueueData> {
         if let Some(mut account) = ui_account.decode::<Account>() {
             let account_info = (&market_info.event_queue_address, &mut account).into_account_info();
             let (_, buf) = strip_header::<EventQueueHeader, Event>(&account_info, false)
                 .context("Failed to parse data from event queue account")?;
 
-            Ok(buf
+            let views = buf
                 .iter()
                 .filter_map(|event| {
                     event
@@ -508,8 +548,15 @@ impl Serum {
                         })
                         .ok()
                 })
-                .filter_map(|view| FillEventView::try_from_event_view(&view))
-                .collect())
+                .collect_vec();
+
+            Ok(EventQueueData {
+                fills: views
+                    .iter()
+                    .filter_map(FillEventView::try_from_event_view)
+                    .collect(),
+                out_orders: views.iter().filter_map(get_out_order_ids).collect(),
+            })
         } else {
             bail!("Failed to decode ui account")
         }
@@ -618,6 +665,42 @@ impl Serum {
         Err(error)
     }
 
+    /// IOC order isn't placed to order book, so its id can be found only in `Fill` or `Out` events of event queue.
+    /// Should be called after transaction with order is confirmed, so the events are already in event queue
+    async fn get_market_order_id(
+        &self,
+        client_order_id: &ClientOrderId,
+        market_data: &MarketData,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        let event_queue_address = market_data.metadata.event_queue_address;
+        let serum_client_order_id = u64::from_str(client_order_id.as_str()).map_err(|err| {
+            ExchangeError::unknown(
+                format!("Failed to convert client_order_id {client_order_id} to u64: {err:?}")
+                    .as_str(),
+            )
+        })?;
+
+        let mut account = self
+            .rpc_client
+            .get_account(&event_queue_address)
+            .await
+            .map_err(|err| {
+                ExchangeError::unknown(
+                    format!("Failed to get event queue account: {:?}", err).as_str(),
+                )
+            })?;
+
+        find_order_id_in_event_queue(&mut account, &event_queue_address, serum_client_order_id)
+            .map_err(|err| {
+                ExchangeError::unknown(format!("Failed to parse event queue: {:?}", err).as_str())
+            })?
+            .ok_or_else(|| {
+                ExchangeError::unknown(
+                    format!("Market order {client_order_id} isn't found in event queue after transaction confirmation").as_str(),
+                )
+            })
+    }
+
     pub(super) async fn create_order_core(
         &self,
         order: &OrderRef,
@@ -666,12 +749,19 @@ impl Serum {
             }))
         });
 
+        let price = self
+            .get_order_limit_price(order, &market_data)
+            .await
+            .map_err(|err| {
+                ExchangeError::unknown(format!("Failed to get order price: {:?}", err).as_str())
+            })?;
         let place_order_ix = self
             .create_new_order_instruction(
                 market_data.program_id,
                 &market_data.metadata,
                 open_order_account,
                 order,
+                price,
             )
             .map_err(|err| {
                 ExchangeError::unknown(
@@ -694,11 +784,22 @@ impl Serum {
         //     .subscribe_to_open_order_account(&currency_pair, open_order_account)
         //     .await;
 
-        self.rpc_client
-            .send_instructions(&self.payer, &instructions)
-            .await?;
-
-        self.get_order_id(&client_order_id, currency_pair).await
+        match order.order_type() {
+            OrderType::Market => {
+                // Events of IOC order can be read from event queue only after transaction confirmation
+                self.rpc_client
+                    .send_and_confirm_instructions(&self.payer, &instructions)
+                    .await?;
+                self.get_market_order_id(&client_order_id, &market_data)
+                    .await
+            }
+            _ => {
+                self.rpc_client
+                    .send_instructions(&self.payer, &instructions)
+                    .await?;
+                self.get_order_id(&client_order_id, currency_pair).await
+            }
+        }
     }
 
     pub(super) async fn cancel_order_core(
@@ -935,6 +1036,66 @@ impl Serum {
     }
 }
 
+fn get_best_level_price(
+    account: &mut Account,
+    address: &Pubkey,
+    market_info: &MarketMetaData,
+    side: Side,
+) -> Result<Option<u64>> {
+    let account_info = (address, &mut *account).into_account_info();
+    let slab = match side {
+        Side::Ask => market_info.state.load_asks_mut(&account_info),
+        Side::Bid => market_info.state.load_bids_mut(&account_info),
+    }
+    .context("Failed to load order book slab")?;
+
+    let best_level = match side {
+        Side::Ask => slab.find_min(),
+        Side::Bid => slab.find_max(),
+    };
+
+    Ok(best_level
+        .and_then(|handle| slab.get(handle))
+        .and_then(|node| node.as_leaf())
+        .map(|leaf| leaf.price().get()))
+}
+
+fn find_order_id_in_event_queue(
+    account: &mut Account,
+    address: &Pubkey,
+    client_order_id: u64,
+) -> Result<Option<ExchangeOrderId>> {
+    let account_info = (address, &mut *account).into_account_info();
+    let (_, buf) = strip_header::<EventQueueHeader, Event>(&account_info, false)
+        .context("Failed to parse data from event queue account")?;
+
+    Ok(find_order_id_in_events(
+        buf.iter().filter_map(|event| event.as_view().ok()),
+        client_order_id,
+    ))
+}
+
+fn find_order_id_in_events(
+    events: impl IntoIterator<Item = EventView>,
+    client_order_id: u64,
+) -> Option<ExchangeOrderId> {
+    events.into_iter().find_map(|view| match view {
+        EventView::Fill {
+            order_id,
+            client_order_id: Some(event_client_order_id),
+            ..
+        }
+        | EventView::Out {
+            order_id,
+            client_order_id: Some(event_client_order_id),
+            ..
+        } if event_client_order_id.get() == client_order_id => {
+            Some(order_id.to_string().as_str().into())
+        }
+        _ => None,
+    })
+}
+
 pub struct SerumBuilder;
 
 impl ExchangeClientBuilder for SerumBuilder {
@@ -1025,3 +1186,79 @@ impl FillEventsCache {
         self.last_prune_time = Instant::now();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serum_dex::fees::FeeTier;
+
+    const CLIENT_ORDER_ID: u64 = 42;
+    const OTHER_CLIENT_ORDER_ID: u64 = 43;
+
+    fn fill_event(order_id: u128, client_order_id: u64) -> EventView {
+        EventView::Fill {
+            side: Side::Bid,
+            maker: false,
+            native_qty_paid: 100,
+            native_qty_received: 10,
+            native_fee_or_rebate: 1,
+            order_id,
+            owner: [0; 4],
+            owner_slot: 0,
+            fee_tier: FeeTier::Base,
+            client_order_id: NonZeroU64::new(client_order_id),
+        }
+    }
+
+    fn out_event(order_id: u128, client_order_id: u64) -> EventView {
+        EventView::Out {
+            side: Side::Bid,
+            release_funds: true,
+            native_qty_unlocked: 0,
+            native_qty_still_locked: 0,
+            order_id,
+            owner: [0; 4],
+            owner_slot: 0,
+            client_order_id: NonZeroU64::new(client_order_id),
+        }
+    }
+
+    #[test]
+    fn market_order_id_found_in_fill_event() {
+        let events = vec![
+            fill_event(1, OTHER_CLIENT_ORDER_ID),
+            fill_event(2, CLIENT_ORDER_ID),
+        ];
+
+        let order_id = find_order_id_in_events(events, CLIENT_ORDER_ID);
+
+        assert_eq!(order_id, Some("2".into()));
+    }
+
+    #[test]
+    fn market_order_id_found_in_out_event() {
+        // IOC order without any match produces only `Out` event
+        let events = vec![
+            fill_event(1, OTHER_CLIENT_ORDER_ID),
+            out_event(2, CLIENT_ORDER_ID),
+        ];
+
+        let order_id = find_order_id_in_events(events, CLIENT_ORDER_ID);
+
+        assert_eq!(order_id, Some("2".into()));
+    }
+
+    #[test]
+    fn market_order_id_not_found_in_events_of_other_orders() {
+        let events = vec![
+            fill_event(1, OTHER_CLIENT_ORDER_ID),
+            out_event(1, OTHER_CLIENT_ORDER_ID),
+            // event without client order id
+            out_event(2, 0),
+        ];
+
+        let order_id = find_order_id_in_events(events, CLIENT_ORDER_ID);
+
+        assert_eq!(order_id, None);
+    }
+}
@@ -55,6 +55,17 @@ impl SolanaHosts {
             market_list_json,
         }
     }
+
+    /// Hosts of `solana-test-validator` running on default ports. Markets should be deployed
+    /// on validator beforehand, so market list isn't requested and should be passed explicitly
+    pub fn local_validator(market_list_json: String) -> Self {
+        SolanaHosts::new(
+            "http://127.0.0.1:8899".to_string(),
+            "ws://127.0.0.1:8900/".to_string(),
+            "".to_string(),
+            Some(market_list_json),
+        )
+    }
 }
 
 pub enum NetworkType {
@@ -214,6 +225,26 @@ impl SolanaClient {
         Ok(())
     }
 
+    /// Send transaction and wait until it's confirmed with commitment of RPC client
+    pub async fn send_and_confirm_instructions(
+        &self,
+        payer: &Keypair,
+        instructions: &[Instruction],
+    ) -> Result<()> {
+        let recent_hash = self.rpc_client.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &[payer],
+            recent_hash,
+        );
+
+        self.rpc_client
+            .send_and_confirm_transaction(&transaction)
+            .await?;
+        Ok(())
+    }
+
     pub async fn create_dex_account(
         &self,
         program_id: &Pubkey,
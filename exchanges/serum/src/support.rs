@@ -1,6 +1,6 @@
 use crate::serum::{downcast_mut_to_serum_extension_data, Serum, SerumExtensionData};
 use std::any::Any;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
@@ -22,6 +22,7 @@ use mmb_core::exchanges::common::send_event;
 use mmb_core::exchanges::general::handlers::handle_order_filled::{
     FillAmount, FillEvent, SpecialOrderData,
 };
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
 use mmb_core::exchanges::traits::{
     HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
     SendWebsocketMessageCb, Support,
@@ -35,7 +36,7 @@ use mmb_domain::order::fill::OrderFillType;
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::snapshot::{
     Amount, ClientOrderId, ExchangeOrderId, OrderInfo, OrderInfoExtensionData, OrderRole,
-    OrderSide, OrderStatus, Price, SortedOrderData,
+    OrderSide, OrderStatus, OrderType, Price, SortedOrderData,
 };
 use mmb_domain::order_book::event::{EventType, OrderBookEvent};
 use mmb_domain::order_book::order_book_data::OrderBookData;
@@ -101,14 +102,16 @@ impl Support for Serum {
     fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
         match role {
             WebSocketRole::Main => true,
-            WebSocketRole::Secondary => false,
+            WebSocketRole::Secondary | WebSocketRole::MarketData(_) => false,
         }
     }
 
     async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
         let url = match role {
             WebSocketRole::Main => self.network_type.ws(),
-            WebSocketRole::Secondary => unimplemented!("Not needed for implementation Serum"),
+            WebSocketRole::Secondary | WebSocketRole::MarketData(_) => {
+                unimplemented!("Not needed for implementation Serum")
+            }
         };
 
         Url::parse(url).with_context(|| format!("Unable parse websocket {role:?} uri from {url}"))
@@ -162,7 +165,8 @@ impl Serum {
             }
             SubscriptionAccountType::EventQueue => {
                 let events = self.get_event_queue_data(ui_account, market_info)?;
-                self.handle_event_queue_orders(events, currency_pair, market_info)?;
+                self.handle_event_queue_orders(events.fills, currency_pair, market_info)?;
+                self.handle_market_orders_out(&events.out_orders);
             }
             SubscriptionAccountType::OpenOrders => {
                 let _orders =
@@ -250,6 +254,7 @@ impl Serum {
                     .get(&fill_data.client_order_id)
                 {
                     self.handle_order_fill(order.value(), &fill_data);
+                    self.add_my_trade(&fill_data);
                 }
                 self.handle_order_trade(&fill_data);
                 self.fill_events_cache.lock().add_event(fill_event);
@@ -260,6 +265,62 @@ impl Serum {
         Ok(())
     }
 
+    /// Unfilled part of market order is cancelled by Serum at once and `Out` event is the only notification about it
+    fn handle_market_orders_out(&self, out_orders: &HashMap<ClientOrderId, ExchangeOrderId>) {
+        for (client_order_id, exchange_order_id) in out_orders {
+            let order = match self.orders.cache_by_client_id.get(client_order_id) {
+                None => continue,
+                Some(order) => order.clone(),
+            };
+
+            // Fully filled order is finished by fill events
+            if order.order_type() != OrderType::Market || order.is_finished() {
+                continue;
+            }
+
+            // Same events are repeated in Solana blocks several times
+            let is_already_cancelled = order.fn_mut(|order| {
+                let serum_extension_data =
+                    downcast_mut_to_serum_extension_data(order.extension_data.as_deref_mut());
+                let is_already_cancelled =
+                    serum_extension_data.actual_status == OrderStatus::Canceled;
+                serum_extension_data.actual_status = OrderStatus::Canceled;
+                is_already_cancelled
+            });
+
+            if !is_already_cancelled {
+                (self.order_cancelled_callback)(
+                    client_order_id.clone(),
+                    exchange_order_id.clone(),
+                    EventSourceType::Rpc,
+                );
+            }
+        }
+    }
+
+    fn add_my_trade(&self, fill_data: &OrderFillData) {
+        const MAX_TRADES_PER_MARKET: usize = 1000;
+
+        let mut my_trades = self.my_trades.lock();
+        let trades = my_trades.entry(fill_data.currency_pair).or_default();
+        if trades.len() == MAX_TRADES_PER_MARKET {
+            trades.pop_front();
+        }
+
+        trades.push_back(OrderTrade::new(
+            fill_data.exchange_order_id.clone(),
+            fill_data.trade_id.clone(),
+            fill_data.date,
+            fill_data.price,
+            fill_data.fill_amount,
+            fill_data.order_role,
+            fill_data.commission.currency_code,
+            None,
+            Some(fill_data.commission.amount),
+            fill_data.fill_type,
+        ));
+    }
+
     fn handle_order_event(&self, orders: &[OrderInfo], currency_pair: CurrencyPair) {
         let orders: DashMap<ClientOrderId, &OrderInfo> = orders
             .iter()
@@ -351,6 +412,7 @@ impl Serum {
                 quantity: fill_data.fill_amount,
                 side: fill_data.order_side,
                 transaction_time: fill_data.date,
+                tick_direction: None,
             },
         );
     }
@@ -474,6 +536,26 @@ impl From<u8> for FeeTier {
     }
 }
 
+pub(super) struct EventQueueData {
+    pub(super) fills: HashSet<FillEventView>,
+    /// Orders which are out of order book after matching or cancellation
+    pub(super) out_orders: HashMap<ClientOrderId, ExchangeOrderId>,
+}
+
+pub(super) fn get_out_order_ids(event: &EventView) -> Option<(ClientOrderId, ExchangeOrderId)> {
+    match event {
+        &EventView::Out {
+            order_id,
+            client_order_id: Some(client_order_id),
+            ..
+        } => Some((
+            client_order_id.to_string().as_str().into(),
+            order_id.to_string().as_str().into(),
+        )),
+        _ => None,
+    }
+}
+
 #[derive(PartialEq, Eq, Hash, Debug)]
 pub(super) struct FillEventView {
     side: OrderSide,
@@ -2,7 +2,7 @@ use crate::serum::serum_builder::SerumBuilder;
 use mmb_utils::cancellation_token::CancellationToken;
 use mmb_utils::logger::init_logger;
 
-#[ignore = "need solana keypair"]
+#[ignore = "need solana keypair and local test validator"]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_balance_successfully() {
     init_logger();
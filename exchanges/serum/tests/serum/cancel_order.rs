@@ -6,7 +6,7 @@ use mmb_utils::cancellation_token::CancellationToken;
 use mmb_utils::infrastructure::init_infrastructure;
 use rust_decimal_macros::dec;
 
-#[ignore = "need solana keypair"]
+#[ignore = "need solana keypair and local test validator"]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn cancelled_successfully() {
     let serum_builder = SerumBuilder::build_account_0().await;
@@ -18,8 +18,8 @@ async fn cancelled_successfully() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         dec!(1),
         dec!(1),
+        currency_pair,
     )
-    .currency_pair(currency_pair)
     .side(OrderSide::Sell)
     .build();
 
@@ -33,7 +33,7 @@ async fn cancelled_successfully() {
         .await;
 }
 
-#[ignore = "need solana keypair"]
+#[ignore = "need solana keypair and local test validator"]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn cancel_all_orders() {
     init_infrastructure();
@@ -47,8 +47,8 @@ async fn cancel_all_orders() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         dec!(1),
         dec!(1),
+        currency_pair,
     )
-    .currency_pair(currency_pair)
     .side(OrderSide::Sell)
     .build();
 
@@ -62,8 +62,8 @@ async fn cancel_all_orders() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         dec!(2),
         dec!(10),
+        currency_pair,
     )
-    .currency_pair(currency_pair)
     .side(OrderSide::Sell)
     .build();
 
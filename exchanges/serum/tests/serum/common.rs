@@ -27,11 +27,8 @@ fn get_key_pair_impl(name: &str) -> Result<String> {
 pub fn get_network_type() -> Result<NetworkType> {
     let markets_json = get_key_pair_impl("SERUM_MARKET_LIST")?;
 
-    Ok(NetworkType::Custom(SolanaHosts::new(
-        "https://api.devnet.solana.com".to_string(),
-        "ws://api.devnet.solana.com/".to_string(),
-        "".to_string(),
-        Some(markets_json),
+    Ok(NetworkType::Custom(SolanaHosts::local_validator(
+        markets_json,
     )))
 }
 
@@ -10,7 +10,7 @@ use std::time::Duration;
 use tokio::sync::broadcast;
 use tokio::time::timeout;
 
-#[ignore = "need solana keypair"]
+#[ignore = "need solana keypair and local test validator"]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn create_successfully() {
     let mut serum_builder = SerumBuilder::build_account_0().await;
@@ -22,8 +22,8 @@ async fn create_successfully() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         dec!(1),
         dec!(1),
+        currency_pair,
     )
-    .currency_pair(currency_pair)
     .side(OrderSide::Sell)
     .timeout(Duration::from_secs(30))
     .build();
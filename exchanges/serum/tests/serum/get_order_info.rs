@@ -8,7 +8,7 @@ use mmb_utils::logger::init_logger;
 use rust_decimal_macros::dec;
 use std::time::Duration;
 
-#[ignore = "need solana keypair"]
+#[ignore = "need solana keypair and local test validator"]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_order_info() {
     init_logger();
@@ -22,8 +22,8 @@ async fn get_order_info() {
         Some("FromGetOpenOrdersTest".to_owned()),
         dec!(1),
         dec!(1),
+        currency_pair,
     )
-    .currency_pair(currency_pair)
     .side(OrderSide::Sell)
     .build();
 
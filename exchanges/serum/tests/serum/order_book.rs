@@ -6,7 +6,7 @@ use mmb_domain::order::snapshot::OrderSide;
 use mmb_domain::order_book_data;
 use rust_decimal_macros::dec;
 
-#[ignore = "need solana keypair"]
+#[ignore = "need solana keypair and local test validator"]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn fill_order_book() {
     let serum_builder_0 = SerumBuilder::build_account_0().await;
@@ -19,8 +19,8 @@ async fn fill_order_book() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         price1,
         amount1,
+        CurrencyPair::from_codes("sol".into(), "test".into()),
     )
-    .currency_pair(CurrencyPair::from_codes("sol".into(), "test".into()))
     .side(OrderSide::Buy)
     .build();
 
@@ -34,8 +34,8 @@ async fn fill_order_book() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         price2,
         amount2,
+        CurrencyPair::from_codes("sol".into(), "test".into()),
     )
-    .currency_pair(CurrencyPair::from_codes("sol".into(), "test".into()))
     .side(OrderSide::Buy)
     .build();
 
@@ -1,10 +1,12 @@
 use crate::serum::serum_builder::SerumBuilder;
 use core_tests::order::OrderProxyBuilder;
+use mmb_core::exchanges::general::exchange::RequestResult;
 use mmb_domain::events::ExchangeEvent;
 use mmb_domain::market::CurrencyPair;
 use mmb_domain::order::event::OrderEventType;
-use mmb_domain::order::snapshot::{ClientOrderId, OrderSide, OrderSnapshot};
+use mmb_domain::order::snapshot::{ClientOrderId, OrderSide, OrderSnapshot, UserOrder};
 use mmb_utils::infrastructure::init_infrastructure;
+use mmb_utils::infrastructure::WithExpect;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use scopeguard::defer;
@@ -12,7 +14,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast;
 
-#[ignore = "need solana keypair"]
+#[ignore = "need solana keypair and local test validator"]
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn partial_order_fill() {
     init_infrastructure();
@@ -26,8 +28,8 @@ async fn partial_order_fill() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         price,
         first_amount,
+        CurrencyPair::from_codes("sol".into(), "test".into()),
     )
-    .currency_pair(CurrencyPair::from_codes("sol".into(), "test".into()))
     .side(OrderSide::Buy)
     .timeout(Duration::from_secs(30))
     .build();
@@ -40,8 +42,8 @@ async fn partial_order_fill() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         price,
         second_amount,
+        CurrencyPair::from_codes("sol".into(), "test".into()),
     )
-    .currency_pair(CurrencyPair::from_codes("sol".into(), "test".into()))
     .side(OrderSide::Sell)
     .timeout(Duration::from_secs(30))
     .build();
@@ -87,7 +89,7 @@ async fn partial_order_fill() {
     assert_eq!(dec!(0.004), commission);
 }
 
-#[ignore = "need solana keypair"]
+#[ignore = "need solana keypair and local test validator"]
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn full_order_fill() {
     init_infrastructure();
@@ -101,8 +103,8 @@ async fn full_order_fill() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         price,
         first_amount,
+        CurrencyPair::from_codes("sol".into(), "test".into()),
     )
-    .currency_pair(CurrencyPair::from_codes("sol".into(), "test".into()))
     .side(OrderSide::Buy)
     .timeout(Duration::from_secs(30))
     .build();
@@ -115,8 +117,8 @@ async fn full_order_fill() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         price,
         second_amount,
+        CurrencyPair::from_codes("sol".into(), "test".into()),
     )
-    .currency_pair(CurrencyPair::from_codes("sol".into(), "test".into()))
     .side(OrderSide::Sell)
     .timeout(Duration::from_secs(30))
     .build();
@@ -154,6 +156,89 @@ async fn full_order_fill() {
     assert_eq!(second_amount, order_snapshot.filled_amount());
 }
 
+#[ignore = "need solana keypair and local test validator"]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn market_order_fill() {
+    init_infrastructure();
+
+    let first_serum_builder = SerumBuilder::build_account_0().await;
+    let first_exchange_account_id = first_serum_builder.exchange.exchange_account_id;
+    let price = dec!(1);
+    let first_amount = dec!(10);
+    let first_order_proxy = OrderProxyBuilder::new(
+        first_exchange_account_id,
+        Some("FromMarketOrderFillTest".to_owned()),
+        price,
+        first_amount,
+        CurrencyPair::from_codes("sol".into(), "test".into()),
+    )
+    .side(OrderSide::Buy)
+    .timeout(Duration::from_secs(30))
+    .build();
+
+    let mut second_serum_builder = SerumBuilder::build_account_1().await;
+    let second_exchange_account_id = second_serum_builder.exchange.exchange_account_id;
+    let second_amount = dec!(5);
+    let mut second_order_proxy = OrderProxyBuilder::new(
+        second_exchange_account_id,
+        Some("FromMarketOrderFillTest".to_owned()),
+        price,
+        second_amount,
+        CurrencyPair::from_codes("sol".into(), "test".into()),
+    )
+    .side(OrderSide::Sell)
+    .timeout(Duration::from_secs(30))
+    .build();
+    second_order_proxy.user_order = UserOrder::Market;
+
+    let first_order_ref = first_order_proxy
+        .create_order(first_serum_builder.exchange.clone())
+        .await
+        .expect("Create first order failed with error");
+    let first_builder_exchange = first_serum_builder.exchange.clone();
+    defer! {
+        tokio::spawn(async move {
+            first_order_proxy
+                .cancel_order_or_fail(&first_order_ref, first_builder_exchange.clone())
+                .await;
+        });
+    }
+
+    // Market order is immediate or cancel, so it doesn't need to be cancelled after test
+    let second_order_ref = second_order_proxy
+        .create_order(second_serum_builder.exchange.clone())
+        .await
+        .expect("Create market order failed with error");
+
+    let order_snapshot = receive_exchange_order_event(
+        &mut second_serum_builder.rx,
+        second_order_ref.client_order_id(),
+    )
+    .await;
+
+    assert_eq!(second_amount, order_snapshot.filled_amount());
+
+    let currency_pair = second_order_proxy.currency_pair;
+    let symbol = second_serum_builder
+        .exchange
+        .symbols
+        .get(&currency_pair)
+        .with_expect(|| format!("Can't find symbol {currency_pair}"))
+        .value()
+        .clone();
+    let trades = match second_serum_builder
+        .exchange
+        .exchange_client
+        .get_my_trades(&symbol, None)
+        .await
+    {
+        RequestResult::Success(trades) => trades,
+        RequestResult::Error(error) => panic!("Failed to get my trades: {error:?}"),
+    };
+
+    assert!(!trades.is_empty());
+}
+
 async fn receive_exchange_order_event(
     receiver: &mut broadcast::Receiver<ExchangeEvent>,
     client_order_id: ClientOrderId,
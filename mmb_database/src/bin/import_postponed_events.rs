@@ -0,0 +1,104 @@
+//! Imports postponed events files into database after engine was stopped before it could resave them.
+//!
+//! Usage: `import_postponed_events <postponed_events_dir> <database_url> [--remove-imported]`
+//!
+//! Importing is idempotent: events with `event_id` that already exists in target table are skipped,
+//! so the tool can be run several times for the same directory.
+//! Files of version 1 have no event ids, their events get fresh ids on every import,
+//! so such files should be imported only once (e.g. with `--remove-imported`).
+
+use anyhow::{bail, Result};
+use mmb_database::postgres_db::postponed_events::{
+    import_postponed_events_dir, ImportedEventsCount, PostponedEventsFileImport,
+};
+use mmb_database::postgres_db::PgPool;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str =
+    "Usage: import_postponed_events <postponed_events_dir> <database_url> [--remove-imported]";
+
+struct Args {
+    postponed_events_dir: PathBuf,
+    database_url: String,
+    remove_imported: bool,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut remove_imported = false;
+    let mut positional = vec![];
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--remove-imported" => remove_imported = true,
+            _ if arg.starts_with("--") => bail!("unknown option `{arg}`"),
+            _ => positional.push(arg),
+        }
+    }
+
+    match <[String; 2]>::try_from(positional) {
+        Ok([postponed_events_dir, database_url]) => Ok(Args {
+            postponed_events_dir: postponed_events_dir.into(),
+            database_url,
+            remove_imported,
+        }),
+        Err(_) => bail!("expected 2 positional arguments"),
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(args).await {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("Importing postponed events failed: {err:?}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Returns `false` if some files were rejected
+async fn run(args: Args) -> Result<bool> {
+    let pool = PgPool::create(&args.database_url, 1).await?;
+
+    let reports =
+        import_postponed_events_dir(&pool, &args.postponed_events_dir, args.remove_imported)
+            .await?;
+
+    let mut total = ImportedEventsCount::default();
+    let mut rejected_files_count = 0;
+    for report in &reports {
+        let path = report.path.display();
+        match &report.import {
+            PostponedEventsFileImport::Restored { table_name, count } => {
+                println!(
+                    "restored {path}: {} events inserted into `{table_name}`, {} duplicates skipped",
+                    count.inserted, count.duplicates
+                );
+                total.inserted += count.inserted;
+                total.duplicates += count.duplicates;
+            }
+            PostponedEventsFileImport::Rejected { reason } => {
+                println!("rejected {path}: {reason}");
+                rejected_files_count += 1;
+            }
+        }
+    }
+
+    println!(
+        "Files: {} restored, {rejected_files_count} rejected. Events: {} inserted, {} duplicates skipped",
+        reports.len() - rejected_files_count,
+        total.inserted,
+        total.duplicates
+    );
+
+    Ok(rejected_files_count == 0)
+}
@@ -18,3 +18,4 @@
 
 #[allow(dead_code)] // TODO: delete it after start using
 pub mod postgres_db;
+pub mod sqlite_db;
@@ -10,10 +10,11 @@ use std::fmt::{Display, Formatter};
 use tokio_postgres::binary_copy::BinaryCopyInWriter;
 use tokio_postgres::types::Type;
 use tokio_postgres::{NoTls, Statement};
+use uuid::Uuid;
 pub type TableName = &'static str;
 pub type TableNameRef<'a> = &'a str;
 
-const EVENT_INSERT_TYPES_LIST: [Type; 2] = [Type::INT4, Type::JSONB];
+pub(crate) const EVENT_INSERT_TYPES_LIST: [Type; 3] = [Type::UUID, Type::INT4, Type::JSONB];
 
 #[macro_export]
 macro_rules! impl_event {
@@ -21,6 +22,16 @@ macro_rules! impl_event {
         impl mmb_database::postgres_db::events::Event for $ty {
             const TABLE_NAME: mmb_database::postgres_db::events::TableName = $table_name;
 
+            fn get_json(&self) -> serde_json::Result<serde_json::Value> {
+                serde_json::to_value(self)
+            }
+        }
+    };
+    ($ty:ty, $table_name:expr, $priority:expr) => {
+        impl mmb_database::postgres_db::events::Event for $ty {
+            const TABLE_NAME: mmb_database::postgres_db::events::TableName = $table_name;
+            const PRIORITY: mmb_database::postgres_db::events::EventPriority = $priority;
+
             fn get_json(&self) -> serde_json::Result<serde_json::Value> {
                 serde_json::to_value(self)
             }
@@ -28,8 +39,19 @@ macro_rules! impl_event {
     };
 }
 
+/// Priority of event recording when events are produced faster than they can be saved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventPriority {
+    /// High-volume events (e.g. market data) that can be dropped under overload
+    Normal,
+    /// Events that must never be dropped (e.g. orders, fills, balances)
+    Critical,
+}
+
 pub trait Event {
     const TABLE_NAME: &'static str;
+    const PRIORITY: EventPriority = EventPriority::Normal;
+
     fn get_version(&self) -> i32 {
         1
     }
@@ -47,13 +69,28 @@ pub struct DbEvent {
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 pub struct InsertEvent {
+    /// Unique identifier of event that is assigned before saving,
+    /// so the same event can't be inserted twice (e.g. on importing postponed events).
+    /// Events of postponed files of version 1 have no identifiers, so they get fresh ones on loading
+    #[serde(default = "Uuid::new_v4")]
+    pub event_id: Uuid,
     pub version: i32,
     pub json: JsonValue,
 }
 
+impl InsertEvent {
+    pub fn new(version: i32, json: JsonValue) -> Self {
+        Self {
+            event_id: Uuid::new_v4(),
+            version,
+            json,
+        }
+    }
+}
+
 impl Display for InsertEvent {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} {}", self.version, self.json)
+        write!(f, "{} {} {}", self.event_id, self.version, self.json)
     }
 }
 
@@ -62,7 +99,7 @@ pub async fn save_events_batch<'a>(
     table_name: &str,
     events: &'a [InsertEvent],
 ) -> Result<()> {
-    let sql = format!("COPY {table_name} (version, json) from stdin BINARY");
+    let sql = format!("COPY {table_name} (event_id, version, json) from stdin BINARY");
 
     let sink = pool
         .0
@@ -78,7 +115,7 @@ pub async fn save_events_batch<'a>(
     for event in events {
         writer
             .as_mut()
-            .write(&[&event.version, &event.json])
+            .write(&[&event.event_id, &event.version, &event.json])
             .await
             .context("from `save_events_batch` on CopyInWriter::write() row")?;
     }
@@ -108,7 +145,7 @@ pub async fn save_events_one_by_one(
         PooledConnection<'a, PostgresConnectionManager<NoTls>>,
         Statement,
     )> {
-        let sql = format!("INSERT INTO {table_name} (version, json) VALUES($1, $2)");
+        let sql = format!("INSERT INTO {table_name} (event_id, version, json) VALUES($1, $2, $3)");
 
         let connection = pool
             .0
@@ -132,7 +169,10 @@ pub async fn save_events_one_by_one(
     let mut failed_events = vec![];
     for event in events {
         let insert_result = connection
-            .execute(&sql_statement, &[&event.version, &event.json])
+            .execute(
+                &sql_statement,
+                &[&event.event_id, &event.version, &event.json],
+            )
             .await;
 
         match insert_result {
@@ -191,10 +231,7 @@ mod tests {
             "first_name": "Ivan",
             "last_name": "Ivanov",
         });
-        let item = InsertEvent {
-            version: 1,
-            json: expected_json.clone(),
-        };
+        let item = InsertEvent::new(1, expected_json.clone());
 
         // act
         save_events_batch(&pool.pool, TABLE_NAME, &[item])
@@ -226,10 +263,7 @@ mod tests {
             "first_name": "Ivan",
             "last_name": "Ivanov",
         });
-        let item = InsertEvent {
-            version: 1,
-            json: expected_json.clone(),
-        };
+        let item = InsertEvent::new(1, expected_json.clone());
 
         // act
         let (results, failed_events) =
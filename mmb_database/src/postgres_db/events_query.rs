@@ -0,0 +1,274 @@
+use crate::postgres_db::events::TableName;
+use crate::postgres_db::PgPool;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
+
+const DEFAULT_PAGE_SIZE: u32 = 1_000;
+
+/// Event that can be read from the table it was recorded to
+pub trait QueryableEvent: DeserializeOwned {
+    const TABLE_NAME: TableName;
+
+    /// Version of json that is deserialized directly to event type
+    const VERSION: i32 = 1;
+    /// Oldest version of json that can be upgraded by `upgrade_json`. Rows of older versions are not selected
+    const MIN_SUPPORTED_VERSION: i32 = Self::VERSION;
+
+    /// JSONPath to exchange account id in event json, e.g. `$.exchange_account_id`.
+    /// `None` if event can't be filtered by exchange account id
+    const EXCHANGE_ACCOUNT_ID_PATH: Option<&'static str>;
+    /// JSONPath to currency pair in event json, e.g. `$.currency_pair`.
+    /// `None` if event can't be filtered by currency pair
+    const CURRENCY_PAIR_PATH: Option<&'static str>;
+
+    /// Converts json of supported previous version to json of current version
+    fn upgrade_json(version: i32, _json: JsonValue) -> Result<JsonValue> {
+        bail!(
+            "can't upgrade json of `{}` event from version {version} to {}",
+            Self::TABLE_NAME,
+            Self::VERSION
+        )
+    }
+
+    fn from_json(version: i32, json: JsonValue) -> Result<Self> {
+        let json = match version == Self::VERSION {
+            true => json,
+            false => Self::upgrade_json(version, json)?,
+        };
+
+        serde_json::from_value(json)
+            .with_context(|| format!("failed deserialization of `{}` event", Self::TABLE_NAME))
+    }
+}
+
+/// Conditions of selecting recorded events. Time range is applied to event insertion time
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventsFilter {
+    pub exchange_account_id: Option<String>,
+    pub currency_pair: Option<String>,
+    /// Inclusive start of time range
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive end of time range
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Keyset pagination by event id
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    /// Id of the last event of the previous page
+    pub after_id: Option<i64>,
+    pub limit: u32,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self {
+            after_id: None,
+            limit: DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StoredEvent<T> {
+    pub id: i64,
+    pub insert_time: DateTime<Utc>,
+    pub event: T,
+}
+
+#[derive(Debug, Clone)]
+pub struct EventsPage<T> {
+    pub events: Vec<StoredEvent<T>>,
+    /// Pagination for the next page or `None` if this page is the last one
+    pub next_page: Option<Pagination>,
+}
+
+/// Selects page of events ordered by insertion
+pub async fn select_events<T: QueryableEvent>(
+    pool: &PgPool,
+    filter: &EventsFilter,
+    pagination: Pagination,
+) -> Result<EventsPage<T>> {
+    let exchange_account_id_path = get_filter_path(
+        &filter.exchange_account_id,
+        T::EXCHANGE_ACCOUNT_ID_PATH,
+        T::TABLE_NAME,
+        "exchange account id",
+    )?;
+    let currency_pair_path = get_filter_path(
+        &filter.currency_pair,
+        T::CURRENCY_PAIR_PATH,
+        T::TABLE_NAME,
+        "currency pair",
+    )?;
+
+    let sql = include_str!("sql/select_events.sql").replace("TABLE_NAME", T::TABLE_NAME);
+    let limit = i64::from(pagination.limit);
+
+    let connection = pool
+        .0
+        .get()
+        .await
+        .context("getting db connection from pool")?;
+    let rows = connection
+        .query(
+            &sql,
+            &[
+                &T::MIN_SUPPORTED_VERSION,
+                &T::VERSION,
+                &exchange_account_id_path,
+                &currency_pair_path,
+                &filter.from,
+                &filter.to,
+                &pagination.after_id,
+                &limit,
+            ],
+        )
+        .await
+        .with_context(|| format!("failed selecting events from `{}`", T::TABLE_NAME))?;
+
+    let mut events = Vec::with_capacity(rows.len());
+    for row in rows {
+        let id: i64 = row.try_get("id")?;
+        let version: i32 = row.try_get("version")?;
+        let event = T::from_json(version, row.try_get("json")?)
+            .with_context(|| format!("event id: {id}"))?;
+
+        events.push(StoredEvent {
+            id,
+            insert_time: row.try_get("insert_time")?,
+            event,
+        });
+    }
+
+    let next_page = match events.last() {
+        Some(last) if events.len() as i64 == limit => Some(Pagination {
+            after_id: Some(last.id),
+            limit: pagination.limit,
+        }),
+        _ => None,
+    };
+
+    Ok(EventsPage { events, next_page })
+}
+
+/// Selects events of all pages
+pub async fn select_all_events<T: QueryableEvent>(
+    pool: &PgPool,
+    filter: &EventsFilter,
+) -> Result<Vec<StoredEvent<T>>> {
+    let mut events = vec![];
+    let mut pagination = Some(Pagination::default());
+    while let Some(current_page) = pagination {
+        let page = select_events(pool, filter, current_page).await?;
+        events.extend(page.events);
+        pagination = page.next_page;
+    }
+
+    Ok(events)
+}
+
+/// JSONPath predicate with value written as string literal, so the filter can use GIN index of json column
+fn get_filter_path(
+    value: &Option<String>,
+    path: Option<&str>,
+    table_name: TableName,
+    field_name: &str,
+) -> Result<Option<String>> {
+    match (value, path) {
+        (None, _) => Ok(None),
+        (Some(value), Some(path)) => {
+            let value = JsonValue::from(value.as_str());
+            Ok(Some(format!("{path} ? (@ == {value})")))
+        }
+        (Some(_), None) => bail!("events `{table_name}` can't be filtered by {field_name}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, PartialEq, Eq)]
+    struct Person {
+        name: String,
+        age: u32,
+    }
+
+    impl QueryableEvent for Person {
+        const TABLE_NAME: TableName = "persons";
+        const VERSION: i32 = 2;
+        const MIN_SUPPORTED_VERSION: i32 = 1;
+        const EXCHANGE_ACCOUNT_ID_PATH: Option<&'static str> = None;
+        const CURRENCY_PAIR_PATH: Option<&'static str> = Some("$.currency_pair");
+
+        fn upgrade_json(version: i32, json: JsonValue) -> Result<JsonValue> {
+            match version {
+                1 => Ok(json!({ "name": json["first_name"], "age": json["age"] })),
+                _ => bail!("unknown version {version}"),
+            }
+        }
+    }
+
+    #[test]
+    fn deserialize_by_version() {
+        let expected = Person {
+            name: "Ivan".to_owned(),
+            age: 30,
+        };
+
+        let person = Person::from_json(2, json!({ "name": "Ivan", "age": 30 })).expect("in test");
+        assert_eq!(person, expected);
+
+        let person =
+            Person::from_json(1, json!({ "first_name": "Ivan", "age": 30 })).expect("in test");
+        assert_eq!(person, expected);
+
+        assert!(Person::from_json(3, json!({ "name": "Ivan", "age": 30 })).is_err());
+    }
+
+    #[test]
+    fn filter_path() {
+        assert_eq!(
+            get_filter_path(
+                &Some("btc/usdt".to_owned()),
+                Person::CURRENCY_PAIR_PATH,
+                "persons",
+                "currency pair"
+            )
+            .expect("in test"),
+            Some(r#"$.currency_pair ? (@ == "btc/usdt")"#.to_owned())
+        );
+        assert_eq!(
+            get_filter_path(
+                &Some(r#"btc"/usdt"#.to_owned()),
+                Person::CURRENCY_PAIR_PATH,
+                "persons",
+                "currency pair"
+            )
+            .expect("in test"),
+            Some(r#"$.currency_pair ? (@ == "btc\"/usdt")"#.to_owned())
+        );
+        assert_eq!(
+            get_filter_path(
+                &None,
+                Person::EXCHANGE_ACCOUNT_ID_PATH,
+                "persons",
+                "exchange account id"
+            )
+            .expect("in test"),
+            None
+        );
+        assert!(get_filter_path(
+            &Some("Binance_0".to_owned()),
+            Person::EXCHANGE_ACCOUNT_ID_PATH,
+            "persons",
+            "exchange account id"
+        )
+        .is_err());
+    }
+}
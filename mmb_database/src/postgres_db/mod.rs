@@ -1,7 +1,9 @@
 pub mod cleanup_database;
 pub mod events;
+pub mod events_query;
 pub mod live_ranges;
 pub mod migrator;
+pub mod postponed_events;
 pub mod tests;
 
 use anyhow::{Context, Result};
@@ -0,0 +1,352 @@
+use crate::postgres_db::events::{InsertEvent, EVENT_INSERT_TYPES_LIST};
+use crate::postgres_db::PgPool;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+pub const POSTPONED_EVENTS_FILE_PREFIX: &str = "events_";
+pub const NOT_FINISHED_POSTPONED_EVENTS_FILE_PREFIX: &str = "writing_yet_";
+pub const POSTPONED_EVENTS_FILE_VERSION: u32 = 2;
+/// Events of version 1 have no `event_id`, so importing of such files isn't idempotent
+const MIN_SUPPORTED_POSTPONED_EVENTS_FILE_VERSION: u32 = 1;
+
+const BUFFER_SIZE: usize = 16384;
+
+/// Content of file with events that were not saved to database in time
+#[derive(Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub struct PostponedEventsFileFormat {
+    pub version: u32,
+    pub table_name: String,
+    pub events: Vec<InsertEvent>,
+}
+
+impl PostponedEventsFileFormat {
+    pub fn new(table_name: String, events: Vec<InsertEvent>) -> Self {
+        Self {
+            version: POSTPONED_EVENTS_FILE_VERSION,
+            table_name,
+            events,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("can't open postponed events file {}", path.display()))?;
+        let reader = BufReader::with_capacity(BUFFER_SIZE, file);
+        serde_json::from_reader(reader)
+            .with_context(|| format!("can't read postponed events file {}", path.display()))
+    }
+
+    /// Checks that file content can be safely inserted into database
+    pub fn validate(&self) -> Result<()> {
+        let supported_versions =
+            MIN_SUPPORTED_POSTPONED_EVENTS_FILE_VERSION..=POSTPONED_EVENTS_FILE_VERSION;
+        if !supported_versions.contains(&self.version) {
+            bail!(
+                "unsupported postponed events file version {} (expected {supported_versions:?})",
+                self.version
+            );
+        }
+
+        // table name is inserted into sql as is
+        let is_valid_table_name = self
+            .table_name
+            .chars()
+            .next()
+            .map_or(false, |x| x.is_ascii_lowercase() || x == '_')
+            && self
+                .table_name
+                .chars()
+                .all(|x| x.is_ascii_lowercase() || x.is_ascii_digit() || x == '_');
+        if !is_valid_table_name {
+            bail!("invalid table name `{}`", self.table_name);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ImportedEventsCount {
+    pub inserted: usize,
+    /// Events that already exist in database
+    pub duplicates: usize,
+}
+
+#[derive(Debug)]
+pub enum PostponedEventsFileImport {
+    Restored {
+        table_name: String,
+        count: ImportedEventsCount,
+    },
+    Rejected {
+        reason: String,
+    },
+}
+
+#[derive(Debug)]
+pub struct PostponedEventsFileReport {
+    pub path: PathBuf,
+    pub import: PostponedEventsFileImport,
+}
+
+/// Inserts events in single transaction skipping events that already exist in table.
+/// Events are identified by `event_id` that is covered by unique index
+pub async fn import_events(
+    pool: &PgPool,
+    table_name: &str,
+    events: &[InsertEvent],
+) -> Result<ImportedEventsCount> {
+    let sql = format!(
+        "INSERT INTO {table_name} (event_id, version, json) VALUES ($1, $2, $3) \
+        ON CONFLICT (event_id) DO NOTHING"
+    );
+
+    let mut connection = pool
+        .0
+        .get()
+        .await
+        .context("getting db connection from pool")?;
+    let transaction = connection
+        .transaction()
+        .await
+        .context("from `import_events` on starting transaction")?;
+
+    let statement = transaction
+        .prepare_typed(&sql, &EVENT_INSERT_TYPES_LIST)
+        .await
+        .context("from `import_events` on client.prepare_typed")?;
+
+    let mut count = ImportedEventsCount::default();
+    for event in events {
+        let inserted = transaction
+            .execute(&statement, &[&event.event_id, &event.version, &event.json])
+            .await
+            .with_context(|| format!("from `import_events` failed inserting event: {event}"))?;
+
+        match inserted {
+            0 => count.duplicates += 1,
+            _ => count.inserted += 1,
+        }
+    }
+
+    transaction
+        .commit()
+        .await
+        .context("from `import_events` on committing transaction")?;
+
+    Ok(count)
+}
+
+/// Imports all postponed events files from directory in order of their creation.
+/// Importing is idempotent, so it can be safely repeated for the same files
+pub async fn import_postponed_events_dir(
+    pool: &PgPool,
+    dir: &Path,
+    remove_imported: bool,
+) -> Result<Vec<PostponedEventsFileReport>> {
+    let mut reports = vec![];
+    for path in get_postponed_events_files(dir)? {
+        let import = match import_postponed_events_file(pool, &path).await {
+            Ok((table_name, count)) => {
+                if remove_imported {
+                    fs::remove_file(&path).with_context(|| {
+                        format!("failed removing imported file {}", path.display())
+                    })?;
+                }
+
+                PostponedEventsFileImport::Restored { table_name, count }
+            }
+            Err(err) => PostponedEventsFileImport::Rejected {
+                reason: format!("{err:#}"),
+            },
+        };
+
+        reports.push(PostponedEventsFileReport { path, import });
+    }
+
+    Ok(reports)
+}
+
+async fn import_postponed_events_file(
+    pool: &PgPool,
+    path: &Path,
+) -> Result<(String, ImportedEventsCount)> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    if file_name.starts_with(NOT_FINISHED_POSTPONED_EVENTS_FILE_PREFIX) {
+        bail!("file was not completely written");
+    }
+
+    let file = PostponedEventsFileFormat::load(path)?;
+    file.validate()?;
+
+    let count = import_events(pool, &file.table_name, &file.events).await?;
+
+    Ok((file.table_name, count))
+}
+
+/// Finished and not finished postponed events files sorted by name (creation time)
+fn get_postponed_events_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+    for entry in fs::read_dir(dir)
+        .with_context(|| format!("can't read postponed events dir {}", dir.display()))?
+    {
+        let entry = entry.context("can't read postponed events dir entry")?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let is_events_file = file_name.starts_with(POSTPONED_EVENTS_FILE_PREFIX)
+            || file_name.starts_with(NOT_FINISHED_POSTPONED_EVENTS_FILE_PREFIX);
+        if is_events_file {
+            paths.push(entry.path());
+        }
+    }
+
+    paths.sort_by_key(|x| {
+        x.file_name()
+            .map(|x| {
+                x.to_string_lossy()
+                    .trim_start_matches(NOT_FINISHED_POSTPONED_EVENTS_FILE_PREFIX)
+                    .to_owned()
+            })
+            .unwrap_or_default()
+    });
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::postgres_db::events::save_events_batch;
+    use crate::postgres_db::tests::{get_database_url, PgPoolMutex};
+    use serde_json::json;
+
+    const TABLE_NAME: &str = "postponed_persons";
+
+    #[test]
+    fn validate_file() {
+        let events = vec![InsertEvent::new(1, json!({ "id": 1 }))];
+
+        let file = PostponedEventsFileFormat::new("balance_updates".to_owned(), events.clone());
+        assert!(file.validate().is_ok());
+
+        let file = PostponedEventsFileFormat::new("orders; DROP TABLE orders".to_owned(), events);
+        assert!(file.validate().is_err());
+
+        let file = PostponedEventsFileFormat {
+            version: 3,
+            table_name: "orders".to_owned(),
+            events: vec![],
+        };
+        assert!(file.validate().is_err());
+    }
+
+    #[test]
+    fn load_file_of_version_1() {
+        let path = std::env::temp_dir().join(format!(
+            "mmb_postponed_events_v1_{}",
+            chrono::Utc::now().timestamp_nanos()
+        ));
+        let content = json!({
+            "version": 1,
+            "table_name": "balance_updates",
+            "events": [
+                { "version": 1, "json": { "id": 1 } },
+                { "version": 1, "json": { "id": 1 } },
+            ],
+        });
+        fs::write(&path, content.to_string()).expect("in test");
+
+        let file = PostponedEventsFileFormat::load(&path);
+        let _ = fs::remove_file(&path);
+
+        let file = file.expect("in test");
+        assert!(file.validate().is_ok());
+        assert_eq!(file.events.len(), 2);
+        assert_eq!(file.events[0].json, json!({ "id": 1 }));
+        assert_ne!(file.events[0].event_id, file.events[1].event_id);
+    }
+
+    #[test]
+    fn postponed_events_files_order() {
+        let dir = std::env::temp_dir().join(format!(
+            "mmb_postponed_events_{}",
+            chrono::Utc::now().timestamp_nanos()
+        ));
+        fs::create_dir_all(&dir).expect("in test");
+
+        let file_names = [
+            "events_2022.01.02_00.00.00.000000_000001",
+            "writing_yet_events_2022.01.01_00.00.00.000000_000002",
+            "events_2022.01.01_00.00.00.000000_000000",
+            "other_file",
+        ];
+        for file_name in file_names {
+            File::create(dir.join(file_name)).expect("in test");
+        }
+
+        let paths = get_postponed_events_files(&dir).expect("in test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let expected = [file_names[2], file_names[1], file_names[0]]
+            .iter()
+            .map(|x| dir.join(x))
+            .collect::<Vec<_>>();
+        assert_eq!(paths, expected);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn import_events_skips_already_saved() {
+        let pool_mutex = PgPoolMutex::create(&get_database_url(), 1).await;
+        let connection = pool_mutex.pool.get_connection_expected().await;
+        connection
+            .batch_execute(
+                &include_str!("./sql/create_or_truncate_table.sql")
+                    .replace("TABLE_NAME", TABLE_NAME),
+            )
+            .await
+            .expect("recreate table");
+        drop(connection);
+
+        // events with equal content are different events
+        let saved_event = InsertEvent::new(1, json!({ "id": 1 }));
+        let events = vec![saved_event.clone(), InsertEvent::new(1, json!({ "id": 1 }))];
+        save_events_batch(&pool_mutex.pool, TABLE_NAME, &[saved_event])
+            .await
+            .expect("in test");
+
+        let count = import_events(&pool_mutex.pool, TABLE_NAME, &events)
+            .await
+            .expect("in test");
+        assert_eq!(
+            count,
+            ImportedEventsCount {
+                inserted: 1,
+                duplicates: 1
+            }
+        );
+
+        let count = import_events(&pool_mutex.pool, TABLE_NAME, &events)
+            .await
+            .expect("in test");
+        assert_eq!(
+            count,
+            ImportedEventsCount {
+                inserted: 0,
+                duplicates: 2
+            }
+        );
+
+        let connection = pool_mutex.pool.get_connection_expected().await;
+        let rows = connection
+            .query(&format!("SELECT * FROM {TABLE_NAME}"), &[])
+            .await
+            .expect("in test");
+        assert_eq!(rows.len(), 2);
+    }
+}
@@ -0,0 +1,10 @@
+SELECT id, insert_time, version, json
+FROM TABLE_NAME
+WHERE version BETWEEN $1 AND $2
+  AND ($3::text IS NULL OR json @? $3::text::jsonpath)
+  AND ($4::text IS NULL OR json @? $4::text::jsonpath)
+  AND ($5::timestamptz IS NULL OR insert_time >= $5)
+  AND ($6::timestamptz IS NULL OR insert_time < $6)
+  AND ($7::bigint IS NULL OR id > $7)
+ORDER BY id
+LIMIT $8
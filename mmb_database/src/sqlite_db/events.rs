@@ -0,0 +1,87 @@
+use crate::postgres_db::events::InsertEvent;
+use crate::sqlite_db::SqlitePool;
+use anyhow::{Context, Result};
+use sqlx::Executor;
+
+/// Saves events in single transaction. Table is created on first saving to it
+pub async fn save_events_batch(
+    pool: &SqlitePool,
+    table_name: &str,
+    events: &[InsertEvent],
+) -> Result<()> {
+    let mut transaction = pool
+        .0
+        .begin()
+        .await
+        .context("from `save_events_batch` on begin transaction")?;
+
+    transaction
+        .execute(
+            include_str!("./sql/create_table_if_not_exists.sql")
+                .replace("TABLE_NAME", table_name)
+                .as_str(),
+        )
+        .await
+        .with_context(|| format!("from `save_events_batch` on creation table {table_name}"))?;
+
+    let sql = format!("INSERT INTO {table_name} (version, json) VALUES(?, ?)");
+    for event in events {
+        let _ = sqlx::query(&sql)
+            .bind(event.version)
+            .bind(event.json.to_string())
+            .execute(&mut transaction)
+            .await
+            .context("from `save_events_batch` on insert event")?;
+    }
+
+    transaction
+        .commit()
+        .await
+        .context("from `save_events_batch` on commit transaction")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::postgres_db::events::InsertEvent;
+    use crate::sqlite_db::events::save_events_batch;
+    use crate::sqlite_db::SqlitePool;
+    use serde_json::json;
+    use sqlx::Row;
+    use std::env;
+
+    const TABLE_NAME: &str = "persons";
+
+    #[tokio::test]
+    async fn save_batch_events() {
+        let path = env::temp_dir().join(format!("mmb_events_{}.sqlite", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let pool = SqlitePool::create(&path).await.expect("in test");
+
+        let expected_json = json!({
+            "first_name": "Ivan",
+            "last_name": "Ivanov",
+        });
+        let item = InsertEvent::new(1, expected_json.clone());
+
+        save_events_batch(&pool, TABLE_NAME, &[item.clone(), item])
+            .await
+            .expect("in test");
+
+        let rows = sqlx::query(&format!("SELECT version, json FROM {TABLE_NAME}"))
+            .fetch_all(&pool.0)
+            .await
+            .expect("in test");
+
+        assert_eq!(rows.len(), 2);
+        let version: i32 = rows[0].get("version");
+        let json: String = rows[0].get("json");
+        assert_eq!(version, 1);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).expect("in test"),
+            expected_json
+        );
+
+        drop(pool);
+        let _ = std::fs::remove_file(&path);
+    }
+}
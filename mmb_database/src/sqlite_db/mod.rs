@@ -0,0 +1,26 @@
+pub mod events;
+
+use anyhow::{Context, Result};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use std::path::Path;
+
+/// Pool of connections to SQLite database file. It is used for recording events on small deployments without Postgres
+#[derive(Clone)]
+pub struct SqlitePool(pub(crate) sqlx::SqlitePool);
+
+impl SqlitePool {
+    pub async fn create(path: &Path) -> Result<SqlitePool> {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+
+        // SQLite allows only single writer at the same time
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .with_context(|| format!("connecting to sqlite database {}", path.display()))?;
+
+        Ok(SqlitePool(pool))
+    }
+}
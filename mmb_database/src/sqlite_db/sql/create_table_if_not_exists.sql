@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS TABLE_NAME(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    insert_time TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    version INTEGER,
+    json TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS TABLE_NAME_insert_time_idx ON TABLE_NAME (insert_time);
@@ -22,12 +22,35 @@ pub trait MmbRpc {
 
     #[rpc(name = "stats")]
     fn stats(&self) -> Result<String>;
+
+    #[rpc(name = "execution_algos")]
+    fn execution_algos(&self) -> Result<String>;
+
+    #[rpc(name = "start_execution_algo")]
+    fn start_execution_algo(&self, parent_order: String) -> Result<String>;
+
+    #[rpc(name = "cancel_execution_algo")]
+    fn cancel_execution_algo(&self, id: u64) -> Result<String>;
+
+    #[rpc(name = "pnl")]
+    fn pnl(&self) -> Result<String>;
+
+    #[rpc(name = "update_currency_pairs")]
+    fn update_currency_pairs(
+        &self,
+        exchange_account_id: String,
+        currency_pairs: String,
+    ) -> Result<String>;
 }
 
 pub enum ErrorCode {
     StopperIsNone = 1,
     UnableToSendSignal = 2,
     FailedToSaveNewConfig = 3,
+    FailedToSerializeData = 4,
+    FailedToStartExecutionAlgo = 5,
+    ExecutionAlgoNotFound = 6,
+    FailedToUpdateCurrencyPairs = 7,
 }
 
 pub fn server_side_error(code: ErrorCode) -> Error {
@@ -35,6 +58,10 @@ pub fn server_side_error(code: ErrorCode) -> Error {
         ErrorCode::StopperIsNone => "Server stopper is none",
         ErrorCode::UnableToSendSignal => "Unable to send signal",
         ErrorCode::FailedToSaveNewConfig => "Failed to save new config",
+        ErrorCode::FailedToSerializeData => "Failed to serialize data",
+        ErrorCode::FailedToStartExecutionAlgo => "Failed to start execution algo",
+        ErrorCode::ExecutionAlgoNotFound => "Execution algo not found",
+        ErrorCode::FailedToUpdateCurrencyPairs => "Failed to update currency pairs",
     };
     log::error!("Rest API error: {}", reason);
     Error::new(jsonrpc_core::ErrorCode::ServerError(code as i64))
@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use chrono::{DateTime, Utc};
+use paperclip::actix::{
+    api_v2_operation,
+    web::{self, Json},
+    Apiv2Schema,
+};
+use serde::{Deserialize, Serialize};
+
+use mmb_database::postgres_db::events_query::Pagination;
+use mmb_domain::events_query::EventsQuery;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+
+use crate::error::AppError;
+use crate::services::data_provider::events::{EventsService, FillsReport, LiquidationPricesPage};
+
+#[derive(Deserialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub struct FillsQuery {
+    exchange_account_id: String,
+    currency_code_pair: String,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub struct FillsGetResponse {
+    exchange_account_id: String,
+    currency_code_pair: String,
+    report: FillsReport,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub struct LiquidationPricesQuery {
+    exchange_account_id: String,
+    currency_code_pair: String,
+    after_id: Option<i64>,
+    limit: Option<u32>,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub struct LiquidationPricesGetResponse {
+    exchange_account_id: String,
+    currency_code_pair: String,
+    page: LiquidationPricesPage,
+}
+
+fn create_events_query(
+    exchange_account_id: &str,
+    currency_code_pair: &str,
+) -> Result<EventsQuery, AppError> {
+    let exchange_account_id = exchange_account_id
+        .parse::<ExchangeAccountId>()
+        .map_err(|e| {
+            log::warn!("parse exchange account id {exchange_account_id} {e:?}");
+            AppError::BadRequest
+        })?;
+
+    let currency_pair = match currency_code_pair.split_once('/') {
+        Some((base, quote)) if !base.is_empty() && !quote.is_empty() && !quote.contains('/') => {
+            CurrencyPair::from_codes(base.into(), quote.into())
+        }
+        _ => {
+            log::warn!("parse currency code pair {currency_code_pair}");
+            return Err(AppError::BadRequest);
+        }
+    };
+
+    Ok(EventsQuery::new(exchange_account_id, currency_pair))
+}
+
+#[api_v2_operation(tags(Events), summary = "Get report of fills in time range")]
+pub async fn fills(
+    query: web::Query<FillsQuery>,
+    events_service: Data<Arc<EventsService>>,
+) -> Result<Json<FillsGetResponse>, AppError> {
+    let mut events_query =
+        create_events_query(&query.exchange_account_id, &query.currency_code_pair)?;
+    events_query.from = query.from;
+    events_query.to = query.to;
+
+    match events_service.fills_report(&events_query).await {
+        Ok(report) => Ok(Json(FillsGetResponse {
+            exchange_account_id: query.exchange_account_id.clone(),
+            currency_code_pair: query.currency_code_pair.clone(),
+            report,
+        })),
+        Err(e) => {
+            log::error!("get fills report {e:?}");
+            Err(AppError::InternalServerError)
+        }
+    }
+}
+
+#[api_v2_operation(tags(Events), summary = "Get page of liquidation prices")]
+pub async fn liquidation_prices(
+    query: web::Query<LiquidationPricesQuery>,
+    events_service: Data<Arc<EventsService>>,
+) -> Result<Json<LiquidationPricesGetResponse>, AppError> {
+    let events_query = create_events_query(&query.exchange_account_id, &query.currency_code_pair)?;
+    let default_pagination = Pagination::default();
+    let pagination = Pagination {
+        after_id: query.after_id,
+        limit: query.limit.unwrap_or(default_pagination.limit),
+    };
+
+    match events_service
+        .liquidation_prices(&events_query, pagination)
+        .await
+    {
+        Ok(page) => Ok(Json(LiquidationPricesGetResponse {
+            exchange_account_id: query.exchange_account_id.clone(),
+            currency_code_pair: query.currency_code_pair.clone(),
+            page,
+        })),
+        Err(e) => {
+            log::error!("get liquidation prices {e:?}");
+            Err(AppError::InternalServerError)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_query_by_market() {
+        let query = create_events_query("Binance_0", "btc/usdt").expect("in test");
+
+        assert_eq!(
+            query.exchange_account_id,
+            Some(ExchangeAccountId::new("Binance", 0))
+        );
+        assert_eq!(
+            query.currency_pair,
+            Some(CurrencyPair::from_codes("btc".into(), "usdt".into()))
+        );
+        assert!(matches!(
+            create_events_query("Binance", "btc/usdt"),
+            Err(AppError::BadRequest)
+        ));
+        for currency_code_pair in ["btcusdt", "btc/", "/usdt", "btc/usdt/eth"] {
+            assert!(matches!(
+                create_events_query("Binance_0", currency_code_pair),
+                Err(AppError::BadRequest)
+            ));
+        }
+    }
+}
@@ -1,5 +1,7 @@
 pub mod account;
 pub mod configuration;
+pub mod events;
 pub mod explanation;
 pub mod liquidity;
+pub mod pnl;
 pub mod ws;
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use paperclip::actix::{
+    api_v2_operation,
+    web::{self, Json},
+    Apiv2Schema,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::services::data_provider::pnl::{PnlLedgerEntry, PnlService, PnlTotals};
+
+#[derive(Deserialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub struct PnlQuery {
+    exchange_account_id: String,
+    currency_code_pair: String,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub struct PnlGetResponse {
+    exchange_account_id: String,
+    currency_code_pair: String,
+    totals: PnlTotals,
+    entries: Vec<PnlLedgerEntry>,
+}
+
+#[api_v2_operation(tags(Pnl), summary = "Get P&L ledger")]
+pub async fn get(
+    query: web::Query<PnlQuery>,
+    pnl_service: Data<Arc<PnlService>>,
+) -> Result<Json<PnlGetResponse>, AppError> {
+    let totals = pnl_service
+        .totals(&query.exchange_account_id, &query.currency_code_pair)
+        .await;
+    let entries = pnl_service
+        .list(&query.exchange_account_id, &query.currency_code_pair, 300)
+        .await;
+    match (totals, entries) {
+        (Ok(totals), Ok(entries)) => {
+            let response = PnlGetResponse {
+                exchange_account_id: query.exchange_account_id.clone(),
+                currency_code_pair: query.currency_code_pair.clone(),
+                totals,
+                entries,
+            };
+            Ok(Json(response))
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            log::error!("get P&L ledger {e:?}");
+            Err(AppError::InternalServerError)
+        }
+    }
+}
@@ -34,6 +34,15 @@ pub(crate) fn http_routes(app: &mut web::ServiceConfig) {
                     .route("/validate", post().to(handlers::configuration::validate)),
             )
             .route("/explanations", get().to(handlers::explanation::get))
+            .route("/pnl", get().to(handlers::pnl::get))
+            .service(
+                web::scope("/events")
+                    .route("/fills", get().to(handlers::events::fills))
+                    .route(
+                        "/liquidation-prices",
+                        get().to(handlers::events::liquidation_prices),
+                    ),
+            )
             .service(web::scope("/liquidity").route(
                 "/supported-exchanges",
                 get().to(handlers::liquidity::supported_exchanges),
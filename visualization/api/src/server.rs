@@ -7,6 +7,7 @@ use actix_web::middleware::Logger;
 use actix_web::web::Data;
 use actix_web::{App, HttpServer};
 use casbin::Enforcer;
+use mmb_database::postgres_db::PgPool;
 use paperclip::actix::OpenApiExt;
 use paperclip::v2::models::DefaultApiRaw;
 use sqlx::postgres::PgPoolOptions;
@@ -19,7 +20,9 @@ use crate::routes::{http_routes, ws_routes};
 use crate::services::account::AccountService;
 use crate::services::auth::AuthService;
 use crate::services::data_provider::balances::BalancesService;
+use crate::services::data_provider::events::EventsService;
 use crate::services::data_provider::explanation::ExplanationService;
+use crate::services::data_provider::pnl::PnlService;
 use crate::services::market_settings::MarketSettingsService;
 use crate::services::settings::SettingsService;
 use crate::services::token::TokenService;
@@ -62,7 +65,12 @@ pub async fn start(
     let auth_service = Arc::new(AuthService::new(enforcer));
     let market_settings_service = Arc::new(MarketSettingsService::from(markets));
     let settings_service = Arc::new(SettingsService::new(connection_pool.clone()));
-    let explanation_service = Arc::new(ExplanationService::new(connection_pool));
+    let explanation_service = Arc::new(ExplanationService::new(connection_pool.clone()));
+    let pnl_service = Arc::new(PnlService::new(connection_pool));
+    let events_pool = PgPool::create(database_url, 5)
+        .await
+        .expect("Unable to create events connection pool");
+    let events_service = Arc::new(EventsService::new(events_pool));
 
     let data_provider = DataProvider::new(
         subscription_manager,
@@ -99,6 +107,8 @@ pub async fn start(
             .app_data(Data::new(market_settings_service.clone()))
             .app_data(Data::new(settings_service.clone()))
             .app_data(Data::new(explanation_service.clone()))
+            .app_data(Data::new(pnl_service.clone()))
+            .app_data(Data::new(events_service.clone()))
             .with_json_spec_at("/swagger-spec")
             .with_swagger_ui_at("/swagger-ui")
             .build()
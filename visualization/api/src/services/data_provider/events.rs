@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use paperclip::actix::Apiv2Schema;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use mmb_database::postgres_db::events_query::Pagination;
+use mmb_database::postgres_db::PgPool;
+use mmb_domain::events::LiquidationPriceEvent;
+use mmb_domain::events_query::EventsQuery;
+use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
+
+/// Data Provider for recorded engine events that are read via typed events query
+#[derive(Clone)]
+pub struct EventsService {
+    pool: PgPool,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub struct FillRecord {
+    pub client_order_id: String,
+    pub date_time: DateTime<Utc>,
+    pub side: String,
+    pub role: String,
+    pub price: Price,
+    pub amount: Amount,
+    pub cost: Decimal,
+    pub commission_currency_code: String,
+    pub commission_amount: Decimal,
+}
+
+/// Traded volume and fills of market in time range
+#[derive(Serialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub struct FillsReport {
+    pub buy_amount: Amount,
+    pub sell_amount: Amount,
+    pub buy_cost: Decimal,
+    pub sell_cost: Decimal,
+    pub fills: Vec<FillRecord>,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub struct LiquidationPriceRecord {
+    pub id: i64,
+    pub date_time: DateTime<Utc>,
+    pub side: String,
+    pub liquidation_price: Price,
+    pub entry_price: Price,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub struct LiquidationPricesPage {
+    pub records: Vec<LiquidationPriceRecord>,
+    /// Id for requesting the next page or `None` if this page is the last one
+    pub next_after_id: Option<i64>,
+}
+
+impl EventsService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn fills_report(&self, query: &EventsQuery) -> anyhow::Result<FillsReport> {
+        let fills = query.select_fills(&self.pool).await?;
+
+        let mut report = FillsReport {
+            buy_amount: Amount::ZERO,
+            sell_amount: Amount::ZERO,
+            buy_cost: Decimal::ZERO,
+            sell_cost: Decimal::ZERO,
+            fills: Vec::with_capacity(fills.len()),
+        };
+        for recorded in fills {
+            let fill = &recorded.fill;
+            match recorded.side {
+                OrderSide::Buy => {
+                    report.buy_amount += fill.amount();
+                    report.buy_cost += fill.cost();
+                }
+                OrderSide::Sell => {
+                    report.sell_amount += fill.amount();
+                    report.sell_cost += fill.cost();
+                }
+            }
+
+            report.fills.push(FillRecord {
+                client_order_id: recorded.client_order_id.to_string(),
+                date_time: fill.receive_time(),
+                side: format!("{:?}", recorded.side),
+                role: format!("{:?}", fill.role()),
+                price: fill.price(),
+                amount: fill.amount(),
+                cost: fill.cost(),
+                commission_currency_code: fill.commission_currency_code().to_string(),
+                commission_amount: fill.commission_amount(),
+            });
+        }
+
+        Ok(report)
+    }
+
+    pub async fn liquidation_prices(
+        &self,
+        query: &EventsQuery,
+        pagination: Pagination,
+    ) -> anyhow::Result<LiquidationPricesPage> {
+        let page = query
+            .select::<LiquidationPriceEvent>(&self.pool, pagination)
+            .await?;
+
+        let records = page
+            .events
+            .into_iter()
+            .map(|it| LiquidationPriceRecord {
+                id: it.id,
+                date_time: it.insert_time,
+                side: format!("{:?}", it.event.side),
+                liquidation_price: it.event.liq_price,
+                entry_price: it.event.entry_price,
+            })
+            .collect_vec();
+
+        Ok(LiquidationPricesPage {
+            records,
+            next_after_id: page.next_page.and_then(|x| x.after_id),
+        })
+    }
+}
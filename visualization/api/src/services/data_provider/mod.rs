@@ -1,4 +1,6 @@
 pub mod balances;
+pub mod events;
 pub mod explanation;
 pub mod liquidity;
 pub(crate) mod model;
+pub mod pnl;
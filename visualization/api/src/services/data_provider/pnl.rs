@@ -0,0 +1,114 @@
+use std::str::FromStr;
+
+use chrono::DateTime;
+use itertools::Itertools;
+use paperclip::actix::Apiv2Schema;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+
+use mmb_domain::order::snapshot::{Amount, Price};
+
+use crate::services::data_provider::model::EventTimedRecord;
+use crate::types::{CurrencyPair, ExchangeId};
+
+/// Data Provider for P&L ledger
+#[derive(Clone)]
+pub struct PnlService {
+    pool: Pool<Postgres>,
+}
+
+#[derive(Serialize, Deserialize, Apiv2Schema)]
+#[serde(rename_all(deserialize = "snake_case", serialize = "camelCase"))]
+pub struct PnlLedgerRecord {
+    pub side: String,
+    pub amount: Amount,
+    pub price: Price,
+    pub realized_pnl: Decimal,
+    pub realized_pnl_usd: Option<Decimal>,
+    pub commission_usd: Option<Decimal>,
+    pub position: Amount,
+    pub average_price: Option<Price>,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub struct PnlLedgerEntry {
+    pub id: i64,
+    pub date_time: DateTime<chrono::Utc>,
+    pub fill: PnlLedgerRecord,
+}
+
+#[derive(sqlx::FromRow)]
+struct PnlTotalsRecord {
+    realized_pnl: String,
+    realized_pnl_usd: String,
+    fees_usd: String,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub struct PnlTotals {
+    pub realized_pnl: Decimal,
+    pub realized_pnl_usd: Decimal,
+    pub fees_usd: Decimal,
+}
+
+impl PnlService {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Last ledger entries in chronological order
+    pub async fn list(
+        &self,
+        exchange_account_id: &ExchangeId,
+        currency_pair: &CurrencyPair,
+        limit: i32,
+    ) -> anyhow::Result<Vec<PnlLedgerEntry>> {
+        let sql = include_str!("../sql/get_pnl_ledger.sql");
+        let records = sqlx::query_as::<Postgres, EventTimedRecord>(sql)
+            .bind(exchange_account_id)
+            .bind(currency_pair)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let list = records
+            .into_iter()
+            .map(|it| {
+                let record: PnlLedgerRecord =
+                    serde_json::from_value(it.json).unwrap_or_else(|_| {
+                        panic!("Incorrect database P&L ledger json data. ID: {:?}", it.id)
+                    });
+
+                PnlLedgerEntry {
+                    id: it.id,
+                    date_time: it.insert_time,
+                    fill: record,
+                }
+            })
+            .collect_vec();
+        Ok(list)
+    }
+
+    /// Totals of all ledger entries of market
+    pub async fn totals(
+        &self,
+        exchange_account_id: &ExchangeId,
+        currency_pair: &CurrencyPair,
+    ) -> anyhow::Result<PnlTotals> {
+        let sql = include_str!("../sql/get_pnl_totals.sql");
+        let record = sqlx::query_as::<Postgres, PnlTotalsRecord>(sql)
+            .bind(exchange_account_id)
+            .bind(currency_pair)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(PnlTotals {
+            realized_pnl: Decimal::from_str(&record.realized_pnl)?,
+            realized_pnl_usd: Decimal::from_str(&record.realized_pnl_usd)?,
+            fees_usd: Decimal::from_str(&record.fees_usd)?,
+        })
+    }
+}
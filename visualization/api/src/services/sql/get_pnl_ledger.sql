@@ -0,0 +1,8 @@
+SELECT id, insert_time, json FROM (
+    SELECT id, insert_time, json FROM pnl_ledger
+    WHERE ((json ->> 'exchange_account_id')::text = $1)
+      AND ((json ->> 'currency_pair')::text = $2)
+    ORDER BY id DESC
+    limit $3
+) AS last_entries
+ORDER BY id
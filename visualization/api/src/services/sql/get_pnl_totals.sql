@@ -0,0 +1,6 @@
+SELECT COALESCE(SUM((json ->> 'realized_pnl')::numeric), 0)::text AS realized_pnl,
+       COALESCE(SUM((json ->> 'realized_pnl_usd')::numeric), 0)::text AS realized_pnl_usd,
+       COALESCE(SUM((json ->> 'commission_usd')::numeric), 0)::text AS fees_usd
+FROM pnl_ledger
+WHERE ((json ->> 'exchange_account_id')::text = $1)
+  AND ((json ->> 'currency_pair')::text = $2)